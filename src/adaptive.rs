@@ -0,0 +1,93 @@
+// --- Adaptive scan interval: long pauses while readings hold steady, short ones while they move or near a threshold ---
+
+use chrono::{DateTime, Duration as ChronoDuration, Local};
+use std::collections::VecDeque;
+
+use crate::model::Config;
+use crate::trend;
+
+/// Readings the rate of change is fitted to.
+pub const FIT_WINDOW_MINS: i64 = 30;
+// Rates per hour at or above which the scanner speeds up
+pub const FAST_TEMP_PER_HOUR: f64 = 2.0;
+pub const FAST_HUM_PER_HOUR: f64 = 10.0;
+// Distance from a warning threshold at which the scanner speeds up
+pub const NEAR_TEMP: f32 = 1.0;
+pub const NEAR_HUM: f32 = 5.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Pace {
+    /// Changing quickly or close to a threshold: shortest pause
+    Fast,
+    /// Not enough readings yet, or neither steady nor fast: `scan_pause_secs`
+    Normal,
+    /// Within the steady bands of the trend arrows: longest pause
+    Slow,
+}
+
+impl Pace {
+    pub fn label(&self) -> &'static str { match self { Pace::Fast => "fast", Pace::Normal => "normal", Pace::Slow => "slow" } }
+}
+
+/// Recent readings of the scanner, kept for `FIT_WINDOW_MINS`.
+#[derive(Default)]
+pub struct AdaptivePause { recent: VecDeque<(DateTime<Local>, f32, u8)> }
+
+impl AdaptivePause {
+    pub fn record(&mut self, at: DateTime<Local>, temp: f32, hum: u8) {
+        self.recent.push_back((at, temp, hum));
+        while self.recent.front().is_some_and(|(t, _, _)| at - *t > ChronoDuration::minutes(FIT_WINDOW_MINS)) { self.recent.pop_front(); }
+    }
+
+    pub fn pace(&self, config: &Config) -> Pace {
+        let Some(&(_, temp, hum)) = self.recent.back() else { return Pace::Normal; };
+        let near = |value: f32, low: f32, high: f32, margin: f32| (value - low).abs() <= margin || (value - high).abs() <= margin;
+        if near(temp, config.temp_warn_low, config.temp_warn_high, NEAR_TEMP) || near(hum as f32, config.hum_warn_low, config.hum_warn_high, NEAR_HUM) { return Pace::Fast; }
+        let window = ChronoDuration::minutes(FIT_WINDOW_MINS);
+        let rate = |value: fn(&(DateTime<Local>, f32, u8)) -> f64| trend::rate_per_hour(self.recent.iter().map(|r| (r.0, value(r))), window).map(f64::abs);
+        match (rate(|r| r.1 as f64), rate(|r| r.2 as f64)) {
+            (Some(t), Some(h)) if t >= FAST_TEMP_PER_HOUR || h >= FAST_HUM_PER_HOUR => Pace::Fast,
+            (Some(t), Some(h)) if t <= trend::STEADY_TEMP_PER_HOUR && h <= trend::STEADY_HUM_PER_HOUR => Pace::Slow,
+            _ => Pace::Normal,
+        }
+    }
+
+    /// Seconds to wait before the next scan.
+    pub fn pause_secs(&self, config: &Config) -> u64 {
+        let (min, max) = bounds(config);
+        match self.pace(config) { Pace::Fast => min, Pace::Normal => config.scan_pause_secs.clamp(min, max), Pace::Slow => max }
+    }
+}
+
+/// (shortest, longest) pause; the longest is never below the shortest.
+pub fn bounds(config: &Config) -> (u64, u64) {
+    let min = config.adaptive_min_pause_secs.max(1);
+    (min, config.adaptive_max_pause_secs.max(min))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adaptive_pause_slows_down_when_steady_and_speeds_up_on_change_or_near_a_threshold() {
+        use chrono::Duration;
+        let config = Config { scan_pause_secs: 20, adaptive_scan: true, adaptive_min_pause_secs: 5, adaptive_max_pause_secs: 300, temp_warn_low: 10.0, temp_warn_high: 30.0, hum_warn_low: 30.0, hum_warn_high: 70.0, ..Config::default() };
+        let start = Local::now() - Duration::minutes(40);
+        let run = |temp_per_min: f32, base: f32| {
+            let mut pause = AdaptivePause::default();
+            for m in 0..=40 { pause.record(start + Duration::minutes(m), base + temp_per_min * m as f32, 50); }
+            pause
+        };
+
+        assert_eq!(AdaptivePause::default().pause_secs(&config), 20, "no readings yet: the fixed pause");
+        assert_eq!((run(0.0, 20.0).pace(&config), run(0.0, 20.0).pause_secs(&config)), (Pace::Slow, 300));
+        // 3 °C per hour
+        assert_eq!((run(0.05, 20.0).pace(&config), run(0.05, 20.0).pause_secs(&config)), (Pace::Fast, 5));
+        // 1 °C per hour is neither steady nor fast
+        assert_eq!(run(1.0 / 60.0, 20.0).pause_secs(&config), 20);
+        assert_eq!(run(0.0, 29.2).pace(&config), Pace::Fast, "steady, but within 1 °C of the upper threshold");
+        // Swapped bounds never make the longest pause shorter than the shortest
+        assert_eq!(run(0.0, 20.0).pause_secs(&Config { adaptive_min_pause_secs: 60, adaptive_max_pause_secs: 10, ..config.clone() }), 60);
+    }
+}
@@ -0,0 +1,202 @@
+// --- Hourly and daily min/max/mean summaries, kept in hourly.csv / daily.csv next to the daily logs ---
+
+use chrono::{DateTime, Local, NaiveDate, Timelike};
+use log::info;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::history::PointOrigin;
+use crate::model::HistoryPoint;
+use crate::storage::{format_log_timestamp, load_history_range, parse_log_timestamp};
+use crate::{integrity, paths};
+
+// The first three columns match the daily logs, so the means load like ordinary readings
+const HEADER: &str = "DateTime,Temperature,Humidity,TemperatureMin,TemperatureMax,HumidityMin,HumidityMax,Samples";
+// Days summarised per rewrite of the summary files, to bound memory on the first run over old logs
+const REFRESH_BATCH_DAYS: usize = 31;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Resolution { Hour, Day }
+
+impl Resolution {
+    pub fn label(&self) -> &'static str { match self { Resolution::Hour => "hourly", Resolution::Day => "daily" } }
+
+    pub fn path_in(&self, dir: &Path) -> PathBuf { dir.join(match self { Resolution::Hour => "hourly.csv", Resolution::Day => "daily.csv" }) }
+
+    fn bucket_start(&self, time: DateTime<Local>) -> DateTime<Local> {
+        let hour = time.with_minute(0).and_then(|t| t.with_second(0)).and_then(|t| t.with_nanosecond(0)).unwrap_or(time);
+        match self {
+            Resolution::Hour => hour,
+            Resolution::Day => time.date_naive().and_hms_opt(0, 0, 0).and_then(|t| t.and_local_timezone(Local).earliest()).unwrap_or(hour),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Summary {
+    pub start: DateTime<Local>,
+    pub temp_mean: f32,
+    pub temp_min: f32,
+    pub temp_max: f32,
+    pub hum_mean: f32,
+    pub hum_min: u8,
+    pub hum_max: u8,
+    pub samples: usize,
+}
+
+impl Summary {
+    pub fn to_point(&self) -> HistoryPoint {
+        HistoryPoint { timestamp: self.start, temp: self.temp_mean, hum: self.hum_mean.round() as u8, origin: PointOrigin::Summary, rssi: None }
+    }
+
+    fn to_row(&self) -> String {
+        format!("{},{:.1},{:.0},{:.1},{:.1},{},{},{}", format_log_timestamp(self.start), self.temp_mean, self.hum_mean, self.temp_min, self.temp_max, self.hum_min, self.hum_max, self.samples)
+    }
+
+    fn from_row(line: &str) -> Option<Self> {
+        let f: Vec<&str> = line.split(',').collect();
+        if f.len() < 8 { return None; }
+        Some(Self {
+            start: parse_log_timestamp(f[0])?,
+            temp_mean: f[1].parse().ok()?, hum_mean: f[2].parse().ok()?,
+            temp_min: f[3].parse().ok()?, temp_max: f[4].parse().ok()?,
+            hum_min: f[5].parse().ok()?, hum_max: f[6].parse().ok()?,
+            samples: f[7].parse().ok()?,
+        })
+    }
+}
+
+/// Groups time-ordered points into hours or local days.
+pub fn summarize<'a>(points: impl IntoIterator<Item = &'a HistoryPoint>, resolution: Resolution) -> Vec<Summary> {
+    let mut out: Vec<Summary> = Vec::new();
+    let mut sums = (0.0f64, 0.0f64);
+    for p in points {
+        let start = resolution.bucket_start(p.timestamp);
+        match out.last_mut() {
+            Some(s) if s.start == start => {
+                s.temp_min = s.temp_min.min(p.temp); s.temp_max = s.temp_max.max(p.temp);
+                s.hum_min = s.hum_min.min(p.hum); s.hum_max = s.hum_max.max(p.hum);
+                s.samples += 1;
+            }
+            _ => {
+                sums = (0.0, 0.0);
+                out.push(Summary { start, temp_mean: 0.0, temp_min: p.temp, temp_max: p.temp, hum_mean: 0.0, hum_min: p.hum, hum_max: p.hum, samples: 1 });
+            }
+        }
+        sums = (sums.0 + p.temp as f64, sums.1 + p.hum as f64);
+        if let Some(s) = out.last_mut() { (s.temp_mean, s.hum_mean) = ((sums.0 / s.samples as f64) as f32, (sums.1 / s.samples as f64) as f32); }
+    }
+    out
+}
+
+// Resolution, length and first/last timestamp of the history the cache was built from
+type CacheKey = (Resolution, usize, Option<DateTime<Local>>, Option<DateTime<Local>>);
+
+/// Summaries of the in-memory history for the plots, rebuilt only when the history or resolution changes.
+#[derive(Default)]
+pub struct PlotCache { key: Option<CacheKey>, summaries: Vec<Summary> }
+
+impl PlotCache {
+    pub fn get(&mut self, history: &VecDeque<HistoryPoint>, resolution: Resolution) -> &[Summary] {
+        let key = Some((resolution, history.len(), history.front().map(|p| p.timestamp), history.back().map(|p| p.timestamp)));
+        if self.key != key { self.summaries = summarize(history, resolution); self.key = key; }
+        &self.summaries
+    }
+}
+
+fn read(resolution: Resolution, dir: &Path) -> Vec<Summary> {
+    fs::read_to_string(resolution.path_in(dir)).unwrap_or_default().lines().skip(1).filter_map(Summary::from_row).collect()
+}
+
+/// Summaries of the data folder that start within [start, end].
+pub fn load(resolution: Resolution, start: DateTime<Local>, end: DateTime<Local>) -> Vec<Summary> {
+    read(resolution, &paths::data_dir()).into_iter().filter(|s| s.start >= start && s.start <= end).collect()
+}
+
+/// Replaces the given days in both summary files with summaries of their points.
+pub fn store(days: &[(NaiveDate, Vec<HistoryPoint>)]) -> io::Result<()> {
+    let dir = paths::data_dir();
+    let dates: BTreeSet<NaiveDate> = days.iter().map(|(d, _)| *d).collect();
+    for resolution in [Resolution::Hour, Resolution::Day] {
+        let mut rows: BTreeMap<DateTime<Local>, Summary> = read(resolution, &dir).into_iter().filter(|s| !dates.contains(&s.start.date_naive())).map(|s| (s.start, s)).collect();
+        for (_, points) in days { rows.extend(summarize(points, resolution).into_iter().map(|s| (s.start, s))); }
+        let text: String = std::iter::once(HEADER.to_string()).chain(rows.values().map(Summary::to_row)).map(|l| l + "\n").collect();
+        let path = resolution.path_in(&dir);
+        let tmp = path.with_extension("csv.tmp");
+        integrity::write_synced(&tmp, text.as_bytes())?;
+        fs::rename(&tmp, &path)?;
+    }
+    Ok(())
+}
+
+/// Summarises every finished day whose log is new or changed since the last run. Returns the days updated.
+pub fn refresh(today: NaiveDate) -> io::Result<usize> {
+    let dir = paths::data_dir();
+    let summarised_at = fs::metadata(Resolution::Day.path_in(&dir)).and_then(|m| m.modified()).unwrap_or(SystemTime::UNIX_EPOCH);
+    let known: BTreeSet<NaiveDate> = read(Resolution::Day, &dir).iter().map(|s| s.start.date_naive()).collect();
+    let mut stale = BTreeSet::new();
+    for entry in fs::read_dir(&dir)?.flatten() {
+        let Some(date) = log_date(&entry.path()) else { continue; };
+        let modified = entry.metadata().and_then(|m| m.modified()).unwrap_or(SystemTime::now());
+        if date < today && (!known.contains(&date) || modified > summarised_at) { stale.insert(date); }
+    }
+    let stale: Vec<NaiveDate> = stale.into_iter().collect();
+    for batch in stale.chunks(REFRESH_BATCH_DAYS) {
+        let days: Vec<(NaiveDate, Vec<HistoryPoint>)> = batch.iter().map(|date| (*date, load_day(*date))).collect();
+        store(&days)?;
+    }
+    if !stale.is_empty() { info!("Summarised {} days into hourly and daily aggregates.", stale.len()); }
+    Ok(stale.len())
+}
+
+pub fn load_day(date: NaiveDate) -> Vec<HistoryPoint> {
+    let start = date.and_hms_opt(0, 0, 0).and_then(|t| t.and_local_timezone(Local).earliest());
+    let end = date.and_hms_opt(23, 59, 59).and_then(|t| t.and_local_timezone(Local).latest());
+    match (start, end) { (Some(start), Some(end)) => load_history_range(start, end), _ => Vec::new() }
+}
+
+/// Date of a daily log, raw (`log_YYYY-MM-DD.csv`) or archived (`.csv.gz`).
+pub fn log_date(path: &Path) -> Option<NaiveDate> {
+    let name = path.file_name()?.to_str()?.strip_prefix("log_")?;
+    let date = name.strip_suffix(".csv.gz").or_else(|| name.strip_suffix(".csv"))?;
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Config;
+    use crate::test_support::*;
+    use crate::ui::plots;
+
+    #[test]
+    fn aggregates_summarise_finished_days_and_pick_plot_resolution() {
+        let _data_dir = TempDataDir::new("aggregate");
+        let today = Local::now().date_naive();
+        let days = [today - chrono::Duration::days(2), today - chrono::Duration::days(1), today];
+        for day in days {
+            let rows = format!("DateTime,Temperature,Humidity\n{},18.0,40\n{},22.0,50\n{},25.0,61\n", format_log_timestamp(local(day, 8, 0)), format_log_timestamp(local(day, 8, 30)), format_log_timestamp(local(day, 9, 0)));
+            fs::write(paths::log_path(day), rows).unwrap();
+        }
+
+        // Today is still being written, so only the finished days are summarised, and only once
+        assert_eq!(refresh(today).unwrap(), 2);
+        assert_eq!(refresh(today).unwrap(), 0);
+        let hourly = load(Resolution::Hour, local(days[0], 0, 0), local(today, 23, 0));
+        assert_eq!(hourly.len(), 4);
+        assert_eq!((hourly[0].temp_min, hourly[0].temp_max, hourly[0].temp_mean, hourly[0].samples), (18.0, 22.0, 20.0, 2));
+        let daily = load(Resolution::Day, local(days[0], 0, 0), local(today, 23, 0));
+        assert_eq!(daily.iter().map(|s| (s.start.date_naive(), s.hum_min, s.hum_max, s.samples)).collect::<Vec<_>>(), vec![(days[0], 40, 61, 3), (days[1], 40, 61, 3)]);
+
+        let mut config = Config { aggregate_plot_hours: 48, ..Default::default() };
+        let hours = |h: f64| (0.0, h * 3600.0);
+        assert_eq!(plots::plot_resolution(&config, hours(24.0)), None);
+        assert_eq!(plots::plot_resolution(&config, hours(24.0 * 30.0)), Some(Resolution::Hour));
+        assert_eq!(plots::plot_resolution(&config, hours(24.0 * 120.0)), Some(Resolution::Day));
+        config.aggregate_plot_hours = 0;
+        assert_eq!(plots::plot_resolution(&config, hours(24.0 * 120.0)), None);
+    }
+}
@@ -0,0 +1,117 @@
+// --- Audible alarm: repeats a sound until acknowledged or the value returns to normal ---
+
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const REPEAT_PAUSE: Duration = Duration::from_secs(2);
+
+#[derive(Default)]
+pub struct SoundAlarm {
+    active: Arc<AtomicBool>,
+}
+
+impl SoundAlarm {
+    pub fn is_active(&self) -> bool { self.active.load(Ordering::Relaxed) }
+
+    /// Starts the repeating alarm; an empty `sound_file` uses the built-in beep.
+    pub fn start(&self, sound_file: &str) {
+        if self.active.swap(true, Ordering::Relaxed) { return; }
+        info!("Sound alarm started.");
+        let active = self.active.clone();
+        let file = (!sound_file.trim().is_empty()).then(|| PathBuf::from(sound_file.trim()));
+        thread::spawn(move || {
+            while active.load(Ordering::Relaxed) {
+                play_once(file.as_deref());
+                thread::sleep(REPEAT_PAUSE);
+            }
+        });
+    }
+
+    pub fn stop(&self) {
+        if self.active.swap(false, Ordering::Relaxed) { info!("Sound alarm stopped."); }
+    }
+}
+
+/// Plays the sound a single time on a background thread (settings "Test" button).
+pub fn play_test(sound_file: &str) {
+    let file = (!sound_file.trim().is_empty()).then(|| PathBuf::from(sound_file.trim()));
+    thread::spawn(move || play_once(file.as_deref()));
+}
+
+/// Quiet short click for the heartbeat indicator; failures are ignored.
+pub fn play_tick() { thread::spawn(|| { let _ = platform::tick(); }); }
+
+fn play_once(file: Option<&Path>) {
+    if let Err(e) = platform::play(file) { warn!("Failed to play alarm sound: {}", e); }
+}
+
+#[cfg(windows)]
+mod platform {
+    use rodio::Source;
+    use std::path::Path;
+    use std::time::Duration;
+
+    pub fn play(file: Option<&Path>) -> Result<(), String> {
+        let (_stream, handle) = rodio::OutputStream::try_default().map_err(|e| e.to_string())?;
+        let sink = rodio::Sink::try_new(&handle).map_err(|e| e.to_string())?;
+        match file {
+            Some(path) => {
+                let reader = std::io::BufReader::new(std::fs::File::open(path).map_err(|e| e.to_string())?);
+                sink.append(rodio::Decoder::new(reader).map_err(|e| e.to_string())?);
+            }
+            None => sink.append(rodio::source::SineWave::new(880.0).take_duration(Duration::from_millis(400)).amplify(0.3)),
+        }
+        sink.sleep_until_end();
+        Ok(())
+    }
+
+    pub fn tick() -> Result<(), String> {
+        let (_stream, handle) = rodio::OutputStream::try_default().map_err(|e| e.to_string())?;
+        let sink = rodio::Sink::try_new(&handle).map_err(|e| e.to_string())?;
+        sink.append(rodio::source::SineWave::new(1500.0).take_duration(Duration::from_millis(25)).amplify(0.05));
+        sink.sleep_until_end();
+        Ok(())
+    }
+}
+
+// Without a bundled audio backend, fall back to the desktop's command-line players
+#[cfg(not(windows))]
+mod platform {
+    use std::path::Path;
+    use std::process::{Command, Stdio};
+
+    #[cfg(target_os = "macos")]
+    const PLAYERS: &[&str] = &["afplay"];
+    #[cfg(target_os = "macos")]
+    const DEFAULT_SOUND: &str = "/System/Library/Sounds/Sosumi.aiff";
+    #[cfg(not(target_os = "macos"))]
+    const PLAYERS: &[&str] = &["paplay", "aplay"];
+    #[cfg(target_os = "macos")]
+    const TICK_SOUND: &str = "/System/Library/Sounds/Tink.aiff";
+    #[cfg(not(target_os = "macos"))]
+    const DEFAULT_SOUND: &str = "/usr/share/sounds/freedesktop/stereo/alarm-clock-elapsed.oga";
+    #[cfg(not(target_os = "macos"))]
+    const TICK_SOUND: &str = "/usr/share/sounds/freedesktop/stereo/audio-volume-change.oga";
+
+    pub fn play(file: Option<&Path>) -> Result<(), String> {
+        let path = file.unwrap_or(Path::new(DEFAULT_SOUND));
+        for player in PLAYERS {
+            if let Ok(status) = Command::new(player).arg(path).stdout(Stdio::null()).stderr(Stdio::null()).status() {
+                if status.success() { return Ok(()); }
+            }
+        }
+        // Last resort: terminal bell
+        eprint!("\x07");
+        Err(format!("no player could play '{}'", path.display()))
+    }
+
+    // No bell fallback here: a beep on every reading would be anything but quiet
+    pub fn tick() -> Result<(), String> {
+        let played = PLAYERS.iter().any(|player| Command::new(player).arg(TICK_SOUND).stdout(Stdio::null()).stderr(Stdio::null()).status().is_ok_and(|s| s.success()));
+        if played { Ok(()) } else { Err("no player available".to_string()) }
+    }
+}
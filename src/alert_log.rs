@@ -0,0 +1,133 @@
+// --- Alert history: every alert appended to `alerts.csv` in the data folder, exportable and importable, shown as plot markers ---
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use log::{error, info};
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::paths;
+use crate::rules::Severity;
+use crate::webhook::{AlertKind, WebhookEvent};
+
+pub const ALERT_LOG_FILE_NAME: &str = "alerts.csv";
+const HEADER: [&str; 6] = ["DateTime", "Event", "Message", "Temperature", "Humidity", "Severity"];
+const TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlertRecord {
+    pub timestamp: DateTime<Local>,
+    /// Webhook event id (`temperature_high`, `sensor_offline`, ...)
+    pub event: String,
+    pub message: String,
+    pub temperature: Option<f32>,
+    pub humidity: Option<u8>,
+    pub severity: Severity,
+}
+
+impl AlertRecord {
+    fn to_row(&self) -> [String; 6] {
+        [self.timestamp.format(TIME_FORMAT).to_string(), self.event.clone(), self.message.clone(), self.temperature.map(|t| format!("{:.1}", t)).unwrap_or_default(), self.humidity.map(|h| h.to_string()).unwrap_or_default(), self.severity.id().to_string()]
+    }
+
+    pub fn is_recovery(&self) -> bool { AlertKind::from_id(&self.event).is_some_and(|k| k.is_recovery()) }
+
+    fn from_row(row: &csv::StringRecord) -> Option<Self> {
+        let naive = NaiveDateTime::parse_from_str(row.get(0)?.trim(), TIME_FORMAT).ok()?;
+        let event = row.get(1)?.to_string();
+        // Rows written before the severity column: recoveries were informational, everything else a warning
+        let fallback = if AlertKind::from_id(&event).is_some_and(|k| k.is_recovery()) { Severity::Info } else { Severity::Warning };
+        let severity = row.get(5).and_then(|v| Severity::ALL.into_iter().find(|s| s.id() == v.trim())).unwrap_or(fallback);
+        Some(Self {
+            timestamp: Local.from_local_datetime(&naive).earliest()?,
+            event,
+            message: row.get(2).unwrap_or_default().to_string(),
+            temperature: row.get(3).and_then(|v| v.trim().parse().ok()),
+            humidity: row.get(4).and_then(|v| v.trim().parse().ok()),
+            severity,
+        })
+    }
+}
+
+impl From<&WebhookEvent> for AlertRecord {
+    fn from(event: &WebhookEvent) -> Self {
+        Self { timestamp: event.timestamp, event: event.kind.id().to_string(), message: event.message.clone(), temperature: event.temperature, humidity: event.humidity, severity: event.severity }
+    }
+}
+
+pub fn alert_log_path() -> PathBuf { paths::data_dir().join(ALERT_LOG_FILE_NAME) }
+
+pub fn append(event: &WebhookEvent) {
+    paths::ensure_dirs();
+    let path = alert_log_path();
+    let result = OpenOptions::new().create(true).append(true).open(&path).map_err(csv::Error::from).and_then(|file| {
+        let is_new = file.metadata().map(|m| m.len() == 0).unwrap_or(true);
+        let mut wtr = csv::WriterBuilder::new().has_headers(false).from_writer(file);
+        if is_new { wtr.write_record(HEADER)?; }
+        wtr.write_record(AlertRecord::from(event).to_row())?;
+        wtr.flush()?;
+        Ok(())
+    });
+    if let Err(e) = result { error!("Failed to append to alert log '{}': {}", path.display(), e); }
+}
+
+fn read(path: &Path) -> Vec<AlertRecord> {
+    let Ok(mut rdr) = csv::ReaderBuilder::new().flexible(true).from_path(path) else { return Vec::new(); };
+    rdr.records().flatten().filter_map(|row| AlertRecord::from_row(&row)).collect()
+}
+
+pub fn load_range(start: DateTime<Local>, end: DateTime<Local>) -> Vec<AlertRecord> {
+    read(&alert_log_path()).into_iter().filter(|r| r.timestamp >= start && r.timestamp <= end).collect()
+}
+
+pub fn write_csv(path: &Path, records: &[AlertRecord]) -> Result<(), csv::Error> {
+    let mut wtr = csv::WriterBuilder::new().from_path(path)?;
+    wtr.write_record(HEADER)?;
+    for record in records { wtr.write_record(record.to_row())?; }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Merges an exported alert history into the local one, skipping entries already present
+/// (same time and event). Returns (added, skipped).
+pub fn import_csv(source: &Path) -> io::Result<(usize, usize)> {
+    let incoming = read(source);
+    if incoming.is_empty() { return Err(io::Error::new(io::ErrorKind::InvalidData, "no alert rows found")); }
+    let path = alert_log_path();
+    let mut records = read(&path);
+    let mut known: HashSet<(NaiveDateTime, String)> = records.iter().map(|r| (r.timestamp.naive_local(), r.event.clone())).collect();
+    let (total, before) = (incoming.len(), records.len());
+    records.extend(incoming.into_iter().filter(|r| known.insert((r.timestamp.naive_local(), r.event.clone()))));
+    let added = records.len() - before;
+    if added == 0 { return Ok((0, total)); }
+    records.sort_by_key(|r| r.timestamp);
+    paths::ensure_dirs();
+    let tmp = path.with_extension("csv.import");
+    write_csv(&tmp, &records).map_err(io::Error::other)?;
+    fs::rename(&tmp, &path)?;
+    info!("Imported {} alerts from '{}' ({} already present).", added, source.display(), total - added);
+    Ok((added, total - added))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+
+    #[test]
+    fn event_log_keeps_severity_and_reads_rows_from_before_the_column() {
+        let _data_dir = TempDataDir::new("event_log");
+        let at = |h| today_at(h, 0);
+        // Two rows in the old five-column layout, then one written by this version
+        fs::write(alert_log_path(), format!("DateTime,Event,Message,Temperature,Humidity\n{},sensor_offline,No data,,\n{},sensor_online,Back,21.0,40\n",
+            at(1).format("%Y-%m-%dT%H:%M:%S"), at(2).format("%Y-%m-%dT%H:%M:%S"))).unwrap();
+        let mut event = WebhookEvent { kind: AlertKind::RuleTriggered, message: "Cellar: Dew point above 12.0 °C".to_string(), device: TARGET.to_string(), device_name: TARGET.to_string(), temperature: Some(14.0), humidity: Some(90), timestamp: at(3), severity: Severity::Critical, channels: Default::default() };
+        append(&event);
+        event.kind = AlertKind::RuleCleared;
+        let events = load_range(at(0), at(4));
+        assert_eq!(events.iter().map(|e| (e.event.as_str(), e.severity, e.is_recovery())).collect::<Vec<_>>(),
+            vec![("sensor_offline", Severity::Warning, false), ("sensor_online", Severity::Info, true), ("rule_triggered", Severity::Critical, false)]);
+        assert!(AlertRecord::from(&event).is_recovery());
+    }
+}
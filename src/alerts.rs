@@ -0,0 +1,41 @@
+// --- Alerts: threshold state, the offline timeout and the channels alerts are dispatched to ---
+
+use chrono::Local;
+use std::sync::mpsc;
+use tokio::sync::mpsc as async_mpsc;
+use std::time::Duration;
+
+use crate::alert_log::{self, AlertRecord};
+use crate::ble::{effective_scan_config, expected_reading_interval_secs};
+use crate::model::{AppMessage, Config};
+use crate::webhook::WebhookEvent;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum TempAlert { Normal, High, Low }
+
+// Warnings are suppressed during scheduled pause windows
+pub(crate) fn temperature_alert(temp: f32, config: &Config, paused: bool) -> TempAlert {
+    if paused { TempAlert::Normal } else if temp > config.temp_warn_high { TempAlert::High } else if temp < config.temp_warn_low { TempAlert::Low } else { TempAlert::Normal }
+}
+
+// The sensor counts as offline after this many expected readings went missing
+pub(crate) fn offline_after(config: &Config) -> Duration {
+    let config = effective_scan_config(config, Local::now());
+    Duration::from_secs(expected_reading_interval_secs(&config) * config.offline_missed_intervals.max(1) as u64)
+}
+
+/// Alert channels that deliver on their own threads.
+#[derive(Clone)]
+pub(crate) struct AlertSenders { pub(crate) webhook: mpsc::Sender<WebhookEvent>, pub(crate) telegram: mpsc::Sender<WebhookEvent>, pub(crate) email: mpsc::Sender<WebhookEvent> }
+
+impl AlertSenders {
+    // Every alert is logged; it goes to the channels its rule picked, where those are set up
+    pub(crate) fn dispatch(&self, event: WebhookEvent, config: &Config, tx: &async_mpsc::UnboundedSender<AppMessage>) {
+        alert_log::append(&event);
+        let _ = tx.send(AppMessage::AlertLogged(AlertRecord::from(&event)));
+        if event.channels.toast { let _ = tx.send(AppMessage::Alert(event.message.clone())); }
+        if config.telegram_enabled && event.channels.telegram { let _ = self.telegram.send(event.clone()); }
+        if config.email_enabled && event.channels.email { let _ = self.email.send(event.clone()); }
+        if config.webhook_enabled && event.channels.webhook { let _ = self.webhook.send(event); }
+    }
+}
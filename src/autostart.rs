@@ -0,0 +1,106 @@
+// --- Start with the system: Run registry key on Windows, XDG autostart entry on Linux, LaunchAgent on macOS ---
+
+use log::info;
+use std::io;
+use std::path::Path;
+
+/// Command-line flag for a launch at login; the window then starts minimized when the user asked for it.
+pub const AUTOSTART_ARG: &str = "--autostart";
+const APP_NAME: &str = "TempMonitor";
+
+/// Registers the current executable to start at login, or removes the registration.
+pub fn set(enabled: bool) -> io::Result<()> {
+    let exe = std::env::current_exe()?;
+    if enabled { platform::register(&exe)?; } else { platform::unregister()?; }
+    info!("Start with the system {} ('{}').", if enabled { "enabled" } else { "disabled" }, exe.display());
+    Ok(())
+}
+
+/// Quoted so paths with spaces survive, e.g. `"C:\Program Files\TempMonitor\temp_monitor.exe" --autostart`.
+pub fn command_line(exe: &Path) -> String { format!("\"{}\" {}", exe.display(), AUTOSTART_ARG) }
+
+#[cfg(windows)]
+mod platform {
+    use std::io;
+    use std::os::windows::process::CommandExt;
+    use std::path::Path;
+    use std::process::Command;
+
+    const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+    // Keeps reg.exe from flashing a console window
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    fn reg(args: &[&str]) -> io::Result<()> {
+        let output = Command::new("reg").args(args).creation_flags(CREATE_NO_WINDOW).output()?;
+        if output.status.success() { Ok(()) } else { Err(io::Error::other(String::from_utf8_lossy(&output.stderr).trim().to_string())) }
+    }
+
+    pub fn register(exe: &Path) -> io::Result<()> { reg(&["add", RUN_KEY, "/v", super::APP_NAME, "/t", "REG_SZ", "/d", &super::command_line(exe), "/f"]) }
+
+    pub fn unregister() -> io::Result<()> {
+        if reg(&["query", RUN_KEY, "/v", super::APP_NAME]).is_err() { return Ok(()); }
+        reg(&["delete", RUN_KEY, "/v", super::APP_NAME, "/f"])
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::fs;
+    use std::io;
+    use std::path::{Path, PathBuf};
+
+    fn plist_path() -> io::Result<PathBuf> {
+        let home = directories::BaseDirs::new().ok_or_else(|| io::Error::other("no home folder"))?;
+        Ok(home.home_dir().join("Library/LaunchAgents/temp_monitor.plist"))
+    }
+
+    pub fn register(exe: &Path) -> io::Result<()> {
+        let path = plist_path()?;
+        if let Some(dir) = path.parent() { fs::create_dir_all(dir)?; }
+        fs::write(path, format!(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n",
+            "<plist version=\"1.0\"><dict><key>Label</key><string>{}</string><key>ProgramArguments</key><array><string>{}</string><string>{}</string></array>",
+            "<key>RunAtLoad</key><true/></dict></plist>\n"), super::APP_NAME, exe.display(), super::AUTOSTART_ARG))
+    }
+
+    pub fn unregister() -> io::Result<()> {
+        let path = plist_path()?;
+        if path.exists() { fs::remove_file(path)?; }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+mod platform {
+    use std::fs;
+    use std::io;
+    use std::path::{Path, PathBuf};
+
+    fn desktop_path() -> io::Result<PathBuf> {
+        let dirs = directories::BaseDirs::new().ok_or_else(|| io::Error::other("no home folder"))?;
+        Ok(dirs.config_dir().join("autostart/temp_monitor.desktop"))
+    }
+
+    pub fn register(exe: &Path) -> io::Result<()> {
+        let path = desktop_path()?;
+        if let Some(dir) = path.parent() { fs::create_dir_all(dir)?; }
+        fs::write(path, format!("[Desktop Entry]\nType=Application\nName={}\nExec={}\nX-GNOME-Autostart-enabled=true\n", super::APP_NAME, super::command_line(exe)))
+    }
+
+    pub fn unregister() -> io::Result<()> {
+        let path = desktop_path()?;
+        if path.exists() { fs::remove_file(path)?; }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn autostart_command_quotes_paths_with_spaces() {
+        let exe = Path::new("C:/Program Files/TempMonitor/temp_monitor.exe");
+        assert_eq!(command_line(exe), format!("\"C:/Program Files/TempMonitor/temp_monitor.exe\" {}", AUTOSTART_ARG));
+    }
+}
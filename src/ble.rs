@@ -0,0 +1,297 @@
+// --- Bluetooth: scan cycles, advertisement decoding into readings and the scan settings in effect ---
+
+use btleplug::api::{BDAddr, Central, CentralEvent, CentralState, Manager as _, Peripheral, PeripheralProperties, ScanFilter};
+use btleplug::platform::Manager;
+use chrono::{DateTime, Local};
+use futures::stream::StreamExt;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc as async_mpsc, watch};
+use std::time::Duration;
+use log::{info, warn, error, debug};
+
+use crate::capture::CaptureRecord;
+use crate::model::{AppMessage, BleDataPoint, Config, ScanControl};
+use crate::scan_stats::{self, AdapterState};
+use crate::sensors::{self, SensorSighting};
+use crate::adaptive::AdaptivePause;
+use crate::{adaptive, capture, decoder, power, schedule, sleep};
+
+// Expected spacing of stored readings, used to tell real gaps from normal sampling
+pub(crate) fn expected_reading_interval_secs(config: &Config) -> u64 {
+    let pause = if config.adaptive_scan { adaptive::bounds(config).1 } else { config.scan_pause_secs };
+    let scan_cycle = if config.continuous_mode { 1 } else { config.scan_timeout_secs + pause };
+    config.duplicate_threshold_secs.max(scan_cycle).max(1)
+}
+
+pub(crate) fn courtesy_active(config: &Config, now: DateTime<Local>) -> bool {
+    config.courtesy_mode || schedule::active_pause(&config.courtesy_windows, now).is_some()
+}
+
+// Courtesy mode and the battery saver set their own pauses, which the adaptive mode leaves alone
+pub(crate) fn adaptive_active(config: &Config, now: DateTime<Local>) -> bool {
+    config.adaptive_scan && !config.continuous_mode && !courtesy_active(config, now) && !power::saver_active()
+}
+
+// Settings the scanner reads at the start of a cycle; changing one restarts the cycle right away
+pub(crate) fn scan_settings_changed(old: &Config, new: &Config) -> bool {
+    let now = Local::now();
+    let (old, new) = (effective_scan_config(old, now), effective_scan_config(new, now));
+    old.target_mac != new.target_mac || old.auto_detect_thermopro != new.auto_detect_thermopro || old.continuous_mode != new.continuous_mode
+        || old.scan_timeout_secs != new.scan_timeout_secs || old.scan_pause_secs != new.scan_pause_secs
+        || old.adaptive_scan != new.adaptive_scan || adaptive::bounds(&old) != adaptive::bounds(&new)
+}
+
+pub(crate) const BATTERY_SCAN_SECS: u64 = 10;
+
+pub(crate) const BATTERY_PAUSE_SECS: u64 = 60;
+
+// Courtesy mode replaces long/continuous scans with short duty-cycled ones, which Bluetooth audio tolerates
+pub(crate) fn effective_scan_config(config: &Config, now: DateTime<Local>) -> Config {
+    let mut effective = config.clone();
+    if courtesy_active(config, now) {
+        effective = Config { continuous_mode: false, scan_timeout_secs: config.courtesy_scan_secs.max(1), scan_pause_secs: config.courtesy_pause_secs.max(1), ..effective };
+    }
+    if power::saver_active() {
+        effective = Config { continuous_mode: false, scan_timeout_secs: effective.scan_timeout_secs.min(BATTERY_SCAN_SECS), scan_pause_secs: effective.scan_pause_secs.max(BATTERY_PAUSE_SECS), ..effective };
+    }
+    effective
+}
+
+// Sensor calibration from Settings, applied before anything is stored or shown
+pub(crate) fn apply_calibration(temp: f32, hum: u8, config: &Config) -> (f32, u8) {
+    let temp = temp + config.temp_offset;
+    let hum = (hum as f32 + config.hum_offset).round().clamp(0.0, 100.0) as u8;
+    (temp, hum)
+}
+
+// The advertisement format is undocumented, so the alarm flag position is configurable and off by default
+pub(crate) fn decode_device_alarm(data: &[u8], config: &Config) -> bool {
+    config.device_alarm_passthrough && data.get(config.device_alarm_byte).is_some_and(|b| b & config.device_alarm_mask != 0)
+}
+
+// Every manufacturer-data entry of a target advertisement, decodable or not, to the capture file and the live view
+fn capture_advertisement(props: &PeripheralProperties, tx: &async_mpsc::UnboundedSender<AppMessage>) {
+    for (company_id, payload) in &props.manufacturer_data {
+        let record = CaptureRecord { timestamp: Local::now(), company_id: *company_id, payload: payload.clone(), rssi: props.rssi };
+        if let Err(e) = capture::append(&capture::capture_path(), &record) { warn!("Could not write the capture file: {}", e); }
+        let _ = tx.send(AppMessage::Captured(record));
+    }
+}
+
+/// How a scan cycle ended.
+#[derive(Default, Debug)]
+pub(crate) struct ScanOutcome {
+    pub(crate) last_reading: Option<(DateTime<Local>, f32, u8)>,
+    /// The radio is off or the adapter stopped answering; the scanner backs off before trying again
+    pub(crate) adapter_lost: bool,
+}
+
+// First retry after losing the adapter; each further failure doubles the wait up to the maximum
+pub(crate) const RETRY_BASE_SECS: u64 = 5;
+pub(crate) const RETRY_MAX_SECS: u64 = 300;
+
+pub(crate) fn retry_delay(failures: u32) -> Duration {
+    Duration::from_secs(RETRY_BASE_SECS.saturating_mul(1 << failures.saturating_sub(1).min(16)).min(RETRY_MAX_SECS))
+}
+
+// A scan wedged by a suspend may not stop either
+const STOP_AFTER_SLEEP: Duration = Duration::from_secs(5);
+
+// One scan cycle on an adapter; generic so tests can drive it with a mock central
+pub(crate) async fn scan_adapter<C: Central>(central: &C, current_config: &Config, tx: &async_mpsc::UnboundedSender<AppMessage>) -> ScanOutcome {
+    let status_msg = if power::saver_active() { "Scanning (battery saver)..." } else if courtesy_active(current_config, Local::now()) { "Scanning (courtesy mode)..." } else if current_config.continuous_mode { "Scanning (continuous mode)..." } else { "Scanning..." };
+    info!("Starting scan on adapter...");
+    let _ = tx.send(AppMessage::StatusUpdate(status_msg.into()));
+    let adapter_info = central.adapter_info().await.ok();
+    let powered_off = matches!(central.adapter_state().await, Ok(CentralState::PoweredOff));
+    scan_stats::update(|stats| { stats.adapter_info = adapter_info; if powered_off { stats.adapter = AdapterState::PoweredOff; } });
+    if powered_off { warn!("Bluetooth is switched off."); return ScanOutcome { adapter_lost: true, ..Default::default() }; }
+    match central.start_scan(ScanFilter::default()).await {
+        Ok(()) => scan_stats::update(|stats| stats.scan_started(Local::now())),
+        Err(e) => { warn!("Could not start the scan: {}", e); scan_stats::record_error(format!("Starting the scan failed: {}", e)); return ScanOutcome { adapter_lost: true, ..Default::default() }; }
+    }
+    let scan_duration = if current_config.continuous_mode { 60 } else { current_config.scan_timeout_secs };
+    let mut outcome = ScanOutcome::default();
+    let _ = tokio::time::timeout(Duration::from_secs(scan_duration), async {
+        // In auto-detect mode the first ThermoPro heard is followed for the rest of this scan
+        let mut detected: Option<BDAddr> = None;
+        // Other ThermoPros are reported once per scan, for the new-sensor prompt
+        let mut reported: HashSet<BDAddr> = HashSet::new();
+        let mut events = match central.events().await {
+            Ok(events) => events,
+            Err(e) => { error!("Could not listen for advertisements: {}", e); scan_stats::record_error(format!("Listening for advertisements failed: {}", e)); outcome.adapter_lost = true; return; }
+        };
+        while let Some(event) = events.next().await {
+            if let CentralEvent::StateUpdate(CentralState::PoweredOff) = event {
+                warn!("Bluetooth was switched off during the scan.");
+                scan_stats::update(|stats| { stats.adapter = AdapterState::PoweredOff; stats.last_error = Some((Local::now(), "Bluetooth was switched off".into())); });
+                outcome.adapter_lost = true;
+                return;
+            }
+            if let CentralEvent::DeviceDiscovered(id) | CentralEvent::DeviceUpdated(id) = event {
+                if let Ok(p) = central.peripheral(&id).await {
+                    if let Ok(Some(props)) = p.properties().await {
+                        let is_target = if current_config.auto_detect_thermopro {
+                            match detected {
+                                Some(address) => address == props.address,
+                                None if decoder::looks_like_thermopro(props.local_name.as_deref(), &props.manufacturer_data) => { info!("Auto-detected ThermoPro sensor at {}.", props.address); detected = Some(props.address); true }
+                                None => false,
+                            }
+                        } else { props.address.to_string().eq_ignore_ascii_case(&current_config.target_mac) };
+                        if is_target && capture::active() { capture_advertisement(&props, tx); }
+                        let decoded = is_target.then(|| decoder::decode(&props, !current_config.auto_detect_thermopro));
+                        scan_stats::update(|stats| stats.record_advertisement(decoded.as_ref().map(Option::is_some)));
+                        if let Some(decoded) = decoded {
+                            info!("Target device found: {}", props.address);
+                            match decoded {
+                                Some(reading) => {
+                                    let (temp, hum) = apply_calibration(reading.temp, reading.hum_percent(), current_config);
+                                    let device_alarm = decode_device_alarm(&reading.payload, current_config);
+                                    if device_alarm { warn!("Device reports its on-board alarm (raw data {:02X?}).", reading.payload); }
+                                    let data_point = BleDataPoint { timestamp: Local::now(), temp, hum, device_id: id.to_string(), model: reading.model, rssi: props.rssi, raw_data: reading.payload, device_alarm, battery: reading.battery, persisted: false };
+                                    info!("Successfully parsed {} data, sending to processor: T={:.1}C, H={}%", data_point.model, temp, hum);
+                                    outcome.last_reading = Some((data_point.timestamp, temp, hum));
+                                    if tx.send(AppMessage::NewData(data_point)).is_err() { break; }
+                                    if !current_config.continuous_mode { return; }
+                                }
+                                None => debug!("Could not decode advertisement from {}: {:02X?}", props.address, props.manufacturer_data),
+                            }
+                        } else if decoder::looks_like_thermopro(props.local_name.as_deref(), &props.manufacturer_data) && !sensors::is_known(current_config, &props.address.to_string()) && reported.insert(props.address) {
+                            let model = decoder::decode(&props, false).map(|r| r.model).unwrap_or("ThermoPro");
+                            debug!("Unconfigured {} seen at {}.", model, props.address);
+                            let _ = tx.send(AppMessage::SensorSeen(SensorSighting { address: props.address.to_string(), model }));
+                        }
+                    }
+                }
+            }
+        }
+    }).await;
+    info!("Scanning finished (timeout).");
+    // A switched-off radio fails the stop too; keep the clearer error from above
+    if outcome.adapter_lost { scan_stats::update(|stats| stats.scan_stopped(Local::now())); } else { stop_scan(central).await; }
+    outcome
+}
+
+pub(crate) async fn stop_scan<C: Central>(central: &C) {
+    if let Err(e) = central.stop_scan().await { scan_stats::record_error(format!("Stopping the scan failed: {}", e)); }
+    scan_stats::update(|stats| stats.scan_stopped(Local::now()));
+}
+
+// Resolves on a pause request, receiving each control message once; anything else is skipped and a closed channel never resolves
+pub(crate) async fn pause_requested(control: &mut async_mpsc::UnboundedReceiver<AppMessage>) {
+    loop {
+        match control.recv().await {
+            Some(AppMessage::Control(ScanControl::Pause)) => return,
+            Some(_) => debug!("Ignoring a control message, the scanner is not paused."),
+            None => std::future::pending::<()>().await,
+        }
+    }
+}
+
+// Blocks while paused; returns false when the app shuts down instead
+pub(crate) async fn wait_for_resume(control: &mut async_mpsc::UnboundedReceiver<AppMessage>, tx: &async_mpsc::UnboundedSender<AppMessage>, shutdown: &mut watch::Receiver<bool>) -> bool {
+    info!("Scanning paused.");
+    scan_stats::set_adapter(AdapterState::Paused);
+    let _ = tx.send(AppMessage::Control(ScanControl::Pause));
+    let _ = tx.send(AppMessage::StatusUpdate("Paused".into()));
+    loop {
+        tokio::select! {
+            message = control.recv() => match message {
+                Some(AppMessage::Control(ScanControl::Resume)) => break,
+                Some(_) => {}
+                None => return false,
+            },
+            _ = shutdown.changed() => return false,
+        }
+    }
+    info!("Scanning resumed.");
+    scan_stats::set_adapter(AdapterState::Idle);
+    let _ = tx.send(AppMessage::Control(ScanControl::Resume));
+    true
+}
+
+/// `scan_settings` is signalled by the GUI when scan-related settings change, cutting the current scan or pause short.
+pub(crate) async fn bluetooth_scanner(tx: async_mpsc::UnboundedSender<AppMessage>, shared_config: Arc<Mutex<Config>>, mut control: async_mpsc::UnboundedReceiver<AppMessage>, mut scan_settings: watch::Receiver<()>, mut shutdown: watch::Receiver<bool>) {
+    info!("Starting main Bluetooth scanner loop.");
+    // After a suspend the adapter's scan often hangs; the watcher cuts it short so the next cycle starts afresh
+    let (woke_tx, mut woke) = watch::channel(());
+    tokio::spawn(sleep::watch_for_sleep(tx.clone(), woke_tx, shutdown.clone()));
+    let mut paused = false;
+    let mut adaptive = AdaptivePause::default();
+    // Consecutive cycles without a usable adapter, for the backoff
+    let mut failures: u32 = 0;
+    while !*shutdown.borrow() {
+        if std::mem::take(&mut paused) && !wait_for_resume(&mut control, &tx, &mut shutdown).await { break; }
+        let current_config = { if let Ok(config) = shared_config.lock() { effective_scan_config(&config, Local::now()) } else { Config::default() } };
+        if current_config.auto_detect_thermopro { debug!("New scanner iteration, auto-detecting ThermoPro."); } else { debug!("New scanner iteration, MAC: {}", current_config.target_mac); }
+        let manager = Manager::new().await.map_err(|e| {
+            error!("Error initializing BT manager: {}", e);
+            scan_stats::update(|stats| { stats.adapter = AdapterState::Missing; stats.last_error = Some((Local::now(), format!("Bluetooth manager: {}", e))); });
+        });
+        let adapters = match &manager {
+            Ok(manager) => manager.adapters().await.unwrap_or_else(|e| { scan_stats::record_error(format!("Listing adapters failed: {}", e)); Vec::new() }),
+            Err(()) => Vec::new(),
+        };
+        if manager.is_ok() && adapters.is_empty() { warn!("No Bluetooth adapter found."); scan_stats::set_adapter(AdapterState::Missing); }
+        let adapter_lost = match adapters.into_iter().next() {
+            Some(central) => tokio::select! {
+                outcome = scan_adapter(&central, &current_config, &tx) => { if let Some((at, temp, hum)) = outcome.last_reading { adaptive.record(at, temp, hum); } outcome.adapter_lost }
+                () = pause_requested(&mut control) => { stop_scan(&central).await; paused = true; continue; }
+                Ok(()) = scan_settings.changed() => { info!("Scan settings changed, restarting scan."); stop_scan(&central).await; continue; }
+                Ok(()) = woke.changed() => { let _ = tokio::time::timeout(STOP_AFTER_SLEEP, stop_scan(&central)).await; continue; }
+                _ = shutdown.changed() => { stop_scan(&central).await; break; }
+            },
+            None => true,
+        };
+        if adapter_lost {
+            failures += 1;
+            let retry = retry_delay(failures);
+            warn!("Bluetooth unavailable ({} failed attempts), retrying in {} s.", failures, retry.as_secs());
+            let state = scan_stats::snapshot().adapter;
+            scan_stats::update(|stats| { stats.failures = failures; stats.retry_at = chrono::Duration::from_std(retry).ok().map(|d| Local::now() + d); });
+            let _ = tx.send(AppMessage::StatusUpdate(if state == AdapterState::PoweredOff { "Bluetooth is off" } else { "Error: BT adapter not found" }.into()));
+            tokio::select! {
+                _ = tokio::time::sleep(retry) => {}
+                () = pause_requested(&mut control) => paused = true,
+                Ok(()) = scan_settings.changed() => info!("Retrying Bluetooth now."),
+                Ok(()) = woke.changed() => failures = 0,
+                _ = shutdown.changed() => break,
+            }
+            continue;
+        }
+        if std::mem::take(&mut failures) > 0 {
+            info!("Bluetooth adapter is back.");
+            scan_stats::update(|stats| { stats.failures = 0; stats.retry_at = None; });
+        }
+        let _ = tx.send(AppMessage::StatusUpdate("Waiting...".into()));
+        let pause_duration = if current_config.continuous_mode { 1 }
+            else if adaptive_active(&current_config, Local::now()) { debug!("Adaptive pace: {}.", adaptive.pace(&current_config).label()); adaptive.pause_secs(&current_config) }
+            else { current_config.scan_pause_secs };
+        debug!("Sleeping for {} seconds.", pause_duration);
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_secs(pause_duration)) => {}
+            () = pause_requested(&mut control) => paused = true,
+            Ok(()) = scan_settings.changed() => info!("Scan settings changed, starting the next scan now."),
+            Ok(()) = woke.changed() => {}
+            _ = shutdown.changed() => break,
+        }
+    }
+    info!("Bluetooth scanner stopped.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_pause_request_behind_other_control_messages_is_not_lost() {
+        let (tx, mut rx) = async_mpsc::unbounded_channel();
+        for control in [ScanControl::Resume, ScanControl::Pause] { tx.send(AppMessage::Control(control)).unwrap(); }
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        assert!(rt.block_on(async { tokio::time::timeout(Duration::from_secs(1), pause_requested(&mut rx)).await }).is_ok());
+        // A closed channel leaves the other branches of the `select!` to decide
+        drop(tx);
+        assert!(rt.block_on(async { tokio::time::timeout(Duration::from_millis(50), pause_requested(&mut rx)).await }).is_err());
+    }
+}
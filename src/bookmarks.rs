@@ -0,0 +1,36 @@
+// --- Saved plot views ("bookmarks"), persisted next to the configuration ---
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::paths;
+
+const BOOKMARKS_FILE_NAME: &str = "bookmarks.json";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct PlotBookmark {
+    pub name: String,
+    /// Visible time range, Unix seconds
+    pub x_range: (f64, f64),
+    /// Locked y ranges; `None` lets the axis auto-fit the data
+    pub temp_y: Option<(f64, f64)>,
+    pub hum_y: Option<(f64, f64)>,
+    pub smoothing: bool,
+    pub thresholds: bool,
+}
+
+fn bookmarks_path() -> PathBuf { paths::config_dir().join(BOOKMARKS_FILE_NAME) }
+
+pub fn load_bookmarks() -> Vec<PlotBookmark> {
+    fs::read_to_string(bookmarks_path()).ok().and_then(|c| serde_json::from_str(&c).ok()).unwrap_or_default()
+}
+
+pub fn save_bookmarks(bookmarks: &[PlotBookmark]) {
+    paths::ensure_dirs();
+    match serde_json::to_string_pretty(bookmarks).map_err(|e| e.to_string()).and_then(|c| fs::write(bookmarks_path(), c).map_err(|e| e.to_string())) {
+        Ok(()) => info!("Saved {} plot bookmarks.", bookmarks.len()),
+        Err(e) => warn!("Failed to save plot bookmarks: {}", e),
+    }
+}
@@ -0,0 +1,63 @@
+// --- First day of week and date format used by aggregate views, reports and shared snapshots ---
+
+use chrono::{DateTime, Local, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::schedule::WEEKDAY_NAMES;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Sunday,
+}
+
+impl WeekStart {
+    pub const ALL: [WeekStart; 2] = [WeekStart::Monday, WeekStart::Sunday];
+
+    pub fn label(&self) -> &'static str { match self { WeekStart::Monday => "Monday", WeekStart::Sunday => "Sunday" } }
+
+    pub fn weekday(&self) -> Weekday { match self { WeekStart::Monday => Weekday::Mon, WeekStart::Sunday => Weekday::Sun } }
+
+    pub fn start_of_week(&self, date: NaiveDate) -> NaiveDate { date.week(self.weekday()).first_day() }
+
+    /// Indices into `WEEKDAY_NAMES` (0 = Monday) in display order.
+    pub fn day_order(&self) -> [usize; 7] {
+        let first = self.weekday().num_days_from_monday() as usize;
+        std::array::from_fn(|i| (first + i) % WEEKDAY_NAMES.len())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum DateFormat {
+    /// 2024-05-31
+    #[default]
+    Iso,
+    /// 31.05.2024
+    DayMonthYear,
+    /// 05/31/2024
+    MonthDayYear,
+}
+
+impl DateFormat {
+    pub const ALL: [DateFormat; 3] = [DateFormat::Iso, DateFormat::DayMonthYear, DateFormat::MonthDayYear];
+
+    pub fn label(&self) -> &'static str {
+        match self { DateFormat::Iso => "2024-05-31", DateFormat::DayMonthYear => "31.05.2024", DateFormat::MonthDayYear => "05/31/2024" }
+    }
+
+    fn pattern(&self) -> &'static str { match self { DateFormat::Iso => "%Y-%m-%d", DateFormat::DayMonthYear => "%d.%m.%Y", DateFormat::MonthDayYear => "%m/%d/%Y" } }
+
+    /// Without the year, for axis labels and compact ranges
+    fn short_pattern(&self) -> &'static str { match self { DateFormat::Iso => "%m-%d", DateFormat::DayMonthYear => "%d.%m.", DateFormat::MonthDayYear => "%m/%d" } }
+
+    pub fn date(&self, date: NaiveDate) -> String { date.format(self.pattern()).to_string() }
+
+    pub fn short_date(&self, date: NaiveDate) -> String { date.format(self.short_pattern()).to_string() }
+
+    pub fn date_time(&self, time: DateTime<Local>) -> String { format!("{} {}", self.date(time.date_naive()), time.format("%H:%M")) }
+
+    pub fn date_time_secs(&self, time: DateTime<Local>) -> String { format!("{} {}", self.date(time.date_naive()), time.format("%H:%M:%S")) }
+
+    pub fn short_date_time(&self, time: DateTime<Local>) -> String { format!("{} {}", self.short_date(time.date_naive()), time.format("%H:%M")) }
+}
@@ -0,0 +1,90 @@
+// --- Raw advertisement capture: every manufacturer-data packet of the target, for decoding the unknown payload bytes ---
+
+use chrono::{DateTime, Local, SecondsFormat};
+use std::collections::VecDeque;
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::paths;
+
+pub const CAPTURE_FILE_NAME: &str = "capture.csv";
+const HEADER: [&str; 4] = ["DateTime", "CompanyId", "Payload", "RSSI"];
+/// Packets kept for the live view; the file keeps all of them.
+pub const VIEW_ROWS: usize = 200;
+
+// Off on every start: capturing writes a row per advertisement, several a second in continuous mode
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+pub fn active() -> bool { ACTIVE.load(Ordering::Relaxed) }
+
+pub fn set_active(on: bool) { ACTIVE.store(on, Ordering::Relaxed); }
+
+pub fn capture_path() -> PathBuf { paths::data_dir().join(CAPTURE_FILE_NAME) }
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CaptureRecord {
+    pub timestamp: DateTime<Local>,
+    pub company_id: u16,
+    /// Manufacturer data after the company id
+    pub payload: Vec<u8>,
+    pub rssi: Option<i16>,
+}
+
+impl CaptureRecord {
+    /// The packet as it was on air: the company id's two bytes (little endian) and then the payload.
+    /// ThermoPro spreads the temperature across both, so the view shows them together.
+    pub fn bytes(&self) -> Vec<u8> { self.company_id.to_le_bytes().iter().chain(&self.payload).copied().collect() }
+
+    fn to_row(&self) -> [String; 4] {
+        [self.timestamp.to_rfc3339_opts(SecondsFormat::Millis, false), format!("0x{:04X}", self.company_id), hex(&self.payload), self.rssi.map(|r| r.to_string()).unwrap_or_default()]
+    }
+
+    fn from_row(row: &csv::StringRecord) -> Option<Self> {
+        let payload = row.get(2)?;
+        Some(Self {
+            timestamp: DateTime::parse_from_rfc3339(row.get(0)?).ok()?.with_timezone(&Local),
+            company_id: u16::from_str_radix(row.get(1)?.trim_start_matches("0x"), 16).ok()?,
+            payload: (0..payload.len()).step_by(2).map(|i| u8::from_str_radix(payload.get(i..i + 2)?, 16).ok()).collect::<Option<_>>()?,
+            rssi: row.get(3).and_then(|r| r.parse().ok()),
+        })
+    }
+}
+
+pub fn hex(bytes: &[u8]) -> String { bytes.iter().map(|b| format!("{:02X}", b)).collect() }
+
+/// Which bytes of `current` differ from `previous`; bytes past the end of `previous` count as changed.
+pub fn changed(previous: &[u8], current: &[u8]) -> Vec<bool> { current.iter().enumerate().map(|(i, b)| previous.get(i) != Some(b)).collect() }
+
+pub fn append(path: &Path, record: &CaptureRecord) -> Result<(), csv::Error> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let is_new = file.metadata().map(|m| m.len() == 0).unwrap_or(true);
+    let mut wtr = csv::WriterBuilder::new().has_headers(false).from_writer(file);
+    if is_new { wtr.write_record(HEADER)?; }
+    wtr.write_record(record.to_row())?;
+    wtr.flush()?;
+    Ok(())
+}
+
+pub fn read(path: &Path) -> Vec<CaptureRecord> {
+    let Ok(mut rdr) = csv::ReaderBuilder::new().flexible(true).from_path(path) else { return Vec::new(); };
+    rdr.records().flatten().filter_map(|row| CaptureRecord::from_row(&row)).collect()
+}
+
+/// Newest packets for the live view.
+#[derive(Default)]
+pub struct CaptureView { pub records: VecDeque<CaptureRecord> }
+
+impl CaptureView {
+    pub fn push(&mut self, record: CaptureRecord) {
+        if self.records.len() >= VIEW_ROWS { self.records.pop_front(); }
+        self.records.push_back(record);
+    }
+
+    /// An empty view starts with the newest packets of an earlier capture.
+    pub fn resume(&mut self, earlier: Vec<CaptureRecord>) {
+        if !self.records.is_empty() { return; }
+        let skip = earlier.len().saturating_sub(VIEW_ROWS);
+        self.records.extend(earlier.into_iter().skip(skip));
+    }
+}
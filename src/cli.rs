@@ -0,0 +1,114 @@
+// --- Command-line options, e.g. for running one instance per sensor with its own config and data folder ---
+
+use std::path::PathBuf;
+
+use crate::autostart::AUTOSTART_ARG;
+use crate::model::Config;
+use crate::{replay, simulator};
+
+pub const USAGE: &str = "\
+Usage: temp_monitor [OPTIONS]
+
+Options:
+  --config <path>     Configuration file to use instead of config.json in the storage folder
+  --data-dir <path>   Folder for logs and other data (overrides the folder chosen in the app)
+  --mac <address>     Sensor to read, overriding the configured address and auto-detection
+  --minimized         Start with the window minimized
+  --headless          Scan and log without a window until Ctrl+C
+  --replay <file>     Feed the readings of a log or CSV export instead of scanning (into a scratch
+                      data folder unless --data-dir is given)
+  --speed <N>         Replay N times faster than recorded, 0 for no waits (default 1)
+  --simulate          Generate readings (sine wave with noise) instead of scanning, into a scratch
+                      data folder unless --data-dir is given
+  -h, --help          Show this help";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Args {
+    pub config: Option<PathBuf>,
+    pub data_dir: Option<PathBuf>,
+    pub mac: Option<String>,
+    pub minimized: bool,
+    pub headless: bool,
+    pub replay: Option<PathBuf>,
+    pub speed: Option<f64>,
+    pub simulate: bool,
+    /// Launched by the start-with-system entry
+    pub autostart: bool,
+    pub help: bool,
+}
+
+/// Parses the arguments after the program name; accepts both `--name value` and `--name=value`.
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let (name, inline) = match arg.split_once('=') { Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value.to_string())), _ => (arg, None) };
+        let mut value = || inline.clone().or_else(|| args.next()).filter(|v| !v.is_empty()).ok_or_else(|| format!("{} needs a value", name));
+        match name.as_str() {
+            "--config" => parsed.config = Some(PathBuf::from(value()?)),
+            "--data-dir" => parsed.data_dir = Some(PathBuf::from(value()?)),
+            "--mac" => {
+                let mac = value()?;
+                if btleplug::api::BDAddr::from_str_delim(&mac).is_err() { return Err(format!("'{}' is not a Bluetooth address like B8:59:CE:33:0F:93", mac)); }
+                parsed.mac = Some(mac.to_uppercase());
+            }
+            "--minimized" => parsed.minimized = true,
+            "--headless" => parsed.headless = true,
+            "--replay" => parsed.replay = Some(PathBuf::from(value()?)),
+            "--simulate" => parsed.simulate = true,
+            "--speed" => {
+                let speed = value()?;
+                parsed.speed = Some(speed.parse().ok().filter(|s: &f64| *s >= 0.0 && s.is_finite()).ok_or_else(|| format!("'{}' is not a replay speed like 60", speed))?);
+            }
+            "-h" | "--help" => parsed.help = true,
+            flag if flag == AUTOSTART_ARG => parsed.autostart = true,
+            other => return Err(format!("Unknown option '{}'", other)),
+        }
+    }
+    if parsed.speed.is_some() && parsed.replay.is_none() { return Err("--speed needs --replay".into()); }
+    if parsed.simulate && parsed.replay.is_some() { return Err("--simulate and --replay cannot be combined".into()); }
+    Ok(parsed)
+}
+
+impl Args {
+    /// `--mac` picks the sensor for this run only; it is saved like any other change if settings are edited later.
+    pub fn apply_to(&self, config: &mut Config) {
+        if let Some(mac) = &self.mac { config.target_mac = mac.clone(); config.auto_detect_thermopro = false; }
+    }
+
+    pub fn replay_speed(&self) -> f64 { self.speed.unwrap_or(1.0) }
+
+    /// Where the data folder comes from for this run: `--data-dir`, or the scratch folder of a replay or simulation.
+    pub fn data_dir_override(&self) -> Option<PathBuf> {
+        self.data_dir.clone().or_else(|| self.replay.as_ref().map(|_| replay::scratch_dir())).or_else(|| self.simulate.then(simulator::scratch_dir))
+    }
+
+    /// Minimized on request, or when started at login with the matching setting.
+    pub fn start_minimized(&self, config: &Config) -> bool { self.minimized || (self.autostart && config.autostart && config.autostart_minimized) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_line_selects_config_sensor_and_window_state() {
+        let args = |list: &[&str]| parse(list.iter().map(|a| a.to_string()));
+        let parsed = args(&["--config", "/srv/cabin.json", "--data-dir=/srv/cabin", "--mac", "11:22:33:44:55:aa", "--minimized", "--headless"]).unwrap();
+        assert_eq!(parsed.config, Some(PathBuf::from("/srv/cabin.json")));
+        assert_eq!(parsed.data_dir, Some(PathBuf::from("/srv/cabin")));
+        assert!(parsed.minimized && parsed.headless && !parsed.autostart);
+        let mut config = Config { auto_detect_thermopro: true, ..Default::default() };
+        parsed.apply_to(&mut config);
+        assert_eq!(config.target_mac, "11:22:33:44:55:AA");
+        assert!(!config.auto_detect_thermopro);
+        // The start-with-system flag only minimizes when the setting asks for it
+        let login = args(&["--autostart"]).unwrap();
+        assert!(login.start_minimized(&Config { autostart: true, autostart_minimized: true, ..Default::default() }));
+        assert!(!login.start_minimized(&Config { autostart: true, autostart_minimized: false, ..Default::default() }));
+        assert!(args(&["--mac", "not-a-mac"]).is_err());
+        assert!(args(&["--config"]).is_err());
+        assert!(args(&["--verbose"]).is_err());
+        assert!(args(&["-h"]).unwrap().help);
+    }
+}
@@ -0,0 +1,81 @@
+// --- Mold risk (VTT critical humidity) and an ASHRAE-style comfort zone from temperature and humidity ---
+
+use crate::model::dew_point;
+
+/// Relative humidity above which mold can grow on wood at `temp` (Hukka & Viitanen, VTT model).
+pub fn critical_rh(temp: f32) -> f32 {
+    if temp > 20.0 { 80.0 } else { (-0.00267 * temp.powi(3) + 0.160 * temp.powi(2) - 3.13 * temp + 100.0).min(100.0) }
+}
+
+/// Humidity above the critical humidity in percentage points; negative is the headroom left.
+/// Mold does not grow below freezing, so those readings count as far below the limit.
+pub fn mold_index(temp: f32, hum: u8) -> f32 {
+    if temp <= 0.0 { return -100.0; }
+    hum as f32 - critical_rh(temp)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum MoldRisk { Low, Elevated, High }
+
+impl MoldRisk {
+    /// High at or above the critical humidity, elevated within 10 points of it.
+    pub fn assess(temp: f32, hum: u8) -> MoldRisk {
+        match mold_index(temp, hum) { i if i >= 0.0 => MoldRisk::High, i if i >= -10.0 => MoldRisk::Elevated, _ => MoldRisk::Low }
+    }
+
+    pub fn label(&self) -> &'static str { match self { MoldRisk::Low => "Mold risk low", MoldRisk::Elevated => "Mold risk elevated", MoldRisk::High => "Mold risk high" } }
+}
+
+// Simplified ASHRAE 55 zone for still air and indoor clothing; the dew point stands in for the humidity-ratio limit
+pub const COMFORT_TEMP: (f32, f32) = (20.0, 26.0);
+pub const COMFORT_RH: (u8, u8) = (30, 60);
+pub const COMFORT_MAX_DEW_POINT: f32 = 16.8;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Comfort { Comfortable, TooCold, TooWarm, TooDry, TooHumid }
+
+impl Comfort {
+    /// Temperature is checked first: a cold room reads as cold even when it is also damp.
+    pub fn assess(temp: f32, hum: u8) -> Comfort {
+        if temp < COMFORT_TEMP.0 { Comfort::TooCold }
+        else if temp > COMFORT_TEMP.1 { Comfort::TooWarm }
+        else if hum > COMFORT_RH.1 || dew_point(temp, hum) > COMFORT_MAX_DEW_POINT { Comfort::TooHumid }
+        else if hum < COMFORT_RH.0 { Comfort::TooDry }
+        else { Comfort::Comfortable }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self { Comfort::Comfortable => "Comfortable", Comfort::TooCold => "Too cold", Comfort::TooWarm => "Too warm", Comfort::TooDry => "Too dry", Comfort::TooHumid => "Too humid" }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+
+    #[test]
+    fn mold_risk_follows_the_critical_humidity_curve_and_comfort_checks_temperature_first() {
+        use crate::model::Config;
+        use crate::rules::{AlertRule, Metric};
+        use crate::webhook::{AlertKind, AlertTracker};
+        assert_eq!(critical_rh(25.0), 80.0);
+        assert!((critical_rh(10.0) - 82.03).abs() < 0.01 && (critical_rh(5.0) - 88.02).abs() < 0.01);
+        // A damp cellar: 85 % is over the limit at 10 °C but 75 % is not
+        assert_eq!([MoldRisk::assess(10.0, 85), MoldRisk::assess(10.0, 75), MoldRisk::assess(10.0, 60), MoldRisk::assess(-2.0, 95)], [MoldRisk::High, MoldRisk::Elevated, MoldRisk::Low, MoldRisk::Low]);
+        assert_eq!(Comfort::assess(22.0, 45), Comfort::Comfortable);
+        assert_eq!(Comfort::assess(18.0, 80), Comfort::TooCold);
+        assert_eq!(Comfort::assess(27.0, 40), Comfort::TooWarm);
+        assert_eq!(Comfort::assess(22.0, 25), Comfort::TooDry);
+        // Within 60 % but the dew point is above 16.8 °C
+        assert_eq!(Comfort::assess(26.0, 58), Comfort::TooHumid);
+
+        let cellar = |hum| reading(chrono::Local::now(), 10.0, hum);
+        let config = Config { alert_rules: vec![AlertRule { metric: Metric::MoldRisk, threshold: 0.0, ..AlertRule::default() }], ..test_config() };
+        let mut tracker = AlertTracker::default();
+        assert!(tracker.on_reading(&cellar(75), &config, false).is_empty());
+        let fired = tracker.on_reading(&cellar(85), &config, false);
+        assert_eq!(fired.iter().map(|e| e.kind).collect::<Vec<_>>(), vec![AlertKind::RuleTriggered]);
+        assert_eq!(fired[0].message, "Mold risk (RH over critical) above 0 %: 3 %");
+    }
+}
@@ -0,0 +1,94 @@
+// --- Keyboard shortcuts and the Ctrl+P command palette: toolbar and menu actions reachable by name or key ---
+
+use egui::{Key, KeyboardShortcut, Modifiers};
+
+// Struct literal rather than `KeyboardShortcut::new`, so the arrays below can be promoted to statics
+macro_rules! shortcut { ($modifiers:expr, $key:expr) => { KeyboardShortcut { modifiers: $modifiers, logical_key: $key } }; }
+
+/// Opens the command palette; handled apart from the commands since it is not one itself.
+pub const PALETTE_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::P);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Command {
+    Settings, ZoomIn, ZoomOut, ResetView, ToggleScanning, ExportCsv, ExportExcel, SavePlotImage, CourtesyMode, Note,
+    PlotsView, DataView, CalendarView, DistributionView, CombinedPlot, Events, Diagnostics, SelfTest, ColdChainReport, SimpleMode, Kiosk, Quit,
+}
+
+impl Command {
+    pub const ALL: [Command; 22] = [
+        Command::Settings, Command::ZoomIn, Command::ZoomOut, Command::ResetView, Command::ToggleScanning, Command::ExportCsv, Command::ExportExcel, Command::SavePlotImage, Command::CourtesyMode, Command::Note,
+        Command::PlotsView, Command::DataView, Command::CalendarView, Command::DistributionView, Command::CombinedPlot, Command::Events, Command::Diagnostics, Command::SelfTest, Command::ColdChainReport, Command::SimpleMode, Command::Kiosk, Command::Quit,
+    ];
+
+    /// The label of the matching menu item or toolbar button, so the palette reads like the menus.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Command::Settings => "Settings", Command::ZoomIn => "Zoom in", Command::ZoomOut => "Zoom out", Command::ResetView => "Center plot",
+            Command::ToggleScanning => "Pause / resume scanning", Command::ExportCsv => "Export visible range (CSV)...", Command::ExportExcel => "Export visible range (Excel)...",
+            Command::SavePlotImage => "Save plot as image...", Command::CourtesyMode => "🎧 Courtesy", Command::Note => "📝 Note", Command::PlotsView => "📈 Plots", Command::DataView => "📋 Data", Command::CalendarView => "📅 Calendar",
+            Command::DistributionView => "📊 Distribution", Command::CombinedPlot => "Combined plot (dual axes)", Command::Events => "Events...", Command::Diagnostics => "Diagnostics...",
+            Command::SelfTest => "Self-test...", Command::ColdChainReport => "Cold-chain report...", Command::SimpleMode => "Simple mode",
+            Command::Kiosk => "Kiosk mode (fullscreen)", Command::Quit => "Quit",
+        }
+    }
+
+    /// Keys that run the command; the first one is shown in the palette. Zoom in also takes "=" for keyboards where "+" needs Shift.
+    pub fn shortcuts(&self) -> &'static [KeyboardShortcut] {
+        match self {
+            Command::Settings => &[shortcut!(Modifiers::COMMAND, Key::Comma)],
+            Command::ZoomIn => &[shortcut!(Modifiers::NONE, Key::Plus), shortcut!(Modifiers::NONE, Key::Equals)],
+            Command::ZoomOut => &[shortcut!(Modifiers::NONE, Key::Minus)],
+            Command::ResetView => &[shortcut!(Modifiers::NONE, Key::Home)],
+            Command::ToggleScanning => &[shortcut!(Modifiers::NONE, Key::Space)],
+            Command::ExportCsv => &[shortcut!(Modifiers::COMMAND, Key::E)],
+            Command::CourtesyMode => &[shortcut!(Modifiers::COMMAND, Key::B)],
+            Command::Kiosk => &[shortcut!(Modifiers::NONE, Key::F11)],
+            _ => &[],
+        }
+    }
+
+    /// Whether the command is offered in simple mode, which hides settings and scanner control.
+    pub fn in_simple_mode(&self) -> bool {
+        matches!(self, Command::ZoomIn | Command::ZoomOut | Command::ResetView | Command::ExportCsv | Command::ExportExcel | Command::SavePlotImage | Command::Kiosk | Command::Quit)
+    }
+}
+
+/// Commands whose label contains every word of `query`, ignoring case, in menu order; `label` gives the shown (translated) text.
+pub fn matching(query: &str, simple: bool, label: impl Fn(Command) -> String) -> Vec<Command> {
+    let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    Command::ALL.into_iter().filter(|c| !simple || c.in_simple_mode())
+        .filter(|c| { let text = label(*c).to_lowercase(); words.iter().all(|w| text.contains(w.as_str())) }).collect()
+}
+
+/// The open palette: what was typed and which of the matches Enter runs.
+#[derive(Default)]
+pub struct CommandPalette { pub query: String, pub selected: usize }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_palette_filters_by_words_and_shortcuts_do_not_clash() {
+        use crate::i18n::{self, Language};
+        use crate::model::Config;
+        let label = |c: Command| c.label().to_string();
+        assert_eq!(matching("export", false, label), vec![Command::ExportCsv, Command::ExportExcel]);
+        assert_eq!(matching("ZOOM   out", false, label), vec![Command::ZoomOut]);
+        assert_eq!(matching("", false, label).len(), Command::ALL.len());
+        // Simple mode offers neither settings nor scanner control
+        assert!(matching("", true, label).iter().all(|c| !matches!(c, Command::Settings | Command::ToggleScanning)));
+        // Matched against the shown text, so Czech users can type Czech
+        assert_eq!(matching("nastav", false, |c| i18n::translate(Language::Czech, c.label()).to_string()), vec![Command::Settings]);
+
+        let quick_export = crate::quick_export::parse_shortcut(&Config::default().quick_export_shortcut).unwrap();
+        let mut seen = vec![PALETTE_SHORTCUT, quick_export];
+        for command in Command::ALL {
+            assert!(i18n::CZECH.iter().any(|(english, _)| *english == command.label()), "no Czech text for {:?}", command);
+            for shortcut in command.shortcuts() {
+                assert!(!seen.contains(shortcut), "{:?} reuses a shortcut", command);
+                seen.push(*shortcut);
+            }
+        }
+    }
+}
@@ -0,0 +1,146 @@
+// --- "Data" view: one day of readings as a sortable, filterable, paged table ---
+
+use chrono::{Duration as ChronoDuration, Local, NaiveDate, TimeZone};
+use egui_extras::{Column, TableBuilder};
+use std::collections::BTreeSet;
+
+use crate::calendar::DateFormat;
+use crate::model::HistoryPoint;
+use crate::storage::load_history_range;
+use crate::units::Channel;
+
+pub enum TableAction {
+    /// Tab-separated selected rows for the clipboard
+    Copy(String),
+    Edit(Vec<HistoryPoint>),
+}
+
+const PAGE_SIZE: usize = 100;
+const COLUMNS: [Option<Channel>; 4] = [None, Some(Channel::Temperature), Some(Channel::Humidity), Some(Channel::Rssi)];
+
+pub struct DataTable {
+    pub day: NaiveDate,
+    rows: Vec<HistoryPoint>,
+    loaded: Option<NaiveDate>,
+    stale: bool,
+    filter: String,
+    /// Index into `COLUMNS`; timestamp is column 0
+    sort_column: usize,
+    ascending: bool,
+    page: usize,
+    /// Selected rows by timestamp, so the selection survives sorting and filtering
+    selected: BTreeSet<i64>,
+}
+
+impl Default for DataTable {
+    fn default() -> Self {
+        Self { day: Local::now().date_naive(), rows: Vec::new(), loaded: None, stale: false, filter: String::new(), sort_column: 0, ascending: true, page: 0, selected: BTreeSet::new() }
+    }
+}
+
+fn cell_text(point: &HistoryPoint, column: Option<Channel>, date_format: DateFormat) -> String {
+    match column {
+        None => date_format.date_time_secs(point.timestamp),
+        Some(channel) => channel.value(point).map(|v| channel.format_number(v)).unwrap_or_else(|| "–".to_string()),
+    }
+}
+
+impl DataTable {
+    /// Re-reads the shown day on the next frame, keeping page and selection (new readings for today).
+    pub fn reload(&mut self) { self.stale = true; }
+
+    pub fn shows_today(&self) -> bool { self.day == Local::now().date_naive() }
+
+    fn ensure_loaded(&mut self) {
+        if self.loaded == Some(self.day) && !self.stale { return; }
+        self.stale = false;
+        let start = Local.from_local_datetime(&self.day.and_hms_opt(0, 0, 0).unwrap_or_default()).earliest().unwrap_or_else(Local::now);
+        self.rows = load_history_range(start, start + ChronoDuration::days(1) - ChronoDuration::seconds(1));
+        if self.loaded.replace(self.day) != Some(self.day) { self.page = 0; self.selected.clear(); }
+    }
+
+    fn visible_rows(&self, date_format: DateFormat) -> Vec<&HistoryPoint> {
+        let needle = self.filter.trim().to_lowercase();
+        let mut rows: Vec<&HistoryPoint> = self.rows.iter()
+            .filter(|p| needle.is_empty() || COLUMNS.iter().any(|c| cell_text(p, *c, date_format).to_lowercase().contains(&needle)))
+            .collect();
+        let key = |p: &HistoryPoint| COLUMNS[self.sort_column].map_or(p.timestamp.timestamp() as f64, |c| c.value(p).unwrap_or(f64::NEG_INFINITY));
+        rows.sort_by(|a, b| key(a).total_cmp(&key(b)));
+        if !self.ascending { rows.reverse(); }
+        rows
+    }
+
+    fn selected_rows(&self) -> Vec<&HistoryPoint> {
+        let mut rows: Vec<&HistoryPoint> = self.rows.iter().filter(|p| self.selected.contains(&p.timestamp.timestamp())).collect();
+        rows.sort_by_key(|p| p.timestamp);
+        rows
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, date_format: DateFormat) -> Option<TableAction> {
+        self.ensure_loaded();
+        let mut action = None;
+        ui.horizontal(|ui| {
+            if ui.button("◀").on_hover_text("Previous day").clicked() { self.day -= ChronoDuration::days(1); }
+            ui.label(date_format.date(self.day));
+            if ui.add_enabled(!self.shows_today(), egui::Button::new("▶")).on_hover_text("Next day").clicked() { self.day += ChronoDuration::days(1); }
+            if ui.button("Today").clicked() { self.day = Local::now().date_naive(); }
+            ui.separator();
+            if ui.add(egui::TextEdit::singleline(&mut self.filter).hint_text("Filter, e.g. 03:12 or 23.4").desired_width(180.0)).changed() { self.page = 0; }
+            ui.separator();
+            if ui.add_enabled(!self.selected.is_empty(), egui::Button::new(format!("Copy {} selected", self.selected.len()))).clicked() {
+                let rows = self.selected_rows();
+                let header = COLUMNS.iter().map(|c| c.map_or("DateTime", |c| c.csv_header())).collect::<Vec<_>>().join("\t");
+                let lines = rows.iter().map(|p| COLUMNS.iter().map(|c| cell_text(p, *c, date_format)).collect::<Vec<_>>().join("\t"));
+                action = Some(TableAction::Copy(std::iter::once(header).chain(lines).collect::<Vec<_>>().join("\n")));
+            }
+            if ui.add_enabled(!self.selected.is_empty(), egui::Button::new("✏ Edit / delete...")).on_hover_text("Correct or delete bogus readings in the CSV log").clicked() {
+                action = Some(TableAction::Edit(self.selected_rows().into_iter().cloned().collect()));
+            }
+            if !self.selected.is_empty() && ui.button("Clear selection").clicked() { self.selected.clear(); }
+        });
+        self.ensure_loaded();
+
+        let rows = self.visible_rows(date_format);
+        let (total, pages) = (rows.len(), rows.len().div_ceil(PAGE_SIZE).max(1));
+        let page = self.page.min(pages - 1);
+        let page_rows: Vec<HistoryPoint> = rows.into_iter().skip(page * PAGE_SIZE).take(PAGE_SIZE).cloned().collect();
+        self.page = page;
+        ui.horizontal(|ui| {
+            if ui.add_enabled(self.page > 0, egui::Button::new("⏮")).clicked() { self.page = 0; }
+            if ui.add_enabled(self.page > 0, egui::Button::new("◀")).clicked() { self.page -= 1; }
+            ui.label(format!("Page {} / {} ({} rows)", self.page + 1, pages, total));
+            if ui.add_enabled(self.page + 1 < pages, egui::Button::new("▶")).clicked() { self.page += 1; }
+            if ui.add_enabled(self.page + 1 < pages, egui::Button::new("⏭")).clicked() { self.page = pages - 1; }
+            ui.label(egui::RichText::new("Click rows to select, headers to sort").weak());
+        });
+
+        let mut sort_by = None;
+        TableBuilder::new(ui)
+            .striped(true)
+            .sense(egui::Sense::click())
+            .column(Column::auto().at_least(150.0))
+            .columns(Column::auto().at_least(90.0), COLUMNS.len() - 1)
+            .header(22.0, |mut header| {
+                for (i, column) in COLUMNS.iter().enumerate() {
+                    header.col(|ui| {
+                        let arrow = if self.sort_column != i { "" } else if self.ascending { " ⏶" } else { " ⏷" };
+                        let title = column.map_or("Time".to_string(), |c| format!("{} ({})", c.name(), c.unit()));
+                        if ui.add(egui::Button::new(egui::RichText::new(format!("{}{}", title, arrow)).strong()).frame(false)).clicked() { sort_by = Some(i); }
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(18.0, page_rows.len(), |mut row| {
+                    let point = &page_rows[row.index()];
+                    let key = point.timestamp.timestamp();
+                    row.set_selected(self.selected.contains(&key));
+                    for column in COLUMNS { row.col(|ui| { ui.label(cell_text(point, column, date_format)); }); }
+                    if row.response().clicked() && !self.selected.remove(&key) { self.selected.insert(key); }
+                });
+            });
+        if let Some(i) = sort_by {
+            if self.sort_column == i { self.ascending = !self.ascending; } else { self.sort_column = i; self.ascending = true; }
+        }
+        action
+    }
+}
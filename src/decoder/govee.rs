@@ -0,0 +1,32 @@
+// --- Govee H5075 / H5074 (and relatives sharing their manufacturer data layout) ---
+
+use btleplug::api::PeripheralProperties;
+
+use super::{AdvertisementDecoder, Reading};
+
+const GOVEE_COMPANY_ID: u16 = 0xEC88;
+
+pub struct Govee;
+
+impl AdvertisementDecoder for Govee {
+    fn family(&self) -> &'static str { "Govee" }
+
+    fn decode(&self, props: &PeripheralProperties) -> Option<Reading> {
+        let data = props.manufacturer_data.get(&GOVEE_COMPANY_ID)?;
+        let (model, temp, hum, battery) = match data.len() {
+            // H5074: 00 | temp i16 LE (0.01 °C) | hum u16 LE (0.01 %) | battery
+            7.. if props.local_name.as_deref().is_none_or(|n| n.contains("5074")) => {
+                ("H5074", i16::from_le_bytes([data[1], data[2]]) as f32 / 100.0, u16::from_le_bytes([data[3], data[4]]) as f32 / 100.0, data[5])
+            }
+            // H5075: 00 | 24-bit BE packed temp*10000 + hum*10, top bit = negative | battery
+            6.. => {
+                let packed = u32::from_be_bytes([0, data[1], data[2], data[3]]);
+                let (negative, value) = (packed & 0x80_0000 != 0, packed & 0x7F_FFFF);
+                let temp = (value / 1000) as f32 / 10.0;
+                ("H5075", if negative { -temp } else { temp }, (value % 1000) as f32 / 10.0, data[4])
+            }
+            _ => return None,
+        };
+        Some(Reading { model, temp, hum, payload: data.clone(), battery: Some(battery.min(100)) })
+    }
+}
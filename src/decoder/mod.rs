@@ -0,0 +1,65 @@
+// --- Advertisement decoders: one per sensor family, tried in registry order ---
+
+use btleplug::api::PeripheralProperties;
+
+mod govee;
+mod switchbot;
+mod thermopro;
+mod xiaomi;
+
+pub use thermopro::looks_like_thermopro;
+
+/// A decoded advertisement, before calibration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reading {
+    pub model: &'static str,
+    pub temp: f32,
+    /// Families with 0.1 % resolution keep it here; storage rounds to whole percent
+    pub hum: f32,
+    /// The bytes the reading came from, kept for the raw-data view and the device alarm flag
+    pub payload: Vec<u8>,
+    /// Battery level in percent, for families that advertise it
+    pub battery: Option<u8>,
+}
+
+impl Reading {
+    pub fn hum_percent(&self) -> u8 { self.hum.round().clamp(0.0, 100.0) as u8 }
+}
+
+/// Adding a sensor family means implementing this and listing it in `DECODERS`.
+pub trait AdvertisementDecoder: Sync {
+    fn family(&self) -> &'static str;
+    /// `None` when the advertisement does not belong to this family or carries no reading.
+    fn decode(&self, props: &PeripheralProperties) -> Option<Reading>;
+}
+
+static DECODERS: [&dyn AdvertisementDecoder; 4] = [&thermopro::ThermoPro, &govee::Govee, &xiaomi::XiaomiAtc, &switchbot::SwitchBot];
+
+pub fn families() -> impl Iterator<Item = &'static str> { DECODERS.iter().map(|d| d.family()) }
+
+/// First decoder that recognises the advertisement. With `lenient` (the device was already matched by MAC)
+/// an unrecognised payload is still read with the TP357 layout, as before decoders existed.
+pub fn decode(props: &PeripheralProperties, lenient: bool) -> Option<Reading> {
+    DECODERS.iter().find_map(|d| d.decode(props)).or_else(|| if lenient { thermopro::decode_lenient(props) } else { None })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn advertisement_parser_reads_the_temperature_across_the_company_id() {
+        let props = |local_name: Option<&str>, company_id: u16, data: Vec<u8>| PeripheralProperties { local_name: local_name.map(str::to_string), manufacturer_data: HashMap::from([(company_id, data)]), ..Default::default() };
+
+        // 23.4 °C is 0x00EA: low byte in the company id, high byte first in the data
+        let reading = decode(&props(None, 0xEAC2, vec![0x00, 45, 0x2C]), false).unwrap();
+        assert_eq!((reading.model, reading.temp, reading.hum_percent()), ("TP357", 23.4, 45));
+        let frost = decode(&props(Some("TP357S (2B0C)"), 0x81C2, vec![0xFF, 80, 0x2C]), false).unwrap();
+        assert_eq!((frost.model, frost.temp, frost.hum_percent()), ("TP357S", -12.7, 80));
+        // The S variant needs its status byte; without the signature only a MAC-matched device is read
+        assert_eq!(decode(&props(Some("TP357S"), 0xEAC2, vec![0x00, 45]), false), None);
+        assert_eq!(decode(&props(None, 0xEA00, vec![0x00, 45, 0x2C]), false), None);
+        assert_eq!(decode(&props(None, 0xEA00, vec![0x00, 45, 0x2C]), true).map(|r| r.temp), Some(23.4));
+    }
+}
@@ -0,0 +1,25 @@
+// --- SwitchBot Meter / Meter Plus (service data) ---
+
+use btleplug::api::bleuuid::uuid_from_u16;
+use btleplug::api::PeripheralProperties;
+
+use super::{AdvertisementDecoder, Reading};
+
+// Older firmware advertises under 0x0D00, newer under the assigned 0xFD3D
+const SERVICE_UUIDS: [u16; 2] = [0xFD3D, 0x0D00];
+const METER_TYPES: [u8; 2] = [b'T', b'i'];
+
+pub struct SwitchBot;
+
+impl AdvertisementDecoder for SwitchBot {
+    fn family(&self) -> &'static str { "SwitchBot" }
+
+    fn decode(&self, props: &PeripheralProperties) -> Option<Reading> {
+        let data = SERVICE_UUIDS.iter().find_map(|uuid| props.service_data.get(&uuid_from_u16(*uuid)))?;
+        // type | status | battery | temp decimals (low nibble) | temp integer (bit 7 = above zero) | humidity
+        if data.len() < 6 || !METER_TYPES.contains(&(data[0] & 0x7F)) { return None; }
+        let magnitude = (data[4] & 0x7F) as f32 + (data[3] & 0x0F) as f32 / 10.0;
+        let temp = if data[4] & 0x80 != 0 { magnitude } else { -magnitude };
+        Some(Reading { model: "SwitchBot Meter", temp, hum: (data[5] & 0x7F) as f32, payload: data.clone(), battery: Some((data[2] & 0x7F).min(100)) })
+    }
+}
@@ -0,0 +1,75 @@
+// --- ThermoPro advertisement decoding: model detection and per-model payload layouts ---
+
+use btleplug::api::PeripheralProperties;
+use std::collections::HashMap;
+
+use super::{AdvertisementDecoder, Reading};
+
+/// Low byte of the manufacturer "company id" in ThermoPro advertisements; the high byte is already temperature data.
+pub const THERMOPRO_SIGNATURE: u8 = 0xC2;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Model { Tp357, Tp357S, Tp358, Tp359, Tp393 }
+
+/// Where the readings sit in the manufacturer data (the bytes after the company id).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Layout {
+    /// High byte of the signed temperature (tenths of °C); the low byte is the company id's high byte
+    temp_hi: usize,
+    hum: usize,
+    min_len: usize,
+}
+
+// TP357 / TP358 send temperature and humidity only; the S variant, TP359 and TP393 append a status byte
+const COMPACT: Layout = Layout { temp_hi: 0, hum: 1, min_len: 2 };
+const EXTENDED: Layout = Layout { temp_hi: 0, hum: 1, min_len: 3 };
+
+impl Model {
+    // Longer prefixes first so "TP357S" is not taken for a TP357
+    const BY_NAME_PREFIX: [(&'static str, Model); 5] = [("TP357S", Model::Tp357S), ("TP357", Model::Tp357), ("TP358", Model::Tp358), ("TP359", Model::Tp359), ("TP393", Model::Tp393)];
+
+    pub fn name(&self) -> &'static str {
+        match self { Model::Tp357 => "TP357", Model::Tp357S => "TP357S", Model::Tp358 => "TP358", Model::Tp359 => "TP359", Model::Tp393 => "TP393" }
+    }
+
+    pub fn from_local_name(local_name: &str) -> Option<Model> {
+        Self::BY_NAME_PREFIX.iter().find(|(prefix, _)| local_name.starts_with(prefix)).map(|(_, model)| *model)
+    }
+
+    fn layout(&self) -> Layout {
+        match self { Model::Tp357 | Model::Tp358 => COMPACT, Model::Tp357S | Model::Tp359 | Model::Tp393 => EXTENDED }
+    }
+}
+
+fn parse(model: Model, company_id: u16, data: &[u8]) -> Option<Reading> {
+    let layout = model.layout();
+    if data.len() < layout.min_len { return None; }
+    let temp = i16::from_le_bytes([(company_id >> 8) as u8, data[layout.temp_hi]]) as f32 / 10.0;
+    Some(Reading { model: model.name(), temp, hum: data[layout.hum] as f32, payload: data.to_vec(), battery: None })
+}
+
+fn signed_entry(manufacturer_data: &HashMap<u16, Vec<u8>>) -> Option<(u16, &Vec<u8>)> {
+    manufacturer_data.iter().find(|(company_id, _)| (**company_id & 0xFF) as u8 == THERMOPRO_SIGNATURE).map(|(id, data)| (*id, data))
+}
+
+pub fn looks_like_thermopro(local_name: Option<&str>, manufacturer_data: &HashMap<u16, Vec<u8>>) -> bool {
+    local_name.and_then(Model::from_local_name).is_some() || signed_entry(manufacturer_data).is_some_and(|(_, data)| data.len() >= EXTENDED.min_len)
+}
+
+/// Picks the model from the advertised name; without one (common on Windows) the TP357 layout is used.
+pub struct ThermoPro;
+
+impl AdvertisementDecoder for ThermoPro {
+    fn family(&self) -> &'static str { "ThermoPro" }
+
+    fn decode(&self, props: &PeripheralProperties) -> Option<Reading> {
+        let (company_id, data) = signed_entry(&props.manufacturer_data)?;
+        parse(props.local_name.as_deref().and_then(Model::from_local_name).unwrap_or(Model::Tp357), company_id, data)
+    }
+}
+
+/// For a device already matched by MAC: an entry without the signature byte is still parsed as a TP357.
+pub fn decode_lenient(props: &PeripheralProperties) -> Option<Reading> {
+    let (company_id, data) = props.manufacturer_data.iter().next()?;
+    parse(props.local_name.as_deref().and_then(Model::from_local_name).unwrap_or(Model::Tp357), *company_id, data)
+}
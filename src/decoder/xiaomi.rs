@@ -0,0 +1,26 @@
+// --- Xiaomi LYWSD03MMC with ATC/pvvx custom firmware (environmental sensing service data) ---
+
+use btleplug::api::bleuuid::uuid_from_u16;
+use btleplug::api::PeripheralProperties;
+
+use super::{AdvertisementDecoder, Reading};
+
+const ENVIRONMENTAL_SENSING: u16 = 0x181A;
+
+pub struct XiaomiAtc;
+
+impl AdvertisementDecoder for XiaomiAtc {
+    fn family(&self) -> &'static str { "Xiaomi (ATC firmware)" }
+
+    fn decode(&self, props: &PeripheralProperties) -> Option<Reading> {
+        let data = props.service_data.get(&uuid_from_u16(ENVIRONMENTAL_SENSING))?;
+        let (temp, hum, battery) = match data.len() {
+            // atc1441: MAC (6) | temp i16 BE (0.1 °C) | hum u8 | battery % | battery mV u16 BE | counter
+            13 => (i16::from_be_bytes([data[6], data[7]]) as f32 / 10.0, data[8] as f32, data[9]),
+            // pvvx: MAC reversed (6) | temp i16 LE (0.01 °C) | hum u16 LE (0.01 %) | battery mV | battery % | counter | flags
+            15.. => (i16::from_le_bytes([data[6], data[7]]) as f32 / 100.0, u16::from_le_bytes([data[8], data[9]]) as f32 / 100.0, data[12]),
+            _ => return None,
+        };
+        Some(Reading { model: "LYWSD03MMC", temp, hum, payload: data.clone(), battery: Some(battery.min(100)) })
+    }
+}
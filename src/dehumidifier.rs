@@ -0,0 +1,36 @@
+// --- Dehumidifier runtime estimate: daily time spent above the RH setpoint ---
+
+use chrono::{Duration as ChronoDuration, NaiveDate};
+use std::collections::BTreeMap;
+
+use crate::model::HistoryPoint;
+
+#[derive(Clone, Debug)]
+pub struct DailyHumidityExcess {
+    pub date: NaiveDate,
+    /// Time the humidity stayed above the setpoint, i.e. the estimated dehumidifier runtime
+    pub secs_above: i64,
+    pub peak: u8,
+}
+
+impl DailyHumidityExcess {
+    pub fn hours(&self) -> f64 { self.secs_above as f64 / 3600.0 }
+    pub fn energy_kwh(&self, power_w: f32) -> f64 { self.hours() * power_w as f64 / 1000.0 }
+}
+
+/// Every interval between consecutive readings counts towards the day of its first reading when that
+/// reading is above the setpoint; intervals longer than `max_gap` are data gaps and are ignored.
+pub fn daily_time_above(points: &[HistoryPoint], setpoint: f32, max_gap: ChronoDuration) -> Vec<DailyHumidityExcess> {
+    let mut days: BTreeMap<NaiveDate, DailyHumidityExcess> = BTreeMap::new();
+    for p in points {
+        let day = days.entry(p.timestamp.date_naive()).or_insert(DailyHumidityExcess { date: p.timestamp.date_naive(), secs_above: 0, peak: 0 });
+        day.peak = day.peak.max(p.hum);
+    }
+    for w in points.windows(2) {
+        let gap = w[1].timestamp - w[0].timestamp;
+        if w[0].hum as f32 > setpoint && gap <= max_gap {
+            if let Some(day) = days.get_mut(&w[0].timestamp.date_naive()) { day.secs_above += gap.num_seconds(); }
+        }
+    }
+    days.into_values().collect()
+}
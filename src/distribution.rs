@@ -0,0 +1,98 @@
+// --- "Distribution" view: histograms of the plotted range with P5/P50/P95 readouts ---
+
+use egui_plot::{Bar, BarChart, GridMark, Plot, VLine};
+
+use crate::calendar::DateFormat;
+use crate::model::HistoryPoint;
+use crate::units::Channel;
+
+pub const PERCENTILES: [f64; 3] = [5.0, 50.0, 95.0];
+
+/// Percentile of sorted values, interpolated between the two nearest ranks.
+pub fn percentile(sorted: &[f64], p: f64) -> Option<f64> {
+    let last = sorted.len().checked_sub(1)?;
+    let rank = (p / 100.0).clamp(0.0, 1.0) * last as f64;
+    let (below, above) = (sorted[rank.floor() as usize], sorted[rank.ceil() as usize]);
+    Some(below + (above - below) * rank.fract())
+}
+
+/// Counts per bin of `width`; bins start at multiples of the width so they stay put while the range changes.
+pub fn histogram(sorted: &[f64], width: f64) -> Vec<(f64, usize)> {
+    let mut bins: Vec<(f64, usize)> = Vec::new();
+    for value in sorted {
+        let start = (value / width).floor() * width;
+        match bins.last_mut() { Some((last, count)) if (*last - start).abs() < width / 2.0 => *count += 1, _ => bins.push((start, 1)) }
+    }
+    bins
+}
+
+/// One channel of the view: values sorted once, then binned and read out.
+#[derive(Default)]
+pub struct ChannelStats { pub sorted: Vec<f64>, pub mean: f64 }
+
+impl ChannelStats {
+    pub fn new(points: &[HistoryPoint], channel: Channel) -> Self {
+        let mut sorted: Vec<f64> = points.iter().filter_map(|p| channel.value(p)).collect();
+        sorted.sort_by(f64::total_cmp);
+        let mean = if sorted.is_empty() { 0.0 } else { sorted.iter().sum::<f64>() / sorted.len() as f64 };
+        Self { sorted, mean }
+    }
+}
+
+#[derive(Default)]
+pub struct DistributionView {
+    // (points, first, last timestamp) of the range the stats were computed for
+    key: Option<(usize, i64, i64)>,
+    temperature: ChannelStats,
+    humidity: ChannelStats,
+}
+
+impl DistributionView {
+    pub fn show(&mut self, ui: &mut egui::Ui, points: &[HistoryPoint], date_format: DateFormat) {
+        let (Some(first), Some(last)) = (points.first(), points.last()) else { ui.label("No readings in the plotted range."); return; };
+        let key = (points.len(), first.timestamp.timestamp(), last.timestamp.timestamp());
+        if self.key != Some(key) {
+            (self.temperature, self.humidity) = (ChannelStats::new(points, Channel::Temperature), ChannelStats::new(points, Channel::Humidity));
+            self.key = Some(key);
+        }
+        ui.label(format!("{} readings from {} to {} (the range shown in the plots)", points.len(), date_format.date_time(first.timestamp), date_format.date_time(last.timestamp)));
+        let height = (ui.available_height() - 60.0) / 2.0;
+        for (stats, channel, width, color) in [(&self.temperature, Channel::Temperature, 0.5, egui::Color32::from_rgb(255, 100, 100)), (&self.humidity, Channel::Humidity, 2.0, egui::Color32::from_rgb(100, 100, 255))] {
+            let readouts: Vec<String> = PERCENTILES.iter().filter_map(|p| Some(format!("P{}: {}", p, channel.format(percentile(&stats.sorted, *p)?)))).collect();
+            ui.label(egui::RichText::new(format!("{} — {}, mean {}", channel.name(), readouts.join(", "), channel.format(stats.mean))).strong());
+            let total = stats.sorted.len() as f64;
+            let bars: Vec<Bar> = histogram(&stats.sorted, width).into_iter()
+                .map(|(start, count)| Bar::new(start + width / 2.0, count as f64 / total * 100.0).width(width * 0.95).name(format!("{} to {}: {} readings", channel.format(start), channel.format(start + width), count)))
+                .collect();
+            Plot::new(("distribution_plot", channel.name())).height(height.max(120.0)).allow_drag(false).allow_zoom(false).allow_scroll(false).include_y(0.0)
+                .x_axis_formatter(move |mark: GridMark, _, _| channel.format(mark.value))
+                .y_axis_formatter(|mark: GridMark, _, _| format!("{:.0} %", mark.value))
+                .show(ui, |plot_ui| {
+                    plot_ui.bar_chart(BarChart::new(bars).color(color).name(channel.name()));
+                    for p in PERCENTILES {
+                        if let Some(value) = percentile(&stats.sorted, p) { plot_ui.vline(VLine::new(value).color(egui::Color32::GRAY).style(egui_plot::LineStyle::dashed_loose()).name(format!("P{}", p))); }
+                    }
+                });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+
+    #[test]
+    fn distribution_percentiles_interpolate_and_histogram_bins_stay_aligned() {
+        let sorted: Vec<f64> = (0..=100).map(f64::from).collect();
+        assert_eq!([5.0, 50.0, 95.0].map(|p| percentile(&sorted, p)), [Some(5.0), Some(50.0), Some(95.0)]);
+        assert_eq!(percentile(&[20.0, 21.0], 50.0), Some(20.5));
+        assert_eq!(percentile(&[], 50.0), None);
+        assert_eq!(histogram(&[20.1, 20.4, 20.5, 21.7], 0.5), vec![(20.0, 2), (20.5, 1), (21.5, 1)]);
+
+        let now = chrono::Local::now();
+        let points: Vec<HistoryPoint> = [(22.0, 40), (20.0, 60), (21.0, 50)].map(|(temp, hum)| point(now, temp, hum)).to_vec();
+        let stats = ChannelStats::new(&points, Channel::Humidity);
+        assert_eq!((stats.sorted, stats.mean), (vec![40.0, 50.0, 60.0], 50.0));
+    }
+}
@@ -0,0 +1,127 @@
+// --- Correcting or deleting logged readings, with an audit trail in `edits.csv` ---
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
+use log::{error, info};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::integrity;
+use crate::model::HistoryPoint;
+use crate::paths;
+use crate::storage::{format_log_timestamp, parse_log_timestamp};
+
+pub const EDIT_LOG_FILE_NAME: &str = "edits.csv";
+// Live readings carry sub-second time while the CSV row is stamped when written
+const MATCH_TOLERANCE_SECS: i64 = 2;
+
+/// Held while a daily log is appended to or rewritten, so an edit cannot drop a reading logged meanwhile.
+pub static LOG_WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PointEdit { Delete, Correct { temp: f32, hum: u8 } }
+
+impl PointEdit {
+    fn action(&self) -> &'static str { match self { PointEdit::Delete => "delete", PointEdit::Correct { .. } => "correct" } }
+}
+
+/// State of the "Edit readings" window, opened from a plot right-click or the data table selection.
+pub struct EditDialog { pub points: Vec<HistoryPoint>, pub temp: f32, pub hum: u8, pub note: String }
+
+impl EditDialog {
+    pub fn new(points: &[HistoryPoint]) -> Option<Self> {
+        let first = points.first()?;
+        Some(Self { points: points.to_vec(), temp: first.temp, hum: first.hum, note: String::new() })
+    }
+}
+
+pub fn edit_log_path() -> PathBuf { paths::data_dir().join(EDIT_LOG_FILE_NAME) }
+
+fn row_time(record: &csv::StringRecord) -> Option<DateTime<Local>> {
+    let first = record.get(0)?;
+    if let Some(time) = parse_log_timestamp(first) { return Some(time); }
+    let naive = NaiveDateTime::parse_from_str(&format!("{} {}", first.trim(), record.get(1)?.trim()), "%Y.%m.%d %H:%M:%S").ok()?;
+    Local.from_local_datetime(&naive).earliest()
+}
+
+// (temperature, humidity) of a row in either the current or the legacy Date;Time;Temp;Hum layout
+fn row_values(record: &csv::StringRecord) -> (String, String) {
+    let offset = if record.len() >= 4 { 2 } else { 1 };
+    (record.get(offset).unwrap_or_default().to_string(), record.get(offset + 1).unwrap_or_default().to_string())
+}
+
+// Comma first, semicolon for legacy logs, like `read_log_records`
+fn read_day(path: &Path) -> io::Result<(u8, csv::StringRecord, Vec<csv::StringRecord>)> {
+    let read = |delimiter: u8| -> io::Result<(u8, csv::StringRecord, Vec<csv::StringRecord>)> {
+        let mut rdr = csv::ReaderBuilder::new().delimiter(delimiter).flexible(true).from_path(path)?;
+        let header = rdr.headers()?.clone();
+        Ok((delimiter, header, rdr.records().filter_map(Result::ok).collect()))
+    };
+    let comma = read(b',')?;
+    if comma.1.len() > 1 { Ok(comma) } else { read(b';') }
+}
+
+fn append_audit(day_rows: &[(DateTime<Local>, (String, String))], edit: PointEdit, note: &str) {
+    let path = edit_log_path();
+    let result = OpenOptions::new().create(true).append(true).open(&path).map_err(csv::Error::from).and_then(|file| {
+        let is_new = file.metadata().map(|m| m.len() == 0).unwrap_or(true);
+        let mut wtr = csv::WriterBuilder::new().has_headers(false).from_writer(file);
+        if is_new { wtr.write_record(["EditedAt", "ReadingTime", "Action", "OldTemperature", "OldHumidity", "NewTemperature", "NewHumidity", "Note"])?; }
+        let edited_at = format_log_timestamp(Local::now());
+        let (new_temp, new_hum) = match edit { PointEdit::Correct { temp, hum } => (format!("{:.1}", temp), hum.to_string()), PointEdit::Delete => (String::new(), String::new()) };
+        for (time, (old_temp, old_hum)) in day_rows {
+            wtr.write_record([edited_at.as_str(), &format_log_timestamp(*time), edit.action(), old_temp, old_hum, &new_temp, &new_hum, note])?;
+        }
+        wtr.flush()?;
+        Ok(())
+    });
+    if let Err(e) = result { error!("Failed to write edit audit log '{}': {}", path.display(), e); }
+}
+
+/// Applies `edit` to the logged rows matching `points` and records each change in the audit log.
+/// Returns how many rows were changed; readings that were never logged are skipped.
+pub fn apply(points: &[HistoryPoint], edit: PointEdit, note: &str) -> io::Result<usize> {
+    let mut by_day: BTreeMap<NaiveDate, Vec<&HistoryPoint>> = BTreeMap::new();
+    for p in points { by_day.entry(p.timestamp.date_naive()).or_default().push(p); }
+    let _guard = LOG_WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut changed = 0;
+    for (day, targets) in by_day {
+        let path = paths::log_path(day);
+        if !path.exists() { continue; }
+        let (delimiter, header, mut records) = read_day(&path)?;
+        let mut hits = Vec::new();
+        for target in targets {
+            // Several rows can share a second; prefer the one holding the same values
+            let same_values = |r: &csv::StringRecord| { let (temp, hum) = row_values(r); temp.replace(',', ".").parse::<f32>().is_ok_and(|t| (t - target.temp).abs() < 0.05) && hum.trim() == target.hum.to_string() };
+            let closest = records.iter().enumerate()
+                .filter(|(i, _)| !hits.iter().any(|(hit, _)| hit == i))
+                .filter_map(|(i, r)| row_time(r).map(|t| (i, !same_values(r), (t - target.timestamp).num_seconds().abs())))
+                .filter(|(_, _, diff)| *diff <= MATCH_TOLERANCE_SECS)
+                .min_by_key(|(_, mismatch, diff)| (*mismatch, *diff));
+            if let Some((i, _, _)) = closest { hits.push((i, row_time(&records[i]).unwrap_or(target.timestamp))); }
+        }
+        if hits.is_empty() { continue; }
+        let audit: Vec<_> = hits.iter().map(|(i, time)| (*time, row_values(&records[*i]))).collect();
+        if let PointEdit::Correct { temp, hum } = edit {
+            for (i, time) in &hits { records[*i] = csv::StringRecord::from(vec![format_log_timestamp(*time), format!("{:.1}", temp), hum.to_string()]); }
+        }
+        let mut indices: Vec<usize> = hits.iter().map(|(i, _)| *i).collect();
+        indices.sort_unstable();
+        if edit == PointEdit::Delete { for i in indices.into_iter().rev() { records.remove(i); } }
+
+        let tmp = path.with_extension("csv.edit");
+        let mut wtr = csv::WriterBuilder::new().delimiter(delimiter).flexible(true).from_writer(Vec::new());
+        wtr.write_record(&header)?;
+        for record in &records { wtr.write_record(record)?; }
+        let bytes = wtr.into_inner().map_err(|e| io::Error::other(e.to_string()))?;
+        // Synced before the rename, so a crash cannot leave a partial log in place of the original
+        integrity::write_synced(&tmp, &bytes)?;
+        fs::rename(&tmp, &path)?;
+        append_audit(&audit, edit, note);
+        changed += audit.len();
+    }
+    info!("Edited {} logged readings ({}){}.", changed, edit.action(), if note.is_empty() { String::new() } else { format!(": {}", note) });
+    Ok(changed)
+}
@@ -0,0 +1,179 @@
+// --- Email alerts over SMTP: threshold and offline events, batched to respect a minimum interval ---
+
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+use crate::model::Config;
+use crate::processor::sleep_unless_stopped;
+use crate::rules::{Channels, Severity};
+use crate::sensors;
+use crate::webhook::{AlertKind, WebhookEvent};
+
+const MAX_ATTEMPTS: u32 = 3;
+const RETRY_DELAY: Duration = Duration::from_secs(30);
+pub const SUBJECT_PLACEHOLDERS: [&str; 4] = ["{message}", "{event}", "{room}", "{device}"];
+pub const DEFAULT_SUBJECT: &str = "Temperature Monitor: {message}";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum SmtpSecurity {
+    /// Plain connection upgraded with STARTTLS (usually port 587)
+    #[default]
+    StartTls,
+    /// TLS from the first byte (usually port 465)
+    Tls,
+    /// No encryption, for relays on the local network only
+    None,
+}
+
+impl SmtpSecurity {
+    pub const ALL: [SmtpSecurity; 3] = [SmtpSecurity::StartTls, SmtpSecurity::Tls, SmtpSecurity::None];
+
+    pub fn label(&self) -> &'static str {
+        match self { SmtpSecurity::StartTls => "STARTTLS", SmtpSecurity::Tls => "TLS", SmtpSecurity::None => "None (unencrypted)" }
+    }
+
+    pub fn default_port(&self) -> u16 {
+        match self { SmtpSecurity::StartTls => 587, SmtpSecurity::Tls => 465, SmtpSecurity::None => 25 }
+    }
+}
+
+pub fn is_configured(config: &Config) -> bool { config.email_enabled && !config.smtp_host.trim().is_empty() && !config.email_from.trim().is_empty() && !recipients(config).is_empty() }
+
+/// Addresses from the comma- or semicolon-separated recipient field.
+pub fn recipients(config: &Config) -> Vec<&str> { config.email_to.split([',', ';']).map(str::trim).filter(|a| !a.is_empty()).collect() }
+
+fn subject(event: &WebhookEvent, config: &Config) -> String {
+    let template = config.email_subject_templates.get(event.kind.id()).map(|t| t.trim()).filter(|t| !t.is_empty()).unwrap_or(DEFAULT_SUBJECT);
+    template.replace("{message}", &event.message).replace("{event}", event.kind.id()).replace("{room}", config.room_name.trim()).replace("{device}", &event.device_name)
+}
+
+/// Subject and plain-text body for a batch; every event keeps its own block so the mail is a complete record.
+pub fn compose(events: &[WebhookEvent], config: &Config) -> (String, String) {
+    let mut subject = events.first().map(|e| subject(e, config)).unwrap_or_default();
+    if events.len() > 1 { subject.push_str(&format!(" (+{} more)", events.len() - 1)); }
+    let blocks: Vec<String> = events.iter().map(|e| {
+        let mut block = format!("{}\n\nEvent: {}\nSeverity: {}\nTime: {}\nDevice: {}", e.message, e.kind.id(), e.severity.label(), e.timestamp.format("%Y-%m-%d %H:%M:%S %:z"), e.device_name);
+        if e.device_name != e.device && !e.device.is_empty() { block.push_str(&format!(" ({})", e.device)); }
+        if !config.room_name.trim().is_empty() { block.push_str(&format!("\nRoom: {}", config.room_name.trim())); }
+        if let Some(t) = e.temperature { block.push_str(&format!("\nTemperature: {:.1} °C", t)); }
+        if let Some(h) = e.humidity { block.push_str(&format!("\nHumidity: {} %", h)); }
+        block
+    }).collect();
+    (subject, blocks.join("\n\n----------\n\n"))
+}
+
+fn send(config: &Config, subject: &str, body: &str) -> Result<(), String> {
+    let mut builder = Message::builder().from(config.email_from.trim().parse().map_err(|e| format!("sender address: {}", e))?).subject(subject).header(ContentType::TEXT_PLAIN);
+    for to in recipients(config) { builder = builder.to(to.parse().map_err(|e| format!("recipient {}: {}", to, e))?); }
+    let message = builder.body(body.to_string()).map_err(|e| e.to_string())?;
+    let host = config.smtp_host.trim();
+    let mut transport = match config.smtp_security {
+        SmtpSecurity::StartTls => SmtpTransport::starttls_relay(host).map_err(|e| e.to_string())?,
+        SmtpSecurity::Tls => SmtpTransport::relay(host).map_err(|e| e.to_string())?,
+        SmtpSecurity::None => SmtpTransport::builder_dangerous(host),
+    }.port(config.smtp_port).timeout(Some(Duration::from_secs(20)));
+    if !config.smtp_username.is_empty() { transport = transport.credentials(Credentials::new(config.smtp_username.clone(), config.smtp_password.clone())); }
+    transport.build().send(&message).map(|_| ()).map_err(|e| e.to_string())
+}
+
+fn deliver(config: &Config, subject: &str, body: &str, stop: &watch::Receiver<bool>) {
+    for attempt in 1..=MAX_ATTEMPTS {
+        match send(config, subject, body) {
+            Ok(()) => { info!("Alert email sent to {}.", config.email_to.trim()); return; }
+            Err(e) if attempt < MAX_ATTEMPTS => {
+                warn!("Alert email failed (attempt {}/{}): {}", attempt, MAX_ATTEMPTS, e);
+                if !sleep_unless_stopped(stop, RETRY_DELAY) { warn!("Alert email not retried, shutting down."); return; }
+            }
+            Err(e) => error!("Alert email failed after {} attempts, giving up: {}", MAX_ATTEMPTS, e),
+        }
+    }
+}
+
+/// Sends a test email on a background thread (settings "Test" button).
+pub fn send_test(config: &Config) {
+    let config = config.clone();
+    let event = WebhookEvent { kind: AlertKind::TemperatureNormal, message: "Test message from Temperature Monitor".to_string(), device: config.target_mac.clone(), device_name: sensors::display_name(&config, &config.target_mac), temperature: None, humidity: None, timestamp: chrono::Local::now(), severity: Severity::Info, channels: Channels::default() };
+    thread::spawn(move || { let (subject, body) = compose(&[event], &config); deliver(&config, &subject, &body, &watch::channel(false).1) });
+}
+
+/// Holds alerts back while the minimum interval since the last email runs, then releases them as one batch.
+#[derive(Default)]
+pub struct Outbox {
+    pending: Vec<WebhookEvent>,
+    last_sent: Option<Instant>,
+}
+
+impl Outbox {
+    pub fn push(&mut self, event: WebhookEvent) { self.pending.push(event); }
+
+    /// Time left until the pending batch may go out; `None` when nothing is pending.
+    pub fn wait(&self, now: Instant, min_interval: Duration) -> Option<Duration> {
+        if self.pending.is_empty() { return None; }
+        Some(self.last_sent.map_or(Duration::ZERO, |last| min_interval.saturating_sub(now.duration_since(last))))
+    }
+
+    pub fn take_due(&mut self, now: Instant, min_interval: Duration) -> Option<Vec<WebhookEvent>> {
+        if self.wait(now, min_interval)? > Duration::ZERO { return None; }
+        self.last_sent = Some(now);
+        Some(std::mem::take(&mut self.pending))
+    }
+}
+
+/// Emails alerts on its own thread; whatever is still held back is sent when the channel closes, without retries after `stop`.
+pub fn run_sender(rx: mpsc::Receiver<WebhookEvent>, shared_config: Arc<Mutex<Config>>, stop: watch::Receiver<bool>) {
+    info!("Starting email sender.");
+    let mut outbox = Outbox::default();
+    loop {
+        let min_interval = Duration::from_secs(shared_config.lock().unwrap().email_min_interval_mins as u64 * 60);
+        let closed = match rx.recv_timeout(outbox.wait(Instant::now(), min_interval).unwrap_or(Duration::from_secs(3600))) {
+            Ok(event) => { outbox.push(event); false }
+            Err(mpsc::RecvTimeoutError::Timeout) => false,
+            Err(mpsc::RecvTimeoutError::Disconnected) => true,
+        };
+        let batch = if closed { outbox.take_due(Instant::now(), Duration::ZERO) } else { outbox.take_due(Instant::now(), min_interval) };
+        if let Some(batch) = batch {
+            let config = shared_config.lock().unwrap().clone();
+            if is_configured(&config) { let (subject, body) = compose(&batch, &config); deliver(&config, &subject, &body, &stop); }
+        }
+        if closed { break; }
+    }
+    info!("Email sender terminated.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+
+    #[test]
+    fn email_alerts_are_batched_by_the_rate_limit_and_keep_every_event() {
+        let event = |kind, message: &str| WebhookEvent { kind, message: message.to_string(), device: TARGET.to_string(), device_name: TARGET.to_string(), temperature: Some(31.25), humidity: Some(50), timestamp: chrono::Local::now(), severity: Default::default(), channels: Default::default() };
+        let (start, interval) = (Instant::now(), Duration::from_secs(900));
+        let mut outbox = Outbox::default();
+        outbox.push(event(AlertKind::TemperatureHigh, "Too hot"));
+        assert_eq!(outbox.take_due(start, interval).map(|b| b.len()), Some(1));
+        // Within the interval alerts are held back, then go out together
+        outbox.push(event(AlertKind::TemperatureNormal, "Back to normal"));
+        outbox.push(event(AlertKind::SensorOffline, "Silent"));
+        assert_eq!(outbox.wait(start + Duration::from_secs(600), interval), Some(Duration::from_secs(300)));
+        assert!(outbox.take_due(start + Duration::from_secs(600), interval).is_none());
+        let batch = outbox.take_due(start + interval, interval).unwrap();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(outbox.wait(start + interval, interval), None);
+
+        let mut config = Config { room_name: "Lab 2".into(), email_to: "a@example.com; b@example.com,".into(), ..test_config() };
+        assert_eq!(recipients(&config), vec!["a@example.com", "b@example.com"]);
+        config.email_subject_templates.insert("temperature_normal".into(), "[{room}] {event}".into());
+        let (subject, body) = compose(&batch, &config);
+        assert_eq!(subject, "[Lab 2] temperature_normal (+1 more)");
+        assert!(body.contains("Back to normal") && body.contains("Silent") && body.contains("Temperature: 31.2 °C") && body.contains("Room: Lab 2"), "{}", body);
+        assert_eq!(compose(&batch[1..], &config).0, "Temperature Monitor: Silent");
+    }
+}
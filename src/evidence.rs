@@ -0,0 +1,184 @@
+// --- Tamper-evident evidence export: readings, gaps, alerts and edits in a hash chain signed per installation ---
+
+use chrono::{DateTime, Duration as ChronoDuration, Local};
+use log::info;
+use ring::digest::{digest, Context, SHA256};
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::alert_log::AlertRecord;
+use crate::model::HistoryPoint;
+use crate::storage::{format_log_timestamp, parse_log_timestamp};
+use crate::{edit, paths};
+
+const MAGIC: &str = "# TempMonitor evidence v1";
+const COLUMNS: &str = "Kind,DateTime,Temperature,Humidity,Detail,Hash";
+const KEY_FILE_NAME: &str = "evidence_key.pk8";
+const HEAD_PREFIX: &str = "# Chain head: ";
+const KEY_PREFIX: &str = "# Public key: ";
+const SIGNATURE_PREFIX: &str = "# Signature: ";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EvidenceSummary { pub readings: usize, pub gaps: usize, pub alerts: usize, pub edits: usize }
+
+fn hex(bytes: &[u8]) -> String { bytes.iter().fold(String::new(), |mut out, b| { let _ = write!(out, "{:02x}", b); out }) }
+
+// Plain `%`, so this parser does not depend on `is_multiple_of` (Rust 1.87)
+#[allow(clippy::manual_is_multiple_of)]
+fn unhex(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 2 != 0 { return None; }
+    (0..text.len()).step_by(2).map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok()).collect()
+}
+
+// Each row's hash covers the previous hash, so changing, reordering or dropping a row breaks every later one
+fn chain(prev: &[u8], row: &str) -> Vec<u8> {
+    let mut context = Context::new(&SHA256);
+    context.update(prev);
+    context.update(row.as_bytes());
+    context.finish().as_ref().to_vec()
+}
+
+fn csv_line(fields: &[String]) -> String {
+    let mut wtr = csv::WriterBuilder::new().terminator(csv::Terminator::Any(b'\n')).from_writer(Vec::new());
+    let _ = wtr.write_record(fields);
+    let bytes = wtr.into_inner().unwrap_or_default();
+    String::from_utf8_lossy(&bytes).trim_end_matches('\n').to_string()
+}
+
+/// Signing key of this installation, created on first use in the data folder.
+fn installation_key() -> io::Result<Ed25519KeyPair> {
+    let path = paths::data_dir().join(KEY_FILE_NAME);
+    if !path.exists() {
+        paths::ensure_dirs();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).map_err(|_| io::Error::other("cannot generate signing key"))?;
+        fs::write(&path, pkcs8.as_ref())?;
+        info!("Created evidence signing key '{}'.", path.display());
+    }
+    Ed25519KeyPair::from_pkcs8(&fs::read(&path)?).map_err(|_| io::Error::new(io::ErrorKind::InvalidData, format!("'{}' is not a valid signing key", path.display())))
+}
+
+fn fingerprint(public_key: &[u8]) -> String { hex(&digest(&SHA256, public_key).as_ref()[..8]) }
+
+/// Short fingerprint of this installation's public key, for comparing against a verified file.
+pub fn key_fingerprint() -> Option<String> { installation_key().ok().map(|key| fingerprint(key.public_key().as_ref())) }
+
+// Edits from the audit log that concern readings in the period
+fn edit_rows(start: DateTime<Local>, end: DateTime<Local>) -> Vec<(DateTime<Local>, [String; 4])> {
+    let Ok(mut rdr) = csv::ReaderBuilder::new().flexible(true).from_path(edit::edit_log_path()) else { return Vec::new(); };
+    rdr.records().flatten().filter_map(|r| {
+        let time = parse_log_timestamp(r.get(1)?)?;
+        if time < start || time > end { return None; }
+        let (edited_at, action, note) = (r.get(0).unwrap_or_default(), r.get(2).unwrap_or_default(), r.get(7).unwrap_or_default());
+        let change = if action == "delete" { "deleted".to_string() } else { format!("corrected to {} °C / {} %", r.get(5).unwrap_or_default(), r.get(6).unwrap_or_default()) };
+        Some((time, [r.get(3).unwrap_or_default().to_string(), r.get(4).unwrap_or_default().to_string(), format!("{} on {}", change, edited_at), note.to_string()]))
+    }).collect()
+}
+
+/// Writes the readings of [start, end] with the gaps longer than `max_gap`, the alerts and the edits
+/// made to those readings, each row chained to the previous one, and signs the final hash.
+pub fn export(path: &Path, points: &[HistoryPoint], alerts: &[AlertRecord], start: DateTime<Local>, end: DateTime<Local>, max_gap: ChronoDuration, room: &str) -> io::Result<EvidenceSummary> {
+    let key = installation_key()?;
+    let mut summary = EvidenceSummary::default();
+    // (time, order within the same second, fields without the hash)
+    let mut rows: Vec<(DateTime<Local>, u8, [String; 5])> = Vec::new();
+    let mut covered_until = start;
+    for p in points.iter().filter(|p| p.timestamp >= start && p.timestamp <= end) {
+        if p.timestamp - covered_until > max_gap { rows.push(gap_row(covered_until, p.timestamp)); summary.gaps += 1; }
+        covered_until = p.timestamp;
+        rows.push((p.timestamp, 0, ["reading".into(), format_log_timestamp(p.timestamp), format!("{:.1}", p.temp), p.hum.to_string(), String::new()]));
+        summary.readings += 1;
+    }
+    if end - covered_until > max_gap { rows.push(gap_row(covered_until, end)); summary.gaps += 1; }
+    for alert in alerts.iter().filter(|a| a.timestamp >= start && a.timestamp <= end) {
+        rows.push((alert.timestamp, 2, ["alert".into(), format_log_timestamp(alert.timestamp), alert.temperature.map(|t| format!("{:.1}", t)).unwrap_or_default(), alert.humidity.map(|h| h.to_string()).unwrap_or_default(), format!("{}: {}", alert.event, alert.message)]));
+        summary.alerts += 1;
+    }
+    for (time, [old_temp, old_hum, change, note]) in edit_rows(start, end) {
+        let detail = if note.is_empty() { change } else { format!("{} ({})", change, note) };
+        rows.push((time, 3, ["edit".into(), format_log_timestamp(time), old_temp, old_hum, detail]));
+        summary.edits += 1;
+    }
+    rows.sort_by_key(|(time, order, _)| (*time, *order));
+
+    let mut out = String::new();
+    let _ = writeln!(out, "{}", MAGIC);
+    let _ = writeln!(out, "# Room: {}", room);
+    let _ = writeln!(out, "# Period: {} – {}", format_log_timestamp(start), format_log_timestamp(end));
+    let _ = writeln!(out, "# Generated: {}", format_log_timestamp(Local::now()));
+    let _ = writeln!(out, "# Gaps: no reading for more than {} min", max_gap.num_minutes());
+    let _ = writeln!(out, "{}", COLUMNS);
+    let mut prev = digest(&SHA256, out.as_bytes()).as_ref().to_vec();
+    for (_, _, fields) in &rows {
+        let line = csv_line(fields);
+        prev = chain(&prev, &line);
+        let _ = writeln!(out, "{},{}", line, hex(&prev));
+    }
+    let _ = writeln!(out, "{}{}", HEAD_PREFIX, hex(&prev));
+    let _ = writeln!(out, "{}{}", KEY_PREFIX, hex(key.public_key().as_ref()));
+    let _ = writeln!(out, "{}{}", SIGNATURE_PREFIX, hex(key.sign(&prev).as_ref()));
+    fs::write(path, out)?;
+    info!("Evidence export '{}': {:?}.", path.display(), summary);
+    Ok(summary)
+}
+
+fn gap_row(from: DateTime<Local>, to: DateTime<Local>) -> (DateTime<Local>, u8, [String; 5]) {
+    (from, 1, ["gap".into(), format_log_timestamp(from), String::new(), String::new(), format!("no readings until {} ({} min)", format_log_timestamp(to), (to - from).num_minutes())])
+}
+
+/// Checks the hash chain and the signature. Returns the number of rows and the signing key's fingerprint.
+pub fn verify(path: &Path) -> Result<(usize, String), String> {
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let mut lines = text.lines();
+    if lines.next() != Some(MAGIC) { return Err("Not an evidence export".to_string()); }
+    let mut header = format!("{}\n", MAGIC);
+    for line in lines.by_ref() {
+        header.push_str(line);
+        header.push('\n');
+        if line == COLUMNS { break; }
+    }
+    let mut prev = digest(&SHA256, header.as_bytes()).as_ref().to_vec();
+    let mut rows = 0;
+    let mut footer = Vec::new();
+    for line in lines {
+        if line.starts_with("# ") { footer.push(line); continue; }
+        if !footer.is_empty() { return Err("Rows were added after the signature".to_string()); }
+        rows += 1;
+        let (body, hash) = line.rsplit_once(',').ok_or_else(|| format!("Row {} is damaged", rows))?;
+        prev = chain(&prev, body);
+        if hex(&prev) != hash { return Err(format!("Row {} or an earlier one was changed, added or removed", rows)); }
+    }
+    let field = |prefix: &str| footer.iter().find_map(|l| l.strip_prefix(prefix)).and_then(unhex).ok_or_else(|| format!("Missing '{}'", prefix.trim_start_matches("# ").trim_end_matches(": ")));
+    if field(HEAD_PREFIX)? != prev { return Err("The chain head does not match the rows".to_string()); }
+    let public_key = field(KEY_PREFIX)?;
+    UnparsedPublicKey::new(&ED25519, &public_key).verify(&prev, &field(SIGNATURE_PREFIX)?).map_err(|_| "The signature is not valid".to_string())?;
+    Ok((rows, fingerprint(&public_key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::PointOrigin;
+    use crate::test_support::*;
+
+    #[test]
+    fn evidence_export_lists_gaps_and_detects_tampering() {
+        let data_dir = TempDataDir::new("evidence");
+        let reading = |s, temp| HistoryPoint { origin: PointOrigin::Backfilled, ..point(rfc3339(s), temp, 70) };
+        let points = vec![reading("2025-11-26T10:00:00+01:00", 18.0), reading("2025-11-26T10:01:00+01:00", 18.1), reading("2025-11-26T11:00:00+01:00", 17.5)];
+        let path = data_dir.dir.join("evidence.csv");
+        let summary = export(&path, &points, &[], rfc3339("2025-11-26T10:00:00+01:00"), rfc3339("2025-11-26T11:00:00+01:00"), ChronoDuration::minutes(5), "Flat 2").unwrap();
+        assert_eq!(summary, EvidenceSummary { readings: 3, gaps: 1, alerts: 0, edits: 0 });
+        assert_eq!(verify(&path).unwrap().0, 4);
+
+        let text = fs::read_to_string(&path).unwrap();
+        fs::write(&path, text.replacen(",18.1,", ",16.1,", 1)).unwrap();
+        assert!(verify(&path).is_err());
+        let dropped: String = text.lines().filter(|l| !l.contains(",18.1,")).map(|l| format!("{}\n", l)).collect();
+        fs::write(&path, dropped).unwrap();
+        assert!(verify(&path).is_err());
+    }
+}
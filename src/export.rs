@@ -0,0 +1,130 @@
+// --- Export of history points to CSV / Excel ---
+
+use rust_xlsxwriter::{Chart, Format, Workbook, XlsxError};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::alert_log::{self, AlertRecord};
+use crate::model::HistoryPoint;
+use crate::notes::{self, Note};
+use crate::units::Channel;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ExportFormat { #[default] Csv, Xlsx }
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 2] = [ExportFormat::Csv, ExportFormat::Xlsx];
+
+    pub fn extension(&self) -> &'static str { match self { ExportFormat::Csv => "csv", ExportFormat::Xlsx => "xlsx" } }
+    pub fn filter_name(&self) -> &'static str { match self { ExportFormat::Csv => "CSV", ExportFormat::Xlsx => "Excel workbook" } }
+}
+
+// Exported value columns after the timestamp
+fn channels(derived: bool) -> &'static [Channel] {
+    if derived { &[Channel::Temperature, Channel::Humidity, Channel::DewPoint] } else { &[Channel::Temperature, Channel::Humidity] }
+}
+
+// Value column header with the sensor's name from settings, e.g. "Temperature – Bedroom"
+fn column_header(header: &str, sensor: Option<&str>) -> String { sensor.map_or(header.to_string(), |name| format!("{} – {}", header, name)) }
+
+/// Alerts and notes go to "Alerts" and "Notes" sheets in Excel, or to `<name>.alerts.csv` and
+/// `<name>.notes.csv` files next to a CSV export. `sensor` is the target's name from settings.
+pub fn export_history(path: &Path, points: &[HistoryPoint], alerts: &[AlertRecord], notes: &[Note], derived: bool, sensor: Option<&str>, format: ExportFormat) -> Result<(), String> {
+    match format {
+        ExportFormat::Csv => {
+            export_history_to_csv(path, points, derived, sensor).map_err(|e| e.to_string())?;
+            if !alerts.is_empty() { alert_log::write_csv(&path.with_extension("alerts.csv"), alerts).map_err(|e| e.to_string())?; }
+            if !notes.is_empty() { notes::write_csv(&path.with_extension("notes.csv"), notes).map_err(|e| e.to_string())?; }
+            Ok(())
+        }
+        ExportFormat::Xlsx => export_history_to_xlsx(path, points, alerts, notes, derived, sensor).map_err(|e| e.to_string()),
+    }
+}
+
+pub fn export_history_to_csv(path: &Path, points: &[HistoryPoint], derived: bool, sensor: Option<&str>) -> Result<(), csv::Error> {
+    let mut wtr = csv::WriterBuilder::new().delimiter(b',').from_path(path)?;
+    let channels = channels(derived);
+    wtr.write_record(std::iter::once("DateTime".to_string()).chain(channels.iter().map(|c| column_header(c.csv_header(), sensor))))?;
+    for p in points {
+        let dt = p.timestamp.format("%Y-%m-%dT%H:%M:%S").to_string();
+        wtr.write_record(std::iter::once(dt).chain(channels.iter().map(|c| c.value(p).map(|v| c.format_number(v)).unwrap_or_default())))?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// "Readings" sheet with a line chart (temperature left axis, humidity right axis) next to the data,
+/// plus "Alerts" and "Notes" sheets when there are any.
+pub fn export_history_to_xlsx(path: &Path, points: &[HistoryPoint], alerts: &[AlertRecord], notes: &[Note], derived: bool, sensor: Option<&str>) -> Result<(), XlsxError> {
+    const SHEET: &str = "Readings";
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name(SHEET)?;
+
+    let header = Format::new().set_bold();
+    let datetime_format = Format::new().set_num_format("yyyy-mm-dd hh:mm:ss");
+    let channels = channels(derived);
+    let number_formats: Vec<Format> = channels.iter().map(|c| Format::new().set_num_format(if c.decimals() == 0 { "0".to_string() } else { format!("0.{}", "0".repeat(c.decimals())) })).collect();
+
+    worksheet.write_string_with_format(0, 0, "DateTime", &header)?;
+    worksheet.set_column_width(0, 20)?;
+    for (col, channel) in channels.iter().enumerate() {
+        let col = col as u16 + 1;
+        let title = column_header(&channel.header(), sensor);
+        worksheet.write_string_with_format(0, col, &title, &header)?;
+        worksheet.set_column_width(col, title.chars().count() as f64 + 3.0)?;
+    }
+
+    for (i, p) in points.iter().enumerate() {
+        let row = i as u32 + 1;
+        worksheet.write_datetime_with_format(row, 0, p.timestamp.naive_local(), &datetime_format)?;
+        for (col, (channel, format)) in channels.iter().zip(&number_formats).enumerate() {
+            if let Some(value) = channel.value(p) { worksheet.write_number_with_format(row, col as u16 + 1, value, format)?; }
+        }
+    }
+
+    if !points.is_empty() {
+        let last_row = points.len() as u32;
+        let mut chart = Chart::new_line();
+        chart.title().set_name("Temperature and humidity");
+        chart.add_series().set_name((SHEET, 0, 1)).set_categories((SHEET, 1, 0, last_row, 0)).set_values((SHEET, 1, 1, last_row, 1));
+        chart.add_series().set_name((SHEET, 0, 2)).set_categories((SHEET, 1, 0, last_row, 0)).set_values((SHEET, 1, 2, last_row, 2)).set_secondary_axis(true);
+        chart.x_axis().set_name("Time").set_date_axis(true).set_num_format("dd.mm hh:mm");
+        chart.y_axis().set_name(Channel::Temperature.unit());
+        chart.y2_axis().set_name(Channel::Humidity.unit());
+        chart.set_width(880).set_height(420);
+        worksheet.insert_chart(1, channels.len() as u16 + 2, &chart)?;
+    }
+
+    if !alerts.is_empty() {
+        let sheet = workbook.add_worksheet();
+        sheet.set_name("Alerts")?;
+        for (col, (title, width)) in [("DateTime", 20.0), ("Event", 22.0), ("Message", 50.0), ("Temperature", 12.0), ("Humidity", 10.0)].into_iter().enumerate() {
+            sheet.write_string_with_format(0, col as u16, title, &header)?;
+            sheet.set_column_width(col as u16, width)?;
+        }
+        for (i, alert) in alerts.iter().enumerate() {
+            let row = i as u32 + 1;
+            sheet.write_datetime_with_format(row, 0, alert.timestamp.naive_local(), &datetime_format)?;
+            sheet.write_string(row, 1, &alert.event)?;
+            sheet.write_string(row, 2, &alert.message)?;
+            if let Some(t) = alert.temperature { sheet.write_number(row, 3, t as f64)?; }
+            if let Some(h) = alert.humidity { sheet.write_number(row, 4, h as f64)?; }
+        }
+    }
+
+    if !notes.is_empty() {
+        let sheet = workbook.add_worksheet();
+        sheet.set_name("Notes")?;
+        for (col, (title, width)) in [("DateTime", 20.0), ("Note", 60.0)].into_iter().enumerate() {
+            sheet.write_string_with_format(0, col as u16, title, &header)?;
+            sheet.set_column_width(col as u16, width)?;
+        }
+        for (i, note) in notes.iter().enumerate() {
+            sheet.write_datetime_with_format(i as u32 + 1, 0, note.timestamp.naive_local(), &datetime_format)?;
+            sheet.write_string(i as u32 + 1, 1, &note.text)?;
+        }
+    }
+
+    workbook.save(path)
+}
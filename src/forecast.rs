@@ -0,0 +1,95 @@
+// --- Short-term forecast: the last hour extrapolated, drawn as a dashed projection on the temperature plot ---
+
+use chrono::{DateTime, Duration as ChronoDuration, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::trend;
+
+/// Readings the projection is fitted to.
+pub const FIT_WINDOW_MINS: i64 = 60;
+// Holt smoothing of the level and of the trend, per one-minute step
+const HOLT_ALPHA: f64 = 0.3;
+const HOLT_BETA: f64 = 0.1;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ForecastMethod {
+    /// Least-squares line through the last hour
+    #[default]
+    Linear,
+    /// Holt's double exponential smoothing: follows a recent bend sooner than the straight line
+    Holt,
+}
+
+impl ForecastMethod {
+    pub const ALL: [ForecastMethod; 2] = [ForecastMethod::Linear, ForecastMethod::Holt];
+
+    pub fn label(&self) -> &'static str { match self { ForecastMethod::Linear => "Linear", ForecastMethod::Holt => "Holt (smoothed trend)" } }
+}
+
+// Minute means of the fit window; minutes without a reading are skipped
+fn minute_means(samples: &[(DateTime<Local>, f64)], newest: DateTime<Local>) -> Vec<f64> {
+    let mut minutes: BTreeMap<i64, (f64, usize)> = BTreeMap::new();
+    for (t, v) in samples.iter().filter(|(t, _)| newest - *t <= ChronoDuration::minutes(FIT_WINDOW_MINS)) {
+        let entry = minutes.entry(t.timestamp() / 60).or_default();
+        *entry = (entry.0 + v, entry.1 + 1);
+    }
+    minutes.values().map(|(sum, n)| sum / *n as f64).collect()
+}
+
+/// (value at the newest sample, change per minute), or `None` while the last hour is too thin to fit.
+fn fit(samples: &[(DateTime<Local>, f64)], method: ForecastMethod) -> Option<(f64, f64)> {
+    let linear = trend::fit(samples.iter().copied(), ChronoDuration::minutes(FIT_WINDOW_MINS)).map(|(value, per_hour)| (value, per_hour / 60.0));
+    match method {
+        ForecastMethod::Linear => linear,
+        ForecastMethod::Holt => {
+            linear?;
+            let means = minute_means(samples, samples.last()?.0);
+            let (mut level, mut slope) = (*means.first()?, means.get(1).zip(means.first()).map_or(0.0, |(b, a)| b - a));
+            for value in means.iter().skip(1) {
+                let previous = level;
+                level = HOLT_ALPHA * value + (1.0 - HOLT_ALPHA) * (level + slope);
+                slope = HOLT_BETA * (level - previous) + (1.0 - HOLT_BETA) * slope;
+            }
+            Some((level, slope))
+        }
+    }
+}
+
+/// Projected (Unix seconds, value) points from the newest sample to `horizon_mins` ahead, one per minute.
+pub fn project(samples: &[(DateTime<Local>, f64)], method: ForecastMethod, horizon_mins: u32) -> Option<Vec<[f64; 2]>> {
+    let newest = samples.last()?.0.timestamp() as f64;
+    let (value, per_minute) = fit(samples, method)?;
+    Some((0..=horizon_mins).map(|m| [newest + m as f64 * 60.0, value + per_minute * m as f64]).collect())
+}
+
+/// When the projection first reaches `threshold` from below (`rising`) or above.
+pub fn crossing(projection: &[[f64; 2]], threshold: f64, rising: bool) -> Option<DateTime<Local>> {
+    if projection.first().is_some_and(|p| if rising { p[1] >= threshold } else { p[1] <= threshold }) { return None; }
+    let point = projection.iter().find(|p| if rising { p[1] >= threshold } else { p[1] <= threshold })?;
+    DateTime::from_timestamp(point[0] as i64, 0).map(|t| t.with_timezone(&Local))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forecast_projects_the_last_hour_and_finds_the_threshold_crossing() {
+        use chrono::Timelike;
+        let start = Local::now().with_nanosecond(0).unwrap();
+        // Greenhouse warming 3 °C an hour, one reading a minute, after a steady morning outside the fit window
+        let samples: Vec<_> = (0..=120).map(|m| (start + ChronoDuration::minutes(m), if m < 60 { 25.0 } else { 25.0 + (m - 60) as f64 * 0.05 })).collect();
+        let newest = samples.last().unwrap().0;
+        let linear = project(&samples, ForecastMethod::Linear, 180).unwrap();
+        assert_eq!(linear.len(), 181);
+        assert_eq!(linear[0][0], newest.timestamp() as f64);
+        assert!((linear[0][1] - 28.0).abs() < 1e-6 && (linear[60][1] - 31.0).abs() < 1e-6, "{:?}", &linear[..2]);
+        assert_eq!(crossing(&linear, 34.97, true), Some(newest + ChronoDuration::minutes(140)));
+        assert_eq!(crossing(&linear, 20.0, false), None);
+        let holt = project(&samples, ForecastMethod::Holt, 60).unwrap();
+        assert!((holt[60][1] - 31.0).abs() < 0.2, "{}", holt[60][1]);
+        // Too little history to extrapolate
+        assert!(project(&samples[..10], ForecastMethod::Linear, 60).is_none());
+    }
+}
@@ -0,0 +1,88 @@
+// --- Temperature gradient between two sensors (e.g. floor vs ceiling), each logged into its own data folder ---
+
+use chrono::{DateTime, Duration as ChronoDuration, Local};
+
+use crate::model::HistoryPoint;
+
+#[derive(Clone, Copy, Debug)]
+pub struct GradientPoint {
+    pub timestamp: DateTime<Local>,
+    /// Sensor A minus sensor B, in °C
+    pub delta: f32,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct GradientStats {
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    pub secs_above: i64,
+    pub secs_below: i64,
+    pub secs_total: i64,
+}
+
+impl GradientStats {
+    pub fn share_outside(&self) -> f64 { if self.secs_total == 0 { 0.0 } else { (self.secs_above + self.secs_below) as f64 / self.secs_total as f64 } }
+}
+
+/// For every reading of `a`, the nearest reading of `b` (both sorted by time), or `None` when there is none
+/// within `max_pair`, so readings are never matched with stale data.
+pub fn nearest<'a>(a: &[HistoryPoint], b: &'a [HistoryPoint], max_pair: ChronoDuration) -> Vec<Option<&'a HistoryPoint>> {
+    let mut j = 0;
+    a.iter().map(|p| {
+        while j + 1 < b.len() && (b[j + 1].timestamp - p.timestamp).abs() <= (b[j].timestamp - p.timestamp).abs() { j += 1; }
+        b.get(j).filter(|q| (q.timestamp - p.timestamp).abs() <= max_pair)
+    }).collect()
+}
+
+/// Sensor A minus its nearest reading of B; readings without a partner are left out.
+pub fn difference(a: &[HistoryPoint], b: &[HistoryPoint], max_pair: ChronoDuration) -> Vec<GradientPoint> {
+    a.iter().zip(nearest(a, b, max_pair)).filter_map(|(p, q)| Some(GradientPoint { timestamp: p.timestamp, delta: p.temp - q?.temp })).collect()
+}
+
+/// Like the dehumidifier estimate, each interval counts towards the state of its first point; intervals
+/// longer than `max_gap` are data gaps and are ignored.
+pub fn stats(points: &[GradientPoint], low: f32, high: f32, max_gap: ChronoDuration) -> Option<GradientStats> {
+    if points.is_empty() { return None; }
+    let mut stats = GradientStats { min: f32::MAX, max: f32::MIN, ..Default::default() };
+    for p in points { stats.min = stats.min.min(p.delta); stats.max = stats.max.max(p.delta); }
+    stats.mean = points.iter().map(|p| p.delta).sum::<f32>() / points.len() as f32;
+    for w in points.windows(2) {
+        let gap = w[1].timestamp - w[0].timestamp;
+        if gap > max_gap { continue; }
+        stats.secs_total += gap.num_seconds();
+        if w[0].delta > high { stats.secs_above += gap.num_seconds(); } else if w[0].delta < low { stats.secs_below += gap.num_seconds(); }
+    }
+    Some(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths;
+    use crate::storage::{load_history_range, load_history_range_in};
+    use crate::test_support::*;
+
+    #[test]
+    fn gradient_pairs_two_sensor_folders_and_counts_time_outside() {
+        let data_dir = TempDataDir::new("gradient");
+        let other = data_dir.dir.join("ceiling");
+        std::fs::create_dir_all(&other).unwrap();
+        let day = chrono::NaiveDate::from_ymd_opt(2025, 11, 26).unwrap();
+        std::fs::write(paths::log_path(day), "DateTime,Temperature,Humidity\n2025-11-26T10:00:00+01:00,20.0,45\n2025-11-26T10:01:00+01:00,20.0,45\n2025-11-26T10:02:00+01:00,20.0,45\n2025-11-26T12:00:00+01:00,20.0,45\n").unwrap();
+        std::fs::write(paths::log_path_in(&other, day), "DateTime,Temperature,Humidity\n2025-11-26T10:00:10+01:00,24.0,40\n2025-11-26T10:01:05+01:00,21.0,40\n2025-11-26T10:02:00+01:00,21.5,40\n").unwrap();
+        let (start, end) = (rfc3339("2025-11-26T00:00:00+01:00"), rfc3339("2025-11-26T23:59:59+01:00"));
+        let floor = load_history_range(start, end);
+        let ceiling = load_history_range_in(&other, start, end);
+
+        // The 12:00 reading has no partner and is left out
+        let points = difference(&floor, &ceiling, ChronoDuration::seconds(30));
+        let deltas: Vec<f32> = points.iter().map(|p| p.delta).collect();
+        assert_eq!(deltas, vec![-4.0, -1.0, -1.5]);
+
+        let stats = stats(&points, -3.0, 3.0, ChronoDuration::minutes(5)).unwrap();
+        assert_eq!((stats.min, stats.max), (-4.0, -1.0));
+        assert_eq!((stats.secs_below, stats.secs_above, stats.secs_total), (60, 0, 120));
+        assert!((stats.share_outside() - 0.5).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,141 @@
+// --- "Calendar" view: the days of a month colored by their mean or maximum temperature ---
+
+use chrono::{Datelike, Duration as ChronoDuration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::aggregate::{self, Resolution, Summary};
+use crate::calendar::WeekStart;
+use crate::palette::ColorScale;
+use crate::schedule::WEEKDAY_NAMES;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum HeatmapValue {
+    #[default]
+    Mean,
+    Max,
+}
+
+impl HeatmapValue {
+    pub const ALL: [HeatmapValue; 2] = [HeatmapValue::Mean, HeatmapValue::Max];
+
+    pub fn label(&self) -> &'static str { match self { HeatmapValue::Mean => "Daily mean", HeatmapValue::Max => "Daily maximum" } }
+
+    pub fn of(&self, day: &Summary) -> f32 { match self { HeatmapValue::Mean => day.temp_mean, HeatmapValue::Max => day.temp_max } }
+}
+
+pub fn first_of_month(date: NaiveDate) -> NaiveDate { date.with_day(1).unwrap_or(date) }
+
+fn next_month(month: NaiveDate) -> NaiveDate { first_of_month(month + ChronoDuration::days(32)) }
+
+/// Daily summaries of the month; days not summarised yet (today, or yesterday before maintenance runs) come from the raw logs.
+pub fn month_summaries(month: NaiveDate, today: NaiveDate) -> BTreeMap<NaiveDate, Summary> {
+    let (start, end) = (first_of_month(month), next_month(month));
+    let bounds = |date: NaiveDate| date.and_hms_opt(0, 0, 0).and_then(|t| t.and_local_timezone(Local).earliest());
+    let mut days: BTreeMap<NaiveDate, Summary> = match (bounds(start), bounds(end)) {
+        (Some(from), Some(to)) => aggregate::load(Resolution::Day, from, to - ChronoDuration::seconds(1)).into_iter().map(|s| (s.start.date_naive(), s)).collect(),
+        _ => BTreeMap::new(),
+    };
+    for date in start.iter_days().take_while(|d| *d < end && *d <= today) {
+        if days.contains_key(&date) { continue; }
+        if let Some(summary) = aggregate::summarize(&aggregate::load_day(date), Resolution::Day).pop() { days.insert(date, summary); }
+    }
+    days
+}
+
+/// Weeks of the month as calendar rows, starting on `week_start`; cells outside the month are `None`.
+pub fn month_grid(month: NaiveDate, week_start: WeekStart) -> Vec<[Option<NaiveDate>; 7]> {
+    let (start, end) = (first_of_month(month), next_month(month));
+    let mut weeks = Vec::new();
+    let mut day = week_start.start_of_week(start);
+    while day < end {
+        weeks.push(std::array::from_fn(|i| Some(day + ChronoDuration::days(i as i64)).filter(|d| (start..end).contains(d))));
+        day += ChronoDuration::days(7);
+    }
+    weeks
+}
+
+pub struct CalendarHeatmap {
+    pub month: NaiveDate,
+    days: BTreeMap<NaiveDate, Summary>,
+    loaded: Option<NaiveDate>,
+}
+
+impl Default for CalendarHeatmap {
+    fn default() -> Self { Self { month: first_of_month(Local::now().date_naive()), days: BTreeMap::new(), loaded: None } }
+}
+
+impl CalendarHeatmap {
+    /// Re-reads the month on the next frame (today's cell changes with every reading).
+    pub fn reload(&mut self) { self.loaded = None; }
+
+    /// Returns the day that was clicked.
+    pub fn show(&mut self, ui: &mut egui::Ui, value: &mut HeatmapValue, scale: ColorScale, week_start: WeekStart) -> Option<NaiveDate> {
+        let today = Local::now().date_naive();
+        if self.loaded != Some(self.month) { self.days = month_summaries(self.month, today); self.loaded = Some(self.month); }
+        ui.horizontal(|ui| {
+            if ui.button("◀").on_hover_text("Previous month").clicked() { self.month = first_of_month(self.month - ChronoDuration::days(1)); }
+            ui.label(egui::RichText::new(self.month.format("%B %Y").to_string()).strong());
+            if ui.add_enabled(self.month < first_of_month(today), egui::Button::new("▶")).on_hover_text("Next month").clicked() { self.month = next_month(self.month); }
+            if ui.button("This month").clicked() { self.month = first_of_month(today); }
+            ui.separator();
+            for option in HeatmapValue::ALL { ui.selectable_value(value, option, option.label()); }
+        });
+        ui.separator();
+        let mut clicked = None;
+        let cell = egui::vec2(((ui.available_width() - 6.0 * ui.spacing().item_spacing.x) / 7.0).clamp(48.0, 120.0), 48.0);
+        egui::Grid::new("calendar_heatmap").spacing(ui.spacing().item_spacing).show(ui, |ui| {
+            for i in week_start.day_order() { ui.label(&WEEKDAY_NAMES[i][..3]); }
+            ui.end_row();
+            for week in month_grid(self.month, week_start) {
+                for date in week {
+                    let Some(date) = date else { ui.label(""); continue; };
+                    let Some(day) = self.days.get(&date) else {
+                        ui.add_enabled(false, egui::Button::new(date.day().to_string()).min_size(cell)).on_disabled_hover_text("No readings");
+                        continue;
+                    };
+                    let temperature = value.of(day);
+                    let fill = scale.color(temperature as f64);
+                    // Dark text on light cells and the other way round
+                    let text = if fill.r() as u32 * 299 + fill.g() as u32 * 587 + fill.b() as u32 * 114 > 128_000 { egui::Color32::BLACK } else { egui::Color32::WHITE };
+                    let label = egui::RichText::new(format!("{}\n{:.1} °C", date.day(), temperature)).color(text);
+                    let mut button = egui::Button::new(label).fill(fill).min_size(cell);
+                    if date == today { button = button.stroke(egui::Stroke::new(2.0, ui.visuals().strong_text_color())); }
+                    let hover = format!("{}\nMean {:.1} °C, {:.1} to {:.1} °C\nHumidity {:.0} % ({} to {} %)", date, day.temp_mean, day.temp_min, day.temp_max, day.hum_mean, day.hum_min, day.hum_max);
+                    if ui.add(button).on_hover_text(hover).clicked() { clicked = Some(date); }
+                }
+                ui.end_row();
+            }
+        });
+        ui.label(egui::RichText::new(format!("Colors span {:.0} to {:.0} °C (Settings → Colors). Click a day to show it in the plots.", scale.min, scale.max)).weak());
+        clicked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths;
+    use crate::test_support::*;
+
+    #[test]
+    fn calendar_heatmap_lays_out_the_month_and_fills_days_from_summaries_and_raw_logs() {
+        let _data_dir = TempDataDir::new("heatmap");
+        // February 2024 starts on a Thursday and has 29 days
+        let february = NaiveDate::from_ymd_opt(2024, 2, 14).unwrap();
+        let weeks = month_grid(february, WeekStart::Monday);
+        assert_eq!(weeks.len(), 5);
+        assert_eq!(weeks[0][..4], [None, None, None, Some(NaiveDate::from_ymd_opt(2024, 2, 1).unwrap())]);
+        assert_eq!(weeks[4][3], Some(NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()));
+        assert_eq!(month_grid(february, WeekStart::Sunday)[0][4], Some(NaiveDate::from_ymd_opt(2024, 2, 1).unwrap()));
+
+        let day = |d: u32| NaiveDate::from_ymd_opt(2024, 2, d).unwrap();
+        aggregate::store(&[(day(3), vec![point(local(day(3), 6, 0), 18.0, 50), point(local(day(3), 14, 0), 22.0, 50)])]).unwrap();
+        // The 10th is not summarised yet, so it comes from its raw log
+        std::fs::write(paths::log_path(day(10)), format!("DateTime,Temperature,Humidity\n{},20.0,40\n{},26.0,40\n", local(day(10), 6, 0).to_rfc3339(), local(day(10), 14, 0).to_rfc3339())).unwrap();
+        let days = month_summaries(february, day(20));
+        assert_eq!(days.keys().copied().collect::<Vec<_>>(), vec![day(3), day(10)]);
+        assert_eq!((HeatmapValue::Mean.of(&days[&day(3)]), HeatmapValue::Max.of(&days[&day(3)])), (20.0, 22.0));
+        assert_eq!((HeatmapValue::Mean.of(&days[&day(10)]), HeatmapValue::Max.of(&days[&day(10)])), (23.0, 26.0));
+    }
+}
@@ -0,0 +1,203 @@
+// --- In-memory history cap: drop the oldest points or keep a downsampled summary of them ---
+
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::model::HistoryPoint;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum HistoryEviction {
+    #[default]
+    DropOldest,
+    /// Averages pairs of the oldest points, so older data gets coarser instead of disappearing
+    Downsample,
+}
+
+impl HistoryEviction {
+    pub const ALL: [HistoryEviction; 2] = [HistoryEviction::DropOldest, HistoryEviction::Downsample];
+
+    pub fn label(&self) -> &'static str {
+        match self { HistoryEviction::DropOldest => "Drop oldest", HistoryEviction::Downsample => "Downsample oldest" }
+    }
+}
+
+/// Where a plotted point came from, for the persistence overlay.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum PointOrigin {
+    /// Loaded from the CSV log (startup, import, recalled view)
+    #[default]
+    Backfilled,
+    /// Received live and written to the CSV log
+    Persisted,
+    /// Received live but not written: logging pause or a failed write
+    LiveOnly,
+    /// Average of older points made by the Downsample eviction; not a row in the CSV
+    Summary,
+}
+
+impl PointOrigin {
+    pub const ALL: [PointOrigin; 4] = [PointOrigin::Persisted, PointOrigin::LiveOnly, PointOrigin::Backfilled, PointOrigin::Summary];
+
+    pub fn label(&self) -> &'static str {
+        match self { PointOrigin::Backfilled => "Loaded from CSV", PointOrigin::Persisted => "Live, saved to CSV", PointOrigin::LiveOnly => "Live only (not in CSV)", PointOrigin::Summary => "Downsampled (not in CSV)" }
+    }
+}
+
+fn mean(a: &HistoryPoint, b: &HistoryPoint) -> HistoryPoint {
+    HistoryPoint { timestamp: a.timestamp + (b.timestamp - a.timestamp) / 2, temp: (a.temp + b.temp) / 2.0, hum: (a.hum as u16 + b.hum as u16).div_ceil(2) as u8, origin: PointOrigin::Summary, rssi: a.rssi.zip(b.rssi).map(|(x, y)| (x + y) / 2).or(a.rssi).or(b.rssi) }
+}
+
+/// Appends `point`, first making room according to `strategy` once `cap` points are held.
+pub fn push_capped(history: &mut VecDeque<HistoryPoint>, point: HistoryPoint, cap: usize, strategy: HistoryEviction) {
+    let cap = cap.max(4);
+    if history.len() >= cap {
+        match strategy {
+            HistoryEviction::DropOldest => { while history.len() >= cap { history.pop_front(); } }
+            HistoryEviction::Downsample => {
+                // Halve the resolution of the oldest half; repeated passes make the summary progressively coarser
+                let older: Vec<HistoryPoint> = history.drain(..cap / 2).collect();
+                let summary: Vec<HistoryPoint> = older.chunks(2).map(|c| if c.len() == 2 { mean(&c[0], &c[1]) } else { c[0].clone() }).collect();
+                for p in summary.into_iter().rev() { history.push_front(p); }
+                while history.len() >= cap { history.pop_front(); }
+            }
+        }
+    }
+    history.push_back(point);
+}
+
+/// Readings younger than this stay at full resolution when the history is kept by hours.
+pub const FULL_RESOLUTION_MINS: i64 = 60;
+/// Older readings are merged into one mean per this many minutes.
+pub const COARSE_MINS: i64 = 5;
+
+/// Drops points older than `hours` before `now` and merges the readings older than `FULL_RESOLUTION_MINS`
+/// into `COARSE_MINS` means, so memory depends on the hours kept rather than on how often readings come.
+pub fn keep_hours(history: &mut VecDeque<HistoryPoint>, now: DateTime<Local>, hours: u32) {
+    let cutoff = now - Duration::hours(hours as i64);
+    while history.front().is_some_and(|p| p.timestamp < cutoff) { history.pop_front(); }
+    let bucket = |p: &HistoryPoint| p.timestamp.timestamp().div_euclid(COARSE_MINS * 60);
+    // Buckets that end before the full-resolution part; the newest point's bucket may still grow while loading
+    let boundary = (now - Duration::minutes(FULL_RESOLUTION_MINS)).timestamp().div_euclid(COARSE_MINS * 60);
+    let newest = history.back().map_or(i64::MIN, bucket);
+    let first_raw = history.iter().position(|p| p.origin != PointOrigin::Summary).unwrap_or(history.len());
+    let end = first_raw + history.range(first_raw..).take_while(|p| bucket(p) < boundary && bucket(p) < newest).count();
+    if end == first_raw { return; }
+    let raw: Vec<HistoryPoint> = history.drain(first_raw..end).collect();
+    let merged: Vec<HistoryPoint> = raw.chunk_by(|a, b| bucket(a) == bucket(b)).map(mean_of).collect();
+    for p in merged.into_iter().rev() { history.insert(first_raw, p); }
+}
+
+fn mean_of(points: &[HistoryPoint]) -> HistoryPoint {
+    let n = points.len() as i64;
+    let first = points[0].timestamp;
+    let rssi: Vec<i16> = points.iter().filter_map(|p| p.rssi).collect();
+    HistoryPoint {
+        timestamp: first + Duration::seconds(points.iter().map(|p| (p.timestamp - first).num_seconds()).sum::<i64>() / n),
+        temp: points.iter().map(|p| p.temp).sum::<f32>() / n as f32,
+        hum: (points.iter().map(|p| p.hum as i64).sum::<i64>() as f64 / n as f64).round() as u8,
+        origin: PointOrigin::Summary,
+        rssi: (!rssi.is_empty()).then(|| (rssi.iter().map(|&r| r as i32).sum::<i32>() / rssi.len() as i32) as i16),
+    }
+}
+
+/// Splits a plot line between consecutive points whose x values (`x0`, `x1`) make `breaks` true.
+pub fn split_line(points: Vec<[f64; 2]>, breaks: impl Fn(f64, f64) -> bool) -> Vec<Vec<[f64; 2]>> {
+    let mut segments: Vec<Vec<[f64; 2]>> = Vec::new();
+    for point in points {
+        match segments.last_mut() {
+            Some(segment) if !segment.last().is_some_and(|p| breaks(p[0], point[0])) => segment.push(point),
+            _ => segments.push(vec![point]),
+        }
+    }
+    segments
+}
+
+/// Splits a plot line where consecutive points are more than `max_gap_secs` apart, so a gap is not bridged.
+pub fn split_at_gaps(points: Vec<[f64; 2]>, max_gap_secs: f64) -> Vec<Vec<[f64; 2]>> { split_line(points, |x0, x1| x1 - x0 > max_gap_secs) }
+
+/// The reading closest in time to `x` (Unix seconds) if it lies within `max_secs`; `history` is in time order.
+pub fn nearest(history: &VecDeque<HistoryPoint>, x: f64, max_secs: f64) -> Option<&HistoryPoint> {
+    let distance = |p: &HistoryPoint| (p.timestamp.timestamp() as f64 - x).abs();
+    let after = history.partition_point(|p| (p.timestamp.timestamp() as f64) < x);
+    [after.checked_sub(1), Some(after)].into_iter().flatten().filter_map(|i| history.get(i)).min_by(|a, b| distance(a).total_cmp(&distance(b))).filter(|p| distance(p) <= max_secs)
+}
+
+/// Plot coordinates of the history, rebuilt only after the history changed rather than every frame.
+#[derive(Default)]
+pub struct PlotSeries { version: Option<u64>, pub temp: Vec<[f64; 2]>, pub hum: Vec<[f64; 2]> }
+
+impl PlotSeries {
+    /// `version` is bumped on every change to `history`; the same version returns the cached series.
+    pub fn get(&mut self, history: &VecDeque<HistoryPoint>, version: u64) -> &Self {
+        if self.version != Some(version) {
+            (self.temp, self.hum) = history.iter().map(|p| { let x = p.timestamp.timestamp() as f64; ([x, p.temp as f64], [x, p.hum as f64]) }).unzip();
+            self.version = Some(version);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+
+    #[test]
+    fn plot_lines_break_where_readings_are_missing_for_longer_than_the_gap() {
+        let points = vec![[0.0, 20.0], [30.0, 20.1], [60.0, 20.2], [400.0, 21.0], [430.0, 21.1], [1000.0, 22.0]];
+        let segments = split_at_gaps(points.clone(), 90.0);
+        assert_eq!(segments, vec![points[..3].to_vec(), points[3..5].to_vec(), points[5..].to_vec()]);
+        assert_eq!(split_at_gaps(points.clone(), 600.0), vec![points]);
+        assert!(split_at_gaps(Vec::new(), 90.0).is_empty());
+        // The default threshold is three reading intervals (30 s duplicate interval in continuous mode)
+        let config = test_config();
+        assert!(config.break_lines_at_gaps);
+        assert_eq!(crate::ble::expected_reading_interval_secs(&config) * config.gap_intervals as u64, 90);
+    }
+
+    fn at(secs: i64) -> DateTime<Local> { DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap().with_timezone(&Local) }
+
+    #[test]
+    fn plot_series_are_rebuilt_only_when_the_history_version_changes() {
+        let mut history: VecDeque<HistoryPoint> = [point(at(0), 20.0, 45), point(at(30), 20.5, 45)].into();
+        let mut series = PlotSeries::default();
+        assert_eq!(series.get(&history, 1).temp, vec![[1_700_000_000.0, 20.0], [1_700_000_030.0, 20.5]]);
+        assert_eq!(series.get(&history, 1).hum[1], [1_700_000_030.0, 45.0]);
+        // Same version: the cached series is returned untouched
+        history.push_back(point(at(60), 21.0, 45));
+        assert_eq!(series.get(&history, 1).temp.len(), 2);
+        assert_eq!(series.get(&history, 2).temp.len(), 3);
+    }
+
+    #[test]
+    fn crosshair_readout_picks_the_nearest_reading_unless_it_is_across_a_gap() {
+        let history: VecDeque<HistoryPoint> = [point(at(0), 20.0, 45), point(at(30), 20.5, 45), point(at(600), 22.0, 45)].into();
+        let temp_at = |secs: f64| nearest(&history, 1_700_000_000.0 + secs, 90.0).map(|p| p.temp);
+        assert_eq!((temp_at(-5.0), temp_at(14.0), temp_at(16.0), temp_at(700.0)), (Some(20.0), Some(20.0), Some(20.5), None));
+        // In the middle of the gap no reading is close enough
+        assert_eq!(temp_at(300.0), None);
+        assert!(nearest(&Default::default(), 0.0, 90.0).is_none());
+    }
+
+    #[test]
+    fn history_kept_by_hours_drops_old_readings_and_coarsens_the_older_part() {
+        use chrono::TimeZone;
+        let now = Local.with_ymd_and_hms(2025, 3, 10, 12, 0, 0).unwrap();
+        // One reading every 30 s for the last 6 hours
+        let mut kept: VecDeque<HistoryPoint> = (0..6 * 120).rev()
+            .map(|i| HistoryPoint { rssi: Some(-60), ..point(now - Duration::seconds(30 * i), 20.0 + (i % 2) as f32, 50) }).collect();
+        keep_hours(&mut kept, now, 3);
+        assert!(kept.front().unwrap().timestamp >= now - Duration::hours(3));
+        // The last hour stays as it was, the two hours before it become 5-minute means
+        let (coarse, raw): (Vec<_>, Vec<_>) = kept.iter().partition(|p| p.origin == PointOrigin::Summary);
+        assert_eq!(coarse.len() as i64, 2 * 60 / COARSE_MINS);
+        assert!(raw.len() >= 120 && raw.len() <= 121 && raw[0].timestamp >= now - Duration::minutes(60));
+        assert!(coarse.iter().all(|p| (p.temp - 20.5).abs() < 0.1 && p.hum == 50 && p.rssi == Some(-60)));
+        assert!(kept.iter().zip(kept.iter().skip(1)).all(|(a, b)| a.timestamp < b.timestamp));
+        // Running again with nothing new changes nothing
+        let before = kept.len();
+        keep_hours(&mut kept, now, 3);
+        assert_eq!(kept.len(), before);
+    }
+}
@@ -0,0 +1,117 @@
+// --- Startup history loading on a background thread, streamed to the GUI in chunks with progress ---
+
+use chrono::{DateTime, Local};
+use log::{error, info, warn};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+use crate::integrity;
+use crate::model::HistoryPoint;
+use crate::storage::parse_history_record;
+
+const CHUNK_POINTS: usize = 2000;
+
+// Counts bytes as the CSV reader consumes them, for the progress bar
+struct CountingReader<R> { inner: R, read: Arc<AtomicU64> }
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+}
+
+pub struct HistoryLoader {
+    /// Points loaded are older than this; live readings received meanwhile are newer
+    pub started_at: DateTime<Local>,
+    rx: mpsc::Receiver<Vec<HistoryPoint>>,
+    bytes_read: Arc<AtomicU64>,
+    bytes_total: u64,
+    done: Arc<AtomicBool>,
+}
+
+impl HistoryLoader {
+    /// Loads the daily logs in the given order (oldest first).
+    pub fn start(paths: Vec<PathBuf>) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let bytes_read = Arc::new(AtomicU64::new(0));
+        let done = Arc::new(AtomicBool::new(false));
+        let bytes_total = paths.iter().map(|path| std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)).sum();
+        let (thread_read, thread_done) = (bytes_read.clone(), done.clone());
+        thread::spawn(move || {
+            for path in &paths {
+                if let Err(e) = stream(path, &tx, thread_read.clone()) { error!("Loading history from '{}' failed: {}", path.display(), e); }
+            }
+            thread_done.store(true, Ordering::Relaxed);
+        });
+        Self { started_at: Local::now(), rx, bytes_read, bytes_total, done }
+    }
+
+    /// Chunks loaded since the last call, oldest first.
+    pub fn poll(&self) -> Vec<Vec<HistoryPoint>> { self.rx.try_iter().collect() }
+
+    pub fn fraction(&self) -> f32 { if self.bytes_total == 0 { 1.0 } else { (self.bytes_read.load(Ordering::Relaxed) as f32 / self.bytes_total as f32).min(1.0) } }
+
+    /// All chunks were sent; the caller should `poll` once more before dropping the loader.
+    pub fn is_finished(&self) -> bool { self.done.load(Ordering::Relaxed) }
+}
+
+// Parses row by row instead of collecting every record first, so memory stays flat for large logs
+fn stream(path: &Path, tx: &mpsc::Sender<Vec<HistoryPoint>>, bytes_read: Arc<AtomicU64>) -> std::io::Result<()> {
+    if !path.exists() { warn!("History file '{}' not found.", path.display()); return Ok(()); }
+    let mut reader = BufReader::new(CountingReader { inner: File::open(path)?, read: bytes_read });
+    let mut header = String::new();
+    reader.read_line(&mut header)?;
+    let mut rdr = csv::ReaderBuilder::new().delimiter(integrity::delimiter(&header)).has_headers(false).flexible(true).from_reader(reader);
+    let (mut chunk, mut total) = (Vec::with_capacity(CHUNK_POINTS), 0);
+    for record in rdr.records().flatten() {
+        let Some(point) = parse_history_record(&record) else { continue; };
+        chunk.push(point);
+        if chunk.len() >= CHUNK_POINTS {
+            total += chunk.len();
+            if tx.send(std::mem::replace(&mut chunk, Vec::with_capacity(CHUNK_POINTS))).is_err() { return Ok(()); }
+        }
+    }
+    total += chunk.len();
+    if !chunk.is_empty() { let _ = tx.send(chunk); }
+    info!("Loaded {} points from '{}'.", total, path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths;
+    use crate::test_support::*;
+
+    #[test]
+    fn history_loader_streams_large_logs_in_chunks() {
+        let _data_dir = TempDataDir::new("loader");
+        let path = paths::log_path(Local::now().date_naive());
+        let rows: String = (0..5000).map(|i| format!("2025-11-26T{:02}:{:02}:{:02}+01:00,{}.5,45\n", i / 3600, i / 60 % 60, i % 60, i % 30)).collect();
+        std::fs::write(&path, format!("DateTime,Temperature,Humidity\n{}not a row\n", rows)).unwrap();
+        let loader = HistoryLoader::start(vec![path]);
+        let started = std::time::Instant::now();
+        while !loader.is_finished() { assert!(started.elapsed().as_secs() < 10, "loader did not finish"); thread::sleep(std::time::Duration::from_millis(5)); }
+        let chunks = loader.poll();
+        assert!(chunks.len() > 1, "expected several chunks, got {}", chunks.len());
+        let points: Vec<_> = chunks.into_iter().flatten().collect();
+        assert_eq!(points.len(), 5000);
+        assert!(points.windows(2).all(|w| w[0].timestamp < w[1].timestamp));
+        assert_eq!(loader.fraction(), 1.0);
+
+        let legacy = paths::log_path(Local::now().date_naive() - chrono::Duration::days(1));
+        std::fs::write(&legacy, "Date;Time;Temperature;Humidity\n2025.11.26;14:23:45;23,4;45\n").unwrap();
+        let loader = HistoryLoader::start(vec![legacy]);
+        while !loader.is_finished() { thread::sleep(std::time::Duration::from_millis(5)); }
+        let points: Vec<_> = loader.poll().into_iter().flatten().collect();
+        assert_eq!(points.len(), 1);
+        assert!((points[0].temp - 23.4).abs() < 0.05);
+    }
+}
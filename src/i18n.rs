@@ -0,0 +1,712 @@
+// --- UI language: the English text is the lookup key, so a string without a translation falls back to English ---
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::LazyLock;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Language {
+    #[default]
+    English,
+    Czech,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Czech];
+
+    /// In the language itself, so it can be found in the list whatever is active.
+    pub fn label(&self) -> &'static str {
+        match self { Language::English => "English", Language::Czech => "Čeština" }
+    }
+
+    fn table(&self) -> Option<&'static HashMap<&'static str, &'static str>> {
+        match self { Language::English => None, Language::Czech => Some(&CZECH_TABLE) }
+    }
+}
+
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+static CZECH_TABLE: LazyLock<HashMap<&'static str, &'static str>> = LazyLock::new(|| CZECH.iter().copied().collect());
+
+pub fn set_language(language: Language) { CURRENT.store(language as u8, Ordering::Relaxed); }
+
+pub fn language() -> Language { if CURRENT.load(Ordering::Relaxed) == Language::Czech as u8 { Language::Czech } else { Language::English } }
+
+/// `text` in the active language.
+pub fn tr(text: &str) -> &str { translate(language(), text) }
+
+pub fn translate(language: Language, text: &str) -> &str {
+    language.table().and_then(|table| table.get(text).copied()).unwrap_or(text)
+}
+
+/// Translates `text` and fills its `{0}`, `{1}`, ... placeholders, which a translation may reorder.
+pub fn trf(text: &str, args: &[&dyn std::fmt::Display]) -> String {
+    args.iter().enumerate().fold(tr(text).to_string(), |s, (i, arg)| s.replace(&format!("{{{}}}", i), &arg.to_string()))
+}
+
+pub const CZECH: &[(&str, &str)] = &[
+    // Menu bar and toolbar
+    ("File", "Soubor"),
+    ("Views", "Pohledy"),
+    ("Settings", "Nastavení"),
+    ("Full mode...", "Plný režim..."),
+    ("Show all panels and settings", "Zobrazit všechny panely a nastavení"),
+    ("Export visible range (CSV)...", "Exportovat zobrazený rozsah (CSV)..."),
+    ("Export visible range (Excel)...", "Exportovat zobrazený rozsah (Excel)..."),
+    ("Quit", "Ukončit"),
+    ("Open data folder...", "Otevřít složku s daty..."),
+    ("Switch to another dataset, e.g. a backup or another location", "Přepnout na jiná data, např. zálohu nebo jiné místo"),
+    ("Recent data folders", "Nedávné složky s daty"),
+    ("Default ({0})", "Výchozí ({0})"),
+    ("Profiles", "Profily"),
+    ("Profile name", "Název profilu"),
+    ("Save current", "Uložit aktuální"),
+    ("Store the sensor, thresholds, calibration and scan timing under this name", "Uložit čidlo, limity, kalibraci a časování skenování pod tímto názvem"),
+    ("Saved profile '{0}'", "Profil '{0}' uložen"),
+    ("Delete '{0}'", "Smazat '{0}'"),
+    ("The current settings stay as they are", "Aktuální nastavení zůstane beze změny"),
+    ("Import settings...", "Importovat nastavení..."),
+    ("Replace the settings with an exported file; data folders and start-with-system stay as they are", "Nahradit nastavení exportovaným souborem; složky s daty a spouštění se systémem zůstanou beze změny"),
+    ("Export settings...", "Exportovat nastavení..."),
+    ("All settings and profiles as JSON, including the webhook URL; passwords, tokens and the PIN are left out", "Všechna nastavení a profily jako JSON, včetně URL webhooku; hesla, tokeny a PIN se nevkládají"),
+    ("Settings exported to {0}", "Nastavení exportováno do {0}"),
+    ("Settings export failed: {0}", "Export nastavení selhal: {0}"),
+    ("Import CSV history...", "Importovat historii z CSV..."),
+    ("Import alert history...", "Importovat historii upozornění..."),
+    ("Merge alerts exported from another machine (*.alerts.csv)", "Sloučit upozornění exportovaná z jiného počítače (*.alerts.csv)"),
+    ("Imported {0} alerts ({1} already present)", "Importováno {0} upozornění ({1} již existovalo)"),
+    ("Alert import failed: {0}", "Import upozornění selhal: {0}"),
+    ("Cold-chain report...", "Protokol chladového řetězce..."),
+    ("Evidence export...", "Průkazný export..."),
+    ("Signed, tamper-evident record of readings, gaps, alerts and edits, e.g. for a landlord or insurer", "Podepsaný záznam měření, výpadků, upozornění a úprav, který odhalí dodatečné změny, např. pro pronajímatele nebo pojišťovnu"),
+    ("Share snapshot link", "Sdílet odkaz na snímek"),
+    ("Publish the last 24 hours as a read-only page behind a private link", "Zveřejnit posledních 24 hodin jako stránku pouze pro čtení za neveřejným odkazem"),
+    ("Stop sharing", "Ukončit sdílení"),
+    ("Dehumidifier estimate...", "Odhad pro odvlhčovač..."),
+    ("Sensor gradient...", "Rozdíl mezi čidly..."),
+    ("Temperature difference between two sensors, e.g. floor vs ceiling", "Rozdíl teplot mezi dvěma čidly, např. podlaha a strop"),
+    ("Events...", "Události..."),
+    ("Threshold breaches, offline periods and recoveries", "Překročení limitů, výpadky senzoru a návraty do normálu"),
+    ("Diagnostics...", "Diagnostika..."),
+    ("Advertisement capture...", "Záznam vysílání..."),
+    ("Raw packets of the sensor with changed bytes highlighted, for decoding the payload", "Surové pakety čidla se zvýrazněnými změněnými bajty, pro dekódování obsahu"),
+    ("Self-test...", "Autotest..."),
+    ("Check Bluetooth, permissions, storage, settings and the clock", "Zkontrolovat Bluetooth, oprávnění, úložiště, nastavení a hodiny"),
+    ("Check logs for damage...", "Zkontrolovat poškození záznamů..."),
+    ("Simple mode", "Jednoduchý režim"),
+    ("Only the current values and plots, for other household members", "Jen aktuální hodnoty a grafy, pro ostatní členy domácnosti"),
+    ("📈 Plots", "📈 Grafy"),
+    ("📋 Data", "📋 Data"),
+    ("Readings of one day as a table", "Měření jednoho dne v tabulce"),
+    ("📅 Calendar", "📅 Kalendář"),
+    ("Daily temperatures of a month as a colored calendar", "Denní teploty za měsíc jako barevný kalendář"),
+    ("📊 Distribution", "📊 Rozložení"),
+    ("Histograms and percentiles of the range shown in the plots", "Histogramy a percentily rozsahu zobrazeného v grafech"),
+    ("Combined plot (dual axes)", "Společný graf (dvě osy)"),
+    ("Temperature and humidity in one plot", "Teplota a vlhkost v jednom grafu"),
+    ("Compare with another day", "Porovnat s jiným dnem"),
+    ("Draw the chosen day's curves behind today's, aligned by time of day", "Vykreslit křivky zvoleného dne za dnešními, zarovnané podle denní doby"),
+    ("Previous day", "Předchozí den"),
+    ("Next day", "Následující den"),
+    ("Yesterday", "Včera"),
+    ("Save current view...", "Uložit aktuální pohled..."),
+    ("Delete view", "Smazat pohled"),
+    ("📝 Note", "📝 Poznámka"),
+    ("Add a timestamped note to the timeline, e.g. when you open a window or turn on the humidifier", "Přidat poznámku s časem na časovou osu, např. když otevřete okno nebo zapnete zvlhčovač"),
+    ("Zoom out", "Oddálit"),
+    ("Zoom in", "Přiblížit"),
+    ("Back to the previous zoom (right-drag on a plot to zoom to a selection)", "Zpět na předchozí přiblížení (tažením pravým tlačítkem v grafu přiblížíte výběr)"),
+    ("Center plot", "Vycentrovat graf"),
+    ("▶ Resume scan", "▶ Pokračovat ve skenování"),
+    ("Start scanning again", "Znovu spustit skenování"),
+    ("⏸ Pause scan", "⏸ Pozastavit skenování"),
+    ("Stop scanning and release the Bluetooth adapter, e.g. for pairing another device", "Zastavit skenování a uvolnit adaptér Bluetooth, např. pro spárování jiného zařízení"),
+    ("🎧 Courtesy", "🎧 Ohleduplný"),
+    ("Short scans to spare Bluetooth audio (Ctrl+B)", "Krátká skenování, která neruší zvuk přes Bluetooth (Ctrl+B)"),
+    ("Loading history {0} %", "Načítání historie {0} %"),
+    ("🔕 Acknowledge alarm", "🔕 Potvrdit alarm"),
+    ("🔋 Battery saver", "🔋 Úspora baterie"),
+    ("On battery: longer scan pauses, slower refresh. Click to run at full speed anyway.", "Na baterii: delší pauzy mezi skenováním, pomalejší překreslování. Kliknutím přepnete na plnou rychlost."),
+    ("No advertisement decoded yet", "Zatím nebylo dekódováno žádné vysílání"),
+    ("Last advertisement {0} s ago", "Poslední vysílání před {0} s"),
+    // Main panel
+    ("Temperature", "Teplota"),
+    ("Humidity", "Vlhkost"),
+    ("Temperature and humidity", "Teplota a vlhkost"),
+    ("Min: {0} / Max: {1}", "Min: {0} / Max: {1}"),
+    ("N/A", "–"),
+    ("Status:", "Stav:"),
+    ("Updated:", "Aktualizováno:"),
+    ("Device ID:", "ID zařízení:"),
+    ("Model:", "Model:"),
+    ("Raw data:", "Surová data:"),
+    ("CSV Write:", "Zápis CSV:"),
+    ("Error", "Chyba"),
+    ("🔔 Device alarm", "🔔 Alarm čidla"),
+    ("⚠ Sensor offline – no reading for {0} min", "⚠ Čidlo nedostupné – žádné měření {0} min"),
+    ("⏸ Logging paused ({0}) until {1}", "⏸ Záznam pozastaven ({0}) do {1}"),
+    ("Selected rows copied to the clipboard", "Vybrané řádky zkopírovány do schránky"),
+    ("Author: Soběslav Holec", "Autor: Soběslav Holec"),
+    // Scanner status
+    ("Initializing...", "Inicializace..."),
+    ("Scanning...", "Skenování..."),
+    ("Replaying...", "Přehrávání..."),
+    ("Replay finished", "Přehrávání dokončeno"),
+    ("Nothing to replay", "Není co přehrát"),
+    ("Simulating...", "Simulace..."),
+    ("Scanning (battery saver)...", "Skenování (úspora baterie)..."),
+    ("Scanning (courtesy mode)...", "Skenování (ohleduplný režim)..."),
+    ("Scanning (continuous mode)...", "Skenování (nepřetržitý režim)..."),
+    ("Waiting...", "Čekání..."),
+    ("Paused", "Pozastaveno"),
+    ("Error: BT adapter not found", "Chyba: adaptér Bluetooth nenalezen"),
+    ("Bluetooth is off", "Bluetooth je vypnutý"),
+    // Settings
+    ("Theme", "Motiv"),
+    ("Dark", "Tmavý"),
+    ("Light", "Světlý"),
+    ("Follow the system", "Podle systému"),
+    ("Accent color", "Zvýrazňující barva"),
+    ("Storage: {0} ({1})", "Úložiště: {0} ({1})"),
+    ("Portable", "Přenosné"),
+    ("Installed", "Instalované"),
+    ("Change...", "Změnit..."),
+    ("Target MAC address:", "MAC adresa čidla:"),
+    ("Supported sensors: {0}", "Podporovaná čidla: {0}"),
+    ("Use the first ThermoPro found (ignore MAC)", "Použít první nalezený ThermoPro (ignorovat MAC)"),
+    ("Matches the TP357 advertisement layout instead of the address; useful when the address is random or changed after a battery swap", "Hledá podle formátu vysílání TP357 místo adresy; hodí se, když je adresa náhodná nebo se změnila po výměně baterie"),
+    ("Known sensors:", "Známá čidla:"),
+    ("Target", "Sledované"),
+    ("Use", "Použít"),
+    ("Read this sensor", "Číst toto čidlo"),
+    ("Forget sensor", "Zapomenout čidlo"),
+    ("Ask again about {0} ignored sensors", "Znovu se ptát na {0} ignorovaných čidel"),
+    ("Scan timeout (s): ", "Délka skenování (s): "),
+    ("Pause between scans (s): ", "Pauza mezi skenováním (s): "),
+    ("Duplicate interval (s): ", "Interval duplicit (s): "),
+    ("Records from the same device will be ignored for this duration.", "Záznamy ze stejného zařízení budou po tuto dobu ignorovány."),
+    ("Save changes at once", "Ukládat změny hned"),
+    ("A reading that moved this much since the last saved one is saved within the duplicate interval too", "Měření, které se od posledního uloženého změnilo aspoň o tolik, se uloží i v intervalu duplicit"),
+    ("Adaptive pause", "Přizpůsobivá pauza"),
+    ("Longest pause while readings hold steady, shortest while they change quickly or are near a warning threshold", "Nejdelší pauza, když jsou hodnoty stálé, nejkratší, když se rychle mění nebo jsou blízko meze varování"),
+    ("from (s): ", "od (s): "),
+    ("to (s): ", "do (s): "),
+    ("Reject spikes", "Odmítat výkyvy"),
+    ("Drop readings far off the recent median, e.g. 0 °C / 0 % from malformed packets. They are written to the app log instead.", "Zahodit měření daleko od nedávného mediánu, např. 0 °C / 0 % z poškozených paketů. Místo toho se zapíší do protokolu aplikace."),
+    ("Max Δ °C: ", "Max. Δ °C: "),
+    ("Max Δ %: ", "Max. Δ %: "),
+    ("Window (s): ", "Okno (s): "),
+    ("Debug: mark saved / live-only / loaded points", "Ladění: označit uložené / jen živé / načtené body"),
+    ("Rings every plotted point by origin, to check the chart against the CSV log. Readings inside the duplicate interval are neither saved nor plotted.", "Označí každý bod grafu podle původu, pro kontrolu grafu proti záznamu CSV. Měření v intervalu duplicit se neukládají ani nevykreslují."),
+    ("Continuous mode", "Nepřetržitý režim"),
+    ("⚠️ Continuous mode only speeds up scanning; duplicate interval still applies.", "⚠️ Nepřetržitý režim jen zrychlí skenování; interval duplicit stále platí."),
+    ("Courtesy mode (Ctrl+B)", "Ohleduplný režim (Ctrl+B)"),
+    ("Short duty-cycled scans that leave Bluetooth headphones undisturbed.", "Krátká přerušovaná skenování, která neruší sluchátka Bluetooth."),
+    ("Courtesy scan (s): ", "Ohleduplné skenování (s): "),
+    ("Courtesy pause (s): ", "Ohleduplná pauza (s): "),
+    ("Courtesy mode schedule:", "Rozvrh ohleduplného režimu:"),
+    ("Add courtesy window", "Přidat ohleduplné okno"),
+    ("Start with the system", "Spouštět se systémem"),
+    ("Keeps logging after a reboot without anyone opening the app", "Záznam pokračuje i po restartu, aniž by někdo musel aplikaci otevřít"),
+    ("Start minimized", "Spouštět minimalizovaně"),
+    ("Sleep", "Spánek"),
+    ("Sleep stops Bluetooth scanning and leaves gaps in the log. The display may still turn off.", "Spánek zastaví skenování Bluetooth a v záznamu vzniknou mezery. Displej se může i tak vypnout."),
+    ("Allow sleep", "Povolit spánek"),
+    ("Keep awake while logging", "Nespat během záznamu"),
+    ("Keep awake in scheduled hours", "Nespat v naplánovaných hodinách"),
+    ("Add keep-awake window", "Přidat okno bez spánku"),
+    ("Load full history from CSV on startup", "Při spuštění načíst celou historii z CSV"),
+    ("⚠️ Restart the application for changes to take effect.", "⚠️ Změny se projeví po restartu aplikace."),
+    ("WARNING: May slow down startup.", "UPOZORNĚNÍ: Může zpomalit spuštění."),
+    ("Keep in memory: ", "Držet v paměti: "),
+    (" points", " bodů"),
+    ("Drop oldest", "Zahodit nejstarší"),
+    ("Downsample oldest", "Zhustit nejstarší"),
+    ("Compress old logs", "Komprimovat staré záznamy"),
+    ("Older daily logs are gzipped and summarised hourly in hourly.csv", "Starší denní záznamy se zabalí gzipem a po hodinách shrnou do hourly.csv"),
+    ("Keep raw for ", "Ponechat surová data "),
+    (" days", " dní"),
+    ("Delete archives after ", "Smazat archivy po "),
+    (" days (0 = never)", " dnech (0 = nikdy)"),
+    ("Data folder: {0} in total; {1} daily logs ({2}), {3} archived ({4})", "Složka s daty: celkem {0}; {1} denních záznamů ({2}), {3} archivovaných ({4})"),
+    ("Warning threshold (°C): ", "Horní limit (°C): "),
+    ("Lower threshold (°C): ", "Dolní limit (°C): "),
+    ("Humidity upper threshold (%): ", "Horní limit vlhkosti (%): "),
+    ("Humidity lower threshold (%): ", "Dolní limit vlhkosti (%): "),
+    ("Show thresholds on the plots", "Zobrazit limity v grafech"),
+    ("Mark alerts on the plots", "Označit upozornění v grafech"),
+    ("Show notes on the plots", "Zobrazit poznámky v grafech"),
+    ("The thresholds color the current values and the plot guides; alerts come from the Alert rules tab.", "Limity barví aktuální hodnoty a vodítka v grafech; upozornění se nastavují na kartě Pravidla upozornění."),
+    ("Colors", "Barvy"),
+    ("Colors of the plotted points and the current values", "Barvy bodů v grafech a aktuálních hodnot"),
+    ("Classic (blue to red)", "Klasické (modrá až červená)"),
+    ("Viridis", "Viridis"),
+    ("Cividis (colorblind-safe)", "Cividis (pro barvoslepé)"),
+    ("Colors from (°C): ", "Barvy od (°C): "),
+    ("Colors from (%): ", "Barvy od (%): "),
+    ("to ", "do "),
+    ("Night shading", "Stínování noci"),
+    ("Off", "Vypnuto"),
+    ("Estimate from time zone", "Odhad z časového pásma"),
+    ("Latitude/longitude", "Zeměpisná šířka/délka"),
+    ("Manual times", "Ruční časy"),
+    ("Latitude: ", "Šířka: "),
+    ("Longitude (east +): ", "Délka (východ +): "),
+    ("Sunrise:", "Východ slunce:"),
+    ("Sunset:", "Západ slunce:"),
+    ("Rough: assumes 45° latitude and the middle of your time zone.", "Přibližné: předpokládá 45° šířky a střed vašeho časového pásma."),
+    ("Plot hourly min/max/mean beyond ", "Hodinové min/max/průměr v grafu nad "),
+    (" h (0 = never)", " h (0 = nikdy)"),
+    ("Daily beyond {0} days; long saved views then load the summaries instead of every reading", "Denní nad {0} dní; dlouhé uložené pohledy pak načítají souhrny místo všech měření"),
+    ("Show moving average", "Zobrazit klouzavý průměr"),
+    ("Trend over the last ", "Trend za posledních "),
+    ("Rate of change shown next to the current values and used by the change alert rules", "Rychlost změny zobrazená u aktuálních hodnot a používaná pravidly upozornění na změnu"),
+    ("Change over the last {0} min", "Změna za posledních {0} min"),
+    ("Show forecast", "Zobrazit předpověď"),
+    ("Extrapolates the last hour as a dashed line on the temperature plot", "Prodlouží poslední hodinu čárkovanou čarou v grafu teploty"),
+    ("Linear", "Lineární"),
+    ("Holt (smoothed trend)", "Holt (vyhlazený trend)"),
+    (" min", " min"),
+    ("Sound alarm when a threshold is exceeded", "Zvukový alarm při překročení limitu"),
+    ("Sound file:", "Zvukový soubor:"),
+    ("built-in beep", "vestavěné pípnutí"),
+    ("Browse...", "Procházet..."),
+    ("Test", "Vyzkoušet"),
+    ("Also sound the alarm when the sensor goes offline", "Spustit alarm i při výpadku čidla"),
+    ("Pass through the sensor's own alarm (experimental)", "Převzít alarm z čidla (experimentální)"),
+    ("Treats byte {0} & 0x{1} of the advertisement as the on-device alarm flag", "Bere bajt {0} & 0x{1} z vysílání jako příznak alarmu čidla"),
+    ("Heartbeat dot", "Tečka aktivity"),
+    ("Blinks in the toolbar on every decoded advertisement", "Blikne v panelu nástrojů při každém dekódovaném vysílání"),
+    ("Tick sound", "Zvuk tiknutí"),
+    ("Sensor offline after missed readings: ", "Čidlo nedostupné po vynechaných měřeních: "),
+    ("Temperature offset (°C): ", "Korekce teploty (°C): "),
+    ("Humidity offset (%): ", "Korekce vlhkosti (%): "),
+    ("Calibration offsets are applied before readings are logged or displayed.", "Kalibrační korekce se použijí před uložením a zobrazením měření."),
+    ("Show temperature-humidity index (THI) for", "Zobrazit teplotně-vlhkostní index (THI) pro"),
+    ("Livestock (cattle, pigs)", "Hospodářská zvířata (skot, prasata)"),
+    ("Dogs", "Psy"),
+    ("Rabbits", "Králíky"),
+    ("Poultry", "Drůbež"),
+    ("Include derived columns (dew point) in exports", "Přidat do exportů odvozené sloupce (rosný bod)"),
+    ("Quick export hotkey", "Klávesová zkratka rychlého exportu"),
+    ("Shortcut:", "Zkratka:"),
+    ("Folder:", "Složka:"),
+    ("exports in the data folder", "exports ve složce s daty"),
+    ("File name:", "Název souboru:"),
+    ("Placeholders: {0}", "Zástupné symboly: {0}"),
+    ("Scheduled pauses (no logging or warnings):", "Naplánované pauzy (bez záznamu a upozornění):"),
+    ("Add pause window", "Přidat pauzu"),
+    ("Every day", "Každý den"),
+    ("Monday", "Pondělí"),
+    ("Tuesday", "Úterý"),
+    ("Wednesday", "Středa"),
+    ("Thursday", "Čtvrtek"),
+    ("Friday", "Pátek"),
+    ("Saturday", "Sobota"),
+    ("Sunday", "Neděle"),
+    ("Simple mode PIN:", "PIN jednoduchého režimu:"),
+    ("none", "žádný"),
+    ("Needed to leave simple mode (File → Simple mode); leave empty to allow anyone", "Potřebný k opuštění jednoduchého režimu (Soubor → Jednoduchý režim); prázdný povolí komukoli"),
+    ("Room:", "Místnost:"),
+    ("e.g. Cellar", "např. Sklep"),
+    ("Alert messages", "Texty upozornění"),
+    ("Used by every alert channel. Placeholders: {0}. Leave empty for the default.", "Používají je všechny kanály upozornění. Zástupné symboly: {0}. Prázdné pole použije výchozí text."),
+    ("Send alerts to a webhook (JSON POST)", "Posílat upozornění na webhook (JSON POST)"),
+    ("URL:", "URL:"),
+    ("Send alerts to Telegram and answer /status", "Posílat upozornění do Telegramu a odpovídat na /status"),
+    ("Create a bot with @BotFather; only the chat below gets alerts and replies", "Bota vytvořte přes @BotFather; upozornění a odpovědi dostává jen níže uvedený chat"),
+    ("Bot token:", "Token bota:"),
+    ("Chat id:", "ID chatu:"),
+    ("123456789 or @channel", "123456789 nebo @kanal"),
+    // alert rules tab
+    ("General", "Obecné"),
+    ("Alert rules", "Pravidla upozornění"),
+    ("A rule fires once its condition has held for the set time and clears when it no longer holds. Quiet hours mute some of its channels, e.g. sound at night or email on Saturday and Sunday (00:00–00:00). Webhook, Telegram and email also need to be set up on the General tab.", "Pravidlo se spustí, jakmile jeho podmínka platí po nastavenou dobu, a zruší se, když přestane platit. Klidové hodiny ztlumí některé jeho kanály, např. zvuk v noci nebo e-mail v sobotu a neděli (00:00–00:00). Webhook, Telegram a e-mail je třeba nastavit i na kartě Obecné."),
+    ("Delete rule", "Smazat pravidlo"),
+    ("for ", "po dobu "),
+    ("Channels:", "Kanály:"),
+    ("Notification", "Oznámení"),
+    ("Sound", "Zvuk"),
+    ("Webhook", "Webhook"),
+    ("Telegram", "Telegram"),
+    ("Email", "E-mail"),
+    ("Add rule", "Přidat pravidlo"),
+    ("Quiet hours ({0})", "Klidové hodiny ({0})"),
+    ("mute:", "ztlumit:"),
+    ("Add quiet hours", "Přidat klidové hodiny"),
+    ("Dew point", "Rosný bod"),
+    ("Signal (RSSI)", "Signál (RSSI)"),
+    ("Battery", "Baterie"),
+    ("Temperature change", "Změna teploty"),
+    ("Humidity change", "Změna vlhkosti"),
+    ("Mold risk (RH over critical)", "Riziko plísní (vlhkost nad kritickou)"),
+    ("Mold risk low", "Riziko plísní nízké"),
+    ("Mold risk elevated", "Riziko plísní zvýšené"),
+    ("Mold risk high", "Riziko plísní vysoké"),
+    ("Humidity {0} % – mold can grow above {1} % at {2} °C", "Vlhkost {0} % – plísně rostou nad {1} % při {2} °C"),
+    ("No mold growth below freezing", "Pod bodem mrazu plísně nerostou"),
+    ("Comfortable", "Příjemně"),
+    ("Too cold", "Příliš chladno"),
+    ("Too warm", "Příliš teplo"),
+    ("Too dry", "Příliš sucho"),
+    ("Too humid", "Příliš vlhko"),
+    ("Comfort zone: {0} to {1} °C, {2} to {3} % humidity, dew point up to {4} °C", "Zóna pohodlí: {0} až {1} °C, vlhkost {2} až {3} %, rosný bod do {4} °C"),
+    ("VPD for growers", "VPD pro pěstitele"),
+    ("Vapor pressure deficit under the humidity reading and as a plot with the target band", "Deficit tlaku par pod vlhkostí a jako graf s cílovým pásmem"),
+    ("leaf offset: ", "posun listu: "),
+    ("Leaf temperature minus air temperature; leaves under lights usually run 1 to 3 °C cooler", "Teplota listu minus teplota vzduchu; listy pod světly bývají o 1 až 3 °C chladnější"),
+    ("target: ", "cíl: "),
+    ("too low (humid)", "příliš nízký (vlhko)"),
+    ("in target", "v cíli"),
+    ("too high (dry)", "příliš vysoký (sucho)"),
+    ("Leaf {0} °C from the air, target {1} to {2} kPa", "List {0} °C od vzduchu, cíl {1} až {2} kPa"),
+    ("Vapor pressure deficit (VPD)", "Deficit tlaku par (VPD)"),
+    ("Signal strength plot", "Graf síly signálu"),
+    ("RSSI of every live reading and packets received per hour, for finding a spot with reliable reception", "RSSI každého živého měření a počet přijatých paketů za hodinu, pro nalezení místa se spolehlivým příjmem"),
+    ("Signal strength (RSSI)", "Síla signálu (RSSI)"),
+    ("{0} packets in the last hour", "{0} paketů za poslední hodinu"),
+    (" · RSSI mean {0}, min {1}, max {2}", " · RSSI průměr {0}, min {1}, max {2}"),
+    ("Packets received per hour, newest first:", "Přijaté pakety za hodinu, od nejnovějších:"),
+    ("Show mold-risk and comfort badges in the toolbar", "Zobrazit v liště riziko plísní a pohodlí"),
+    ("From the latest temperature and humidity; alert rules can use the mold risk too", "Z poslední teploty a vlhkosti; riziko plísní lze použít i v pravidlech upozornění"),
+    ("above", "nad"),
+    ("below", "pod"),
+    ("Info", "Informace"),
+    ("Warning", "Varování"),
+    ("Critical", "Kritické"),
+    ("Send alerts by email (SMTP)", "Posílat upozornění e-mailem (SMTP)"),
+    ("SMTP server:", "SMTP server:"),
+    ("smtp.example.com", "smtp.example.com"),
+    ("port ", "port "),
+    ("STARTTLS", "STARTTLS"),
+    ("TLS", "TLS"),
+    ("None (unencrypted)", "Žádné (nešifrováno)"),
+    ("User name:", "Uživatelské jméno:"),
+    ("Password:", "Heslo:"),
+    ("From:", "Odesílatel:"),
+    ("monitor@example.com", "monitor@example.com"),
+    ("To:", "Příjemci:"),
+    ("lab@example.com, qa@example.com", "lab@example.com, qa@example.com"),
+    ("At most one email every (min): ", "Nejvýše jeden e-mail za (min): "),
+    ("Alerts in between are collected and sent together, none are dropped", "Upozornění mezitím se shromáždí a odešlou společně, žádné se neztratí"),
+    ("Email subjects", "Předměty e-mailů"),
+    ("Placeholders: {0}. Leave empty for the default.", "Zástupné symboly: {0}. Prázdné pole použije výchozí text."),
+    ("Battery saver (longer scan pauses and slower refresh on battery)", "Úspora baterie (na baterii delší pauzy mezi skenováním a pomalejší překreslování)"),
+    ("Also pause snapshot sharing on battery", "Na baterii pozastavit i sdílení snímku"),
+    ("Refresh", "Překreslování"),
+    ("Redraw on new data", "Překreslit při nových datech"),
+    ("Redraw every second", "Překreslovat každou sekundu"),
+    ("Redrawing only when a reading or status arrives saves CPU and battery; the clock keeps relative times ticking", "Překreslování jen při novém měření nebo stavu šetří procesor i baterii; po sekundách běží relativní časy plynule"),
+    ("Snapshot sharing port: ", "Port pro sdílení snímku: "),
+    ("Snapshot link expires after (h, 0 = never): ", "Platnost odkazu na snímek (h, 0 = neomezeně): "),
+    ("First day of week", "První den v týdnu"),
+    ("Date format", "Formát data"),
+    ("Log level", "Úroveň protokolu"),
+    ("Forward readings to InfluxDB v2", "Přeposílat měření do InfluxDB v2"),
+    ("Organization:", "Organizace:"),
+    ("Bucket:", "Bucket:"),
+    ("Token:", "Token:"),
+    ("Measurement:", "Measurement:"),
+    ("Could not change start with the system: {0}", "Spouštění se systémem nelze změnit: {0}"),
+    ("Bluetooth is switched off.", "Bluetooth je vypnutý."),
+    ("No Bluetooth adapter found.", "Bluetooth adaptér nebyl nalezen."),
+    ("Retrying in {0} s (attempt {1}).", "Další pokus za {0} s (pokus {1})."),
+    ("Retry now", "Zkusit znovu"),
+    ("The PC was asleep for {0} min; scanning restarted.", "Počítač spal {0} min; skenování bylo znovu spuštěno."),
+    ("Break lines at gaps", "Přerušit čáru v mezerách"),
+    ("No line is drawn across a stretch without readings", "Přes úsek bez měření se nekreslí čára"),
+    ("longer than ", "delších než "),
+    ("Keep the last ", "Držet v paměti posledních "),
+    (" h in memory (0 = by points)", " h (0 = podle počtu bodů)"),
+    ("Readings older than {0} min are merged into {1}-minute means", "Měření starší než {0} min se slučují do {1}minutových průměrů"),
+    ("Sensor:", "Čidlo:"),
+    ("Name this sensor", "Pojmenovat toto čidlo"),
+    ("Add the target to the known sensors to give it a name and plot color", "Přidá cílové čidlo mezi známá, aby mu šlo dát jméno a barvu v grafu"),
+    ("e.g. Bedroom", "např. Ložnice"),
+    ("Own plot color", "Vlastní barva v grafu"),
+    ("Line", "Čára"),
+    ("Average", "Průměr"),
+    ("Points", "Body"),
+    ("Thresholds", "Limity"),
+    ("Pause / resume scanning", "Pozastavit / obnovit skenování"),
+    ("Type a command...", "Napište příkaz..."),
+    ("No matching command", "Žádný odpovídající příkaz"),
+    ("Kiosk mode (fullscreen)", "Režim kiosku (celá obrazovka)"),
+    ("Keep the display on in kiosk mode", "V režimu kiosku nevypínat displej"),
+    ("Kiosk mode (F11) shows the readings fullscreen, e.g. on a wall-mounted display", "Režim kiosku (F11) zobrazí hodnoty přes celou obrazovku, např. na displeji na zdi"),
+    ("Save plot as image...", "Uložit graf jako obrázek..."),
+    ("Plot image size:", "Velikost obrázku grafu:"),
+    ("Resolution of File → Save plot as image; text grows with the width", "Rozlišení pro Soubor → Uložit graf jako obrázek; text roste se šířkou"),
+    ("Cannot share: {0}", "Nelze sdílet: {0}"),
+    ("Let other devices on the network open snapshot links", "Povolit otevírání odkazů na snímek z jiných zařízení v síti"),
+    ("Off: links only open on this PC", "Vypnuto: odkazy se otevřou jen na tomto počítači"),
+    ("(all day)", "(celý den)"),
+    ("Virtual sensors...", "Virtuální senzory..."),
+    ("Mean, minimum or maximum of several sensors, e.g. a house average", "Průměr, minimum nebo maximum několika senzorů, např. průměr domu"),
+    ("Virtual sensors", "Virtuální senzory"),
+    ("Combines the logs of sensors that each have their own copy of the app, paired like in the gradient window. The combined readings are logged while this app runs and can raise alerts.", "Spojuje záznamy senzorů, z nichž každý má vlastní kopii aplikace, spárované jako v okně gradientu. Spojená měření se zaznamenávají, dokud tato aplikace běží, a mohou spouštět upozornění."),
+    ("Show in the plot below", "Zobrazit v grafu níže"),
+    ("Mean", "Průměr"),
+    ("Minimum", "Minimum"),
+    ("Maximum", "Maximum"),
+    ("Alerts", "Upozornění"),
+    ("Check the combined readings against the alert rules too", "Kontrolovat pravidly upozornění i spojená měření"),
+    ("This app's logs", "Záznamy této aplikace"),
+    ("Add sensor folder", "Přidat složku senzoru"),
+    ("Add virtual sensor", "Přidat virtuální senzor"),
+    ("Last {0} days", "Posledních {0} dní"),
+    ("{0} combined readings, the last {1} °C and {2} %.", "{0} spojených měření, poslední {1} °C a {2} %."),
+    ("No readings of all its sensors close enough together in this period.", "V tomto období nejsou dost blízká měření všech jeho senzorů."),
+    ("Logged in {0}", "Zaznamenáno do {0}"),
+    ("Nothing to save in the visible range.", "Ve viditelném rozsahu není co uložit."),
+    ("Plot saved to {0}", "Graf uložen do {0}"),
+    ("Saving the plot image failed!", "Uložení obrázku grafu selhalo!"),
+    // Toasts
+    ("Courtesy mode on", "Ohleduplný režim zapnut"),
+    ("Courtesy mode off", "Ohleduplný režim vypnut"),
+    ("Full mode", "Plný režim"),
+    ("Sharing is paused while on battery", "Sdílení je při napájení z baterie pozastaveno"),
+    ("Cannot share on port {0}: {1}", "Nelze sdílet na portu {0}: {1}"),
+    ("Snapshot link copied: {0}", "Odkaz na snímek zkopírován: {0}"),
+    ("Cannot switch storage mode: {0}", "Nelze přepnout režim ukládání: {0}"),
+    ("{0} mode: data in {1}", "Režim {0}: data v {1}"),
+    ("Migrated {0} files, originals in {1}", "Přesunuto {0} souborů, originály v {1}"),
+    ("Migration failed: {0}", "Přesun selhal: {0}"),
+    ("View '{0}' saved", "Pohled '{0}' uložen"),
+    ("Added '{0}' – choose it as the target in Settings", "Přidán '{0}' – vyberte ho jako cíl v Nastavení"),
+    ("Wrong PIN", "Chybný PIN"),
+    ("Export failed!", "Export selhal!"),
+    ("Nothing to export in the visible range.", "Ve viditelném rozsahu není co exportovat."),
+    ("Exported {0} records.", "Exportováno {0} záznamů."),
+    ("Quick export failed: {0}", "Rychlý export selhal: {0}"),
+    ("Exported {0} records to {1}", "Exportováno {0} záznamů do {1}"),
+    ("Editing readings failed: {0}", "Úprava měření selhala: {0}"),
+    ("Profile: {0}", "Profil: {0}"),
+    ("Settings imported from {0}", "Nastavení importováno z {0}"),
+    ("Settings import failed: {0}", "Import nastavení selhal: {0}"),
+    ("Folder '{0}' not found", "Složka '{0}' nenalezena"),
+    ("Saving the note failed: {0}", "Uložení poznámky selhalo: {0}"),
+    ("Deleting the note failed: {0}", "Smazání poznámky selhalo: {0}"),
+    ("Report saved.", "Protokol uložen."),
+    ("Saving report failed!", "Uložení protokolu selhalo!"),
+    ("Copied to clipboard!", "Zkopírováno do schránky!"),
+    ("No heat stress", "Bez tepelného stresu"),
+    ("Mild heat stress", "Mírný tepelný stres"),
+    ("Moderate heat stress", "Střední tepelný stres"),
+    ("Severe heat stress", "Silný tepelný stres"),
+    ("{0}: mild from {1}, moderate from {2}, severe from {3}", "{0}: mírný od {1}, střední od {2}, silný od {3}"),
+    // Dialogs
+    ("Move old data", "Přesun starých dat"),
+    ("Found configuration/logs from an older version in {0}.", "Ve složce {0} bylo nalezeno nastavení/záznamy ze starší verze."),
+    ("They can be moved to the current storage location:", "Lze je přesunout do současného umístění dat:"),
+    ("move", "přesunout"),
+    ("merge into existing log", "sloučit s existujícím záznamem"),
+    ("replace (old kept as .bak)", "nahradit (staré zůstane jako .bak)"),
+    ("The originals are kept in a legacy-backup folder next to the executable.", "Originály zůstanou ve složce legacy-backup vedle programu."),
+    ("Migrate", "Přesunout"),
+    ("Don't migrate", "Nepřesouvat"),
+    ("Leave the files where they are and don't ask again", "Nechat soubory na místě a už se neptat"),
+    ("Later", "Později"),
+    ("New sensor {0} ({1}) detected — add it?", "Nalezeno nové čidlo {0} ({1}) — přidat?"),
+    ("Add", "Přidat"),
+    ("Not now", "Teď ne"),
+    ("Never", "Nikdy"),
+    ("Don't ask about this device again", "U tohoto zařízení se už neptat"),
+    ("PIN:", "PIN:"),
+    ("Unlock", "Odemknout"),
+    ("Save view", "Uložit pohled"),
+    ("Name:", "Název:"),
+    ("Lock Y axes to the current range", "Zamknout osy Y na současný rozsah"),
+    ("A view with this name will be replaced.", "Pohled s tímto názvem bude nahrazen."),
+    ("Save", "Uložit"),
+    ("Scanning paused – Bluetooth adapter released", "Vyhledávání pozastaveno – Bluetooth adaptér uvolněn"),
+    ("Scanning resumed", "Vyhledávání obnoveno"),
+    ("Edit readings", "Úprava měření"),
+    ("Reading at {0}", "Měření v {0}"),
+    ("{0} readings, {1} – {2}", "{0} měření, {1} – {2}"),
+    ("Temperature (°C): ", "Teplota (°C): "),
+    ("Humidity (%): ", "Vlhkost (%): "),
+    ("Reason (kept in edits.csv)", "Důvod (uloží se do edits.csv)"),
+    ("Save correction", "Uložit opravu"),
+    ("Set all to these values", "Nastavit všem tyto hodnoty"),
+    ("🗑 Delete reading", "🗑 Smazat měření"),
+    ("🗑 Delete {0} readings", "🗑 Smazat {0} měření"),
+    ("Changes are written to the daily CSV log; every change is recorded in edits.csv in the data folder.", "Změny se zapisují do denního CSV záznamu; každá změna se zaznamená do edits.csv ve složce s daty."),
+    ("{0} readings updated in the CSV log", "{0} měření upraveno v CSV záznamu"),
+    ("{0} readings updated in the CSV log, {1} not found there", "{0} měření upraveno v CSV záznamu, {1} tam nenalezeno"),
+    ("Setup", "Nastavení umístění"),
+    ("Where should Temperature Monitor keep its configuration and CSV logs?", "Kam má Temperature Monitor ukládat nastavení a CSV záznamy?"),
+    ("Installed – per-user folders (recommended)", "Instalované – složky uživatele (doporučeno)"),
+    ("Portable – next to the executable (USB stick)", "Přenosné – vedle programu (USB disk)"),
+    ("Executable folder: {0}", "Složka programu: {0}"),
+    ("A 'portable.flag' file will be created next to the executable.", "Vedle programu se vytvoří soubor 'portable.flag'."),
+    ("Continue", "Pokračovat"),
+    ("Cancel", "Zrušit"),
+    ("Import history", "Import historie"),
+    ("Source: {0}", "Zdroj: {0}"),
+    ("{0} / {1} rows, {2} rows/s", "{0} / {1} řádků, {2} řádků/s"),
+    ("Reading file...", "Čtení souboru..."),
+    ("Remaining: {0}", "Zbývá: {0}"),
+    ("estimating...", "odhaduji..."),
+    ("Done: {0} rows imported, {1} skipped (duplicates or unreadable).", "Hotovo: importováno {0} řádků, {1} přeskočeno (duplicitní nebo nečitelné)."),
+    ("Cancelled: {0} rows imported before stopping.", "Zrušeno: před zastavením importováno {0} řádků."),
+    ("Import failed: {0}", "Import selhal: {0}"),
+    ("Close", "Zavřít"),
+    ("Data folder: {0}", "Složka s daty: {0}"),
+    ("Diagnostics", "Diagnostika"),
+    ("Bluetooth scanner since start", "Bluetooth vyhledávání od spuštění"),
+    ("Status", "Stav"),
+    ("Adapter", "Adaptér"),
+    ("Not checked yet", "Zatím neověřeno"),
+    ("No adapter found", "Adaptér nenalezen"),
+    ("Powered off", "Vypnutý"),
+    ("Idle (between scans)", "Nečinný (mezi vyhledáváními)"),
+    ("Scanning", "Vyhledává"),
+    ("Reconnect attempts", "Pokusy o připojení"),
+    ("{0} failed, next at {1}", "{0} neúspěšných, další v {1}"),
+    ("Last scan started", "Poslední vyhledávání začalo"),
+    ("{0} ({1} scans)", "{0} ({1} vyhledávání)"),
+    ("Last scan stopped", "Poslední vyhledávání skončilo"),
+    ("Advertisements seen", "Zachycená vysílání"),
+    ("Matching the target", "Od sledovaného čidla"),
+    ("Parse failures", "Chyby dekódování"),
+    (" ({0} % decoded)", " ({0} % dekódováno)"),
+    ("Last Bluetooth error", "Poslední chyba Bluetooth"),
+    ("None", "Žádná"),
+    ("Sink writes since start", "Zápisy od spuštění"),
+    ("Sink", "Cíl"),
+    ("Writes", "Zápisy"),
+    ("Failures", "Chyby"),
+    ("p50 (ms)", "p50 (ms)"),
+    ("p95 (ms)", "p95 (ms)"),
+    ("p99 (ms)", "p99 (ms)"),
+    ("CSV log", "CSV záznam"),
+    ("InfluxDB", "InfluxDB"),
+    ("Write latency (last {0} writes per sink)", "Doba zápisu (posledních {0} zápisů na cíl)"),
+    ("Advertisement capture", "Záznam vysílání"),
+    ("Capture", "Zaznamenávat"),
+    ("Every advertisement of the sensor is appended to {0}", "Každé vysílání čidla se připisuje do {0}"),
+    ("{0} packets shown (last {1})", "Zobrazeno {0} paketů (posledních {1})"),
+    ("Clear view", "Vyčistit"),
+    ("Time", "Čas"),
+    ("Company id {0}, payload {1}", "ID výrobce {0}, data {1}"),
+    ("Log check", "Kontrola záznamů"),
+    ("✔ No damaged rows found.", "✔ Žádné poškozené řádky."),
+    ("These rows could not be read, usually because a crash or power cut interrupted a write. Repairing moves them to a .corrupt.txt file next to the log.", "Tyto řádky nelze přečíst, obvykle proto, že zápis přerušil pád programu nebo výpadek proudu. Oprava je přesune do souboru .corrupt.txt vedle záznamu."),
+    ("{0} damaged rows", "{0} poškozených řádků"),
+    ("Repair", "Opravit"),
+    ("  … and {0} more", "  … a dalších {0}"),
+    ("Moved {0} damaged rows aside", "Přesunuto {0} poškozených řádků stranou"),
+    ("Repair failed: {0}", "Oprava selhala: {0}"),
+    ("Events", "Události"),
+    ("No alerts in the loaded history.", "V načtené historii nejsou žádná upozornění."),
+    ("Show", "Zobrazit"),
+    ("Zoom the plots to an hour either side", "Přiblížit grafy na hodinu před a po"),
+    ("Notes", "Poznámky"),
+    ("YYYY-MM-DD HH:MM", "RRRR-MM-DD HH:MM"),
+    ("e.g. opened window", "např. otevřené okno"),
+    ("Time as YYYY-MM-DD HH:MM", "Čas ve tvaru RRRR-MM-DD HH:MM"),
+    ("No notes in the loaded history.", "V načtené historii nejsou žádné poznámky."),
+    ("Delete note", "Smazat poznámku"),
+    ("Self-test", "Autotest"),
+    ("Checking...", "Kontroluji..."),
+    ("Open settings", "Otevřít nastavení"),
+    ("Bluetooth settings", "Nastavení Bluetooth"),
+    ("Privacy settings", "Nastavení soukromí"),
+    ("Date & time settings", "Nastavení data a času"),
+    ("Open data folder", "Otevřít složku s daty"),
+    ("Run again", "Spustit znovu"),
+    ("Dehumidifier estimate", "Odhad pro odvlhčovač"),
+    ("RH setpoint (%): ", "Cílová RV (%): "),
+    ("Dehumidifier power (W): ", "Příkon odvlhčovače (W): "),
+    ("{0}: {1} h, {2} kWh (peak {3}%)", "{0}: {1} h, {2} kWh (špička {3}%)"),
+    ("Runtime (h)", "Doba chodu (h)"),
+    ("Above {0}% RH: {1} h in total, {2} h/day on average.", "Nad {0}% RV: celkem {1} h, průměrně {2} h/den."),
+    ("Estimated energy at {0} W: {1} kWh in total, {2} kWh/day on average.", "Odhad spotřeby při {0} W: celkem {1} kWh, průměrně {2} kWh/den."),
+    ("Per week", "Po týdnech"),
+    ("Week of {0}: {1} h, {2} kWh", "Týden od {0}: {1} h, {2} kWh"),
+    ("Runtime assumes the dehumidifier runs whenever humidity is above the setpoint.", "Doba chodu předpokládá, že odvlhčovač běží vždy, když je vlhkost nad cílovou hodnotou."),
+    ("Sensor gradient", "Rozdíl mezi čidly"),
+    ("Sensor A data folder:", "Složka s daty čidla A:"),
+    ("Sensor B data folder:", "Složka s daty čidla B:"),
+    ("Upper threshold (°C): ", "Horní limit (°C): "),
+    ("A − B: min {0} °C, max {1} °C, mean {2} °C.", "A − B: min {0} °C, max {1} °C, průměr {2} °C."),
+    ("Above {0} °C for {1} h, below {2} °C for {3} h ({4} % of the time outside).", "Nad {0} °C po {1} h, pod {2} °C po {3} h ({4} % času mimo)."),
+    ("No overlapping readings of both sensors in this period.", "V tomto období nejsou překrývající se měření obou čidel."),
+    ("Log each sensor with its own copy of the app (e.g. a portable copy) and point the folders at their data.", "Zaznamenávejte každé čidlo vlastní kopií aplikace (např. přenosnou) a nastavte složky na jejich data."),
+    ("Evidence export", "Průkazný export"),
+    ("Readings, gaps, alerts and any edits of the period in one file. Every row is chained to the previous one by a SHA-256 hash and the result is signed, so a changed, added or removed row is detected by \"Verify\".", "Měření, výpadky, upozornění a případné úpravy za období v jednom souboru. Každý řádek je s předchozím svázán hashem SHA-256 a výsledek je podepsán, takže změněný, přidaný nebo odebraný řádek odhalí \"Ověřit\"."),
+    ("Start:", "Začátek:"),
+    ("End:", "Konec:"),
+    ("YYYY-MM-DD", "RRRR-MM-DD"),
+    ("Export...", "Exportovat..."),
+    ("Verify file...", "Ověřit soubor..."),
+    ("Signing key of this installation: {0}", "Podpisový klíč této instalace: {0}"),
+    ("Exported {0} readings, {1} gaps, {2} alerts, {3} edits", "Exportováno {0} měření, {1} výpadků, {2} upozornění, {3} úprav"),
+    ("Evidence export failed: {0}", "Průkazný export selhal: {0}"),
+    ("{0} is intact: {1} rows, signed by key {2}", "{0} je neporušený: {1} řádků, podepsáno klíčem {2}"),
+    ("Cold-chain report", "Protokol chladového řetězce"),
+    ("Period", "Období"),
+    ("Last 24 hours", "Posledních 24 hodin"),
+    ("Last 7 days", "Posledních 7 dní"),
+    ("Last 30 days", "Posledních 30 dní"),
+    ("This week", "Tento týden"),
+    ("Last week", "Minulý týden"),
+    ("Custom", "Vlastní"),
+    ("Generate", "Vytvořit"),
+    ("Acceptance range uses the warning thresholds from Settings.", "Přípustný rozsah používá meze varování z Nastavení."),
+    ("Save report...", "Uložit protokol..."),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn czech_covers_every_translated_ui_string_and_keeps_placeholders() {
+        let placeholders = |text: &str| { let mut found: Vec<String> = text.split('{').skip(1).filter_map(|p| p.split_once('}')).map(|(n, _)| n.to_string()).collect(); found.sort_unstable(); found };
+        let mut keys = std::collections::HashSet::new();
+        for (english, czech) in CZECH {
+            assert!(keys.insert(*english), "duplicate key {:?}", english);
+            assert_eq!(placeholders(english), placeholders(czech), "placeholders differ for {:?}", english);
+        }
+        // Strings that read the same in both languages
+        let universal = ["OK", "HH:MM", "RSSI:", "https://ntfy.sh/my-topic"];
+        let source = [include_str!("ui/mod.rs"), include_str!("ui/panels.rs"), include_str!("ui/plots.rs")].concat();
+        for call in ["tr(\"", "trf(\""] {
+            for rest in source.split(call).skip(1) {
+                let end = rest.match_indices('"').map(|(i, _)| i).find(|&i| !rest[..i].ends_with('\\')).unwrap();
+                let key = rest[..end].replace("\\\"", "\"");
+                assert!(keys.contains(key.as_str()) || universal.contains(&key.as_str()), "no Czech text for {:?}", key);
+            }
+        }
+        // Text passed to these without tr/trf is a miss; ids, units and symbols may stay as they are
+        let text_calls = [".label(", ".button(", "small_button(", "heading(", "Window::new(", "RichText::new(", "Button::new(", "on_hover_text(", "hint_text(", "prefix(", "selected_text(", "checkbox(", "radio_value(", "selectable_value(", "selectable_label(", "from_label(", "menu_button(", "toast_message = Some(("];
+        let verbatim = ["Language / Jazyk", " kPa", " px", "command_palette", "THI {:.1} – {}", "VPD {} – {}"];
+        for call in text_calls {
+            for rest in source.split(call).skip(1) {
+                let line = rest.lines().next().unwrap_or_default();
+                assert!(!line.trim_start_matches('&').starts_with("self.scan_status"), "the scanner status reaches the UI without tr");
+                let Some(quote) = line.find('"') else { continue; };
+                let before = &line[..quote];
+                // Past the call's closing parenthesis the literal belongs to something else
+                let closed = before.chars().try_fold(0, |depth: i32, c| { let depth = depth + (c == '(') as i32 - (c == ')') as i32; (depth >= 0).then_some(depth) }).is_none();
+                if closed || before.contains("tr(") || before.contains("trf(") { continue; }
+                let literal = &line[quote + 1..][..line[quote + 1..].find('"').unwrap_or_default()];
+                let text: String = literal.split('{').enumerate().map(|(i, part)| if i == 0 { part } else { part.split_once('}').map_or(part, |(_, after)| after) }).collect();
+                let has_words = text.as_bytes().windows(2).any(|pair| pair[0].is_ascii_alphabetic() && pair[1].is_ascii_alphabetic());
+                assert!(!has_words || verbatim.contains(&literal), "{:?} reaches the UI without tr/trf", literal);
+            }
+        }
+        // The scanner status is English text shown through tr, so each one it can take needs Czech
+        let scanner = [include_str!("ble.rs"), include_str!("replay.rs"), include_str!("simulator.rs")].concat();
+        let statuses = scanner.lines().filter_map(|l| l.split_once("StatusUpdate(").or_else(|| l.split_once("status_msg = "))).map(|(_, rest)| rest)
+            .chain(source.lines().filter_map(|l| l.split_once("scan_status: ")).map(|(_, rest)| rest.split(',').next().unwrap_or_default()));
+        for status in statuses.flat_map(|rest| rest.split('"').skip(1).step_by(2)) {
+            assert!(keys.contains(status), "no Czech text for the scanner status {:?}", status);
+        }
+        assert_eq!(translate(Language::Czech, "Settings"), "Nastavení");
+        assert_eq!(translate(Language::Czech, "Not in the table"), "Not in the table");
+        assert_eq!(translate(Language::English, "Settings"), "Settings");
+    }
+}
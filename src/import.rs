@@ -0,0 +1,116 @@
+// --- CSV history import on a background thread with progress, ETA and cancel ---
+
+use chrono::{NaiveDate, NaiveDateTime};
+use log::{error, info};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::model::HistoryPoint;
+use crate::paths;
+use crate::storage::{format_log_timestamp, parse_history_record, read_log_records};
+
+const BATCH_ROWS: usize = 1000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ImportState { Reading, Importing, Done, Cancelled, Failed(String) }
+
+#[derive(Clone, Debug)]
+pub struct ImportProgress {
+    pub state: ImportState,
+    pub rows_total: usize,
+    pub rows_done: usize,
+    pub imported: usize,
+    pub skipped: usize,
+    pub started: Instant,
+}
+
+impl ImportProgress {
+    pub fn rows_per_sec(&self) -> f64 { self.rows_done as f64 / self.started.elapsed().as_secs_f64().max(0.001) }
+
+    pub fn eta(&self) -> Option<Duration> {
+        let rate = self.rows_per_sec();
+        (self.rows_done > 0 && rate > 0.0).then(|| Duration::from_secs_f64(self.rows_total.saturating_sub(self.rows_done) as f64 / rate))
+    }
+
+    pub fn fraction(&self) -> f32 { if self.rows_total == 0 { 0.0 } else { self.rows_done as f32 / self.rows_total as f32 } }
+
+    pub fn is_running(&self) -> bool { matches!(self.state, ImportState::Reading | ImportState::Importing) }
+}
+
+pub struct ImportJob {
+    pub source: PathBuf,
+    progress: Arc<Mutex<ImportProgress>>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl ImportJob {
+    pub fn start(source: PathBuf) -> Self {
+        let progress = Arc::new(Mutex::new(ImportProgress { state: ImportState::Reading, rows_total: 0, rows_done: 0, imported: 0, skipped: 0, started: Instant::now() }));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (thread_source, thread_progress, thread_cancel) = (source.clone(), progress.clone(), cancel.clone());
+        thread::spawn(move || {
+            let result = run_import(&thread_source, &thread_progress, &thread_cancel);
+            let mut progress = thread_progress.lock().unwrap();
+            progress.state = match result {
+                Ok(()) if thread_cancel.load(Ordering::Relaxed) => ImportState::Cancelled,
+                Ok(()) => ImportState::Done,
+                Err(e) => { error!("Import of '{}' failed: {}", thread_source.display(), e); ImportState::Failed(e.to_string()) }
+            };
+            info!("Import of '{}' finished ({:?}): {} imported, {} skipped.", thread_source.display(), progress.state, progress.imported, progress.skipped);
+        });
+        Self { source, progress, cancel }
+    }
+
+    pub fn progress(&self) -> ImportProgress { self.progress.lock().unwrap().clone() }
+
+    /// Stops after the batch in progress; batches already committed stay imported.
+    pub fn cancel(&self) { self.cancel.store(true, Ordering::Relaxed); }
+}
+
+fn run_import(source: &Path, progress: &Mutex<ImportProgress>, cancel: &AtomicBool) -> io::Result<()> {
+    info!("Importing history from '{}'.", source.display());
+    let records = read_log_records(source);
+    if records.is_empty() { return Err(io::Error::new(io::ErrorKind::InvalidData, "no readable rows")); }
+    { let mut p = progress.lock().unwrap(); p.rows_total = records.len(); p.state = ImportState::Importing; }
+    for batch in records.chunks(BATCH_ROWS) {
+        if cancel.load(Ordering::Relaxed) { break; }
+        let points: Vec<HistoryPoint> = batch.iter().filter_map(parse_history_record).collect();
+        let mut by_day: BTreeMap<NaiveDate, Vec<HistoryPoint>> = BTreeMap::new();
+        for p in points.iter() { by_day.entry(p.timestamp.date_naive()).or_default().push(p.clone()); }
+        let mut imported = 0;
+        for (day, day_points) in by_day { imported += merge_into_day_log(day, day_points)?; }
+        let mut p = progress.lock().unwrap();
+        p.rows_done += batch.len();
+        p.imported += imported;
+        p.skipped += batch.len() - imported;
+    }
+    Ok(())
+}
+
+// Merges rows into one daily log, dropping timestamps already present; the file is replaced atomically
+pub fn merge_into_day_log(day: NaiveDate, new_points: Vec<HistoryPoint>) -> io::Result<usize> {
+    let path = paths::log_path(day);
+    let mut points: Vec<HistoryPoint> = if path.exists() { read_log_records(&path).iter().filter_map(parse_history_record).collect() } else { Vec::new() };
+    let mut known: HashSet<NaiveDateTime> = points.iter().map(|p| p.timestamp.naive_local()).collect();
+    let before = points.len();
+    points.extend(new_points.into_iter().filter(|p| known.insert(p.timestamp.naive_local())));
+    let added = points.len() - before;
+    if added == 0 { return Ok(0); }
+    points.sort_by_key(|p| p.timestamp);
+    paths::ensure_dirs();
+    let tmp = path.with_extension("csv.import");
+    {
+        let mut wtr = csv::WriterBuilder::new().delimiter(b',').from_path(&tmp)?;
+        wtr.write_record(["DateTime", "Temperature", "Humidity"])?;
+        for p in &points { wtr.write_record(&[format_log_timestamp(p.timestamp), format!("{:.1}", p.temp), p.hum.to_string()])?; }
+        wtr.flush()?;
+    }
+    fs::rename(&tmp, &path)?;
+    Ok(added)
+}
@@ -0,0 +1,94 @@
+// --- InfluxDB v2 forwarding (HTTP write API, line protocol) ---
+
+use log::{debug, error, info, warn};
+use std::collections::VecDeque;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+use crate::model::{BleDataPoint, Config};
+use crate::sink_stats::{self, Sink};
+
+const MAX_BUFFERED_LINES: usize = 10_000;
+const MAX_BATCH_LINES: usize = 500;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(5);
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+// Tag values must escape commas, spaces and equal signs
+fn escape_tag(value: &str) -> String {
+    value.replace(',', "\\,").replace(' ', "\\ ").replace('=', "\\=")
+}
+
+pub fn to_line_protocol(measurement: &str, data: &BleDataPoint) -> String {
+    format!("{},device={} temperature={:.1},humidity={}i {}", escape_tag(measurement), escape_tag(&data.device_id), data.temp, data.hum, data.timestamp.timestamp())
+}
+
+// Err((retryable, message)); a 400 means the payload itself is rejected, so retrying is pointless
+fn write_batch(config: &Config, lines: &[String]) -> Result<(), (bool, String)> {
+    let url = format!("{}/api/v2/write", config.influx_url.trim_end_matches('/'));
+    ureq::post(&url)
+        .query("org", &config.influx_org)
+        .query("bucket", &config.influx_bucket)
+        .query("precision", "s")
+        .set("Authorization", &format!("Token {}", config.influx_token))
+        .set("Content-Type", "text/plain; charset=utf-8")
+        .timeout(Duration::from_secs(10))
+        .send_string(&lines.join("\n"))
+        .map(|_| ())
+        .map_err(|e| match e {
+            ureq::Error::Status(400, response) => (false, format!("rejected: {}", response.into_string().unwrap_or_default())),
+            other => (true, other.to_string()),
+        })
+}
+
+/// Runs on its own thread so a slow or unreachable server never blocks CSV logging.
+/// Lines are buffered and retried with exponential backoff; the oldest are dropped once the buffer is full.
+/// After `stop` it only waits for the channel to close, then skips the backoff for one last attempt with everything queued.
+pub fn run_writer(rx: mpsc::Receiver<String>, shared_config: Arc<Mutex<Config>>, stop: watch::Receiver<bool>) {
+    info!("Starting InfluxDB writer.");
+    let mut buffer: VecDeque<String> = VecDeque::new();
+    let mut backoff = INITIAL_BACKOFF;
+    let mut next_attempt = Instant::now();
+    loop {
+        let closed = match rx.recv_timeout(Duration::from_secs(1)) {
+            Ok(line) => {
+                if buffer.len() >= MAX_BUFFERED_LINES { buffer.pop_front(); warn!("InfluxDB buffer full, dropping oldest reading."); }
+                buffer.push_back(line);
+                false
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => false,
+            Err(mpsc::RecvTimeoutError::Disconnected) => true,
+        };
+        let config = shared_config.lock().unwrap().clone();
+        if !config.influx_enabled { buffer.clear(); }
+        if !buffer.is_empty() && (closed || (!*stop.borrow() && Instant::now() >= next_attempt)) { flush(&config, &mut buffer, &mut backoff, &mut next_attempt); }
+        if closed {
+            if !buffer.is_empty() { warn!("InfluxDB writer stopping with {} unsent lines.", buffer.len()); }
+            info!("InfluxDB writer terminated.");
+            break;
+        }
+    }
+}
+
+fn flush(config: &Config, buffer: &mut VecDeque<String>, backoff: &mut Duration, next_attempt: &mut Instant) {
+    while !buffer.is_empty() {
+        let batch: Vec<String> = buffer.iter().take(MAX_BATCH_LINES).cloned().collect();
+        match sink_stats::timed(Sink::Influx, || write_batch(config, &batch)) {
+            Ok(()) => {
+                debug!("Wrote {} lines to InfluxDB.", batch.len());
+                buffer.drain(..batch.len());
+                *backoff = INITIAL_BACKOFF;
+            }
+            Err((false, e)) => {
+                error!("InfluxDB rejected {} lines, dropping them: {}", batch.len(), e);
+                buffer.drain(..batch.len());
+            }
+            Err((true, e)) => {
+                error!("InfluxDB write failed ({} lines pending), retrying in {}s: {}", buffer.len(), backoff.as_secs(), e);
+                *next_attempt = Instant::now() + *backoff;
+                *backoff = (*backoff * 2).min(MAX_BACKOFF);
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+// --- Single-instance guard: a second launch brings the running window to the front instead of scanning too ---
+
+use log::{info, warn};
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+const FOCUS_REQUEST: &str = "focus";
+
+/// Held for the life of the process; dropping it lets another instance start.
+pub struct InstanceGuard { _lock: platform::Lock, listener: Option<TcpListener> }
+
+// One instance per configuration file, so a second portable copy or a `--config` launch for another sensor can still run
+fn instance_key(config: &Path) -> String {
+    let mut hasher = DefaultHasher::new();
+    config.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Takes the lock for the configuration file; when another instance holds it, asks that one to show its window and returns `None`.
+pub fn acquire(config: &Path) -> Option<InstanceGuard> {
+    if let Some(dir) = config.parent() { let _ = fs::create_dir_all(dir); }
+    // Next to the configuration, e.g. `config.lock` and `config.port`
+    let port_file = config.with_extension("port");
+    let Some(lock) = platform::Lock::acquire(&config.with_extension("lock"), &instance_key(config)) else {
+        info!("Another instance is running for '{}', bringing it to the front.", config.display());
+        if let Err(e) = request_focus(&port_file) { warn!("Could not reach the running instance: {}", e); }
+        return None;
+    };
+    // Port 0 lets the system pick a free port; it is published for later launches
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).ok();
+    if let Some(port) = listener.as_ref().and_then(|l| l.local_addr().ok()).map(|a| a.port()) {
+        if let Err(e) = fs::write(&port_file, port.to_string()) { warn!("Could not publish the instance port: {}", e); }
+    }
+    Some(InstanceGuard { _lock: lock, listener })
+}
+
+fn request_focus(port_file: &Path) -> std::io::Result<()> {
+    let port: u16 = fs::read_to_string(port_file)?.trim().parse().map_err(std::io::Error::other)?;
+    let mut stream = TcpStream::connect_timeout(&(Ipv4Addr::LOCALHOST, port).into(), Duration::from_secs(2))?;
+    writeln!(stream, "{}", FOCUS_REQUEST)
+}
+
+impl InstanceGuard {
+    /// Calls `on_focus` whenever another launch asks for the window; keeps the lock until the process exits.
+    pub fn listen(self, on_focus: impl Fn() + Send + 'static) {
+        thread::spawn(move || {
+            // Moves the whole guard in (a closure would only capture the listener), so the lock lives as long as the thread
+            let guard = self;
+            let Some(listener) = &guard.listener else { loop { thread::park(); } };
+            for stream in listener.incoming().flatten() {
+                let mut line = String::new();
+                let _ = stream.set_read_timeout(Some(Duration::from_secs(2)));
+                if BufReader::new(stream).read_line(&mut line).is_ok() && line.trim() == FOCUS_REQUEST { info!("Second launch detected, showing the window."); on_focus(); }
+            }
+        });
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::ffi::c_void;
+    use std::path::Path;
+
+    const ERROR_ALREADY_EXISTS: u32 = 183;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn CreateMutexW(attributes: *const c_void, initial_owner: i32, name: *const u16) -> *mut c_void;
+        fn GetLastError() -> u32;
+        fn CloseHandle(handle: *mut c_void) -> i32;
+    }
+
+    /// Named mutex in the session namespace; Windows releases it when the process ends, even after a crash.
+    pub struct Lock(*mut c_void);
+
+    // The handle is only closed on drop, never used concurrently
+    unsafe impl Send for Lock {}
+
+    impl Lock {
+        pub fn acquire(_lock_file: &Path, key: &str) -> Option<Self> {
+            let name: Vec<u16> = format!("Local\\TempMonitor_{}", key).encode_utf16().chain(std::iter::once(0)).collect();
+            // SAFETY: the name is NUL-terminated; a null handle means failure
+            let handle = unsafe { CreateMutexW(std::ptr::null(), 0, name.as_ptr()) };
+            // Without a mutex the app still starts, just unguarded
+            if handle.is_null() { return Some(Self(handle)); }
+            // SAFETY: reads the error of the CreateMutexW call above on this thread
+            if unsafe { GetLastError() } == ERROR_ALREADY_EXISTS {
+                // SAFETY: the handle was returned by CreateMutexW and is not used again
+                unsafe { CloseHandle(handle) };
+                return None;
+            }
+            Some(Self(handle))
+        }
+    }
+
+    impl Drop for Lock {
+        fn drop(&mut self) {
+            // SAFETY: the handle came from CreateMutexW and is closed only here
+            if !self.0.is_null() { unsafe { CloseHandle(self.0) }; }
+        }
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use std::fs::{File, OpenOptions, TryLockError};
+    use std::path::Path;
+
+    /// Advisory lock on the lock file; the system releases it when the process ends, even after a crash.
+    pub struct Lock { _file: Option<File> }
+
+    impl Lock {
+        /// `None` only when another process holds the lock; a file that cannot be locked does not block starting.
+        pub fn acquire(lock_file: &Path, _key: &str) -> Option<Self> {
+            let Ok(file) = OpenOptions::new().create(true).truncate(false).write(true).open(lock_file) else { return Some(Self { _file: None }); };
+            match file.try_lock() {
+                Err(TryLockError::WouldBlock) => None,
+                _ => Some(Self { _file: Some(file) }),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn second_instance_is_refused_and_focuses_the_first() {
+        let dir = std::env::temp_dir().join(format!("temp_monitor_test_{}_instance", std::process::id()));
+        let config = dir.join("config.json");
+        let first = acquire(&config).expect("first instance starts");
+        let (tx, rx) = std::sync::mpsc::channel();
+        first.listen(move || { let _ = tx.send(()); });
+        assert!(acquire(&config).is_none());
+        assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+        // Another configuration file (a second portable copy, or `--config` for another sensor) has its own instance
+        assert!(acquire(&dir.join("cabin.json")).is_some());
+        let other = dir.with_file_name(format!("temp_monitor_test_{}_instance_b", std::process::id()));
+        assert!(acquire(&other.join("config.json")).is_some());
+        let _ = fs::remove_dir_all(&other);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,131 @@
+// --- Crash-safe appends to the daily logs (journal + fsync) and a check/repair for damaged rows ---
+
+use chrono::{Duration as ChronoDuration, Local};
+use log::{info, warn};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::edit::LOG_WRITE_LOCK;
+use crate::paths;
+use crate::storage::parse_history_record;
+
+// Holds the row being appended: target file name on the first line, the row on the second
+const JOURNAL_FILE_NAME: &str = "pending_row.journal";
+const HEADER: &str = "DateTime,Temperature,Humidity";
+
+fn journal_path() -> PathBuf { paths::data_dir().join(JOURNAL_FILE_NAME) }
+
+pub(crate) fn write_synced(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut file = fs::File::create(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+// Appends `line` (with its newline) in one write, adding the header to a new file and cutting off
+// the torn tail of an earlier interrupted write so the row cannot be glued onto it
+pub(crate) fn append_line(path: &Path, line: &str) -> io::Result<()> {
+    let mut file = OpenOptions::new().read(true).append(true).create(true).open(path)?;
+    let len = file.metadata()?.len();
+    let mut buffer = String::new();
+    if len == 0 {
+        buffer.push_str(HEADER);
+        buffer.push('\n');
+    } else {
+        let mut last = [0u8; 1];
+        file.seek(SeekFrom::Start(len - 1))?;
+        file.read_exact(&mut last)?;
+        if last[0] != b'\n' { buffer.push('\n'); }
+    }
+    buffer.push_str(line);
+    buffer.push('\n');
+    file.write_all(buffer.as_bytes())?;
+    file.sync_data()
+}
+
+/// Appends one CSV row to `path`: the row is first journaled and synced, then appended and synced,
+/// then the journal is removed. A crash in between is finished by `recover` on the next start.
+pub fn append_row(path: &Path, fields: &[String]) -> io::Result<()> {
+    let line = fields.join(",");
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let journal = journal_path();
+    write_synced(&journal, format!("{}\n{}\n", file_name, line).as_bytes())?;
+    append_line(path, &line)?;
+    fs::remove_file(&journal)
+}
+
+/// Completes a row left in the journal by a crash. Returns a note for the user when one was recovered.
+pub fn recover() -> Option<String> {
+    let journal = journal_path();
+    let text = fs::read_to_string(&journal).ok()?;
+    let mut lines = text.lines();
+    let (Some(file_name), Some(line)) = (lines.next(), lines.next()) else { let _ = fs::remove_file(&journal); return None; };
+    let path = paths::data_dir().join(file_name);
+    let already_written = fs::read_to_string(&path).map(|log| log.lines().any(|l| l == line)).unwrap_or(false);
+    let result = if already_written { Ok(()) } else { append_line(&path, line) };
+    match result.and_then(|_| fs::remove_file(&journal)) {
+        Ok(()) if !already_written => { info!("Recovered an interrupted write to '{}'.", path.display()); Some(format!("Recovered a reading interrupted by a crash in {}", file_name)) }
+        Ok(()) => None,
+        Err(e) => { warn!("Could not recover journaled row for '{}': {}", path.display(), e); None }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DamagedLog { pub path: PathBuf, pub bad_lines: Vec<(usize, String)> }
+
+pub(crate) fn delimiter(header: &str) -> u8 { if !header.contains(',') && header.contains(';') { b';' } else { b',' } }
+
+/// Lines (1-based number, text) of a daily log that do not parse as readings; the header and blank lines are fine.
+pub fn check(path: &Path) -> io::Result<Vec<(usize, String)>> {
+    let bytes = fs::read(path)?;
+    let text = String::from_utf8_lossy(&bytes);
+    let mut lines = text.lines().enumerate();
+    let Some((_, header)) = lines.next() else { return Ok(Vec::new()); };
+    let delimiter = delimiter(header);
+    Ok(lines.filter(|(_, line)| !line.trim().is_empty()).filter(|(_, line)| {
+        let mut rdr = csv::ReaderBuilder::new().delimiter(delimiter).has_headers(false).flexible(true).from_reader(line.as_bytes());
+        rdr.records().next().and_then(Result::ok).is_none_or(|record| parse_history_record(&record).is_none())
+    }).map(|(i, line)| (i + 1, line.to_string())).collect())
+}
+
+fn log_files() -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(paths::data_dir()) else { return Vec::new(); };
+    let mut files: Vec<PathBuf> = entries.flatten().map(|e| e.path())
+        .filter(|p| p.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with("log_") && n.ends_with(".csv")))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Checks every daily log, or only the last `recent_days` ones (the startup check).
+pub fn check_logs(recent_days: Option<i64>) -> Vec<DamagedLog> {
+    let since = recent_days.map(|days| paths::log_path(Local::now().date_naive() - ChronoDuration::days(days)));
+    log_files().into_iter()
+        .filter(|p| since.as_ref().is_none_or(|since| p >= since))
+        .filter_map(|path| match check(&path) {
+            Ok(bad_lines) if !bad_lines.is_empty() => Some(DamagedLog { path, bad_lines }),
+            Ok(_) => None,
+            Err(e) => { warn!("Could not check '{}': {}", path.display(), e); None }
+        })
+        .collect()
+}
+
+/// Moves the damaged lines to `<log>.corrupt.txt` (appending, with line numbers) and replaces the log
+/// with the remaining lines atomically. Returns the number of lines moved.
+pub fn repair(damaged: &DamagedLog) -> io::Result<usize> {
+    let _guard = LOG_WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let text = String::from_utf8_lossy(&fs::read(&damaged.path)?).into_owned();
+    // Match number and text, in case the log changed since it was checked
+    let is_bad = |n: usize, line: &str| damaged.bad_lines.iter().any(|(bad, text)| *bad == n && text == line);
+    let kept: String = text.lines().enumerate().filter(|(i, l)| !is_bad(i + 1, l)).map(|(_, l)| format!("{}\n", l)).collect();
+
+    let mut quarantine = OpenOptions::new().create(true).append(true).open(damaged.path.with_extension("corrupt.txt"))?;
+    for (n, line) in &damaged.bad_lines { writeln!(quarantine, "{}:{}", n, line)?; }
+    quarantine.sync_all()?;
+
+    let tmp = damaged.path.with_extension("csv.repair");
+    write_synced(&tmp, kept.as_bytes())?;
+    fs::rename(&tmp, &damaged.path)?;
+    info!("Repaired '{}': moved {} damaged lines aside.", damaged.path.display(), damaged.bad_lines.len());
+    Ok(damaged.bad_lines.len())
+}
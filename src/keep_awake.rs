@@ -0,0 +1,116 @@
+// --- Keeps the PC from sleeping while readings are being logged (optionally only in scheduled hours), and the display on in kiosk mode ---
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum KeepAwakeMode {
+    #[default]
+    Off,
+    WhileLogging,
+    /// Only inside `keep_awake_windows` (and while logging)
+    Scheduled,
+}
+
+impl KeepAwakeMode {
+    pub const ALL: [KeepAwakeMode; 3] = [KeepAwakeMode::Off, KeepAwakeMode::WhileLogging, KeepAwakeMode::Scheduled];
+
+    pub fn label(&self) -> &'static str {
+        match self { KeepAwakeMode::Off => "Allow sleep", KeepAwakeMode::WhileLogging => "Keep awake while logging", KeepAwakeMode::Scheduled => "Keep awake in scheduled hours" }
+    }
+}
+
+/// Holds the OS sleep inhibition while active; released on drop.
+#[derive(Default)]
+pub struct KeepAwake { inhibitor: Option<platform::Inhibitor>, display: bool }
+
+impl KeepAwake {
+    pub fn is_active(&self) -> bool { self.inhibitor.is_some() }
+
+    /// Requests or releases the inhibition, with `display` also keeping the screen on; does nothing when the state is unchanged.
+    pub fn set(&mut self, active: bool, display: bool) {
+        let display = active && display;
+        if active == self.is_active() && display == self.display { return; }
+        // One request per app: Windows keeps a single execution state per thread, so a change replaces the old one
+        let was_active = self.inhibitor.take().is_some();
+        self.display = false;
+        if active {
+            match platform::Inhibitor::acquire(display) {
+                Ok(inhibitor) => {
+                    info!("{}", if display { "Sleep and display sleep inhibited." } else { "Sleep inhibited while logging." });
+                    self.inhibitor = Some(inhibitor);
+                    self.display = display;
+                }
+                Err(e) => warn!("Could not keep the PC awake: {}", e),
+            }
+        } else if was_active {
+            info!("Sleep allowed again.");
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    const ES_CONTINUOUS: u32 = 0x8000_0000;
+    const ES_SYSTEM_REQUIRED: u32 = 0x0000_0001;
+    const ES_DISPLAY_REQUIRED: u32 = 0x0000_0002;
+
+    #[link(name = "kernel32")]
+    extern "system" { fn SetThreadExecutionState(flags: u32) -> u32; }
+
+    // The execution state belongs to the calling thread, which is the GUI thread for the app's lifetime
+    pub struct Inhibitor;
+
+    impl Inhibitor {
+        pub fn acquire(display: bool) -> Result<Self, String> {
+            let flags = ES_CONTINUOUS | ES_SYSTEM_REQUIRED | if display { ES_DISPLAY_REQUIRED } else { 0 };
+            // SAFETY: plain flag call without pointers
+            if unsafe { SetThreadExecutionState(flags) } == 0 { return Err("SetThreadExecutionState failed".to_string()); }
+            Ok(Inhibitor)
+        }
+    }
+
+    impl Drop for Inhibitor {
+        fn drop(&mut self) {
+            // SAFETY: as above
+            unsafe { SetThreadExecutionState(ES_CONTINUOUS); }
+        }
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use std::process::{Child, Command, Stdio};
+
+    /// A helper process that holds the inhibition for as long as it runs: `caffeinate` on macOS,
+    /// `systemd-inhibit` around a `cat` of our stdin pipe elsewhere, so it also ends if the app crashes. Its idle lock is
+    /// what keeps the screen on under desktops that honour it, so the display flag changes only the stated reason there.
+    pub struct Inhibitor(Child);
+
+    impl Inhibitor {
+        pub fn acquire(display: bool) -> Result<Self, String> {
+            let pid = std::process::id().to_string();
+            let mut command = if cfg!(target_os = "macos") {
+                let mut c = Command::new("caffeinate");
+                c.args(["-i", "-w", &pid]);
+                if display { c.arg("-d"); }
+                c
+            } else {
+                let mut c = Command::new("systemd-inhibit");
+                let why = if display { "--why=Showing the kiosk dashboard" } else { "--why=Logging sensor readings" };
+                c.args(["--what=sleep:idle", "--who=TempMonitor", why, "--mode=block", "cat"]);
+                c
+            };
+            command.stdin(Stdio::piped()).stdout(Stdio::null()).stderr(Stdio::null()).spawn().map(Inhibitor).map_err(|e| e.to_string())
+        }
+    }
+
+    impl Drop for Inhibitor {
+        fn drop(&mut self) {
+            // Closing stdin ends `cat`; caffeinate has no reader and is killed
+            drop(self.0.stdin.take());
+            if cfg!(target_os = "macos") { let _ = self.0.kill(); }
+            let _ = self.0.wait();
+        }
+    }
+}
@@ -0,0 +1,118 @@
+// --- Main window layout: remembered window geometry, and header / plot / footer sizes that adapt to small windows and kiosk mode ---
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+/// Below this width the header's four cells are stacked two by two.
+pub const NARROW_WIDTH: f32 = 700.0;
+/// Below this height the author footer is dropped to leave the room to the plots.
+pub const SHORT_HEIGHT: f32 = 500.0;
+const HEADER: f32 = 0.10;
+/// Height of one header row in points, below which its text would be clipped.
+const HEADER_ROW_MIN: f32 = 64.0;
+const FOOTER: f32 = 0.05;
+/// Kiosk mode: the readings take this share, to be read from across the room.
+const KIOSK_HEADER: f32 = 0.40;
+/// Points the normal header text needs; kiosk text grows with the room it gets beyond that, up to `KIOSK_MAX_SCALE`.
+const HEADER_TEXT_HEIGHT: f32 = 130.0;
+const HEADER_TEXT_WIDTH: f32 = 220.0;
+const KIOSK_MAX_SCALE: f32 = 3.0;
+
+/// Outer position and inner size in points, kept in the config so each configuration (e.g. one per sensor) opens where it was left.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct WindowGeometry { pub x: f32, pub y: f32, pub width: f32, pub height: f32, pub maximized: bool }
+
+impl WindowGeometry {
+    pub const MIN_SIZE: [f32; 2] = [400.0, 300.0];
+
+    pub fn apply(&self, viewport: egui::ViewportBuilder) -> egui::ViewportBuilder {
+        viewport.with_position([self.x, self.y]).with_inner_size([self.width.max(Self::MIN_SIZE[0]), self.height.max(Self::MIN_SIZE[1])]).with_maximized(self.maximized)
+    }
+
+    /// The window as it is now; `None` while minimized (Windows then reports it at -32000, -32000) or before it is shown.
+    /// While maximized the last normal position and size are kept, so un-maximizing after a restart restores them; in fullscreen all of it.
+    pub fn from_viewport(info: &egui::ViewportInfo, last: Option<WindowGeometry>) -> Option<WindowGeometry> {
+        if info.minimized == Some(true) { return None; }
+        if info.fullscreen == Some(true) { return last; }
+        let maximized = info.maximized == Some(true);
+        if maximized { return last.map(|g| WindowGeometry { maximized, ..g }); }
+        let (outer, inner) = (info.outer_rect?, info.inner_rect?);
+        if outer.min.x <= -30000.0 || outer.min.y <= -30000.0 || inner.width() < 1.0 { return None; }
+        Some(WindowGeometry { x: outer.min.x, y: outer.min.y, width: inner.width(), height: inner.height(), maximized })
+    }
+}
+
+/// Shares of the central panel's height for the `StripBuilder`, top to bottom.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MainLayout {
+    /// Header cells in two rows of two instead of one row
+    pub stacked_header: bool,
+    pub header: f32,
+    /// Points the header keeps however small the window gets
+    pub header_min: f32,
+    pub plots: Vec<f32>,
+    /// 0 when the footer is dropped
+    pub footer: f32,
+    /// Size of the header text relative to normal; above 1 only in kiosk mode
+    pub text_scale: f32,
+}
+
+/// Layout for a central panel of `size` points: the fixed proportions at a comfortable size, a taller stacked header
+/// when narrow and no footer when short, the plots sharing what is left in their usual proportions.
+/// Kiosk mode shows only the two readings, in one row, with no footer and text scaled to fill their larger share.
+pub fn main_layout(size: egui::Vec2, combined_plot: bool, extra_plots: usize, kiosk: bool) -> MainLayout {
+    let plots: &[f32] = match (combined_plot, extra_plots) { (true, 0) => &[0.85], (false, 0) => &[0.425, 0.425], (true, 1) => &[0.55, 0.30], (false, 1) => &[0.31, 0.27, 0.27], (true, _) => &[0.45, 0.20, 0.20], (false, _) => &[0.25, 0.20, 0.20, 0.20] };
+    let stacked_header = !kiosk && size.x < NARROW_WIDTH;
+    let header = if kiosk { KIOSK_HEADER } else if stacked_header { HEADER * 2.0 } else { HEADER };
+    let footer = if kiosk || size.y < SHORT_HEIGHT { 0.0 } else { FOOTER };
+    let scale = (1.0 - header - footer) / (1.0 - HEADER - FOOTER);
+    let text_scale = if kiosk { (size.y * header / HEADER_TEXT_HEIGHT).min(size.x / 2.0 / HEADER_TEXT_WIDTH).clamp(1.0, KIOSK_MAX_SCALE) } else { 1.0 };
+    MainLayout { stacked_header, header, header_min: if stacked_header { HEADER_ROW_MIN * 2.0 } else { HEADER_ROW_MIN }, plots: plots.iter().map(|p| p * scale).collect(), footer, text_scale }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Config;
+
+    #[test]
+    fn layout_stacks_the_header_when_narrow_and_window_geometry_skips_minimized_states() {
+        use egui::{pos2, vec2, Rect, ViewportInfo};
+        let total = |l: &MainLayout| l.header + l.plots.iter().sum::<f32>() + l.footer;
+        let wide = main_layout(vec2(1000.0, 700.0), false, 1, false);
+        assert_eq!((wide.stacked_header, wide.plots.len()), (false, 3));
+        assert!((total(&wide) - 1.0).abs() < 1e-4);
+        let small = main_layout(vec2(600.0, 400.0), false, 1, false);
+        assert_eq!((small.stacked_header, small.footer), (true, 0.0));
+        assert!((total(&small) - 1.0).abs() < 1e-4 && small.header > wide.header && small.header_min > wide.header_min);
+        // The plots keep their proportions to each other
+        assert!((small.plots[0] / small.plots[1] - wide.plots[0] / wide.plots[1]).abs() < 1e-4);
+
+        let shown = |outer: Rect| ViewportInfo { outer_rect: Some(outer), inner_rect: Some(Rect::from_min_size(outer.min + vec2(8.0, 30.0), vec2(900.0, 500.0))), minimized: Some(false), maximized: Some(false), ..Default::default() };
+        let normal = WindowGeometry::from_viewport(&shown(Rect::from_min_size(pos2(120.0, 80.0), vec2(916.0, 538.0))), None).unwrap();
+        assert_eq!(normal, WindowGeometry { x: 120.0, y: 80.0, width: 900.0, height: 500.0, maximized: false });
+        assert_eq!(WindowGeometry::from_viewport(&ViewportInfo { minimized: Some(true), ..shown(Rect::ZERO) }, Some(normal)), None);
+        assert_eq!(WindowGeometry::from_viewport(&shown(Rect::from_min_size(pos2(-32000.0, -32000.0), vec2(160.0, 28.0))), Some(normal)), None);
+        // Maximized: the normal geometry is kept for when the window is restored
+        assert_eq!(WindowGeometry::from_viewport(&ViewportInfo { maximized: Some(true), ..shown(Rect::ZERO) }, Some(normal)), Some(WindowGeometry { maximized: true, ..normal }));
+    }
+
+    #[test]
+    fn kiosk_layout_drops_the_footer_and_scales_the_readings_to_the_screen() {
+        use crate::commands::Command;
+        use egui::{vec2, Key, ViewportInfo};
+        let normal = main_layout(vec2(1920.0, 1080.0), false, 0, false);
+        let kiosk = main_layout(vec2(1920.0, 1080.0), false, 0, true);
+        assert_eq!((normal.text_scale, kiosk.footer, kiosk.stacked_header), (1.0, 0.0, false));
+        assert!(kiosk.header > normal.header && kiosk.text_scale > 2.0 && kiosk.text_scale <= 3.0);
+        assert!((kiosk.header + kiosk.plots.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+        // A small portrait panel still gets one row of two readings, never smaller than normal
+        let portrait = main_layout(vec2(480.0, 800.0), false, 0, true);
+        assert!(!portrait.stacked_header && portrait.text_scale >= 1.0 && portrait.text_scale < kiosk.text_scale);
+        assert!(Command::Kiosk.in_simple_mode() && Command::Kiosk.shortcuts().iter().any(|s| s.logical_key == Key::F11));
+        // The fullscreen size is not remembered as the window's own
+        let last = WindowGeometry { x: 120.0, y: 80.0, width: 900.0, height: 500.0, maximized: false };
+        assert_eq!(WindowGeometry::from_viewport(&ViewportInfo { fullscreen: Some(true), minimized: Some(false), ..Default::default() }, Some(last)), Some(last));
+        assert!(!Config::default().kiosk_mode && Config::default().kiosk_keep_display_on);
+    }
+}
@@ -0,0 +1,43 @@
+// --- Plot legend: checkboxes above the temperature and humidity plots that show or hide their series ---
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::tr;
+
+/// Series drawn on one plot; kept in the config so the choice survives a restart.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(default)]
+pub struct SeriesToggles { pub raw: bool, pub smoothed: bool, pub points: bool, pub thresholds: bool }
+
+impl Default for SeriesToggles {
+    fn default() -> Self { Self { raw: true, smoothed: true, points: true, thresholds: true } }
+}
+
+/// Colors of the swatches, matching the plot's own series.
+pub struct LegendColors { pub raw: egui::Color32, pub smoothed: egui::Color32 }
+
+/// One checkbox per series; the smoothed line and thresholds only when turned on in Settings. True when a box changed.
+pub fn show(ui: &mut egui::Ui, toggles: &mut SeriesToggles, colors: LegendColors, smoothing: bool, thresholds: bool) -> bool {
+    let swatch = |symbol: &str, text: &str, color: egui::Color32| egui::RichText::new(format!("{} {}", symbol, text)).color(color);
+    let mut changed = ui.checkbox(&mut toggles.raw, swatch("━", tr("Line"), colors.raw)).changed();
+    if smoothing { changed |= ui.checkbox(&mut toggles.smoothed, swatch("━", tr("Average"), colors.smoothed)).changed(); }
+    changed |= ui.checkbox(&mut toggles.points, swatch("●", tr("Points"), ui.visuals().text_color())).changed();
+    if thresholds { changed |= ui.checkbox(&mut toggles.thresholds, swatch("┄", tr("Thresholds"), egui::Color32::from_rgb(255, 80, 80))).changed(); }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Config;
+
+    #[test]
+    fn plot_series_toggles_default_to_shown_and_keep_unsaved_ones_on() {
+        let config: Config = serde_json::from_str(r#"{"hum_series": {"points": false}}"#).unwrap();
+        assert_eq!(config.temp_series, SeriesToggles::default());
+        assert_eq!(config.hum_series, SeriesToggles { points: false, ..SeriesToggles::default() });
+        let saved: Config = serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+        assert_eq!((saved.temp_series, saved.hum_series), (config.temp_series, config.hum_series));
+    }
+}
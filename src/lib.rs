@@ -0,0 +1,176 @@
+// --- Temperature Monitor library: the modules the binary wires into the window or the headless scanner ---
+
+use eframe::egui;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc as async_mpsc, watch};
+use std::time::Duration;
+use log::info;
+
+mod adaptive;
+mod aggregate;
+mod alarm;
+mod alert_log;
+mod alerts;
+mod autostart;
+mod ble;
+mod bookmarks;
+mod calendar;
+mod capture;
+pub mod cli;
+mod comfort;
+mod commands;
+mod data_table;
+pub mod decoder;
+mod dehumidifier;
+mod distribution;
+mod edit;
+mod email;
+mod evidence;
+mod export;
+mod forecast;
+mod gradient;
+mod heatmap;
+mod history;
+mod history_loader;
+mod i18n;
+mod import;
+mod influx;
+mod instance;
+mod integrity;
+mod keep_awake;
+mod layout;
+mod legend;
+mod link;
+mod logging;
+mod migration;
+#[cfg(test)]
+mod mock_ble;
+pub mod model;
+mod notes;
+mod overlay;
+mod palette;
+mod paths;
+mod plot_image;
+mod power;
+mod processor;
+mod profiles;
+mod quick_export;
+mod repaint;
+mod replay;
+mod report;
+mod retention;
+mod rules;
+mod scan_stats;
+mod schedule;
+mod selftest;
+mod sensors;
+mod share;
+mod simulator;
+mod sink_stats;
+mod sleep;
+mod spike;
+pub mod storage;
+mod sun;
+#[cfg(test)]
+mod test_support;
+#[cfg(test)]
+mod tests;
+mod telegram;
+mod templates;
+mod theme;
+mod thi;
+mod trend;
+mod ui;
+mod units;
+mod virtual_sensor;
+mod vpd;
+mod webhook;
+
+use ble::bluetooth_scanner;
+use instance::InstanceGuard;
+use model::{AppMessage, Config};
+use processor::{background_data_processor, SHUTDOWN_GRACE};
+use storage::load_config;
+use ui::TempMonitorApp;
+
+// Storage locations and logging for either mode; `None` when another copy already runs with this configuration
+fn start(args: &cli::Args) -> Option<(Config, InstanceGuard)> {
+    // Absolute, so the configuration folder and the instance lock do not depend on the working directory
+    if let Some(path) = &args.config { paths::set_config_path_override(Some(std::path::absolute(path).unwrap_or_else(|_| path.clone()))); }
+    if let Some(dir) = args.data_dir_override() {
+        if args.data_dir.is_none() { let _ = fs::create_dir_all(&dir); }
+        paths::set_data_dir_override(Some(std::path::absolute(&dir).unwrap_or(dir)));
+    }
+    let config = load_config();
+    logging::init(&config.log_level);
+    info!("Logger initialized, starting application...");
+    // A second copy would scan too and append duplicate rows to the same logs
+    let instance = instance::acquire(&paths::config_path())?;
+    Some((config, instance))
+}
+
+/// Opens the window with the options parsed by `cli::parse`.
+pub fn run(args: cli::Args) -> Result<(), eframe::Error> {
+    let Some((config, instance)) = start(&args) else { return Ok(()); };
+    let viewport = egui::ViewportBuilder::default().with_inner_size([850.0, 450.0]).with_min_inner_size(layout::WindowGeometry::MIN_SIZE).with_decorations(true).with_transparent(true).with_app_id("temp_monitor_sobes");
+    let viewport = match config.window { Some(window) => window.apply(viewport), None => viewport };
+    let viewport = viewport.with_fullscreen(config.kiosk_mode);
+    // Keeps `frame.info().system_theme` current for the "Follow the system" theme. The geometry is kept in the
+    // config instead of eframe's storage, which all configurations (`--config`) would share.
+    let options = eframe::NativeOptions { viewport, follow_system_theme: true, persist_window: false, ..Default::default() };
+    eframe::run_native("Temperature Monitor", options, Box::new(move |cc| {
+        let ctx = cc.egui_ctx.clone();
+        instance.listen(move || {
+            for command in [egui::ViewportCommand::Visible(true), egui::ViewportCommand::Minimized(false), egui::ViewportCommand::Focus] { ctx.send_viewport_cmd(command); }
+            ctx.request_repaint();
+        });
+        Box::new(TempMonitorApp::new(cc, &args))
+    }))
+}
+
+/// Scanner and processor without a window (logs, Influx and webhooks keep working) until Ctrl+C;
+/// the processor's messages go to `on_message`.
+pub fn run_headless(args: &cli::Args, mut on_message: impl FnMut(&AppMessage)) {
+    let Some((mut config, _instance)) = start(args) else { return; };
+    args.apply_to(&mut config);
+    if args.data_dir_override().is_none() && Path::new(&config.data_folder).is_dir() { paths::set_data_dir_override(Some(PathBuf::from(&config.data_folder))); }
+    if let Some(note) = integrity::recover() { info!("{}", note); }
+    info!("Running headless for '{}', data in '{}'.", config.target_mac, paths::data_dir().display());
+    let (gui_tx, mut gui_rx) = async_mpsc::unbounded_channel(); let (scanner_tx, processor_rx) = async_mpsc::unbounded_channel();
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    // Kept alive so the scanner waits on them instead of seeing closed channels
+    let (_control_tx, control_rx) = async_mpsc::unbounded_channel();
+    let (_scan_settings_tx, scan_settings_rx) = watch::channel(());
+    let shared_config = Arc::new(Mutex::new(config));
+    let rt = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+    let tasks = vec![
+        rt.spawn(background_data_processor(processor_rx, gui_tx, shared_config.clone(), shutdown_rx.clone())),
+        spawn_reading_source(&rt, args, scanner_tx, shared_config, control_rx, scan_settings_rx, shutdown_rx),
+    ];
+    rt.block_on(async {
+        loop {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => break,
+                message = gui_rx.recv() => match message {
+                    Some(message) => on_message(&message),
+                    None => break,
+                },
+            }
+        }
+    });
+    info!("Stopping headless run.");
+    let _ = shutdown_tx.send(true);
+    rt.block_on(async { let _ = tokio::time::timeout(SHUTDOWN_GRACE, futures::future::join_all(tasks)).await; });
+    rt.shutdown_timeout(Duration::from_secs(1));
+}
+
+// Readings come from the Bluetooth scanner, a file with `--replay` or the simulator with `--simulate`; the processor cannot tell the difference
+fn spawn_reading_source(rt: &tokio::runtime::Runtime, args: &cli::Args, tx: async_mpsc::UnboundedSender<AppMessage>, shared_config: Arc<Mutex<Config>>, control: async_mpsc::UnboundedReceiver<AppMessage>, scan_settings: watch::Receiver<()>, shutdown: watch::Receiver<bool>) -> tokio::task::JoinHandle<()> {
+    match &args.replay {
+        Some(path) => rt.spawn(replay::run(path.clone(), args.replay_speed(), tx, shutdown)),
+        None if args.simulate => rt.spawn(simulator::run(tx, shared_config, shutdown)),
+        None => rt.spawn(bluetooth_scanner(tx, shared_config, control, scan_settings, shutdown)),
+    }
+}
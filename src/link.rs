@@ -0,0 +1,76 @@
+// --- Link quality: packets received per hour and RSSI statistics, for finding a spot with reliable reception ---
+
+use chrono::{DateTime, Duration as ChronoDuration, DurationRound, Local};
+use std::collections::VecDeque;
+
+use crate::model::HistoryPoint;
+
+/// Hours of packet arrivals kept for the per-hour counts.
+pub const PACKET_HOURS: i64 = 24;
+
+/// Arrival times of decoded advertisements of the target sensor, including the duplicates that are not stored.
+#[derive(Default)]
+pub struct PacketLog { arrivals: VecDeque<DateTime<Local>> }
+
+impl PacketLog {
+    pub fn record(&mut self, at: DateTime<Local>) {
+        self.arrivals.push_back(at);
+        while self.arrivals.front().is_some_and(|t| at - *t > ChronoDuration::hours(PACKET_HOURS)) { self.arrivals.pop_front(); }
+    }
+
+    /// Packets in each clock hour of the last `PACKET_HOURS`, oldest first; the current hour is still filling.
+    pub fn per_hour(&self, now: DateTime<Local>) -> Vec<(DateTime<Local>, usize)> {
+        let current = now.duration_trunc(ChronoDuration::hours(1)).unwrap_or(now);
+        let first = current - ChronoDuration::hours(PACKET_HOURS - 1);
+        let mut counts = vec![0; PACKET_HOURS as usize];
+        for t in self.arrivals.iter().filter(|t| **t >= first && **t <= now) {
+            if let Some(count) = counts.get_mut((*t - first).num_hours() as usize) { *count += 1; }
+        }
+        counts.into_iter().enumerate().map(|(i, n)| (first + ChronoDuration::hours(i as i64), n)).collect()
+    }
+
+    /// Packets within the hour before `now`.
+    pub fn last_hour(&self, now: DateTime<Local>) -> usize { self.arrivals.iter().filter(|t| now - **t <= ChronoDuration::hours(1)).count() }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RssiStats { pub mean: f64, pub min: i16, pub max: i16, pub samples: usize }
+
+impl RssiStats {
+    /// Over the points that carry a signal strength; readings loaded from the CSV log have none.
+    pub fn of<'a>(points: impl Iterator<Item = &'a HistoryPoint>) -> Option<RssiStats> {
+        let values: Vec<i16> = points.filter_map(|p| p.rssi).collect();
+        Some(RssiStats { mean: values.iter().map(|v| *v as f64).sum::<f64>() / values.len() as f64, min: *values.iter().min()?, max: *values.iter().max()?, samples: values.len() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+
+    #[test]
+    fn link_stats_count_packets_per_clock_hour_and_skip_readings_without_rssi() {
+        use crate::history::{push_capped, HistoryEviction};
+        use chrono::TimeZone;
+        let now = Local.with_ymd_and_hms(2025, 1, 15, 10, 30, 0).unwrap();
+        let mut packets = PacketLog::default();
+        // One from two days ago is dropped, two in the 9:00 hour, three in the current one
+        for minutes_ago in [48 * 60, 85, 60, 20, 10, 0] { packets.record(now - ChronoDuration::minutes(minutes_ago)); }
+        let per_hour = packets.per_hour(now);
+        assert_eq!(per_hour.len(), PACKET_HOURS as usize);
+        assert_eq!(per_hour.last().unwrap(), &(Local.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap(), 3));
+        assert_eq!(per_hour[per_hour.len() - 2].1, 2);
+        assert_eq!(per_hour.iter().map(|(_, n)| n).sum::<usize>(), 5);
+        assert_eq!(packets.last_hour(now), 4);
+
+        let signal = |rssi| HistoryPoint { rssi, ..point(now, 21.0, 50) };
+        let history = [signal(None), signal(Some(-70)), signal(Some(-60)), signal(Some(-80))];
+        assert_eq!(RssiStats::of(history.iter()), Some(RssiStats { mean: -70.0, min: -80, max: -60, samples: 3 }));
+        assert_eq!(RssiStats::of(history[..1].iter()), None);
+        // Downsampled pairs keep the mean signal strength
+        let mut capped = VecDeque::new();
+        for p in [signal(Some(-70)), signal(Some(-60)), signal(None), signal(Some(-50)), signal(Some(-40))] { push_capped(&mut capped, p, 4, HistoryEviction::Downsample); }
+        assert_eq!(capped[0].rssi, Some(-65));
+    }
+}
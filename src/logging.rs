@@ -0,0 +1,74 @@
+// --- Application log: console in debug builds, size-rotated `app.log` in the data folder always ---
+
+use log::{error, LevelFilter};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::paths;
+
+pub const LOG_FILE_NAME: &str = "app.log";
+pub const LEVELS: [&str; 5] = ["error", "warn", "info", "debug", "trace"];
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+const KEEP_ROTATED: usize = 3;
+
+/// Appends to `app.log`; when it grows past the limit it becomes `app.1.log` (older ones shift up, the last is dropped).
+struct RotatingFile { path: PathBuf, file: File, size: u64, }
+
+impl RotatingFile {
+    fn open(path: PathBuf) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { path, file, size })
+    }
+
+    fn rotated_path(&self, n: usize) -> PathBuf { self.path.with_extension(format!("{}.log", n)) }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let _ = fs::remove_file(self.rotated_path(KEEP_ROTATED));
+        for n in (1..KEEP_ROTATED).rev() { let _ = fs::rename(self.rotated_path(n), self.rotated_path(n + 1)); }
+        fs::rename(&self.path, self.rotated_path(1))?;
+        *self = Self::open(self.path.clone())?;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > MAX_LOG_BYTES { self.rotate()?; }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> { self.file.flush() }
+}
+
+pub fn parse_level(level: &str) -> LevelFilter { level.parse().unwrap_or(LevelFilter::Info) }
+
+pub fn log_path() -> PathBuf { paths::data_dir().join(LOG_FILE_NAME) }
+
+/// Installs the logger and a panic hook that records panics before the default handler runs.
+pub fn init(level: &str) {
+    paths::ensure_dirs();
+    let mut dispatch = fern::Dispatch::new()
+        .format(|out, message, record| out.finish(format_args!("[{}] [{}] - {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"), record.level(), message)))
+        .level(LevelFilter::Trace);
+    #[cfg(debug_assertions)]
+    { dispatch = dispatch.chain(io::stderr()); }
+    match RotatingFile::open(log_path()) {
+        Ok(file) => dispatch = dispatch.chain(Box::new(file) as Box<dyn Write + Send>),
+        Err(e) => eprintln!("Cannot open log file '{}': {}", log_path().display(), e),
+    }
+    if dispatch.apply().is_err() { return; }
+    set_level(level);
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        error!("Panic: {}", info);
+        default_hook(info);
+    }));
+}
+
+/// Changes the level at runtime (Settings); the dispatcher itself passes everything through.
+pub fn set_level(level: &str) { log::set_max_level(parse_level(level)); }
@@ -0,0 +1,84 @@
+// --- One-time migration of a legacy layout (config + logs next to the executable) into the active storage ---
+
+use chrono::{Local, NaiveDate};
+use log::{info, warn};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::storage::{parse_history_record, read_log_records};
+use crate::{import, paths};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MigrationAction {
+    Move,
+    /// A daily log already exists in the target; rows are merged, duplicates skipped
+    Merge,
+    /// The target configuration is kept as `config.json.bak` and replaced
+    Replace,
+}
+
+impl MigrationAction {
+    pub fn label(&self) -> &'static str {
+        match self { MigrationAction::Move => "move", MigrationAction::Merge => "merge into existing log", MigrationAction::Replace => "replace (old kept as .bak)" }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MigrationItem { pub source: PathBuf, pub target: PathBuf, pub action: MigrationAction, }
+
+fn log_date(path: &Path) -> Option<NaiveDate> {
+    let name = path.file_name()?.to_str()?;
+    NaiveDate::parse_from_str(name.strip_prefix("log_")?.strip_suffix(".csv")?, "%Y-%m-%d").ok()
+}
+
+/// Files left next to the executable by versions that stored everything in the working directory.
+/// Empty in portable mode, where that folder is the active storage.
+pub fn plan() -> Vec<MigrationItem> {
+    let legacy_dir = paths::exe_dir();
+    if paths::storage_mode() == paths::StorageMode::Portable || legacy_dir == paths::data_dir() { return Vec::new(); }
+    let mut items = Vec::new();
+    let legacy_config = legacy_dir.join(paths::CONFIG_FILE_NAME);
+    if legacy_config.exists() {
+        let target = paths::config_path();
+        let action = if target.exists() { MigrationAction::Replace } else { MigrationAction::Move };
+        items.push(MigrationItem { source: legacy_config, target, action });
+    }
+    let mut logs: Vec<(NaiveDate, PathBuf)> = fs::read_dir(&legacy_dir).into_iter().flatten().flatten().map(|e| e.path()).filter_map(|p| log_date(&p).map(|d| (d, p))).collect();
+    logs.sort();
+    for (date, source) in logs {
+        let target = paths::log_path(date);
+        let action = if target.exists() { MigrationAction::Merge } else { MigrationAction::Move };
+        items.push(MigrationItem { source, target, action });
+    }
+    items
+}
+
+/// Copies every item into place, then moves the originals into a `legacy-backup-<timestamp>` folder
+/// next to the executable. Returns that folder.
+pub fn run(items: &[MigrationItem]) -> io::Result<PathBuf> {
+    paths::ensure_dirs();
+    let backup_dir = paths::exe_dir().join(Local::now().format("legacy-backup-%Y%m%d-%H%M%S").to_string());
+    fs::create_dir_all(&backup_dir)?;
+    for item in items {
+        match item.action {
+            MigrationAction::Move => { fs::copy(&item.source, &item.target)?; }
+            MigrationAction::Replace => {
+                fs::copy(&item.target, item.target.with_extension("json.bak"))?;
+                fs::copy(&item.source, &item.target)?;
+            }
+            MigrationAction::Merge => {
+                let Some(date) = log_date(&item.source) else { continue; };
+                let added = import::merge_into_day_log(date, read_log_records(&item.source).iter().filter_map(parse_history_record).collect())?;
+                info!("Merged {} rows from '{}'.", added, item.source.display());
+            }
+        }
+        let backup = backup_dir.join(item.source.file_name().unwrap_or_default());
+        if fs::rename(&item.source, &backup).is_err() {
+            fs::copy(&item.source, &backup)?;
+            if let Err(e) = fs::remove_file(&item.source) { warn!("Could not remove '{}' after backing it up: {}", item.source.display(), e); }
+        }
+    }
+    info!("Migrated {} legacy files, originals backed up in '{}'.", items.len(), backup_dir.display());
+    Ok(backup_dir)
+}
@@ -0,0 +1,127 @@
+// --- Mock btleplug Central/Peripheral replaying scripted advertisements (tests only) ---
+
+use async_trait::async_trait;
+use btleplug::api::bleuuid::uuid_from_u16;
+use btleplug::api::{BDAddr, Central, CentralEvent, CentralState, Characteristic, Descriptor, Peripheral, PeripheralProperties, ScanFilter, Service, ValueNotification, WriteType};
+use btleplug::platform::PeripheralId;
+use btleplug::{Error, Result};
+use futures::stream::{self, Stream, StreamExt};
+use std::collections::{BTreeSet, HashMap};
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+/// One advertisement as the OS would report it.
+#[derive(Clone, Debug)]
+pub struct ScriptedAdvertisement {
+    pub address: String,
+    pub local_name: Option<String>,
+    pub rssi: Option<i16>,
+    pub manufacturer_data: HashMap<u16, Vec<u8>>,
+    /// Keyed by 16-bit service UUID
+    pub service_data: HashMap<u16, Vec<u8>>,
+}
+
+impl ScriptedAdvertisement {
+    /// TP357 layout: the low byte of the temperature travels in the company id's high byte.
+    pub fn tp357(address: &str, temp: f32, hum: u8) -> Self {
+        let [lo, hi] = ((temp * 10.0).round() as i16).to_le_bytes();
+        let company_id = u16::from_le_bytes([0xC2, lo]);
+        Self { address: address.to_string(), local_name: None, rssi: Some(-60), manufacturer_data: HashMap::from([(company_id, vec![hi, hum, 0x2C])]), service_data: HashMap::new() }
+    }
+
+    pub fn named(mut self, local_name: &str) -> Self { self.local_name = Some(local_name.to_string()); self }
+}
+
+// PeripheralId has no public constructor, but its serde form is stable per platform
+pub fn peripheral_id(address: &str) -> PeripheralId {
+    #[cfg(target_os = "linux")]
+    let json = format!(r#"{{"object_path":"/org/bluez/hci0/dev_{}"}}"#, address.replace(':', "_"));
+    #[cfg(target_os = "windows")]
+    let json = format!(r#""{}""#, address);
+    #[cfg(target_os = "macos")]
+    let json = format!(r#""00000000-0000-0000-0000-{}""#, address.replace(':', ""));
+    serde_json::from_str(&json).expect("valid mock peripheral id")
+}
+
+#[derive(Clone, Debug)]
+pub struct MockPeripheral {
+    id: PeripheralId,
+    properties: Arc<Mutex<PeripheralProperties>>,
+}
+
+#[async_trait]
+impl Peripheral for MockPeripheral {
+    fn id(&self) -> PeripheralId { self.id.clone() }
+    fn address(&self) -> BDAddr { self.properties.lock().unwrap().address }
+    async fn properties(&self) -> Result<Option<PeripheralProperties>> { Ok(Some(self.properties.lock().unwrap().clone())) }
+    fn services(&self) -> BTreeSet<Service> { BTreeSet::new() }
+    async fn is_connected(&self) -> Result<bool> { Ok(false) }
+    async fn connect(&self) -> Result<()> { Err(Error::NotSupported("mock peripheral".into())) }
+    async fn disconnect(&self) -> Result<()> { Ok(()) }
+    async fn discover_services(&self) -> Result<()> { Ok(()) }
+    async fn write(&self, _characteristic: &Characteristic, _data: &[u8], _write_type: WriteType) -> Result<()> { Err(Error::NotConnected) }
+    async fn read(&self, _characteristic: &Characteristic) -> Result<Vec<u8>> { Err(Error::NotConnected) }
+    async fn subscribe(&self, _characteristic: &Characteristic) -> Result<()> { Err(Error::NotConnected) }
+    async fn unsubscribe(&self, _characteristic: &Characteristic) -> Result<()> { Err(Error::NotConnected) }
+    async fn notifications(&self) -> Result<Pin<Box<dyn Stream<Item = ValueNotification> + Send>>> { Ok(Box::pin(stream::empty())) }
+    async fn write_descriptor(&self, _descriptor: &Descriptor, _data: &[u8]) -> Result<()> { Err(Error::NotConnected) }
+    async fn read_descriptor(&self, _descriptor: &Descriptor) -> Result<Vec<u8>> { Err(Error::NotConnected) }
+}
+
+/// Emits one discovery/update event per scripted advertisement. The peripheral's properties are
+/// updated lazily as each event is pulled, exactly like a real adapter refreshing its cache.
+#[derive(Clone, Debug, Default)]
+pub struct MockCentral {
+    script: Vec<ScriptedAdvertisement>,
+    peripherals: Arc<Mutex<HashMap<PeripheralId, MockPeripheral>>>,
+    /// Advertisements played before the radio reports itself switched off
+    radio_off_after: Option<usize>,
+}
+
+impl MockCentral {
+    pub fn new(script: Vec<ScriptedAdvertisement>) -> Self { Self { script, peripherals: Arc::default(), radio_off_after: None } }
+
+    /// Switch the radio off after `played` advertisements, as toggling Bluetooth mid-scan does.
+    pub fn radio_off_after(mut self, played: usize) -> Self { self.radio_off_after = Some(played); self }
+}
+
+#[async_trait]
+impl Central for MockCentral {
+    type Peripheral = MockPeripheral;
+
+    async fn events(&self) -> Result<Pin<Box<dyn Stream<Item = CentralEvent> + Send>>> {
+        let peripherals = self.peripherals.clone();
+        let events = stream::iter(self.script.clone()).map(move |adv| {
+            let id = peripheral_id(&adv.address);
+            let properties = PeripheralProperties {
+                address: BDAddr::from_str(&adv.address).expect("valid mock address"),
+                local_name: adv.local_name,
+                rssi: adv.rssi,
+                manufacturer_data: adv.manufacturer_data,
+                service_data: adv.service_data.into_iter().map(|(uuid, data)| (uuid_from_u16(uuid), data)).collect(),
+                ..Default::default()
+            };
+            let mut peripherals = peripherals.lock().unwrap();
+            match peripherals.get(&id) {
+                Some(existing) => { *existing.properties.lock().unwrap() = properties; CentralEvent::DeviceUpdated(id) }
+                None => {
+                    peripherals.insert(id.clone(), MockPeripheral { id: id.clone(), properties: Arc::new(Mutex::new(properties)) });
+                    CentralEvent::DeviceDiscovered(id)
+                }
+            }
+        });
+        match self.radio_off_after {
+            Some(played) => Ok(Box::pin(events.take(played).chain(stream::once(async { CentralEvent::StateUpdate(CentralState::PoweredOff) })))),
+            None => Ok(Box::pin(events)),
+        }
+    }
+
+    async fn start_scan(&self, _filter: ScanFilter) -> Result<()> { Ok(()) }
+    async fn stop_scan(&self) -> Result<()> { Ok(()) }
+    async fn peripherals(&self) -> Result<Vec<MockPeripheral>> { Ok(self.peripherals.lock().unwrap().values().cloned().collect()) }
+    async fn peripheral(&self, id: &PeripheralId) -> Result<MockPeripheral> { self.peripherals.lock().unwrap().get(id).cloned().ok_or(Error::DeviceNotFound) }
+    async fn add_peripheral(&self, _address: &PeripheralId) -> Result<MockPeripheral> { Err(Error::NotSupported("mock central".into())) }
+    async fn adapter_info(&self) -> Result<String> { Ok("mock adapter".to_string()) }
+    async fn adapter_state(&self) -> Result<CentralState> { Ok(CentralState::PoweredOn) }
+}
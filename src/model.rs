@@ -0,0 +1,396 @@
+// --- Data model: configuration, readings and the messages between the scanner, processor and GUI ---
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::alert_log::AlertRecord;
+use crate::calendar::{DateFormat, WeekStart};
+use crate::capture::CaptureRecord;
+use crate::email;
+use crate::export::ExportFormat;
+use crate::forecast::ForecastMethod;
+use crate::heatmap::HeatmapValue;
+use crate::history::{HistoryEviction, PointOrigin};
+use crate::i18n::Language;
+use crate::keep_awake::KeepAwakeMode;
+use crate::layout::WindowGeometry;
+use crate::legend::SeriesToggles;
+use crate::palette::Palette;
+use crate::plot_image::ImageSize;
+use crate::profiles::Profile;
+use crate::repaint::RepaintMode;
+use crate::rules::{self, AlertRule};
+use crate::schedule::{PauseInterval, PauseWindow};
+use crate::sensors::{SensorEntry, SensorSighting};
+use crate::simulator::SimulatorSettings;
+use crate::sleep::SleepPeriod;
+use crate::sun::NightShading;
+use crate::theme::ThemeMode;
+use crate::thi::Species;
+use crate::virtual_sensor::VirtualSensor;
+
+pub const MAX_HISTORY_POINTS: usize = 200;
+
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct Config {
+    pub target_mac: String,
+    pub auto_detect_thermopro: bool,
+    /// Sensors accepted from the new-sensor prompt; one of them can be made the target
+    pub sensors: Vec<SensorEntry>,
+    /// Devices the user chose never to be asked about again
+    pub ignored_sensors: Vec<String>,
+    /// Named sets of sensor, thresholds, calibration and scan timing, switched from File → Profiles
+    pub profiles: Vec<Profile>,
+    /// Profile the current values belong to; empty when none is selected
+    pub active_profile: String,
+    pub scan_timeout_secs: u64,
+    pub scan_pause_secs: u64,
+    /// Pause between `adaptive_min_pause_secs` and `adaptive_max_pause_secs` depending on how fast readings change
+    pub adaptive_scan: bool,
+    pub adaptive_min_pause_secs: u64,
+    pub adaptive_max_pause_secs: u64,
+    pub duplicate_threshold_secs: u64,
+    /// Save within the duplicate interval too when the value moved at least this much since the last saved reading
+    pub duplicate_change_enabled: bool,
+    pub duplicate_change_temp: f32,
+    pub duplicate_change_hum: u8,
+    pub spike_filter_enabled: bool,
+    pub spike_max_temp_delta: f32,
+    pub spike_max_hum_delta: f32,
+    pub spike_window_secs: u64,
+    pub temp_warn_high: f32,
+    pub temp_warn_low: f32,
+    pub hum_warn_high: f32,
+    pub hum_warn_low: f32,
+    /// What raises alerts; the thresholds above still color the current values and draw the plot guides
+    pub alert_rules: Vec<AlertRule>,
+    pub continuous_mode: bool,
+    pub courtesy_mode: bool,
+    pub courtesy_windows: Vec<PauseWindow>,
+    pub keep_awake: KeepAwakeMode,
+    pub keep_awake_windows: Vec<PauseWindow>,
+    pub courtesy_scan_secs: u64,
+    pub courtesy_pause_secs: u64,
+    pub load_all_history: bool,
+    pub history_cap: usize,
+    pub history_eviction: HistoryEviction,
+    /// Keep the last this many hours in memory (older readings coarsened) instead of `history_cap` points; 0 = by points
+    pub history_hours: u32,
+    pub retention_enabled: bool,
+    pub retention_raw_days: u32,
+    /// Days before archived logs are deleted (only the hourly summary stays); 0 keeps them forever
+    pub retention_archive_days: u32,
+    pub export_derived_columns: bool,
+    pub plot_image_size: ImageSize,
+    pub quick_export_enabled: bool,
+    pub quick_export_shortcut: String,
+    pub quick_export_dir: String,
+    pub quick_export_format: ExportFormat,
+    pub quick_export_name: String,
+    pub show_threshold_lines: bool,
+    /// Series shown on the temperature and humidity plots, set with the checkboxes above them
+    pub temp_series: SeriesToggles,
+    pub hum_series: SeriesToggles,
+    /// Vertical lines on the plots where alerts were logged
+    pub show_event_markers: bool,
+    pub show_notes: bool,
+    /// Draw another day's curves behind today's (the day is picked in the Views menu)
+    pub day_overlay: bool,
+    /// What colors the days in the Calendar view
+    pub heatmap_value: HeatmapValue,
+    /// Colors of plotted points and the current values; the ranges map onto the ends of the palette
+    pub color_palette: Palette,
+    pub temp_color_min: f32,
+    pub temp_color_max: f32,
+    pub hum_color_min: f32,
+    pub hum_color_max: f32,
+    pub night_shading: NightShading,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub sunrise: String,
+    pub sunset: String,
+    pub smoothing_enabled: bool,
+    pub smoothing_window_mins: u32,
+    /// Plot lines break where readings are missing for longer than `gap_intervals` expected reading intervals
+    pub break_lines_at_gaps: bool,
+    pub gap_intervals: u32,
+    /// Span for the trend arrows next to the current values and for the rate-of-change alert rules
+    pub trend_window_mins: u32,
+    /// Dashed projection of the last hour on the temperature plot
+    pub forecast_enabled: bool,
+    pub forecast_method: ForecastMethod,
+    pub forecast_horizon_mins: u32,
+    /// Visible span above which the plots show hourly (or daily) min/max/mean instead of every reading; 0 never
+    pub aggregate_plot_hours: u32,
+    /// One plot with temperature on the left axis and humidity on the right instead of two stacked plots
+    pub combined_plot: bool,
+    pub pause_windows: Vec<PauseWindow>,
+    pub temp_offset: f32,
+    pub hum_offset: f32,
+    pub rh_setpoint: f32,
+    pub dehumidifier_power_w: f32,
+    /// Data folders of the two sensors compared in the gradient window; empty means this app's own logs
+    pub gradient_sensor_a_dir: String,
+    pub gradient_sensor_b_dir: String,
+    pub gradient_warn_high: f32,
+    pub gradient_warn_low: f32,
+    pub virtual_sensors: Vec<VirtualSensor>,
+    pub sound_alarm_enabled: bool,
+    pub sound_alarm_file: String,
+    pub share_port: u16,
+    pub share_expiry_hours: u32,
+    pub share_on_network: bool,
+    pub week_start: WeekStart,
+    pub date_format: DateFormat,
+    pub room_name: String,
+    /// Message template per alert kind (keyed by the webhook event id); empty uses the built-in text
+    pub alert_templates: BTreeMap<String, String>,
+    pub webhook_enabled: bool,
+    pub webhook_url: String,
+    pub telegram_enabled: bool,
+    pub telegram_bot_token: String,
+    /// Numeric chat id or @channel name that gets alerts and is answered on /status
+    pub telegram_chat_id: String,
+    pub email_enabled: bool,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_security: email::SmtpSecurity,
+    pub smtp_username: String,
+    pub smtp_password: String,
+    pub email_from: String,
+    /// Comma-separated recipients
+    pub email_to: String,
+    /// Subject template per alert kind (keyed by the webhook event id); empty uses the default
+    pub email_subject_templates: BTreeMap<String, String>,
+    /// Alerts arriving sooner after the previous email are held back and sent together
+    pub email_min_interval_mins: u32,
+    pub offline_missed_intervals: u32,
+    pub offline_sound_alarm: bool,
+    pub device_alarm_passthrough: bool,
+    pub thi_enabled: bool,
+    pub thi_species: Species,
+    /// Mold-risk and comfort badges in the toolbar
+    pub comfort_badges: bool,
+    /// VPD readout and plot for growers, with the leaf-minus-air temperature and the target band in kPa
+    pub vpd_enabled: bool,
+    pub vpd_leaf_offset: f32,
+    pub vpd_target_low: f32,
+    pub vpd_target_high: f32,
+    /// Signal-strength plot with packets received per hour, for placing the sensor
+    pub rssi_plot: bool,
+    /// Signal of the simulated sensor (`--simulate`)
+    pub simulator: SimulatorSettings,
+    pub heartbeat_dot: bool,
+    pub battery_saver: bool,
+    pub battery_saver_stops_sharing: bool,
+    pub repaint_mode: RepaintMode,
+    /// UI language; log files, CSV headers and webhook payloads stay in English
+    pub language: Language,
+    pub theme: ThemeMode,
+    /// Selection and link color; `None` keeps the theme's own
+    pub accent_color: Option<[u8; 3]>,
+    /// Data folder opened from the File menu; empty uses the storage mode's folder
+    pub data_folder: String,
+    pub recent_data_folders: Vec<String>,
+    /// Registered to start at login (Run key on Windows); re-registered on start in case the executable moved
+    pub autostart: bool,
+    pub autostart_minimized: bool,
+    pub heartbeat_tick: bool,
+    pub device_alarm_byte: usize,
+    pub device_alarm_mask: u8,
+    pub influx_enabled: bool,
+    pub influx_url: String,
+    pub influx_org: String,
+    pub influx_bucket: String,
+    pub influx_token: String,
+    pub influx_measurement: String,
+    pub log_level: String,
+    pub legacy_migration_done: bool,
+    /// Where the window was when last closed; `None` until it was shown once
+    pub window: Option<WindowGeometry>,
+    pub persistence_overlay: bool,
+    /// Hides the advanced panels and menus for other household members
+    pub simple_mode: bool,
+    /// Asked for when leaving simple mode; empty means no PIN
+    pub admin_pin: String,
+    /// Fullscreen dashboard (F11) for a wall-mounted display; kept so the display restarts into it
+    pub kiosk_mode: bool,
+    pub kiosk_keep_display_on: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let mut config = Self {
+            target_mac: "B8:59:CE:33:0F:93".to_string(),
+            auto_detect_thermopro: false,
+            sensors: Vec::new(),
+            ignored_sensors: Vec::new(),
+            profiles: Vec::new(),
+            active_profile: String::new(),
+            scan_timeout_secs: 20,
+            scan_pause_secs: 20,
+            adaptive_scan: false,
+            adaptive_min_pause_secs: 5,
+            adaptive_max_pause_secs: 300,
+            duplicate_threshold_secs: 30,
+            duplicate_change_enabled: false,
+            duplicate_change_temp: 0.5,
+            duplicate_change_hum: 3,
+            spike_filter_enabled: false,
+            spike_max_temp_delta: 5.0,
+            spike_max_hum_delta: 15.0,
+            spike_window_secs: 600,
+            temp_warn_high: 30.0,
+            temp_warn_low: 10.0,
+            hum_warn_high: 80.0,
+            hum_warn_low: 20.0,
+            alert_rules: Vec::new(),
+            continuous_mode: true,
+            courtesy_mode: false,
+            courtesy_windows: Vec::new(),
+            keep_awake: KeepAwakeMode::Off,
+            keep_awake_windows: Vec::new(),
+            courtesy_scan_secs: 5,
+            courtesy_pause_secs: 25,
+            load_all_history: true,
+            history_cap: MAX_HISTORY_POINTS,
+            history_eviction: HistoryEviction::DropOldest,
+            history_hours: 0,
+            retention_enabled: false,
+            retention_raw_days: 30,
+            retention_archive_days: 0,
+            export_derived_columns: false,
+            plot_image_size: ImageSize::default(),
+            quick_export_enabled: false,
+            quick_export_shortcut: "Ctrl+Shift+E".to_string(),
+            quick_export_dir: String::new(),
+            quick_export_format: ExportFormat::Csv,
+            quick_export_name: "{room}_{date}_{time}".to_string(),
+            show_threshold_lines: true,
+            temp_series: SeriesToggles::default(),
+            hum_series: SeriesToggles::default(),
+            show_event_markers: true,
+            show_notes: true,
+            day_overlay: false,
+            heatmap_value: HeatmapValue::Mean,
+            color_palette: Palette::Classic,
+            temp_color_min: 0.0,
+            temp_color_max: 40.0,
+            hum_color_min: 0.0,
+            hum_color_max: 100.0,
+            night_shading: NightShading::Off,
+            latitude: 50.08,
+            longitude: 14.42,
+            sunrise: "06:00".to_string(),
+            sunset: "20:00".to_string(),
+            smoothing_enabled: false,
+            smoothing_window_mins: 15,
+            break_lines_at_gaps: true,
+            gap_intervals: 3,
+            trend_window_mins: 30,
+            forecast_enabled: false,
+            forecast_method: ForecastMethod::Linear,
+            forecast_horizon_mins: 60,
+            aggregate_plot_hours: 48,
+            combined_plot: false,
+            pause_windows: Vec::new(),
+            temp_offset: 0.0,
+            hum_offset: 0.0,
+            rh_setpoint: 60.0,
+            dehumidifier_power_w: 250.0,
+            gradient_sensor_a_dir: String::new(),
+            gradient_sensor_b_dir: String::new(),
+            gradient_warn_high: 3.0,
+            gradient_warn_low: -3.0,
+            virtual_sensors: Vec::new(),
+            sound_alarm_enabled: false,
+            sound_alarm_file: String::new(),
+            share_port: 8357,
+            share_expiry_hours: 24,
+            share_on_network: false,
+            week_start: WeekStart::Monday,
+            date_format: DateFormat::Iso,
+            room_name: String::new(),
+            alert_templates: BTreeMap::new(),
+            webhook_enabled: false,
+            webhook_url: String::new(),
+            telegram_enabled: false,
+            telegram_bot_token: String::new(),
+            telegram_chat_id: String::new(),
+            email_enabled: false,
+            smtp_host: String::new(),
+            smtp_port: 587,
+            smtp_security: email::SmtpSecurity::StartTls,
+            smtp_username: String::new(),
+            smtp_password: String::new(),
+            email_from: String::new(),
+            email_to: String::new(),
+            email_subject_templates: BTreeMap::new(),
+            email_min_interval_mins: 15,
+            offline_missed_intervals: 5,
+            offline_sound_alarm: false,
+            device_alarm_passthrough: false,
+            thi_enabled: false,
+            thi_species: Species::Livestock,
+            comfort_badges: true,
+            vpd_enabled: false,
+            vpd_leaf_offset: -2.0,
+            vpd_target_low: 0.8,
+            vpd_target_high: 1.2,
+            rssi_plot: false,
+            simulator: SimulatorSettings::default(),
+            heartbeat_dot: true,
+            battery_saver: true,
+            battery_saver_stops_sharing: true,
+            repaint_mode: RepaintMode::OnData,
+            language: Language::English,
+            theme: ThemeMode::Dark,
+            accent_color: None,
+            data_folder: String::new(),
+            recent_data_folders: Vec::new(),
+            autostart: false,
+            autostart_minimized: true,
+            heartbeat_tick: false,
+            device_alarm_byte: 2,
+            device_alarm_mask: 0x01,
+            influx_enabled: false,
+            influx_url: "http://localhost:8086".to_string(),
+            influx_org: String::new(),
+            influx_bucket: "tp357".to_string(),
+            influx_token: String::new(),
+            influx_measurement: "environment".to_string(),
+            log_level: "info".to_string(),
+            legacy_migration_done: false,
+            window: None,
+            persistence_overlay: false,
+            simple_mode: false,
+            admin_pin: String::new(),
+            kiosk_mode: false,
+            kiosk_keep_display_on: true,
+        };
+        config.alert_rules = rules::from_thresholds(&config);
+        config
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HistoryPoint { pub timestamp: DateTime<Local>, pub temp: f32, pub hum: u8, pub origin: PointOrigin, pub rssi: Option<i16>, }
+
+#[derive(Clone, Debug)]
+pub struct BleDataPoint { pub timestamp: DateTime<Local>, pub temp: f32, pub hum: u8, pub device_id: String, pub model: &'static str, pub rssi: Option<i16>, pub raw_data: Vec<u8>, pub device_alarm: bool, pub battery: Option<u8>, pub persisted: bool, }
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ScanControl { Pause, Resume }
+
+/// `Control` goes GUI → scanner → processor → GUI, so the toolbar only shows the state the scanner confirmed.
+pub enum AppMessage { NewData(BleDataPoint), StatusUpdate(String), CsvWriteStatus(bool), Alert(String), AlertLogged(AlertRecord), AlarmSound(bool), Heartbeat, Control(ScanControl), SensorSeen(SensorSighting), Captured(CaptureRecord), Slept(SleepPeriod), Paused(PauseInterval), }
+
+// Dew point via the Magnus formula (°C)
+pub fn dew_point(temp: f32, hum: u8) -> f32 {
+    let (a, b) = (17.62_f32, 243.12_f32);
+    let gamma = (a * temp) / (b + temp) + (hum.max(1) as f32 / 100.0).ln();
+    b * gamma / (a - gamma)
+}
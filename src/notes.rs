@@ -0,0 +1,109 @@
+// --- Timeline notes ("opened window", "turned on humidifier"), kept in `notes.csv` in the data folder ---
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use log::{error, info};
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::paths;
+
+pub const NOTES_FILE_NAME: &str = "notes.csv";
+const HEADER: [&str; 2] = ["DateTime", "Note"];
+const TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+/// Format of the time field in the notes window.
+pub const INPUT_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Note {
+    pub timestamp: DateTime<Local>,
+    pub text: String,
+}
+
+impl Note {
+    fn to_row(&self) -> [String; 2] { [self.timestamp.format(TIME_FORMAT).to_string(), self.text.clone()] }
+
+    fn from_row(row: &csv::StringRecord) -> Option<Self> {
+        let naive = NaiveDateTime::parse_from_str(row.get(0)?.trim(), TIME_FORMAT).ok()?;
+        Some(Self { timestamp: Local.from_local_datetime(&naive).earliest()?, text: row.get(1)?.to_string() })
+    }
+}
+
+/// Time typed in the notes window, e.g. "2025-11-26 14:20".
+pub fn parse_time(input: &str) -> Option<DateTime<Local>> {
+    Local.from_local_datetime(&NaiveDateTime::parse_from_str(input.trim(), INPUT_FORMAT).ok()?).earliest()
+}
+
+pub fn notes_path() -> PathBuf { paths::data_dir().join(NOTES_FILE_NAME) }
+
+pub fn append(note: &Note) -> Result<(), csv::Error> {
+    paths::ensure_dirs();
+    let file = OpenOptions::new().create(true).append(true).open(notes_path())?;
+    let is_new = file.metadata().map(|m| m.len() == 0).unwrap_or(true);
+    let mut wtr = csv::WriterBuilder::new().has_headers(false).from_writer(file);
+    if is_new { wtr.write_record(HEADER)?; }
+    wtr.write_record(note.to_row())?;
+    wtr.flush()?;
+    info!("Note added at {}.", note.timestamp.format(TIME_FORMAT));
+    Ok(())
+}
+
+fn read(path: &Path) -> Vec<Note> {
+    let Ok(mut rdr) = csv::ReaderBuilder::new().flexible(true).from_path(path) else { return Vec::new(); };
+    let mut notes: Vec<Note> = rdr.records().flatten().filter_map(|row| Note::from_row(&row)).collect();
+    // Notes can be added for a past time, so the file is not in time order
+    notes.sort_by_key(|n| n.timestamp);
+    notes
+}
+
+pub fn load_range(start: DateTime<Local>, end: DateTime<Local>) -> Vec<Note> {
+    read(&notes_path()).into_iter().filter(|n| n.timestamp >= start && n.timestamp <= end).collect()
+}
+
+pub fn write_csv(path: &Path, notes: &[Note]) -> Result<(), csv::Error> {
+    let mut wtr = csv::WriterBuilder::new().from_path(path)?;
+    wtr.write_record(HEADER)?;
+    for note in notes { wtr.write_record(note.to_row())?; }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Rewrites the notes file without `note`; the file is replaced atomically.
+pub fn remove(note: &Note) -> io::Result<()> {
+    let path = notes_path();
+    let mut notes = read(&path);
+    let Some(i) = notes.iter().position(|n| n == note) else { return Ok(()); };
+    notes.remove(i);
+    let tmp = path.with_extension("csv.tmp");
+    write_csv(&tmp, &notes).map_err(io::Error::other)?;
+    fs::rename(&tmp, &path).inspect_err(|e| error!("Failed to replace '{}': {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::{export_history, ExportFormat};
+    use crate::test_support::*;
+
+    #[test]
+    fn notes_are_kept_in_time_order_deleted_and_exported_next_to_the_readings() {
+        let data_dir = TempDataDir::new("notes");
+        let at = |h| today_at(h, 30);
+        assert_eq!(parse_time(&at(9).format(INPUT_FORMAT).to_string()), Some(at(9)));
+        assert_eq!(parse_time("9:30"), None);
+        // Added afterwards for an earlier time, and with a comma that CSV has to quote
+        let (window, humidifier) = (Note { timestamp: at(10), text: "opened window".to_string() }, Note { timestamp: at(8), text: "humidifier on, level 2".to_string() });
+        append(&window).unwrap();
+        append(&humidifier).unwrap();
+        assert_eq!(load_range(at(0), at(23)), vec![humidifier.clone(), window.clone()]);
+        assert_eq!(load_range(at(9), at(23)), vec![window.clone()]);
+
+        let exported = data_dir.dir.join("export.csv");
+        export_history(&exported, &[point(at(8), 21.0, 40)], &[], &load_range(at(0), at(23)), false, None, ExportFormat::Csv).unwrap();
+        let written = fs::read_to_string(exported.with_extension("notes.csv")).unwrap();
+        assert!(written.contains("\"humidifier on, level 2\"") && written.contains("opened window"));
+
+        remove(&humidifier).unwrap();
+        assert_eq!(load_range(at(0), at(23)), vec![window]);
+    }
+}
@@ -0,0 +1,58 @@
+// --- Day overlay: another day's readings moved onto today's timeline, drawn behind today's curves ---
+
+use chrono::{Duration as ChronoDuration, Local, NaiveDate, TimeZone};
+
+use crate::model::HistoryPoint;
+use crate::storage::load_history_range;
+
+pub struct DayOverlay {
+    /// The day drawn behind today's data; yesterday unless chosen otherwise
+    pub day: NaiveDate,
+    points: Vec<HistoryPoint>,
+    // (shown day, day it was moved onto); reloaded when either changes, e.g. after midnight
+    loaded: Option<(NaiveDate, NaiveDate)>,
+}
+
+impl Default for DayOverlay {
+    fn default() -> Self { Self { day: Local::now().date_naive() - ChronoDuration::days(1), points: Vec::new(), loaded: None } }
+}
+
+impl DayOverlay {
+    /// Points of the chosen day moved onto `target`, read from the logs once per day.
+    pub fn points(&mut self, target: NaiveDate) -> &[HistoryPoint] {
+        if self.loaded != Some((self.day, target)) {
+            let start = |day: NaiveDate| Local.from_local_datetime(&day.and_hms_opt(0, 0, 0).unwrap_or_default()).earliest().unwrap_or_else(Local::now);
+            let day_points = load_history_range(start(self.day), start(self.day + ChronoDuration::days(1)) - ChronoDuration::seconds(1));
+            self.points = shift_to_day(&day_points, self.day, target);
+            self.loaded = Some((self.day, target));
+        }
+        &self.points
+    }
+}
+
+/// Moves points from `from` to `to` by clock time, so 14:00 stays 14:00 across a DST change.
+pub fn shift_to_day(points: &[HistoryPoint], from: NaiveDate, to: NaiveDate) -> Vec<HistoryPoint> {
+    let days = ChronoDuration::days((to - from).num_days());
+    points.iter().filter_map(|p| Some(HistoryPoint { timestamp: Local.from_local_datetime(&(p.timestamp.naive_local() + days)).earliest()?, ..p.clone() })).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths;
+    use crate::test_support::*;
+
+    #[test]
+    fn day_overlay_moves_the_chosen_day_onto_today_by_clock_time() {
+        let _data_dir = TempDataDir::new("overlay");
+        let today = Local::now().date_naive();
+        let day = today - ChronoDuration::days(3);
+        let at = |date, h| local(date, h, 15);
+        std::fs::write(paths::log_path(day), format!("DateTime,Temperature,Humidity\n{},19.5,55\n{},22.0,48\n", at(day, 6).to_rfc3339(), at(day, 14).to_rfc3339())).unwrap();
+        let mut overlay = DayOverlay { day, ..Default::default() };
+        let points = overlay.points(today);
+        assert_eq!(points.iter().map(|p| (p.timestamp, p.temp, p.hum)).collect::<Vec<_>>(), vec![(at(today, 6), 19.5, 55), (at(today, 14), 22.0, 48)]);
+        // Yesterday (the default) has no log here
+        assert!(DayOverlay::default().points(today).is_empty());
+    }
+}
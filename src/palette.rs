@@ -0,0 +1,134 @@
+// --- Color scales for plotted points and the current values: classic ramp or colorblind-safe palettes ---
+
+use eframe::egui::Color32;
+use serde::{Deserialize, Serialize};
+
+use crate::model::Config;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Palette {
+    /// Blue → green → orange → red
+    #[default]
+    Classic,
+    /// Perceptually uniform purple → green → yellow
+    Viridis,
+    /// Blue → gray → yellow, readable with red-green and blue-yellow color vision deficiency
+    Cividis,
+}
+
+// matplotlib's viridis and cividis sampled at even steps
+const VIRIDIS: [(u8, u8, u8); 11] = [(68, 1, 84), (72, 36, 117), (65, 68, 135), (53, 95, 141), (42, 120, 142), (33, 145, 140), (34, 168, 132), (68, 191, 112), (122, 209, 81), (189, 223, 38), (253, 231, 37)];
+const CIVIDIS: [(u8, u8, u8); 10] = [(0, 32, 77), (0, 51, 111), (57, 72, 107), (87, 92, 109), (112, 113, 115), (138, 135, 121), (166, 157, 117), (196, 181, 108), (228, 207, 91), (255, 234, 70)];
+
+impl Palette {
+    pub const ALL: [Palette; 3] = [Palette::Classic, Palette::Viridis, Palette::Cividis];
+
+    pub fn label(&self) -> &'static str {
+        match self { Palette::Classic => "Classic (blue to red)", Palette::Viridis => "Viridis", Palette::Cividis => "Cividis (colorblind-safe)" }
+    }
+
+    /// Color at `t` (0..=1); `reversed` runs the scale from the high end, e.g. dry air warm and humid air cool.
+    pub fn sample(&self, t: f64, reversed: bool) -> Color32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Palette::Classic if reversed => classic_reversed(t),
+            Palette::Classic => classic(t),
+            Palette::Viridis => interpolate(&VIRIDIS, if reversed { 1.0 - t } else { t }),
+            Palette::Cividis => interpolate(&CIVIDIS, if reversed { 1.0 - t } else { t }),
+        }
+    }
+}
+
+// 0.0 → blue, ~0.33 → green, ~0.66 → orange, 1.0 → red
+fn classic(t: f64) -> Color32 {
+    if t < 0.33 {
+        // blue → green
+        Color32::from_rgb(0, (t * 3.0 * 255.0) as u8, 255)
+    } else if t < 0.66 {
+        // green → orange
+        Color32::from_rgb(0, 255, 255 - ((t - 0.33) * 3.0 * 255.0) as u8)
+    } else {
+        // orange → red
+        Color32::from_rgb(255, 255 - ((t - 0.66) * 3.0 * 255.0) as u8, 0)
+    }
+}
+
+fn classic_reversed(t: f64) -> Color32 {
+    if t < 0.33 {
+        // red → orange
+        Color32::from_rgb(255, (t * 3.0 * 255.0) as u8, 0)
+    } else if t < 0.66 {
+        // orange → green
+        Color32::from_rgb(255 - ((t - 0.33) * 3.0 * 255.0) as u8, 255, 0)
+    } else {
+        // green → blue
+        Color32::from_rgb(0, 255 - ((t - 0.66) * 3.0 * 255.0) as u8, 255)
+    }
+}
+
+fn interpolate(stops: &[(u8, u8, u8)], t: f64) -> Color32 {
+    let position = t * (stops.len() - 1) as f64;
+    let i = (position as usize).min(stops.len() - 2);
+    let (a, b, f) = (stops[i], stops[i + 1], position - i as f64);
+    let mix = |x: u8, y: u8| (x as f64 + (y as f64 - x as f64) * f).round() as u8;
+    Color32::from_rgb(mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+}
+
+/// Steps the plotted points are grouped into, one `Points` item each instead of one per reading.
+pub const COLOR_BUCKETS: usize = 48;
+
+/// Palette and value range used for one channel.
+#[derive(Clone, Copy, Debug)]
+pub struct ColorScale { pub palette: Palette, pub min: f64, pub max: f64, pub reversed: bool }
+
+impl ColorScale {
+    pub fn temperature(config: &Config) -> Self { Self { palette: config.color_palette, min: config.temp_color_min as f64, max: config.temp_color_max as f64, reversed: false } }
+
+    /// Runs from the high end, so dry air gets the warm colors as with the classic ramp.
+    pub fn humidity(config: &Config) -> Self { Self { palette: config.color_palette, min: config.hum_color_min as f64, max: config.hum_color_max as f64, reversed: true } }
+
+    pub fn color(&self, value: f64) -> Color32 { self.palette.sample(self.position(value), self.reversed) }
+
+    /// Which of the `COLOR_BUCKETS` steps the value falls into; values outside the range take the end steps.
+    pub fn bucket(&self, value: f64) -> usize { ((self.position(value).clamp(0.0, 1.0) * COLOR_BUCKETS as f64) as usize).min(COLOR_BUCKETS - 1) }
+
+    /// Color of a bucket's middle.
+    pub fn bucket_color(&self, bucket: usize) -> Color32 { self.palette.sample((bucket as f64 + 0.5) / COLOR_BUCKETS as f64, self.reversed) }
+
+    fn position(&self, value: f64) -> f64 { if self.max > self.min { (value - self.min) / (self.max - self.min) } else { 0.5 } }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_scales_follow_the_palette_and_configured_range() {
+        let config = Config { color_palette: Palette::Viridis, temp_color_min: 10.0, temp_color_max: 30.0, ..Default::default() };
+        let scale = ColorScale::temperature(&config);
+        assert_eq!(scale.color(10.0), Color32::from_rgb(68, 1, 84));
+        assert_eq!(scale.color(30.0), Color32::from_rgb(253, 231, 37));
+        // Outside the range the ends are held, halfway is the middle stop
+        assert_eq!(scale.color(-5.0), scale.color(10.0));
+        assert_eq!(scale.color(20.0), Color32::from_rgb(33, 145, 140));
+        // Humidity runs from the high end: dry air takes the yellow end
+        assert_eq!(ColorScale::humidity(&config).color(0.0), Color32::from_rgb(253, 231, 37));
+        assert_eq!(Palette::Cividis.sample(0.0, false), Color32::from_rgb(0, 32, 77));
+        // The classic ramp is unchanged: blue for cold, red for hot
+        let classic = ColorScale::temperature(&Config::default());
+        assert_eq!((classic.color(0.0), classic.color(40.0)), (Color32::from_rgb(0, 0, 255), Color32::from_rgb(255, 0, 0)));
+    }
+
+    #[test]
+    fn plotted_points_fall_into_a_fixed_number_of_color_buckets() {
+        let scale = ColorScale { palette: Palette::Viridis, min: 0.0, max: 40.0, reversed: false };
+        // Ends and values outside the range take the end buckets
+        assert_eq!((scale.bucket(-5.0), scale.bucket(0.0), scale.bucket(40.0), scale.bucket(55.0)), (0, 0, COLOR_BUCKETS - 1, COLOR_BUCKETS - 1));
+        let readings: Vec<f64> = (0..10_000).map(|i| 15.0 + (i as f64 / 100.0).sin() * 10.0).collect();
+        let used: std::collections::HashSet<usize> = readings.iter().map(|&v| scale.bucket(v)).collect();
+        assert!(used.len() <= COLOR_BUCKETS);
+        // The bucket color stays close to the exact color of the value
+        let (exact, bucketed) = (scale.color(21.3), scale.bucket_color(scale.bucket(21.3)));
+        assert!(exact.r().abs_diff(bucketed.r()) <= 8 && exact.g().abs_diff(bucketed.g()) <= 8 && exact.b().abs_diff(bucketed.b()) <= 8);
+    }
+}
@@ -0,0 +1,100 @@
+// --- Storage locations: portable (next to the executable) vs installed (per-user folders) ---
+
+use chrono::NaiveDate;
+use directories::ProjectDirs;
+use log::{info, warn};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+pub const PORTABLE_FLAG: &str = "portable.flag";
+pub const CONFIG_FILE_NAME: &str = "config.json";
+
+// Explicit data folder taking precedence over the storage mode (opened from the File menu, or set by tests)
+static DATA_DIR_OVERRIDE: RwLock<Option<PathBuf>> = RwLock::new(None);
+// Configuration file given with `--config`, replacing `config.json` in the storage mode's folder
+static CONFIG_PATH_OVERRIDE: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StorageMode { Portable, Installed }
+
+impl StorageMode {
+    pub fn label(&self) -> &'static str {
+        match self { StorageMode::Portable => "Portable", StorageMode::Installed => "Installed" }
+    }
+}
+
+pub fn exe_dir() -> PathBuf {
+    std::env::current_exe().ok().and_then(|p| p.parent().map(|d| d.to_path_buf())).unwrap_or_else(|| PathBuf::from("."))
+}
+
+fn project_dirs() -> Option<ProjectDirs> { ProjectDirs::from("", "", "TempMonitor") }
+
+/// Portable mode is selected by a `portable.flag` file next to the executable.
+pub fn storage_mode() -> StorageMode {
+    if exe_dir().join(PORTABLE_FLAG).exists() { StorageMode::Portable } else { StorageMode::Installed }
+}
+
+pub fn config_dir() -> PathBuf {
+    if let Some(dir) = CONFIG_PATH_OVERRIDE.read().unwrap().as_deref().and_then(Path::parent) { return dir.to_path_buf(); }
+    match (storage_mode(), project_dirs()) {
+        (StorageMode::Installed, Some(dirs)) => dirs.config_dir().to_path_buf(),
+        _ => exe_dir(),
+    }
+}
+
+pub fn set_data_dir_override(dir: Option<PathBuf>) {
+    *DATA_DIR_OVERRIDE.write().unwrap() = dir;
+}
+
+pub fn data_dir() -> PathBuf {
+    if let Some(dir) = DATA_DIR_OVERRIDE.read().unwrap().clone() { return dir; }
+    default_data_dir()
+}
+
+/// The storage mode's data folder, whichever folder is currently open.
+pub fn default_data_dir() -> PathBuf {
+    match (storage_mode(), project_dirs()) {
+        (StorageMode::Installed, Some(dirs)) => dirs.data_dir().to_path_buf(),
+        _ => exe_dir(),
+    }
+}
+
+pub fn set_config_path_override(path: Option<PathBuf>) {
+    *CONFIG_PATH_OVERRIDE.write().unwrap() = path;
+}
+
+pub fn config_path() -> PathBuf {
+    if let Some(path) = CONFIG_PATH_OVERRIDE.read().unwrap().clone() { return path; }
+    config_dir().join(CONFIG_FILE_NAME)
+}
+
+pub fn log_path(date: NaiveDate) -> PathBuf { log_path_in(&data_dir(), date) }
+
+/// Daily log in another data folder, e.g. one written by a second copy of the app for another sensor.
+pub fn log_path_in(dir: &Path, date: NaiveDate) -> PathBuf { dir.join(date.format("log_%Y-%m-%d.csv").to_string()) }
+
+/// No configuration has been saved in the active location yet.
+pub fn is_first_run() -> bool { !config_path().exists() }
+
+/// A portable copy that predates the flag keeps its `config.json` next to the executable.
+pub fn has_legacy_portable_layout() -> bool { exe_dir().join(CONFIG_FILE_NAME).exists() }
+
+/// Creates or removes `portable.flag` and makes sure the target folders exist.
+pub fn set_storage_mode(mode: StorageMode) -> io::Result<()> {
+    let flag = exe_dir().join(PORTABLE_FLAG);
+    match mode {
+        StorageMode::Portable => fs::write(&flag, "This file switches Temperature Monitor to portable mode.\n")?,
+        StorageMode::Installed => if flag.exists() { fs::remove_file(&flag)?; },
+    }
+    ensure_dirs();
+    info!("Storage mode set to {:?} (config: '{}', data: '{}').", mode, config_dir().display(), data_dir().display());
+    Ok(())
+}
+
+pub fn ensure_dirs() {
+    for dir in [config_dir(), data_dir()] {
+        if let Err(e) = fs::create_dir_all(&dir) { warn!("Failed to create directory '{}': {}", dir.display(), e); }
+    }
+}
@@ -0,0 +1,140 @@
+// --- Plot images: lays plots out off screen with egui, paints its output in software and saves it as PNG ---
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use eframe::egui;
+use egui::epaint::{ClippedPrimitive, ImageData, ImageDelta, Primitive, TextureId, Vertex};
+use egui::{Color32, ColorImage};
+use serde::{Deserialize, Serialize};
+
+/// Width in points the image is laid out at; the resolution only scales it, so text keeps its size relative to the plots.
+const LAYOUT_WIDTH: f32 = 1000.0;
+/// egui_plot sizes its axes from the previous frame, so a few frames run before the last one is painted.
+const FRAMES: usize = 3;
+
+/// Resolution of File → Save plot as image, in pixels.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(default)]
+pub struct ImageSize { pub width: u32, pub height: u32 }
+
+impl ImageSize {
+    pub const MIN: u32 = 200;
+    pub const MAX: u32 = 8000;
+}
+
+impl Default for ImageSize {
+    fn default() -> Self { Self { width: 1600, height: 900 } }
+}
+
+struct Texture { size: [usize; 2], pixels: Vec<Color32> }
+
+/// Runs `draw` in a panel of `size` pixels with `visuals` and returns what egui paints, as an opaque image.
+pub fn render(size: ImageSize, visuals: &egui::Visuals, mut draw: impl FnMut(&mut egui::Ui)) -> ColorImage {
+    let (width, height) = (size.width.clamp(ImageSize::MIN, ImageSize::MAX), size.height.clamp(ImageSize::MIN, ImageSize::MAX));
+    let pixels_per_point = width as f32 / LAYOUT_WIDTH;
+    let ctx = egui::Context::default();
+    ctx.set_visuals(visuals.clone());
+    let mut input = egui::RawInput { screen_rect: Some(egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(width as f32, height as f32) / pixels_per_point)), ..Default::default() };
+    input.viewports.entry(egui::ViewportId::ROOT).or_default().native_pixels_per_point = Some(pixels_per_point);
+    let mut textures = HashMap::new();
+    let mut shapes = Vec::new();
+    for _ in 0..FRAMES {
+        let output = ctx.run(input.clone(), |ctx| { egui::CentralPanel::default().show(ctx, |ui| draw(ui)); });
+        for (id, delta) in output.textures_delta.set { apply_delta(&mut textures, id, delta); }
+        shapes = output.shapes;
+    }
+    let mut image = ColorImage::new([width as usize, height as usize], visuals.panel_fill.to_opaque());
+    paint(&mut image, &ctx.tessellate(shapes, pixels_per_point), &textures, pixels_per_point);
+    image
+}
+
+pub fn save_png(path: &Path, image: &ColorImage) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| e.to_string())?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), image.width() as u32, image.height() as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer.write_image_data(image.as_raw()).map_err(|e| e.to_string())
+}
+
+// Whole textures, or patches of the font atlas as new glyphs are laid out
+fn apply_delta(textures: &mut HashMap<TextureId, Texture>, id: TextureId, delta: ImageDelta) {
+    let (size, pixels): ([usize; 2], Vec<Color32>) = match &delta.image { ImageData::Color(image) => (image.size, image.pixels.clone()), ImageData::Font(font) => (font.size, font.srgba_pixels(None).collect()) };
+    match (delta.pos, textures.get_mut(&id)) {
+        (Some([x, y]), Some(texture)) => {
+            for (row, patch) in pixels.chunks_exact(size[0]).enumerate() {
+                let start = (y + row) * texture.size[0] + x;
+                texture.pixels[start..start + size[0]].copy_from_slice(patch);
+            }
+        }
+        _ => { textures.insert(id, Texture { size, pixels }); }
+    }
+}
+
+fn paint(image: &mut ColorImage, primitives: &[ClippedPrimitive], textures: &HashMap<TextureId, Texture>, pixels_per_point: f32) {
+    let bounds = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(image.width() as f32, image.height() as f32));
+    for primitive in primitives {
+        let (Primitive::Mesh(mesh), clip) = (&primitive.primitive, (primitive.clip_rect * pixels_per_point).intersect(bounds)) else { continue; };
+        let Some(texture) = textures.get(&mesh.texture_id) else { continue; };
+        for triangle in mesh.indices.chunks_exact(3) {
+            fill_triangle(image, clip, texture, [0, 1, 2].map(|i| &mesh.vertices[triangle[i] as usize]), pixels_per_point);
+        }
+    }
+}
+
+// Samples pixel centers; egui feathers its edges with vertex alpha, so no further anti-aliasing is needed
+fn fill_triangle(image: &mut ColorImage, clip: egui::Rect, texture: &Texture, vertices: [&Vertex; 3], pixels_per_point: f32) {
+    let [a, b, c] = vertices.map(|v| v.pos.to_vec2() * pixels_per_point);
+    let edge = |from: egui::Vec2, to: egui::Vec2, p: egui::Vec2| (to.x - from.x) * (p.y - from.y) - (to.y - from.y) * (p.x - from.x);
+    let area = edge(a, b, c);
+    if area.abs() < 1e-6 { return; }
+    let (min, max) = (a.min(b).min(c).max(clip.min.to_vec2()), a.max(b).max(c).min(clip.max.to_vec2()));
+    for y in min.y.floor() as usize..max.y.ceil().max(0.0) as usize {
+        for x in min.x.floor() as usize..max.x.ceil().max(0.0) as usize {
+            let p = egui::vec2(x as f32 + 0.5, y as f32 + 0.5);
+            let weights = [edge(b, c, p) / area, edge(c, a, p) / area, edge(a, b, p) / area];
+            if weights.iter().any(|w| *w < 0.0) { continue; }
+            let mix = |f: &dyn Fn(&Vertex) -> f32| weights.iter().zip(vertices).map(|(w, v)| w * f(v)).sum::<f32>();
+            let (u, v) = (mix(&|v| v.uv.x), mix(&|v| v.uv.y));
+            let texel = texture.pixels[((v * texture.size[1] as f32) as usize).min(texture.size[1] - 1) * texture.size[0] + ((u * texture.size[0] as f32) as usize).min(texture.size[0] - 1)];
+            let source = [0, 1, 2, 3].map(|i| mix(&|v| v.color[i] as f32) * texel[i] as f32 / 255.0);
+            // Premultiplied "over"
+            let target = &mut image[(x, y)];
+            let keep = 1.0 - source[3] / 255.0;
+            *target = Color32::from_rgba_premultiplied(
+                (source[0] + target.r() as f32 * keep).round() as u8, (source[1] + target.g() as f32 * keep).round() as u8,
+                (source[2] + target.b() as f32 * keep).round() as u8, (source[3] + target.a() as f32 * keep).round() as u8,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plot_image_paints_the_plot_and_its_text_at_the_requested_size() {
+        let visuals = egui::Visuals::light();
+        let image = render(ImageSize { width: 800, height: 500 }, &visuals, |ui| {
+            ui.label(egui::RichText::new("Temperature").size(30.0).color(Color32::BLACK));
+            egui_plot::Plot::new("image_test").show(ui, |plot_ui| plot_ui.line(egui_plot::Line::new(egui_plot::PlotPoints::new(vec![[0.0, 0.0], [1.0, 1.0]])).color(Color32::RED).width(4.0)));
+        });
+        assert_eq!(image.size, [800, 500]);
+        assert!(image.pixels.iter().all(|p| p.a() == 255));
+        assert_eq!(image[(0, 0)], visuals.panel_fill.to_opaque());
+        // The label's glyphs come from the font atlas, the line from plain vertex colors
+        assert!(image.pixels[..800 * 40].iter().any(|p| p.r() < 60 && p.g() < 60 && p.b() < 60));
+        assert!(image.pixels.iter().any(|p| p.r() > 200 && p.g() < 60 && p.b() < 60));
+
+        let path = std::env::temp_dir().join(format!("temp_monitor_test_{}_plot.png", std::process::id()));
+        save_png(&path, &image).unwrap();
+        let decoder = png::Decoder::new(File::open(&path).unwrap());
+        let reader = decoder.read_info().unwrap();
+        assert_eq!((reader.info().width, reader.info().height), (800, 500));
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,64 @@
+// --- Power source detection and the battery saver state shared with the scanner ---
+// The `battery` crate was considered, but it reads charge, health and timings through a backend per platform
+// for what is a single "on battery" bit here; one OS call per platform does that, as in `sleep` and `keep_awake`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static SAVER_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Set by the GUI; read by the scanner to stretch its duty cycle.
+pub fn set_saver_active(active: bool) { SAVER_ACTIVE.store(active, Ordering::Relaxed); }
+
+pub fn saver_active() -> bool { SAVER_ACTIVE.load(Ordering::Relaxed) }
+
+/// `Some(true)` when running on battery, `None` when it cannot be determined (desktops, unsupported systems).
+pub fn on_battery() -> Option<bool> { platform::on_battery() }
+
+#[cfg(windows)]
+mod platform {
+    #[repr(C)]
+    #[derive(Default)]
+    struct SystemPowerStatus { ac_line_status: u8, battery_flag: u8, battery_life_percent: u8, system_status_flag: u8, battery_life_time: u32, battery_full_life_time: u32, }
+
+    #[link(name = "kernel32")]
+    extern "system" { fn GetSystemPowerStatus(status: *mut SystemPowerStatus) -> i32; }
+
+    pub fn on_battery() -> Option<bool> {
+        let mut status = SystemPowerStatus::default();
+        // SAFETY: the struct matches SYSTEM_POWER_STATUS and outlives the call
+        if unsafe { GetSystemPowerStatus(&mut status) } == 0 { return None; }
+        match status.ac_line_status { 0 => Some(true), 1 => Some(false), _ => None }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::process::Command;
+
+    pub fn on_battery() -> Option<bool> {
+        let output = Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+        let text = String::from_utf8_lossy(&output.stdout);
+        if text.contains("'Battery Power'") { Some(true) } else if text.contains("'AC Power'") { Some(false) } else { None }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::fs;
+
+    fn read(path: &std::path::Path, name: &str) -> String { fs::read_to_string(path.join(name)).map(|s| s.trim().to_string()).unwrap_or_default() }
+
+    pub fn on_battery() -> Option<bool> {
+        let supplies: Vec<_> = fs::read_dir("/sys/class/power_supply").ok()?.flatten().map(|e| e.path()).collect();
+        if supplies.iter().any(|p| matches!(read(p, "type").as_str(), "Mains" | "USB") && read(p, "online") == "1") { return Some(false); }
+        let batteries: Vec<_> = supplies.iter().filter(|p| read(p, "type") == "Battery").collect();
+        if batteries.is_empty() { return None; }
+        Some(batteries.iter().any(|p| read(p, "status") == "Discharging"))
+    }
+}
+
+// BSDs and other systems: unknown, so the battery saver never engages on its own
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+mod platform {
+    pub fn on_battery() -> Option<bool> { None }
+}
@@ -0,0 +1,200 @@
+// --- Background processor: spike filter, alerts, duplicate suppression and logging of every reading ---
+
+use chrono::{DateTime, Local};
+use std::collections::HashMap;
+use std::sync::{mpsc, Arc, Mutex};
+use tokio::sync::{mpsc as async_mpsc, watch};
+use std::thread;
+use std::time::{Duration, Instant};
+use log::{info, warn, error, debug};
+
+use crate::alerts::{offline_after, AlertSenders};
+use crate::ble::expected_reading_interval_secs;
+use crate::model::{AppMessage, BleDataPoint, Config, ScanControl};
+use crate::sink_stats::{self, Sink};
+use crate::storage::{load_history_range_in, log_derived_to_csv, log_to_csv};
+use crate::webhook::{self, AlertTracker};
+use crate::{email, influx, schedule, spike, telegram};
+
+/// How long the app waits at exit for the processor, which includes the workers sending what they still hold.
+pub(crate) const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
+/// The last reading that passed the duplicate gate.
+pub(crate) struct SavedReading { pub(crate) timestamp: DateTime<Local>, pub(crate) temp: f32, pub(crate) hum: u8 }
+
+// Sensor steps are 0.1 °C, so 22.1 -> 22.6 must count as 0.5 despite float rounding
+const CHANGE_EPSILON: f32 = 0.01;
+
+/// Saved once `duplicate_threshold_secs` have passed since the last saved reading, or sooner when the change
+/// mode is on and the value moved far enough. A clock set back counts as a new reading rather than a duplicate.
+pub(crate) fn is_new_reading(last: Option<&SavedReading>, point: &BleDataPoint, config: &Config) -> bool {
+    let Some(last) = last else { return true; };
+    let elapsed = point.timestamp - last.timestamp;
+    if elapsed < chrono::Duration::zero() || elapsed.num_seconds() as u64 >= config.duplicate_threshold_secs { return true; }
+    config.duplicate_change_enabled
+        && ((point.temp - last.temp).abs() >= config.duplicate_change_temp - CHANGE_EPSILON || point.hum.abs_diff(last.hum) >= config.duplicate_change_hum.max(1))
+}
+
+#[derive(Default)]
+struct VirtualState { last: Option<DateTime<Local>>, alerts: AlertTracker }
+
+/// Virtual sensors follow this sensor's logged readings: each new combined reading goes into the virtual sensor's
+/// own log and, where switched on, through the alert rules under its name.
+fn follow_virtual_sensors(config: &Config, now: DateTime<Local>, states: &mut HashMap<String, VirtualState>, senders: &AlertSenders, tx: &async_mpsc::UnboundedSender<AppMessage>) {
+    let max_pair = chrono::Duration::seconds(expected_reading_interval_secs(config) as i64);
+    for sensor in &config.virtual_sensors {
+        let Some(point) = sensor.latest(now, max_pair) else { continue; };
+        // After a restart, carry on from what is logged already
+        let state = states.entry(sensor.name.clone()).or_insert_with(|| VirtualState { last: load_history_range_in(&sensor.folder(), now - max_pair * 2, now).last().map(|p| p.timestamp), ..VirtualState::default() });
+        if state.last.is_some_and(|last| last >= point.timestamp) { continue; }
+        state.last = Some(point.timestamp);
+        debug!("Virtual sensor '{}': temp={:.1}, hum={}", sensor.name, point.temp, point.hum);
+        if let Err(e) = log_derived_to_csv(&sensor.folder(), point.timestamp, point.temp, point.hum) { error!("Failed to log virtual sensor '{}': {}", sensor.name, e); }
+        if !sensor.alerts { continue; }
+        // `sensors::display_name` falls back to the "MAC", so the payloads carry the virtual sensor's name; the message names it too
+        let sensor_config = Config { target_mac: sensor.name.clone(), ..config.clone() };
+        for mut event in state.alerts.on_reading(&point, &sensor_config, false) {
+            event.message = format!("{}: {}", sensor.name, event.message);
+            info!("Alert: {}", event.message);
+            senders.dispatch(event, &sensor_config, tx);
+        }
+    }
+}
+
+/// Sleeps on a worker thread in short steps; false when `stop` was signalled meanwhile, so retries end with the processor.
+pub(crate) fn sleep_unless_stopped(stop: &watch::Receiver<bool>, duration: Duration) -> bool {
+    let until = Instant::now() + duration;
+    while !*stop.borrow() {
+        let left = until.saturating_duration_since(Instant::now());
+        if left.is_zero() { return true; }
+        thread::sleep(left.min(Duration::from_millis(100)));
+    }
+    false
+}
+
+pub(crate) async fn background_data_processor(mut rx: async_mpsc::UnboundedReceiver<AppMessage>, tx: async_mpsc::UnboundedSender<AppMessage>, shared_config: Arc<Mutex<Config>>, mut shutdown: watch::Receiver<bool>) {
+    info!("Starting background data processor.");
+    // Reading time, not wall time, so a replay keeps the same rows at any speed
+    let mut last_saved: Option<SavedReading> = None;
+    // Signalled when the processor returns, for whatever reason; the workers then stop retrying
+    let (workers_stop, workers_stop_rx) = watch::channel(false);
+    let (influx_tx, influx_rx) = mpsc::channel();
+    let (influx_config, influx_stop) = (shared_config.clone(), workers_stop_rx.clone());
+    let influx_worker = thread::spawn(move || influx::run_writer(influx_rx, influx_config, influx_stop));
+    let (webhook_tx, webhook_rx) = mpsc::channel();
+    let (webhook_config, webhook_stop) = (shared_config.clone(), workers_stop_rx.clone());
+    let webhook_worker = thread::spawn(move || webhook::run_sender(webhook_rx, webhook_config, webhook_stop));
+    let (telegram_tx, telegram_rx) = mpsc::channel();
+    let (telegram_config, telegram_stop) = (shared_config.clone(), workers_stop_rx.clone());
+    let telegram_worker = thread::spawn(move || telegram::run_sender(telegram_rx, telegram_config, telegram_stop));
+    let (email_tx, email_rx) = mpsc::channel();
+    let (email_config, email_stop) = (shared_config.clone(), workers_stop_rx.clone());
+    let email_worker = thread::spawn(move || email::run_sender(email_rx, email_config, email_stop));
+    let senders = AlertSenders { webhook: webhook_tx, telegram: telegram_tx, email: email_tx };
+    let latest: telegram::LatestReading = Arc::default();
+    let (bot_config, bot_latest, bot_stop) = (shared_config.clone(), latest.clone(), workers_stop_rx);
+    // Not joined: it holds nothing to send, and a long poll can keep it for POLL_TIMEOUT_SECS after the stop
+    thread::spawn(move || telegram::run_bot(bot_config, bot_latest, bot_stop));
+    let mut alerts = AlertTracker::default();
+    let mut rule_alarm = false;
+    let mut spikes = spike::SpikeFilter::default();
+    let mut last_reading = Instant::now();
+    let mut offline_check = tokio::time::interval(Duration::from_secs(5));
+    let mut scanning_paused = false;
+    let mut virtual_states = HashMap::new();
+    // Recorded once as it begins, so later edits of the windows leave past pauses alone
+    let mut last_pause = schedule::read(&schedule::pause_log_path()).pop();
+    loop {
+        let received = tokio::select! {
+            received = rx.recv() => match received { Some(received) => received, None => break },
+            _ = offline_check.tick() => {
+                if scanning_paused { continue; }
+                let config = shared_config.lock().unwrap().clone();
+                if let Some(event) = alerts.on_silence(last_reading.elapsed(), offline_after(&config), &config) {
+                    senders.dispatch(event, &config, &tx);
+                }
+                continue;
+            }
+            _ = shutdown.changed() => { info!("Shutdown requested."); break; }
+        };
+        match received {
+            AppMessage::NewData(mut data_point) => {
+                let _ = tx.send(AppMessage::Heartbeat);
+                let config = shared_config.lock().unwrap().clone();
+                let now = Instant::now();
+                last_reading = now;
+                if config.spike_filter_enabled {
+                    if let Err(reason) = spikes.check(data_point.timestamp, data_point.temp, data_point.hum, config.spike_max_temp_delta, config.spike_max_hum_delta, config.spike_window_secs) {
+                        warn!("Rejected reading as a spike: {} (raw data {:02X?})", reason, data_point.raw_data);
+                        continue;
+                    }
+                }
+                *latest.lock().unwrap() = Some(data_point.clone());
+                let pause = schedule::active_pause(&config.pause_windows, data_point.timestamp);
+                if let Some(interval) = pause.as_ref().filter(|p| last_pause.as_ref() != Some(*p)) {
+                    info!("Pause '{}' until {}.", interval.label, interval.end.format("%Y-%m-%d %H:%M"));
+                    if let Err(e) = schedule::append(&schedule::pause_log_path(), interval) { error!("Failed to append to pause log: {}", e); }
+                    last_pause = Some(interval.clone());
+                    let _ = tx.send(AppMessage::Paused(interval.clone()));
+                }
+                for event in alerts.on_reading(&data_point, &config, pause.is_some()) {
+                    info!("Alert: {}", event.message);
+                    senders.dispatch(event, &config, &tx);
+                }
+                let sounding = alerts.sounding(data_point.timestamp);
+                if sounding != std::mem::replace(&mut rule_alarm, sounding) { let _ = tx.send(AppMessage::AlarmSound(sounding)); }
+                let should_save = is_new_reading(last_saved.as_ref(), &data_point, &config);
+                if should_save {
+                    if let Some(pause) = &pause {
+                        debug!("Logging paused ({}), not writing to CSV.", pause.label);
+                    } else {
+                        info!("Writing data to CSV: temp={}, hum={}", data_point.temp, data_point.hum);
+                        let write_ok = sink_stats::timed(Sink::Csv, || log_to_csv(data_point.timestamp, data_point.temp, data_point.hum)).is_ok();
+                        if !write_ok { error!("Failed to write to CSV file!"); }
+                        data_point.persisted = write_ok;
+                        let _ = tx.send(AppMessage::CsvWriteStatus(write_ok));
+                        if config.influx_enabled { let _ = influx_tx.send(influx::to_line_protocol(&config.influx_measurement, &data_point)); }
+                        if !config.virtual_sensors.is_empty() {
+                            // Reads the member sensors' logs, so it runs on a blocking thread rather than in this task
+                            let (config, senders, tx, mut states, timestamp) = (config.clone(), senders.clone(), tx.clone(), std::mem::take(&mut virtual_states), data_point.timestamp);
+                            virtual_states = tokio::task::spawn_blocking(move || { follow_virtual_sensors(&config, timestamp, &mut states, &senders, &tx); states }).await.unwrap_or_default();
+                        }
+                    }
+                    last_saved = Some(SavedReading { timestamp: data_point.timestamp, temp: data_point.temp, hum: data_point.hum });
+                    if tx.send(AppMessage::NewData(data_point)).is_err() { error!("GUI channel closed, terminating background processor."); break; }
+                } else {
+                    debug!("Skipping write and UI update (duplicate).");
+                }
+            },
+            AppMessage::Control(control) => {
+                scanning_paused = control == ScanControl::Pause;
+                last_reading = Instant::now();
+                if tx.send(AppMessage::Control(control)).is_err() { error!("GUI channel closed, terminating background processor."); break; }
+            },
+            AppMessage::StatusUpdate(status) => {
+                let forwarded = tx.send(AppMessage::StatusUpdate(status)).is_ok();
+                if !forwarded { error!("GUI channel closed, terminating background processor."); break; }
+            },
+            AppMessage::SensorSeen(sighting) => {
+                let forwarded = tx.send(AppMessage::SensorSeen(sighting)).is_ok();
+                if !forwarded { error!("GUI channel closed, terminating background processor."); break; }
+            },
+            AppMessage::Captured(record) => {
+                let forwarded = tx.send(AppMessage::Captured(record)).is_ok();
+                if !forwarded { error!("GUI channel closed, terminating background processor."); break; }
+            },
+            AppMessage::Slept(period) => {
+                // No offline alert for the time the whole PC was asleep
+                last_reading = Instant::now();
+                if tx.send(AppMessage::Slept(period)).is_err() { error!("GUI channel closed, terminating background processor."); break; }
+            },
+            _ => {}
+        }
+    }
+    // Closed channels make the workers send what is queued (without retries once stopped) and return
+    let _ = workers_stop.send(true);
+    drop((senders, influx_tx));
+    let workers = [influx_worker, webhook_worker, telegram_worker, email_worker];
+    let _ = tokio::task::spawn_blocking(move || for worker in workers { let _ = worker.join(); }).await;
+    info!("Background processor terminated.");
+}
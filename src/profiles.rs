@@ -0,0 +1,135 @@
+// --- Named settings profiles (e.g. "Greenhouse", "Server room") and settings import/export ---
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::model::Config;
+
+/// The per-location part of the configuration: sensor, thresholds and scan timing.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct Profile {
+    pub name: String,
+    pub target_mac: String,
+    pub auto_detect_thermopro: bool,
+    pub room_name: String,
+    pub temp_warn_high: f32,
+    pub temp_warn_low: f32,
+    pub hum_warn_high: f32,
+    pub hum_warn_low: f32,
+    pub temp_offset: f32,
+    pub hum_offset: f32,
+    pub scan_timeout_secs: u64,
+    pub scan_pause_secs: u64,
+    pub continuous_mode: bool,
+}
+
+impl Profile {
+    pub fn capture(name: &str, config: &Config) -> Self {
+        Self {
+            name: name.to_string(), target_mac: config.target_mac.clone(), auto_detect_thermopro: config.auto_detect_thermopro, room_name: config.room_name.clone(),
+            temp_warn_high: config.temp_warn_high, temp_warn_low: config.temp_warn_low, hum_warn_high: config.hum_warn_high, hum_warn_low: config.hum_warn_low,
+            temp_offset: config.temp_offset, hum_offset: config.hum_offset,
+            scan_timeout_secs: config.scan_timeout_secs, scan_pause_secs: config.scan_pause_secs, continuous_mode: config.continuous_mode,
+        }
+    }
+
+    pub fn apply(&self, config: &mut Config) {
+        config.target_mac = self.target_mac.clone(); config.auto_detect_thermopro = self.auto_detect_thermopro; config.room_name = self.room_name.clone();
+        (config.temp_warn_high, config.temp_warn_low, config.hum_warn_high, config.hum_warn_low) = (self.temp_warn_high, self.temp_warn_low, self.hum_warn_high, self.hum_warn_low);
+        (config.temp_offset, config.hum_offset) = (self.temp_offset, self.hum_offset);
+        (config.scan_timeout_secs, config.scan_pause_secs, config.continuous_mode) = (self.scan_timeout_secs, self.scan_pause_secs, self.continuous_mode);
+    }
+}
+
+/// Adds the current settings as profile `name`, or overwrites the one with that name, and makes it active.
+pub fn save_as(config: &mut Config, name: &str) {
+    let profile = Profile::capture(name, config);
+    match config.profiles.iter_mut().find(|p| p.name == name) { Some(existing) => *existing = profile, None => config.profiles.push(profile) }
+    config.active_profile = name.to_string();
+}
+
+/// Keeps edits made under the active profile, then loads `name`; false when there is no such profile.
+pub fn switch(config: &mut Config, name: &str) -> bool {
+    let Some(target) = config.profiles.iter().find(|p| p.name == name).cloned() else { return false; };
+    if !config.active_profile.is_empty() && config.profiles.iter().any(|p| p.name == config.active_profile) { let active = config.active_profile.clone(); save_as(config, &active); }
+    target.apply(config);
+    config.active_profile = name.to_string();
+    true
+}
+
+// Passwords, tokens and the simple-mode PIN stay on this PC: left out of exports and kept on import
+fn secrets(config: &mut Config) -> [&mut String; 4] {
+    [&mut config.smtp_password, &mut config.telegram_bot_token, &mut config.influx_token, &mut config.admin_pin]
+}
+
+pub fn export(config: &Config, path: &Path) -> Result<(), String> {
+    let mut shared = config.clone();
+    secrets(&mut shared).into_iter().for_each(String::clear);
+    let json = serde_json::to_string_pretty(&shared).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| e.to_string())
+}
+
+/// Reads exported settings; what belongs to this machine (data folders, start with the system, migration state, credentials) is kept.
+pub fn import(path: &Path, current: &Config) -> Result<Config, String> {
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let mut imported: Config = serde_json::from_str(&text).map_err(|e| format!("not a settings file ({})", e))?;
+    imported.data_folder = current.data_folder.clone();
+    imported.recent_data_folders = current.recent_data_folders.clone();
+    (imported.autostart, imported.autostart_minimized) = (current.autostart, current.autostart_minimized);
+    imported.legacy_migration_done = current.legacy_migration_done;
+    (imported.window, imported.kiosk_mode) = (current.window, current.kiosk_mode);
+    let mut local = current.clone();
+    for (secret, kept) in secrets(&mut imported).into_iter().zip(secrets(&mut local)) { *secret = std::mem::take(kept); }
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+
+    #[test]
+    fn profiles_switch_sensor_and_thresholds_and_settings_round_trip() {
+        let mut config = Config { target_mac: TARGET.to_string(), temp_warn_high: 30.0, ..Default::default() };
+        save_as(&mut config, "Greenhouse");
+        (config.target_mac, config.temp_warn_high, config.room_name) = (OTHER.to_string(), 24.0, "Rack 2".to_string());
+        save_as(&mut config, "Server room");
+        assert!(switch(&mut config, "Greenhouse"));
+        assert_eq!((config.target_mac.as_str(), config.temp_warn_high, config.room_name.as_str()), (TARGET, 30.0, ""));
+        // Edits made under a profile are kept when switching away
+        config.temp_warn_high = 32.0;
+        assert!(switch(&mut config, "Server room"));
+        assert!(switch(&mut config, "Greenhouse"));
+        assert_eq!(config.temp_warn_high, 32.0);
+        assert!(!switch(&mut config, "Cellar"));
+
+        let path = std::env::temp_dir().join(format!("temp_monitor_test_{}_settings.json", std::process::id()));
+        export(&config, &path).unwrap();
+        let local = Config { data_folder: "/srv/cabin".to_string(), autostart: true, ..Default::default() };
+        let imported = import(&path, &local).unwrap();
+        assert_eq!(imported.profiles, config.profiles);
+        assert_eq!(imported.active_profile, "Greenhouse");
+        assert_eq!((imported.data_folder.as_str(), imported.autostart), ("/srv/cabin", true));
+        fs::write(&path, "not json").unwrap();
+        assert!(import(&path, &local).is_err());
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn exported_settings_leave_out_credentials_and_import_keeps_the_local_ones() {
+        let path = std::env::temp_dir().join(format!("temp_monitor_test_{}_secrets.json", std::process::id()));
+        let shared = Config { smtp_password: "hunter2".into(), telegram_bot_token: "123:abc".into(), influx_token: "influx-secret".into(), admin_pin: "1234".into(), webhook_url: "https://hooks.example/1".into(), ..test_config() };
+        export(&shared, &path).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(["hunter2", "123:abc", "influx-secret", "\"1234\""].iter().all(|secret| !text.contains(secret)), "{}", text);
+        assert!(text.contains("https://hooks.example/1"));
+        // Nothing in the file overwrites this PC's own credentials, not even a blank
+        let local = Config { smtp_password: "local-pass".into(), admin_pin: "9999".into(), ..Default::default() };
+        let imported = import(&path, &local).unwrap();
+        assert_eq!((imported.smtp_password.as_str(), imported.telegram_bot_token.as_str(), imported.influx_token.as_str(), imported.admin_pin.as_str()), ("local-pass", "", "", "9999"));
+        assert_eq!((imported.target_mac.as_str(), imported.webhook_url.as_str()), (TARGET, "https://hooks.example/1"));
+        let _ = fs::remove_file(&path);
+    }
+}
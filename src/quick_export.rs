@@ -0,0 +1,43 @@
+// --- One-key export of the visible range to a fixed folder with an auto-generated file name ---
+
+use chrono::{DateTime, Local};
+use egui::{Key, KeyboardShortcut, Modifiers};
+use std::path::{Path, PathBuf};
+
+use crate::export::ExportFormat;
+
+pub const NAME_PLACEHOLDERS: &str = "{date} {time} {room}";
+
+/// Parses "Ctrl+Shift+E", "Alt+F9" and the like; `Cmd` is the command key on macOS and Ctrl elsewhere.
+pub fn parse_shortcut(text: &str) -> Option<KeyboardShortcut> {
+    let mut parts: Vec<&str> = text.split('+').map(str::trim).filter(|p| !p.is_empty()).collect();
+    let key = Key::from_name(parts.pop()?)?;
+    let mut modifiers = Modifiers::NONE;
+    for part in parts {
+        modifiers = modifiers | match part.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => Modifiers::CTRL,
+            "shift" => Modifiers::SHIFT,
+            "alt" | "option" => Modifiers::ALT,
+            "cmd" | "command" => Modifiers::COMMAND,
+            _ => return None,
+        };
+    }
+    Some(KeyboardShortcut::new(modifiers, key))
+}
+
+/// Fills the name template and makes it safe as a file name.
+pub fn file_name(template: &str, room: &str, now: DateTime<Local>, format: ExportFormat) -> String {
+    let room = if room.trim().is_empty() { "sensor" } else { room.trim() };
+    let name = template.replace("{date}", &now.format("%Y-%m-%d").to_string()).replace("{time}", &now.format("%H%M%S").to_string()).replace("{room}", room);
+    let name: String = name.chars().map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ' ') { c } else { '_' }).collect();
+    let name = name.trim();
+    format!("{}.{}", if name.is_empty() { "export" } else { name }, format.extension())
+}
+
+/// `dir/name`, or `dir/name_2`, `dir/name_3`, ... if that file already exists.
+pub fn unique_path(dir: &Path, name: &str) -> PathBuf {
+    let path = dir.join(name);
+    if !path.exists() { return path; }
+    let (stem, extension) = name.rsplit_once('.').unwrap_or((name, ""));
+    (2..).map(|n| dir.join(format!("{}_{}.{}", stem, n, extension))).find(|p| !p.exists()).unwrap_or(path)
+}
@@ -0,0 +1,55 @@
+// --- Repaint strategy: redraw when scanner messages arrive instead of on a fixed clock ---
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+
+/// Wake-up for work done in `update` (power polling, schedules, maintenance) when no message arrives.
+pub const HOUSEKEEPING_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum RepaintMode {
+    /// Only on new messages, input and known deadlines (toast expiry, sensor offline)
+    #[default]
+    OnData,
+    /// Every second (every 5 s with the battery saver), as before
+    Clock,
+}
+
+impl RepaintMode {
+    pub const ALL: [RepaintMode; 2] = [RepaintMode::OnData, RepaintMode::Clock];
+
+    pub fn label(&self) -> &'static str {
+        match self { RepaintMode::OnData => "Redraw on new data", RepaintMode::Clock => "Redraw every second" }
+    }
+}
+
+/// Passes messages on to the GUI channel and wakes the GUI for each, so it can sleep in between.
+pub async fn forward_and_wake<T>(mut rx: UnboundedReceiver<T>, tx: UnboundedSender<T>, ctx: egui::Context) {
+    while let Some(message) = rx.recv().await {
+        if tx.send(message).is_err() { break; }
+        ctx.request_repaint();
+    }
+}
+
+/// Time until the next repaint nobody asks for: the clock tick, or the nearest deadline capped at the housekeeping interval.
+pub fn idle_interval(mode: RepaintMode, battery_saver: bool, deadlines: impl IntoIterator<Item = Duration>) -> Duration {
+    match mode {
+        RepaintMode::Clock => Duration::from_secs(if battery_saver { 5 } else { 1 }),
+        RepaintMode::OnData => deadlines.into_iter().fold(HOUSEKEEPING_INTERVAL, Duration::min),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn idle_repaint_waits_for_the_nearest_deadline() {
+        assert_eq!(idle_interval(RepaintMode::Clock, false, [Duration::from_millis(200)]), Duration::from_secs(1));
+        assert_eq!(idle_interval(RepaintMode::Clock, true, []), Duration::from_secs(5));
+        assert_eq!(idle_interval(RepaintMode::OnData, false, []), HOUSEKEEPING_INTERVAL);
+        assert_eq!(idle_interval(RepaintMode::OnData, false, [Duration::from_secs(90), Duration::from_secs(2)]), Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,47 @@
+// --- Replay: the rows of a log or exported CSV fed to the processor as if they were live readings ---
+
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::{mpsc as async_mpsc, watch};
+
+use crate::model::{AppMessage, BleDataPoint, HistoryPoint};
+use crate::storage::{parse_history_record, read_log_records};
+
+/// Device id of replayed readings, so they are told apart from a real sensor in the logs.
+pub const REPLAY_DEVICE: &str = "replay";
+
+/// Data folder of a replay without `--data-dir`, so replayed rows never end up in the real logs.
+pub fn scratch_dir() -> PathBuf { std::env::temp_dir().join("temp_monitor_replay") }
+
+/// Readings of the file in time order.
+pub fn load(path: &Path) -> Vec<HistoryPoint> {
+    let mut points: Vec<HistoryPoint> = read_log_records(path).iter().filter_map(parse_history_record).collect();
+    points.sort_by_key(|p| p.timestamp);
+    points
+}
+
+/// Waits between consecutive readings: the gaps in the file divided by `speed`; a speed of 0 sends without waiting.
+pub fn delays(points: &[HistoryPoint], speed: f64) -> Vec<Duration> {
+    std::iter::once(Duration::ZERO)
+        .chain(points.windows(2).map(|pair| if speed > 0.0 { (pair[1].timestamp - pair[0].timestamp).to_std().unwrap_or_default().div_f64(speed) } else { Duration::ZERO }))
+        .collect()
+}
+
+/// Sends the readings with their original timestamps, then returns, which closes the channel and ends the processor.
+pub async fn run(path: PathBuf, speed: f64, tx: async_mpsc::UnboundedSender<AppMessage>, mut shutdown: watch::Receiver<bool>) {
+    let points = load(&path);
+    if points.is_empty() { warn!("No readings to replay in '{}'.", path.display()); let _ = tx.send(AppMessage::StatusUpdate("Nothing to replay".into())); return; }
+    info!("Replaying {} readings from '{}' at {}x.", points.len(), path.display(), speed);
+    let _ = tx.send(AppMessage::StatusUpdate("Replaying...".into()));
+    for (point, delay) in points.iter().zip(delays(&points, speed)) {
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = shutdown.changed() => return,
+        }
+        let data = BleDataPoint { timestamp: point.timestamp, temp: point.temp, hum: point.hum, device_id: REPLAY_DEVICE.into(), model: "Replay", rssi: None, raw_data: Vec::new(), device_alarm: false, battery: None, persisted: false };
+        if tx.send(AppMessage::NewData(data)).is_err() { return; }
+    }
+    info!("Replay of '{}' finished.", path.display());
+    let _ = tx.send(AppMessage::StatusUpdate("Replay finished".into()));
+}
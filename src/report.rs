@@ -0,0 +1,186 @@
+// --- Cold-chain compliance report (Mean Kinetic Temperature + excursions) ---
+
+use chrono::{DateTime, Duration as ChronoDuration, Local, TimeZone};
+use std::fmt::Write as _;
+
+use crate::calendar::{DateFormat, WeekStart};
+use crate::model::HistoryPoint;
+use crate::schedule::{paused_secs, PauseInterval};
+
+// ΔH/R for the standard activation energy of 83.144 kJ/mol (USP <1160>, ICH Q1A)
+const ACTIVATION_ENERGY_OVER_R: f64 = 10_000.0;
+const KELVIN_OFFSET: f64 = 273.15;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ReportPeriod { Last24Hours, Last7Days, Last30Days, ThisWeek, LastWeek, Custom }
+
+impl ReportPeriod {
+    pub const ALL: [ReportPeriod; 6] = [ReportPeriod::Last24Hours, ReportPeriod::Last7Days, ReportPeriod::Last30Days, ReportPeriod::ThisWeek, ReportPeriod::LastWeek, ReportPeriod::Custom];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReportPeriod::Last24Hours => "Last 24 hours", ReportPeriod::Last7Days => "Last 7 days", ReportPeriod::Last30Days => "Last 30 days",
+            ReportPeriod::ThisWeek => "This week", ReportPeriod::LastWeek => "Last week", ReportPeriod::Custom => "Custom",
+        }
+    }
+
+    /// Calendar weeks begin on `week_start`; the rolling periods end at `now`. `None` for `Custom`, whose range is typed in.
+    pub fn range(&self, now: DateTime<Local>, week_start: WeekStart) -> Option<(DateTime<Local>, DateTime<Local>)> {
+        let week = Local.from_local_datetime(&week_start.start_of_week(now.date_naive()).and_hms_opt(0, 0, 0).unwrap_or_default()).earliest().unwrap_or(now);
+        match self {
+            ReportPeriod::Last24Hours => Some((now - ChronoDuration::hours(24), now)),
+            ReportPeriod::Last7Days => Some((now - ChronoDuration::days(7), now)),
+            ReportPeriod::Last30Days => Some((now - ChronoDuration::days(30), now)),
+            ReportPeriod::ThisWeek => Some((week, now)),
+            ReportPeriod::LastWeek => Some((week - ChronoDuration::days(7), week)),
+            ReportPeriod::Custom => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Excursion { pub start: DateTime<Local>, pub end: DateTime<Local>, pub peak: f32, pub above: bool, }
+
+impl Excursion {
+    pub fn duration(&self) -> ChronoDuration { self.end - self.start }
+}
+
+#[derive(Clone, Debug)]
+pub struct ColdChainReport {
+    pub period_start: DateTime<Local>,
+    pub period_end: DateTime<Local>,
+    pub samples: usize,
+    pub mkt: Option<f32>,
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+    pub mean: Option<f32>,
+    pub completeness: f32,
+    pub paused_secs: i64,
+    pub limit_low: f32,
+    pub limit_high: f32,
+    pub excursions: Vec<Excursion>,
+}
+
+/// Mean Kinetic Temperature in °C, each reading weighted by the time it stands for: half the way to each
+/// neighbour, leaving out pauses and gaps longer than `max_gap` as `completeness` does. Equal weights when no
+/// two readings are close enough, e.g. a single one.
+pub fn mean_kinetic_temperature(points: &[HistoryPoint], max_gap: ChronoDuration, paused: &[PauseInterval]) -> Option<f32> {
+    if points.is_empty() { return None; }
+    let covered: Vec<f64> = points.windows(2)
+        .map(|w| (w[1].timestamp - w[0].timestamp).num_seconds() - paused_secs(paused, w[0].timestamp, w[1].timestamp))
+        .map(|secs| if secs <= max_gap.num_seconds() { secs.max(0) as f64 } else { 0.0 })
+        .collect();
+    let mut weights: Vec<f64> = (0..points.len()).map(|i| (if i > 0 { covered[i - 1] } else { 0.0 } + covered.get(i).copied().unwrap_or(0.0)) / 2.0).collect();
+    if weights.iter().sum::<f64>() <= 0.0 { weights.fill(1.0); }
+    let arrhenius: f64 = points.iter().zip(&weights).map(|(p, w)| w * (-ACTIVATION_ENERGY_OVER_R / (p.temp as f64 + KELVIN_OFFSET)).exp()).sum();
+    let mkt_kelvin = ACTIVATION_ENERGY_OVER_R / -(arrhenius / weights.iter().sum::<f64>()).ln();
+    Some((mkt_kelvin - KELVIN_OFFSET) as f32)
+}
+
+/// Contiguous runs of readings outside `[low, high]`. A run ends at the first reading back in range.
+pub fn find_excursions(points: &[HistoryPoint], low: f32, high: f32) -> Vec<Excursion> {
+    let mut excursions = Vec::new();
+    let mut current: Option<Excursion> = None;
+    for p in points {
+        let above = p.temp > high;
+        let out_of_range = above || p.temp < low;
+        match current.as_mut() {
+            Some(exc) if out_of_range && exc.above == above => {
+                exc.end = p.timestamp;
+                if (above && p.temp > exc.peak) || (!above && p.temp < exc.peak) { exc.peak = p.temp; }
+            }
+            _ => {
+                if let Some(mut exc) = current.take() { exc.end = p.timestamp; excursions.push(exc); }
+                if out_of_range { current = Some(Excursion { start: p.timestamp, end: p.timestamp, peak: p.temp, above }); }
+            }
+        }
+    }
+    excursions.extend(current);
+    excursions
+}
+
+/// Share of the period covered by readings spaced no further apart than `max_gap`.
+/// Scheduled pauses count neither as expected nor as missing time.
+pub fn completeness(points: &[HistoryPoint], start: DateTime<Local>, end: DateTime<Local>, max_gap: ChronoDuration, paused: &[PauseInterval]) -> f32 {
+    let period = (end - start).num_seconds() - paused_secs(paused, start, end);
+    if period <= 0 { return 0.0; }
+    let covered: i64 = points.windows(2)
+        .map(|w| (w[1].timestamp - w[0].timestamp).num_seconds() - paused_secs(paused, w[0].timestamp, w[1].timestamp))
+        .filter(|gap| *gap <= max_gap.num_seconds())
+        .sum();
+    (covered as f32 / period as f32).clamp(0.0, 1.0)
+}
+
+impl ColdChainReport {
+    pub fn build(points: &[HistoryPoint], period_start: DateTime<Local>, period_end: DateTime<Local>, limit_low: f32, limit_high: f32, max_gap: ChronoDuration, paused: &[PauseInterval]) -> Self {
+        let points: Vec<HistoryPoint> = points.iter().filter(|p| !paused.iter().any(|i| i.contains(p.timestamp))).cloned().collect();
+        let temps: Vec<f32> = points.iter().map(|p| p.temp).collect();
+        Self {
+            period_start, period_end,
+            samples: temps.len(),
+            mkt: mean_kinetic_temperature(&points, max_gap, paused),
+            min: temps.iter().copied().reduce(f32::min),
+            max: temps.iter().copied().reduce(f32::max),
+            mean: if temps.is_empty() { None } else { Some(temps.iter().sum::<f32>() / temps.len() as f32) },
+            completeness: completeness(&points, period_start, period_end, max_gap, paused),
+            paused_secs: paused_secs(paused, period_start, period_end),
+            limit_low, limit_high,
+            excursions: find_excursions(&points, limit_low, limit_high),
+        }
+    }
+
+    pub fn total_excursion_time(&self) -> ChronoDuration {
+        self.excursions.iter().map(|e| e.duration()).fold(ChronoDuration::zero(), |acc, d| acc + d)
+    }
+
+    /// Plain-text report suitable for attaching to cold-chain documentation.
+    pub fn to_text(&self, date_format: DateFormat) -> String {
+        let fmt_temp = |v: Option<f32>| v.map(|t| format!("{:.2} °C", t)).unwrap_or_else(|| "N/A".to_string());
+        let mut out = String::new();
+        let _ = writeln!(out, "TEMPERATURE MONITORING REPORT");
+        let _ = writeln!(out, "Generated:        {}", date_format.date_time_secs(Local::now()));
+        let _ = writeln!(out, "Period:           {} – {}", date_format.date_time(self.period_start), date_format.date_time(self.period_end));
+        let _ = writeln!(out, "Acceptance range: {:.1} °C to {:.1} °C", self.limit_low, self.limit_high);
+        let _ = writeln!(out);
+        let _ = writeln!(out, "Samples:          {}", self.samples);
+        let _ = writeln!(out, "Data completeness: {:.1} %", self.completeness * 100.0);
+        if self.paused_secs > 0 { let _ = writeln!(out, "Scheduled pauses: {} min (excluded from statistics)", self.paused_secs / 60); }
+        let _ = writeln!(out, "MKT (ΔH = 83.144 kJ/mol): {}", fmt_temp(self.mkt));
+        let _ = writeln!(out, "Minimum:          {}", fmt_temp(self.min));
+        let _ = writeln!(out, "Maximum:          {}", fmt_temp(self.max));
+        let _ = writeln!(out, "Arithmetic mean:  {}", fmt_temp(self.mean));
+        let _ = writeln!(out);
+        let total = self.total_excursion_time();
+        let _ = writeln!(out, "Excursions:       {} (total {} min)", self.excursions.len(), total.num_minutes());
+        for (i, exc) in self.excursions.iter().enumerate() {
+            let _ = writeln!(out, "  #{:<3} {} – {}  {:>5} min  {} limit, peak {:.1} °C", i + 1, date_format.date_time_secs(exc.start), exc.end.format("%H:%M:%S"), exc.duration().num_minutes(), if exc.above { "above upper" } else { "below lower" }, exc.peak);
+        }
+        let verdict = if self.excursions.is_empty() && self.mkt.is_some_and(|m| m >= self.limit_low && m <= self.limit_high) { "COMPLIANT" } else { "REVIEW REQUIRED" };
+        let _ = writeln!(out);
+        let _ = writeln!(out, "Result:           {}", verdict);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+
+    #[test]
+    fn mean_kinetic_temperature_weights_readings_by_the_time_they_cover() {
+        let start = Local::now() - ChronoDuration::hours(3);
+        let at = |mins, temp| point(start + ChronoDuration::minutes(mins), temp, 50);
+        let max_gap = ChronoDuration::minutes(10);
+        // An hour at 20 °C read every minute, then an hour at 30 °C read every 6 minutes
+        let points: Vec<HistoryPoint> = (0..=60).map(|m| at(m, 20.0)).chain((1..=10).map(|i| at(60 + i * 6, 30.0))).collect();
+        let mkt = mean_kinetic_temperature(&points, max_gap, &[]).unwrap();
+        // Counted per reading, the 20 °C hour would pull it down to about 22 °C
+        assert!((25.5..26.5).contains(&mkt), "{}", mkt);
+        // A reading before a long outage does not stand for the whole outage
+        let outage = [at(0, 20.0), at(1, 20.0), at(2, 40.0), at(180, 20.0), at(181, 20.0)];
+        assert!(mean_kinetic_temperature(&outage, max_gap, &[]).unwrap() < 31.0);
+        assert_eq!(mean_kinetic_temperature(&[at(0, 25.0)], max_gap, &[]).map(|t| (t * 100.0).round()), Some(2500.0));
+        assert_eq!(ReportPeriod::Custom.range(start, WeekStart::default()), None);
+    }
+}
@@ -0,0 +1,134 @@
+// --- Data retention: old daily logs are summarised and gzipped, very old archives removed ---
+
+use chrono::{Duration as ChronoDuration, NaiveDate};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::info;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::aggregate::{self, log_date};
+use crate::edit::LOG_WRITE_LOCK;
+use crate::paths;
+use crate::storage::{parse_history_record, read_log_records};
+
+pub fn compressed_path(log: &Path) -> PathBuf { log.with_extension("csv.gz") }
+
+/// Opens a daily log, decompressing it when it is an archived `.csv.gz`.
+pub fn open_log(path: &Path) -> io::Result<Box<dyn Read>> {
+    let file = fs::File::open(path)?;
+    Ok(if path.extension().is_some_and(|e| e == "gz") { Box::new(GzDecoder::new(file)) } else { Box::new(file) })
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MaintenanceSummary { pub compressed: usize, pub deleted: usize }
+
+/// Compresses daily logs older than `raw_days` (after refreshing their summaries) and deletes
+/// archives older than `archive_days`; 0 keeps the archives forever. Today's log is never touched.
+pub fn run(today: NaiveDate, raw_days: u32, archive_days: u32) -> io::Result<MaintenanceSummary> {
+    let mut summary = MaintenanceSummary::default();
+    let compress_before = today - ChronoDuration::days(raw_days.max(1) as i64);
+    let delete_before = (archive_days > 0).then(|| today - ChronoDuration::days(archive_days.max(raw_days.max(1)) as i64));
+    let mut logs: Vec<(NaiveDate, PathBuf)> = fs::read_dir(paths::data_dir())?.flatten().map(|e| e.path()).filter_map(|p| Some((log_date(&p)?, p))).collect();
+    logs.sort();
+    for (date, path) in logs {
+        let compressed = path.extension().is_some_and(|e| e == "gz");
+        if !compressed && date < compress_before {
+            compress_day(&path, date)?;
+            summary.compressed += 1;
+        } else if compressed && delete_before.is_some_and(|before| date < before) {
+            fs::remove_file(&path)?;
+            summary.deleted += 1;
+        }
+    }
+    if summary.compressed + summary.deleted > 0 { info!("Retention: compressed {} daily logs, deleted {} archives.", summary.compressed, summary.deleted); }
+    Ok(summary)
+}
+
+// Folds the raw log into the day's archive (a log re-created by an import joins the existing one)
+fn compress_day(path: &Path, date: NaiveDate) -> io::Result<()> {
+    let _guard = LOG_WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let archive = compressed_path(path);
+    let mut contents = Vec::new();
+    if archive.exists() { open_log(&archive)?.read_to_end(&mut contents)?; }
+    let raw = fs::read(path)?;
+    let raw = if contents.is_empty() { &raw[..] } else { raw.splitn(2, |b| *b == b'\n').nth(1).unwrap_or_default() };
+    if !contents.is_empty() && !contents.ends_with(b"\n") { contents.push(b'\n'); }
+    contents.extend_from_slice(raw);
+
+    let tmp = archive.with_extension("gz.tmp");
+    let mut encoder = GzEncoder::new(fs::File::create(&tmp)?, Compression::default());
+    encoder.write_all(&contents)?;
+    encoder.finish()?.sync_all()?;
+    fs::rename(&tmp, &archive)?;
+    aggregate::store(&[(date, read_log_records(&archive).iter().filter_map(parse_history_record).collect())])?;
+    fs::remove_file(path)
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct StorageUsage {
+    pub raw_files: usize,
+    pub raw_bytes: u64,
+    pub archived_files: usize,
+    pub archived_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Sizes of everything in the data folder, split into raw and archived daily logs.
+pub fn storage_usage() -> StorageUsage {
+    let mut usage = StorageUsage::default();
+    let Ok(entries) = fs::read_dir(paths::data_dir()) else { return usage; };
+    for entry in entries.flatten() {
+        let Ok(meta) = entry.metadata() else { continue; };
+        if !meta.is_file() { continue; }
+        let path = entry.path();
+        usage.total_bytes += meta.len();
+        if log_date(&path).is_none() { continue; }
+        if path.extension().is_some_and(|e| e == "gz") { usage.archived_files += 1; usage.archived_bytes += meta.len(); } else { usage.raw_files += 1; usage.raw_bytes += meta.len(); }
+    }
+    usage
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1 << 30 => format!("{:.1} GB", b as f64 / (1u64 << 30) as f64),
+        b if b >= 1 << 20 => format!("{:.1} MB", b as f64 / (1u64 << 20) as f64),
+        b if b >= 1 << 10 => format!("{:.0} kB", b as f64 / (1u64 << 10) as f64),
+        b => format!("{} B", b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{format_log_timestamp, load_history_range};
+    use crate::test_support::*;
+
+    #[test]
+    fn retention_archives_old_logs_and_keeps_hourly_means() {
+        let _data_dir = TempDataDir::new("retention");
+        let today = chrono::Local::now().date_naive();
+        let old = today - ChronoDuration::days(40);
+        let recent = today - ChronoDuration::days(3);
+        let rows = |day| format!("DateTime,Temperature,Humidity\n{},20.0,40\n{},22.0,50\n{},25.0,60\n", format_log_timestamp(local(day, 10, 0)), format_log_timestamp(local(day, 10, 30)), format_log_timestamp(local(day, 11, 0)));
+        fs::write(paths::log_path(old), rows(old)).unwrap();
+        fs::write(paths::log_path(recent), rows(recent)).unwrap();
+
+        let summary = run(today, 30, 0).unwrap();
+        assert_eq!((summary.compressed, summary.deleted), (1, 0));
+        assert!(!paths::log_path(old).exists() && compressed_path(&paths::log_path(old)).exists());
+        assert!(paths::log_path(recent).exists());
+        let range = |day| load_history_range(local(day, 0, 0), local(day, 23, 0));
+        assert_eq!(range(old).len(), 3);
+
+        // Once the archive is gone the day is still covered by its hourly means
+        let summary = run(today, 30, 35).unwrap();
+        assert_eq!((summary.compressed, summary.deleted), (0, 1));
+        let hourly: Vec<(f32, u8)> = range(old).iter().map(|p| (p.temp, p.hum)).collect();
+        assert_eq!(hourly, vec![(21.0, 45), (25.0, 60)]);
+        let usage = storage_usage();
+        assert_eq!((usage.raw_files, usage.archived_files), (1, 0));
+    }
+}
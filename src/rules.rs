@@ -0,0 +1,278 @@
+// --- Alert rules: metric, comparison, hold time, severity and channels per rule ---
+
+use chrono::{DateTime, Duration as ChronoDuration, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::comfort;
+use crate::model::{dew_point, BleDataPoint, Config};
+use crate::schedule::{self, PauseWindow};
+use crate::trend;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Metric {
+    #[default]
+    Temperature,
+    Humidity,
+    DewPoint,
+    Rssi,
+    /// Only from sensor families that advertise it; rules on it stay quiet for the others
+    Battery,
+    /// Rate of change per hour over the trend window (`trend_window_mins`)
+    TemperatureRate,
+    HumidityRate,
+    /// Humidity above the critical humidity for mold growth at the current temperature
+    MoldRisk,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 8] = [Metric::Temperature, Metric::Humidity, Metric::DewPoint, Metric::Rssi, Metric::Battery, Metric::TemperatureRate, Metric::HumidityRate, Metric::MoldRisk];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Metric::Temperature => "Temperature", Metric::Humidity => "Humidity", Metric::DewPoint => "Dew point", Metric::Rssi => "Signal (RSSI)", Metric::Battery => "Battery",
+            Metric::TemperatureRate => "Temperature change", Metric::HumidityRate => "Humidity change", Metric::MoldRisk => "Mold risk (RH over critical)",
+        }
+    }
+
+    pub fn unit(&self) -> &'static str {
+        match self { Metric::Temperature | Metric::DewPoint => "°C", Metric::Humidity | Metric::Battery | Metric::MoldRisk => "%", Metric::Rssi => "dBm", Metric::TemperatureRate => "°C/h", Metric::HumidityRate => "%/h" }
+    }
+
+    fn decimals(&self) -> usize { if matches!(self, Metric::Temperature | Metric::DewPoint | Metric::TemperatureRate) { 1 } else { 0 } }
+
+    /// Value from a single reading; the rates need the readings before it and come from `RuleEngine`.
+    pub fn value(&self, data: &BleDataPoint) -> Option<f32> {
+        match self {
+            Metric::Temperature => Some(data.temp),
+            Metric::Humidity => Some(data.hum as f32),
+            Metric::DewPoint => Some(dew_point(data.temp, data.hum)),
+            Metric::Rssi => data.rssi.map(f32::from),
+            Metric::Battery => data.battery.map(f32::from),
+            Metric::MoldRisk => Some(comfort::mold_index(data.temp, data.hum)),
+            Metric::TemperatureRate | Metric::HumidityRate => None,
+        }
+    }
+
+    pub fn format(&self, value: f32) -> String { format!("{:.*} {}", self.decimals(), value, self.unit()) }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Comparison {
+    #[default]
+    Above,
+    Below,
+}
+
+impl Comparison {
+    pub const ALL: [Comparison; 2] = [Comparison::Above, Comparison::Below];
+
+    pub fn label(&self) -> &'static str { match self { Comparison::Above => "above", Comparison::Below => "below" } }
+
+    fn holds(&self, value: f32, threshold: f32) -> bool { match self { Comparison::Above => value > threshold, Comparison::Below => value < threshold } }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Severity {
+    Info,
+    #[default]
+    Warning,
+    Critical,
+}
+
+impl Severity {
+    pub const ALL: [Severity; 3] = [Severity::Info, Severity::Warning, Severity::Critical];
+
+    pub fn label(&self) -> &'static str { match self { Severity::Info => "Info", Severity::Warning => "Warning", Severity::Critical => "Critical" } }
+
+    /// Value of the `severity` field in webhook payloads.
+    pub fn id(&self) -> &'static str { match self { Severity::Info => "info", Severity::Warning => "warning", Severity::Critical => "critical" } }
+}
+
+/// Where a rule's alerts go; webhook, Telegram and email also need to be set up in the general settings.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(default)]
+pub struct Channels { pub toast: bool, pub sound: bool, pub webhook: bool, pub telegram: bool, pub email: bool }
+
+impl Default for Channels {
+    fn default() -> Self { Self { toast: true, sound: true, webhook: true, telegram: true, email: true } }
+}
+
+impl Channels {
+    pub const NONE: Channels = Channels { toast: false, sound: false, webhook: false, telegram: false, email: false };
+
+    fn without(self, muted: Channels) -> Channels {
+        Channels { toast: self.toast && !muted.toast, sound: self.sound && !muted.sound, webhook: self.webhook && !muted.webhook, telegram: self.telegram && !muted.telegram, email: self.email && !muted.email }
+    }
+}
+
+/// Channels a rule keeps quiet during a window, e.g. sound at night or email at weekends.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct QuietHours {
+    #[serde(flatten)]
+    pub window: PauseWindow,
+    pub muted: Channels,
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        Self { window: PauseWindow { label: "Night".to_string(), weekday: None, start: "22:00".to_string(), end: "07:00".to_string() }, muted: Channels { sound: true, ..Channels::NONE } }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct AlertRule {
+    pub enabled: bool,
+    /// Shown before the alert text; empty uses the plain alert text
+    pub name: String,
+    pub metric: Metric,
+    pub comparison: Comparison,
+    pub threshold: f32,
+    /// How long the condition has to hold before the alert fires; 0 fires on the first reading
+    pub duration_mins: u32,
+    pub severity: Severity,
+    pub channels: Channels,
+    pub quiet_hours: Vec<QuietHours>,
+}
+
+impl Default for AlertRule {
+    fn default() -> Self {
+        Self { enabled: true, name: String::new(), metric: Metric::Temperature, comparison: Comparison::Above, threshold: 30.0, duration_mins: 0, severity: Severity::Warning, channels: Channels::default(), quiet_hours: Vec::new() }
+    }
+}
+
+impl AlertRule {
+    /// E.g. "Temperature above 30.0 °C for 10 min".
+    pub fn describe(&self) -> String {
+        let mut text = format!("{} {} {}", self.metric.label(), self.comparison.label(), self.metric.format(self.threshold));
+        if self.duration_mins > 0 { text.push_str(&format!(" for {} min", self.duration_mins)); }
+        text
+    }
+
+    /// The rule's channels minus those muted by quiet hours active at `t`.
+    pub fn channels_at(&self, t: DateTime<Local>) -> Channels {
+        self.quiet_hours.iter().filter(|q| schedule::active_pause(std::slice::from_ref(&q.window), t).is_some()).fold(self.channels, |channels, q| channels.without(q.muted))
+    }
+}
+
+/// The rules that reproduce the former fixed thresholds: temperature with sound, humidity without, as before.
+pub fn from_thresholds(config: &Config) -> Vec<AlertRule> {
+    let rule = |metric, comparison, threshold, sound| AlertRule { metric, comparison, threshold, channels: Channels { sound, ..Channels::default() }, ..AlertRule::default() };
+    vec![
+        rule(Metric::Temperature, Comparison::Above, config.temp_warn_high, true),
+        rule(Metric::Temperature, Comparison::Below, config.temp_warn_low, true),
+        rule(Metric::Humidity, Comparison::Above, config.hum_warn_high, false),
+        rule(Metric::Humidity, Comparison::Below, config.hum_warn_low, false),
+    ]
+}
+
+#[derive(Default, Clone, Debug)]
+struct RuleState { rule: Option<AlertRule>, since: Option<DateTime<Local>>, active: bool }
+
+/// A rule turning on or off with the reading that did it.
+#[derive(Clone, Debug)]
+pub struct Transition { pub rule: AlertRule, pub active: bool, pub value: f32 }
+
+/// Per-rule hold timers and states; a rule that is edited starts over as inactive.
+#[derive(Default)]
+pub struct RuleEngine { states: Vec<RuleState>, recent: VecDeque<BleDataPoint> }
+
+impl RuleEngine {
+    /// `paused` (a pause window) clears every rule as if its condition no longer held; the rate metrics
+    /// are taken over `rate_window`.
+    pub fn evaluate(&mut self, data: &BleDataPoint, rules: &[AlertRule], paused: bool, rate_window: ChronoDuration) -> Vec<Transition> {
+        self.recent.push_back(data.clone());
+        while self.recent.front().is_some_and(|p| data.timestamp - p.timestamp > rate_window) { self.recent.pop_front(); }
+        let rate = |value: fn(&BleDataPoint) -> f64| trend::rate_per_hour(self.recent.iter().map(|p| (p.timestamp, value(p))), rate_window).map(|r| r as f32);
+        let (temp_rate, hum_rate) = (rate(|p| p.temp as f64), rate(|p| p.hum as f64));
+        self.states.resize_with(rules.len(), RuleState::default);
+        let mut transitions = Vec::new();
+        for (rule, state) in rules.iter().zip(&mut self.states) {
+            if state.rule.as_ref() != Some(rule) { *state = RuleState { rule: Some(rule.clone()), ..RuleState::default() }; }
+            let value = match rule.metric { Metric::TemperatureRate => temp_rate, Metric::HumidityRate => hum_rate, metric => metric.value(data) };
+            let Some(value) = value else { continue };
+            if rule.enabled && !paused && rule.comparison.holds(value, rule.threshold) {
+                let since = *state.since.get_or_insert(data.timestamp);
+                if !state.active && data.timestamp - since >= ChronoDuration::minutes(rule.duration_mins as i64) {
+                    state.active = true;
+                    transitions.push(Transition { rule: rule.clone(), active: true, value });
+                }
+            } else {
+                state.since = None;
+                if std::mem::take(&mut state.active) { transitions.push(Transition { rule: rule.clone(), active: false, value }); }
+            }
+        }
+        transitions
+    }
+
+    /// Whether an active rule asks for the sound alarm at `now`.
+    pub fn sounding(&self, now: DateTime<Local>) -> bool { self.states.iter().any(|s| s.active && s.rule.as_ref().is_some_and(|r| r.channels_at(now).sound)) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+
+    #[test]
+    fn alert_rules_hold_for_their_duration_and_route_to_their_channels() {
+        use crate::webhook::{AlertKind, AlertTracker};
+        let start = Local::now();
+        let at = |mins, temp, rssi| BleDataPoint { rssi: Some(rssi), ..reading(start + ChronoDuration::minutes(mins), temp, 40) };
+        let hot = AlertRule { name: "Freezer warm".into(), threshold: -15.0, duration_mins: 10, severity: Severity::Critical, channels: Channels { email: false, ..Channels::default() }, ..AlertRule::default() };
+        let weak_signal = AlertRule { metric: Metric::Rssi, comparison: Comparison::Below, threshold: -90.0, channels: Channels { sound: false, ..Channels::default() }, ..AlertRule::default() };
+        let battery = AlertRule { metric: Metric::Battery, comparison: Comparison::Below, threshold: 20.0, ..AlertRule::default() };
+        let config = Config { alert_rules: vec![hot, weak_signal, battery], ..test_config() };
+        let mut tracker = AlertTracker::default();
+
+        // Above the limit, but not yet for ten minutes
+        assert!(tracker.on_reading(&at(0, -12.0, -60), &config, false).is_empty());
+        assert!(tracker.on_reading(&at(9, -12.0, -60), &config, false).is_empty());
+        let fired = tracker.on_reading(&at(10, -12.0, -95), &config, false);
+        assert_eq!(fired.iter().map(|e| e.kind).collect::<Vec<_>>(), vec![AlertKind::TemperatureHigh, AlertKind::RuleTriggered]);
+        assert_eq!(fired[0].message, "Freezer warm: Temperature -12.0 °C is above -15.0 °C");
+        assert_eq!((fired[0].severity, fired[0].channels.email), (Severity::Critical, false));
+        assert_eq!(fired[1].message, "Signal (RSSI) below -90 dBm: -95 dBm");
+        assert_eq!(fired[1].to_json()["severity"], "warning");
+        assert!(tracker.sounding(start));
+        // A dip below the limit restarts the hold time; sensors without a battery level never trip battery rules
+        let cleared = tracker.on_reading(&at(11, -20.0, -60), &config, false);
+        assert_eq!(cleared.iter().map(|e| (e.kind, e.severity)).collect::<Vec<_>>(), vec![(AlertKind::TemperatureNormal, Severity::Info), (AlertKind::RuleCleared, Severity::Info)]);
+        assert!(!tracker.sounding(start));
+        assert!(tracker.on_reading(&at(12, -12.0, -60), &config, false).is_empty());
+
+        // The fixed thresholds of older configurations become equivalent rules
+        let legacy = Config { temp_warn_high: 25.0, hum_warn_low: 35.0, ..test_config() };
+        let migrated = from_thresholds(&legacy);
+        assert_eq!(migrated.iter().map(|r| r.describe()).collect::<Vec<_>>(), vec!["Temperature above 25.0 °C", "Temperature below 10.0 °C", "Humidity above 80 %", "Humidity below 35 %"]);
+        assert!(migrated[0].channels.sound && !migrated[2].channels.sound);
+    }
+
+    #[test]
+    fn quiet_hours_mute_channels_per_rule_on_their_schedule() {
+        use crate::webhook::AlertTracker;
+        use chrono::TimeZone;
+        let weekend = |weekday| QuietHours { window: PauseWindow { label: "Weekend".into(), weekday: Some(weekday), start: "00:00".into(), end: "00:00".into() }, muted: Channels { email: true, ..Channels::NONE } };
+        let rule = AlertRule { metric: Metric::Humidity, threshold: 60.0, quiet_hours: vec![QuietHours::default(), weekend(5), weekend(6)], ..AlertRule::default() };
+        let at = |day, hour| Local.with_ymd_and_hms(2024, 6, day, hour, 0, 0).unwrap();
+        // Monday noon: everything; Monday night: no sound; Saturday night: neither sound nor email
+        assert_eq!(rule.channels_at(at(17, 12)), Channels::default());
+        assert_eq!(rule.channels_at(at(17, 23)), Channels { sound: false, ..Channels::default() });
+        assert_eq!(rule.channels_at(at(15, 23)), Channels { sound: false, email: false, ..Channels::default() });
+        assert!(rule.channels_at(at(16, 6)).webhook && !rule.channels_at(at(16, 6)).email);
+
+        let config = Config { alert_rules: vec![rule.clone()], ..test_config() };
+        let mut tracker = AlertTracker::default();
+        let events = tracker.on_reading(&reading(at(15, 23), 20.0, 75), &config, false);
+        assert_eq!(events.len(), 1);
+        assert!(events[0].channels.toast && events[0].channels.webhook && !events[0].channels.email);
+        // The alarm stays silent at night and sounds once the quiet hours end
+        assert!(!tracker.sounding(at(15, 23)));
+        assert!(tracker.sounding(at(16, 8)));
+        // Windows sit flat next to the muted channels in the saved rule
+        let saved = serde_json::to_value(&rule).unwrap();
+        assert_eq!(saved["quiet_hours"][0]["start"], "22:00");
+        assert_eq!(serde_json::from_value::<AlertRule>(saved).unwrap(), rule);
+    }
+}
@@ -0,0 +1,79 @@
+// --- Scanner diagnostics: adapter state, scan times, advertisement counts and the last Bluetooth error ---
+
+use chrono::{DateTime, Local};
+use std::sync::Mutex;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum AdapterState {
+    /// Before the first scan cycle
+    #[default]
+    Unknown,
+    Missing,
+    PoweredOff,
+    /// Between scans
+    Idle,
+    Scanning,
+    Paused,
+}
+
+impl AdapterState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AdapterState::Unknown => "Not checked yet", AdapterState::Missing => "No adapter found", AdapterState::PoweredOff => "Powered off",
+            AdapterState::Idle => "Idle (between scans)", AdapterState::Scanning => "Scanning", AdapterState::Paused => "Paused",
+        }
+    }
+}
+
+/// Counted since the app started.
+#[derive(Clone, Default, Debug)]
+pub struct ScanStats {
+    pub adapter: AdapterState,
+    /// Adapter name and address as the Bluetooth stack reports them
+    pub adapter_info: Option<String>,
+    pub scans: u64,
+    pub scan_started: Option<DateTime<Local>>,
+    pub scan_stopped: Option<DateTime<Local>>,
+    /// Advertisement events of every device in range
+    pub advertisements: u64,
+    pub matching: u64,
+    /// Advertisements of the target that no decoder could read
+    pub parse_failures: u64,
+    pub last_error: Option<(DateTime<Local>, String)>,
+    /// Scan cycles in a row without a usable adapter; 0 while scanning works
+    pub failures: u32,
+    /// Next attempt while `failures` is above 0
+    pub retry_at: Option<DateTime<Local>>,
+}
+
+impl ScanStats {
+    const fn new() -> Self {
+        Self { adapter: AdapterState::Unknown, adapter_info: None, scans: 0, scan_started: None, scan_stopped: None, advertisements: 0, matching: 0, parse_failures: 0, last_error: None, failures: 0, retry_at: None }
+    }
+
+    /// `decoded` is `None` for devices other than the target.
+    pub fn record_advertisement(&mut self, decoded: Option<bool>) {
+        self.advertisements += 1;
+        if let Some(ok) = decoded {
+            self.matching += 1;
+            if !ok { self.parse_failures += 1; }
+        }
+    }
+
+    pub fn scan_started(&mut self, at: DateTime<Local>) { self.scans += 1; self.scan_started = Some(at); self.adapter = AdapterState::Scanning; }
+
+    pub fn scan_stopped(&mut self, at: DateTime<Local>) { self.scan_stopped = Some(at); if self.adapter == AdapterState::Scanning { self.adapter = AdapterState::Idle; } }
+
+    /// Matching advertisements that decoded, as a share of all matching ones.
+    pub fn decode_rate(&self) -> Option<f64> { (self.matching > 0).then(|| (self.matching - self.parse_failures) as f64 / self.matching as f64) }
+}
+
+static STATS: Mutex<ScanStats> = Mutex::new(ScanStats::new());
+
+pub fn update(change: impl FnOnce(&mut ScanStats)) { change(&mut STATS.lock().unwrap_or_else(|e| e.into_inner())); }
+
+pub fn set_adapter(state: AdapterState) { update(|stats| stats.adapter = state); }
+
+pub fn record_error(message: impl Into<String>) { let message = message.into(); update(|stats| stats.last_error = Some((Local::now(), message))); }
+
+pub fn snapshot() -> ScanStats { STATS.lock().unwrap_or_else(|e| e.into_inner()).clone() }
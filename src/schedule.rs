@@ -0,0 +1,112 @@
+// --- Scheduled logging/alerting pause windows (e.g. weekly cleaning hour) ---
+
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+
+use crate::paths;
+use crate::storage::{format_log_timestamp, parse_log_timestamp};
+
+/// Pauses as they happened; the windows in settings may have been edited since.
+pub const PAUSE_LOG_FILE_NAME: &str = "pauses.csv";
+const HEADER: [&str; 3] = ["Start", "End", "Label"];
+
+pub const WEEKDAY_NAMES: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct PauseWindow {
+    pub label: String,
+    /// 0 = Monday … 6 = Sunday, `None` = every day
+    pub weekday: Option<u8>,
+    /// "HH:MM", local time; an end before the start crosses midnight, and an end equal to the start means the whole day
+    pub start: String,
+    pub end: String,
+}
+
+impl Default for PauseWindow {
+    fn default() -> Self {
+        Self { label: "Cleaning".to_string(), weekday: Some(0), start: "08:00".to_string(), end: "09:00".to_string() }
+    }
+}
+
+impl PauseWindow {
+    pub fn weekday_label(&self) -> &'static str {
+        self.weekday.and_then(|d| WEEKDAY_NAMES.get(d as usize).copied()).unwrap_or("Every day")
+    }
+
+    fn times(&self) -> Option<(NaiveTime, NaiveTime)> {
+        let start = NaiveTime::parse_from_str(self.start.trim(), "%H:%M").ok()?;
+        let end = NaiveTime::parse_from_str(self.end.trim(), "%H:%M").ok()?;
+        Some((start, end))
+    }
+
+    pub fn is_valid(&self) -> bool { self.times().is_some() }
+
+    pub fn is_all_day(&self) -> bool { self.times().is_some_and(|(start, end)| start == end) }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct PauseInterval { pub start: DateTime<Local>, pub end: DateTime<Local>, pub label: String, }
+
+impl PauseInterval {
+    pub fn contains(&self, t: DateTime<Local>) -> bool { t >= self.start && t < self.end }
+}
+
+/// Concrete pause intervals overlapping `[from, to]`.
+pub fn pause_intervals(windows: &[PauseWindow], from: DateTime<Local>, to: DateTime<Local>) -> Vec<PauseInterval> {
+    let mut intervals = Vec::new();
+    // Start a day early so windows crossing midnight into `from` are included
+    let mut day = from.date_naive() - ChronoDuration::days(1);
+    while day <= to.date_naive() {
+        for window in windows {
+            let Some((start_time, end_time)) = window.times() else { continue; };
+            if window.weekday.is_some_and(|d| d as u32 != day.weekday().num_days_from_monday()) { continue; }
+            let end_day = if end_time <= start_time { day + ChronoDuration::days(1) } else { day };
+            let (Some(start), Some(end)) = (day.and_time(start_time).and_local_timezone(Local).earliest(), end_day.and_time(end_time).and_local_timezone(Local).latest()) else { continue; };
+            if end > from && start < to { intervals.push(PauseInterval { start, end, label: window.label.clone() }); }
+        }
+        day = match day.succ_opt() { Some(next) => next, None => break };
+    }
+    intervals.sort_by_key(|i| i.start);
+    intervals
+}
+
+pub fn active_pause(windows: &[PauseWindow], now: DateTime<Local>) -> Option<PauseInterval> {
+    pause_intervals(windows, now, now).into_iter().find(|i| i.contains(now))
+}
+
+pub fn pause_log_path() -> PathBuf { paths::data_dir().join(PAUSE_LOG_FILE_NAME) }
+
+pub fn append(path: &Path, interval: &PauseInterval) -> Result<(), csv::Error> {
+    paths::ensure_dirs();
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let is_new = file.metadata().map(|m| m.len() == 0).unwrap_or(true);
+    let mut wtr = csv::WriterBuilder::new().has_headers(false).from_writer(file);
+    if is_new { wtr.write_record(HEADER)?; }
+    wtr.write_record([format_log_timestamp(interval.start), format_log_timestamp(interval.end), interval.label.clone()])?;
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Recorded pauses, by start.
+pub fn read(path: &Path) -> Vec<PauseInterval> {
+    let Ok(mut rdr) = csv::ReaderBuilder::new().flexible(true).from_path(path) else { return Vec::new(); };
+    let mut intervals: Vec<PauseInterval> = rdr.records().flatten()
+        .filter_map(|row| Some(PauseInterval { start: parse_log_timestamp(row.get(0)?)?, end: parse_log_timestamp(row.get(1)?)?, label: row.get(2).unwrap_or_default().to_string() }))
+        .collect();
+    intervals.sort_by_key(|i| i.start);
+    intervals
+}
+
+/// Seconds of `[from, to]` covered by the given intervals (intervals may overlap each other).
+pub fn paused_secs(intervals: &[PauseInterval], from: DateTime<Local>, to: DateTime<Local>) -> i64 {
+    let mut total = 0;
+    let mut cursor = from;
+    for interval in intervals {
+        let start = interval.start.max(cursor);
+        let end = interval.end.min(to);
+        if end > start { total += (end - start).num_seconds(); cursor = end; }
+    }
+    total
+}
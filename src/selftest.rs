@@ -0,0 +1,229 @@
+// --- Startup self-test: Bluetooth, permissions, data folder, disk space, configuration and clock ---
+
+use btleplug::api::{Central, CentralState, Manager as _};
+use btleplug::platform::{Adapter, Manager};
+use chrono::{Datelike, NaiveDate};
+use log::{info, warn};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::aggregate::log_date;
+use crate::model::Config;
+use crate::paths;
+
+const BLUETOOTH_TIMEOUT: Duration = Duration::from_secs(5);
+const DISK_FAIL_BYTES: u64 = 100 << 20;
+const DISK_WARN_BYTES: u64 = 1 << 30;
+// No clock running the app can be earlier than this
+const EARLIEST_SANE_YEAR: i32 = 2024;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CheckStatus { Pass, Warn, Fail }
+
+impl CheckStatus {
+    pub fn icon(&self) -> &'static str { match self { CheckStatus::Pass => "✔", CheckStatus::Warn => "⚠", CheckStatus::Fail => "✖" } }
+}
+
+/// Where the user can fix a failed check.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Fix {
+    /// The app's own Settings window
+    AppSettings,
+    /// A folder, or a system settings page given as a URI (e.g. `ms-settings:bluetooth`)
+    Open { label: &'static str, target: String },
+}
+
+#[derive(Clone, Debug)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// What to do about a warning or failure
+    pub hint: String,
+    pub fix: Option<Fix>,
+}
+
+impl CheckResult {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self { Self { name, status: CheckStatus::Pass, detail: detail.into(), hint: String::new(), fix: None } }
+
+    fn problem(name: &'static str, status: CheckStatus, detail: impl Into<String>, hint: impl Into<String>, fix: Option<Fix>) -> Self {
+        Self { name, status, detail: detail.into(), hint: hint.into(), fix }
+    }
+}
+
+// System settings pages per platform; Linux desktops differ too much, so the hint gives a command instead
+fn system_page(label: &'static str, windows: &str, macos: &str) -> Option<Fix> {
+    let target = if cfg!(windows) { windows } else if cfg!(target_os = "macos") { macos } else { return None; };
+    Some(Fix::Open { label, target: target.to_string() })
+}
+
+fn folder(path: &Path) -> Option<Fix> { Some(Fix::Open { label: "Open data folder", target: path.display().to_string() }) }
+
+/// Opens a folder or settings URI with the system's default handler.
+pub fn open(target: &str) {
+    let result = if cfg!(windows) { Command::new("explorer").arg(target).spawn() } else if cfg!(target_os = "macos") { Command::new("open").arg(target).spawn() } else { Command::new("xdg-open").arg(target).spawn() };
+    if let Err(e) = result { warn!("Cannot open '{}': {}", target, e); }
+}
+
+/// Runs every check; the Bluetooth part gives up after a few seconds so a hanging stack cannot stall it.
+pub async fn run(config: Config) -> Vec<CheckResult> {
+    let bluetooth = async {
+        match Manager::new().await { Ok(manager) => bluetooth_checks(manager.adapters().await).await, Err(e) => bluetooth_checks::<Adapter>(Err(e)).await }
+    };
+    let mut results = match tokio::time::timeout(BLUETOOTH_TIMEOUT, bluetooth).await {
+        Ok(checks) => checks.to_vec(),
+        Err(_) => vec![
+            CheckResult::problem("Bluetooth adapter", CheckStatus::Warn, "The Bluetooth stack did not answer within 5 s", "Restart Bluetooth or the computer if no readings arrive.", system_page("Bluetooth settings", "ms-settings:bluetooth", "x-apple.systempreferences:com.apple.preferences.Bluetooth")),
+        ],
+    };
+    let dir = paths::data_dir();
+    results.push(check_data_dir(&dir));
+    results.push(check_disk(&dir, free_space(&dir)));
+    results.push(check_config(&paths::config_path(), &config));
+    results.push(check_clock(chrono::Local::now().date_naive(), newest_log_date(&dir)));
+    let failed = results.iter().filter(|r| r.status != CheckStatus::Pass).map(|r| r.name).collect::<Vec<_>>();
+    if failed.is_empty() { info!("Self-test passed."); } else { warn!("Self-test found problems: {}.", failed.join(", ")); }
+    results
+}
+
+/// Adapter presence and power state, and whether the system lets the app use it.
+pub async fn bluetooth_checks<C: Central>(adapters: btleplug::Result<Vec<C>>) -> [CheckResult; 2] {
+    let settings = || system_page("Bluetooth settings", "ms-settings:bluetooth", "x-apple.systempreferences:com.apple.preferences.Bluetooth");
+    let privacy = || system_page("Privacy settings", "ms-settings:privacy-radios", "x-apple.systempreferences:com.apple.preference.security?Privacy_Bluetooth");
+    let denied = || CheckResult::problem("Permissions", CheckStatus::Fail, "Access to Bluetooth was denied", if cfg!(any(windows, target_os = "macos")) { "Allow this app to use Bluetooth in the system privacy settings." } else { "Add your user to the 'bluetooth' group (sudo usermod -aG bluetooth $USER) and log in again." }, privacy());
+    let adapters = match adapters {
+        Ok(adapters) => adapters,
+        Err(btleplug::Error::PermissionDenied) => return [CheckResult::problem("Bluetooth adapter", CheckStatus::Warn, "Not checked, access denied", "Fix the permissions first.", None), denied()],
+        Err(e) => return [
+            CheckResult::problem("Bluetooth adapter", CheckStatus::Fail, format!("The Bluetooth stack is not available: {}", e), if cfg!(any(windows, target_os = "macos")) { "Check that the computer has Bluetooth and that it is turned on." } else { "Start the Bluetooth service (sudo systemctl start bluetooth)." }, settings()),
+            CheckResult::problem("Permissions", CheckStatus::Warn, "Not checked without a Bluetooth stack", "", None),
+        ],
+    };
+    let Some(adapter) = adapters.first() else {
+        return [
+            CheckResult::problem("Bluetooth adapter", CheckStatus::Fail, "No Bluetooth adapter found", "Plug in a Bluetooth dongle or enable Bluetooth in the BIOS / device manager.", settings()),
+            CheckResult::problem("Permissions", CheckStatus::Warn, "Not checked without an adapter", "", None),
+        ];
+    };
+    let info = adapter.adapter_info().await;
+    let permissions = if matches!(info, Err(btleplug::Error::PermissionDenied)) { denied() } else { CheckResult::pass("Permissions", "The app may use the adapter") };
+    let info = info.unwrap_or_else(|_| "Adapter".to_string());
+    let adapter = match adapter.adapter_state().await {
+        Ok(CentralState::PoweredOn) => CheckResult::pass("Bluetooth adapter", format!("{} is on", info)),
+        Ok(CentralState::PoweredOff) => CheckResult::problem("Bluetooth adapter", CheckStatus::Fail, format!("{} is switched off", info), if cfg!(any(windows, target_os = "macos")) { "Turn Bluetooth on." } else { "Turn Bluetooth on (rfkill unblock bluetooth; bluetoothctl power on)." }, settings()),
+        Err(btleplug::Error::PermissionDenied) => return [CheckResult::problem("Bluetooth adapter", CheckStatus::Warn, format!("{}: state not readable", info), "Fix the permissions first.", None), denied()],
+        _ => CheckResult::problem("Bluetooth adapter", CheckStatus::Warn, format!("{} found, power state unknown", info), "If no readings arrive, check that Bluetooth is turned on.", settings()),
+    };
+    [adapter, permissions]
+}
+
+fn check_data_dir(dir: &Path) -> CheckResult {
+    let probe = dir.join(".selftest");
+    let result = fs::create_dir_all(dir).and_then(|_| fs::write(&probe, b"ok")).and_then(|_| fs::remove_file(&probe));
+    match result {
+        Ok(()) => CheckResult::pass("Data folder", format!("'{}' is writable", dir.display())),
+        Err(e) => CheckResult::problem("Data folder", CheckStatus::Fail, format!("Cannot write to '{}': {}", dir.display(), e), "Readings are not saved. Check the folder's permissions, or switch storage mode (portable / installed).", folder(dir)),
+    }
+}
+
+/// Free space on the data folder's drive; below 100 MB readings may stop being saved.
+pub fn check_disk(dir: &Path, free: Option<u64>) -> CheckResult {
+    match free {
+        None => CheckResult::problem("Disk space", CheckStatus::Warn, "Free space could not be determined", "", None),
+        Some(bytes) if bytes < DISK_FAIL_BYTES => CheckResult::problem("Disk space", CheckStatus::Fail, format!("Only {} free", crate::retention::format_bytes(bytes)), "Free up space, or enable data retention to compress old logs.", folder(dir)),
+        Some(bytes) if bytes < DISK_WARN_BYTES => CheckResult::problem("Disk space", CheckStatus::Warn, format!("{} free", crate::retention::format_bytes(bytes)), "Consider enabling data retention to compress old logs.", Some(Fix::AppSettings)),
+        Some(bytes) => CheckResult::pass("Disk space", format!("{} free", crate::retention::format_bytes(bytes))),
+    }
+}
+
+/// The file must parse, and the values must make sense together.
+pub fn check_config(path: &Path, config: &Config) -> CheckResult {
+    if let Ok(text) = fs::read_to_string(path) {
+        if let Err(e) = serde_json::from_str::<Config>(&text) {
+            return CheckResult::problem("Configuration", CheckStatus::Fail, format!("'{}' cannot be read ({}), defaults are in use", path.display(), e), "Review the settings and save them to rewrite the file.", Some(Fix::AppSettings));
+        }
+    }
+    let mut problems = Vec::new();
+    if config.temp_warn_low >= config.temp_warn_high { problems.push("the low temperature threshold is not below the high one"); }
+    if config.hum_warn_low >= config.hum_warn_high { problems.push("the low humidity threshold is not below the high one"); }
+    if !config.auto_detect_thermopro && btleplug::api::BDAddr::from_str(&config.target_mac).is_err() { problems.push("the sensor MAC address is not valid"); }
+    if problems.is_empty() { CheckResult::pass("Configuration", "Valid") } else { CheckResult::problem("Configuration", CheckStatus::Fail, problems.join("; "), "Correct these values in Settings.", Some(Fix::AppSettings)) }
+}
+
+/// A clock before the newest log (or obviously unset) files readings under the wrong day.
+pub fn check_clock(today: NaiveDate, newest_log: Option<NaiveDate>) -> CheckResult {
+    let fix = || system_page("Date & time settings", "ms-settings:dateandtime", "x-apple.systempreferences:com.apple.preference.datetime");
+    let hint = if cfg!(any(windows, target_os = "macos")) { "Turn on automatic time in the system's date and time settings." } else { "Turn on time synchronisation (timedatectl set-ntp true)." };
+    if today.year() < EARLIEST_SANE_YEAR { return CheckResult::problem("Clock", CheckStatus::Fail, format!("The system date is {}", today), hint, fix()); }
+    match newest_log {
+        Some(newest) if newest > today => CheckResult::problem("Clock", CheckStatus::Fail, format!("The system date {} is before the newest log ({})", today, newest), hint, fix()),
+        _ => CheckResult::pass("Clock", today.to_string()),
+    }
+}
+
+fn newest_log_date(dir: &Path) -> Option<NaiveDate> {
+    fs::read_dir(dir).ok()?.flatten().filter_map(|e| log_date(&e.path())).max()
+}
+
+#[cfg(windows)]
+fn free_space(dir: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+
+    #[link(name = "kernel32")]
+    extern "system" { fn GetDiskFreeSpaceExW(directory: *const u16, free_to_caller: *mut u64, total: *mut u64, total_free: *mut u64) -> i32; }
+
+    let wide: Vec<u16> = dir.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut free = 0u64;
+    // SAFETY: the path is NUL-terminated and the out pointers are valid or null
+    if unsafe { GetDiskFreeSpaceExW(wide.as_ptr(), &mut free, std::ptr::null_mut(), std::ptr::null_mut()) } == 0 { return None; }
+    Some(free)
+}
+
+#[cfg(not(windows))]
+fn free_space(dir: &Path) -> Option<u64> {
+    // POSIX output: "Filesystem 1024-blocks Used Available Capacity Mounted on", then one line per file system
+    let output = Command::new("df").arg("-Pk").arg(existing_ancestor(dir)).output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let kib: u64 = text.lines().nth(1)?.split_whitespace().nth(3)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(not(windows))]
+fn existing_ancestor(dir: &Path) -> std::path::PathBuf { dir.ancestors().find(|p| p.exists()).unwrap_or(dir).to_path_buf() }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_ble::MockCentral;
+    use crate::test_support::*;
+
+    #[test]
+    fn selftest_flags_missing_adapter_bad_config_and_clock() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let [adapter, permissions] = rt.block_on(bluetooth_checks(Ok(vec![MockCentral::new(Vec::new())])));
+        assert_eq!((adapter.status, permissions.status), (CheckStatus::Pass, CheckStatus::Pass));
+        let [adapter, _] = rt.block_on(bluetooth_checks::<MockCentral>(Ok(Vec::new())));
+        assert_eq!(adapter.status, CheckStatus::Fail);
+        let [_, permissions] = rt.block_on(bluetooth_checks::<MockCentral>(Err(btleplug::Error::PermissionDenied)));
+        assert_eq!(permissions.status, CheckStatus::Fail);
+
+        let data_dir = TempDataDir::new("selftest");
+        let config_path = data_dir.dir.join("config.json");
+        assert_eq!(check_config(&config_path, &Config::default()).status, CheckStatus::Pass);
+        let swapped = Config { temp_warn_low: 30.0, temp_warn_high: 20.0, ..Default::default() };
+        let result = check_config(&config_path, &swapped);
+        assert_eq!((result.status, result.fix), (CheckStatus::Fail, Some(Fix::AppSettings)));
+        fs::write(&config_path, "{ not json").unwrap();
+        assert_eq!(check_config(&config_path, &Config::default()).status, CheckStatus::Fail);
+
+        let day = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        assert_eq!(check_clock(day, Some(day)).status, CheckStatus::Pass);
+        assert_eq!(check_clock(day, day.succ_opt()).status, CheckStatus::Fail);
+        assert_eq!(check_clock(NaiveDate::from_ymd_opt(2001, 1, 1).unwrap(), None).status, CheckStatus::Fail);
+        assert_eq!(check_disk(&data_dir.dir, Some(50 << 20)).status, CheckStatus::Fail);
+        assert_eq!(check_disk(&data_dir.dir, Some(500 << 30)).status, CheckStatus::Pass);
+    }
+}
@@ -0,0 +1,99 @@
+// --- Known sensors and the prompt for newly seen ThermoPro devices ---
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::model::Config;
+
+/// Scans a new device must be heard in before the user is asked about it, so passing neighbours are not offered
+pub const SIGHTINGS_BEFORE_PROMPT: u32 = 3;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct SensorEntry {
+    pub mac: String,
+    pub name: String,
+    pub model: String,
+    /// Plot line color; the built-in colors when unset
+    pub color: Option<[u8; 3]>,
+}
+
+impl SensorEntry {
+    /// Defaults for an accepted sensor: the model and the end of the MAC, e.g. "TP357 0F:93".
+    pub fn new(sighting: &SensorSighting) -> Self {
+        let suffix = sighting.address.get(sighting.address.len().saturating_sub(5)..).unwrap_or_default();
+        Self { mac: sighting.address.clone(), name: format!("{} {}", sighting.model, suffix), model: sighting.model.to_string(), color: None }
+    }
+}
+
+/// An unconfigured ThermoPro heard during one scan.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SensorSighting { pub address: String, pub model: &'static str }
+
+/// The target, an accepted sensor or one the user declined for good.
+pub fn is_known(config: &Config, address: &str) -> bool {
+    config.target_mac.eq_ignore_ascii_case(address)
+        || config.sensors.iter().any(|s| s.mac.eq_ignore_ascii_case(address))
+        || config.ignored_sensors.iter().any(|mac| mac.eq_ignore_ascii_case(address))
+}
+
+/// The known-sensor entry of `mac`.
+pub fn entry<'a>(config: &'a Config, mac: &str) -> Option<&'a SensorEntry> { config.sensors.iter().find(|s| s.mac.eq_ignore_ascii_case(mac)) }
+
+/// The name the user gave `mac`, if any.
+pub fn friendly_name<'a>(config: &'a Config, mac: &str) -> Option<&'a str> { entry(config, mac).map(|s| s.name.trim()).filter(|name| !name.is_empty()) }
+
+/// The name the user gave `mac`, or the MAC itself.
+pub fn display_name(config: &Config, mac: &str) -> String { friendly_name(config, mac).unwrap_or(mac).to_string() }
+
+/// Plot color chosen for the target sensor.
+pub fn target_color(config: &Config) -> Option<[u8; 3]> { entry(config, &config.target_mac).and_then(|s| s.color) }
+
+/// Counts scans per unknown device and says when one has been around long enough to ask about.
+#[derive(Default)]
+pub struct NewSensorTracker { seen: HashMap<String, u32>, snoozed: HashSet<String> }
+
+impl NewSensorTracker {
+    pub fn record(&mut self, sighting: SensorSighting, config: &Config) -> Option<SensorSighting> {
+        let address = sighting.address.to_ascii_uppercase();
+        if is_known(config, &address) || self.snoozed.contains(&address) { return None; }
+        let count = self.seen.entry(address).or_default();
+        *count += 1;
+        (*count >= SIGHTINGS_BEFORE_PROMPT).then_some(sighting)
+    }
+
+    /// "Not now": no more prompts for this device until the app restarts.
+    pub fn snooze(&mut self, address: &str) { self.snoozed.insert(address.to_ascii_uppercase()); }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::email;
+    use crate::export::export_history_to_csv;
+    use crate::test_support::*;
+    use crate::webhook::AlertTracker;
+    use chrono::Local;
+
+    #[test]
+    fn named_sensor_replaces_its_mac_in_alerts_and_export_headers() {
+        let data_dir = TempDataDir::new("sensor_names");
+        let mut config = Config { duplicate_threshold_secs: 0, ..test_config() };
+        config.alert_templates.insert("temperature_high".into(), "{device}: {value}".into());
+        // Unnamed: the MAC stands in
+        assert_eq!(display_name(&config, TARGET), TARGET);
+        config.sensors.push(SensorEntry { mac: TARGET.to_lowercase(), name: " Bedroom ".into(), model: "TP357".into(), color: Some([0, 200, 0]) });
+        assert_eq!((friendly_name(&config, TARGET), target_color(&config)), (Some("Bedroom"), Some([0, 200, 0])));
+        assert_eq!(display_name(&config, OTHER), OTHER);
+
+        let events = AlertTracker::default().on_reading(&reading(Local::now(), 31.5, 40), &config, false);
+        assert_eq!(events[0].message, "Bedroom: 31.5 °C");
+        let json = events[0].to_json();
+        assert_eq!((json["device"].as_str(), json["device_name"].as_str()), (Some(TARGET), Some("Bedroom")));
+        assert!(email::compose(&events, &config).1.contains(&format!("Device: Bedroom ({})", TARGET)));
+
+        let exported = data_dir.dir.join("named.csv");
+        export_history_to_csv(&exported, &[point(Local::now(), 21.0, 40)], false, friendly_name(&config, TARGET)).unwrap();
+        assert!(std::fs::read_to_string(&exported).unwrap().starts_with("DateTime,Temperature – Bedroom,Humidity – Bedroom\n"));
+    }
+}
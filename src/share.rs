@@ -0,0 +1,167 @@
+// --- Read-only snapshot page (last 24 h chart + stats) served on this PC or the local network ---
+
+use chrono::Local;
+use log::{debug, info, warn};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::fmt::Write as _;
+use std::net::{IpAddr, Ipv4Addr, UdpSocket};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::calendar::DateFormat;
+use crate::model::HistoryPoint;
+use crate::units::Channel;
+
+struct Snapshot { token: String, html: String, expires: Option<Instant>, }
+
+pub struct ShareServer {
+    /// `None` while suspended (battery saver); the snapshot is kept so the same link works after resuming
+    server: Option<Arc<tiny_http::Server>>,
+    snapshot: Arc<Mutex<Option<Snapshot>>>,
+    pub port: u16,
+    /// The port actually bound, which the system picks when `port` is 0
+    listening_port: u16,
+    /// Listening on every interface rather than only on this PC (`share_on_network`)
+    pub on_network: bool,
+}
+
+impl ShareServer {
+    pub fn start(port: u16, on_network: bool) -> Result<Self, String> {
+        let snapshot: Arc<Mutex<Option<Snapshot>>> = Arc::new(Mutex::new(None));
+        let server = listen(port, on_network, snapshot.clone())?;
+        let listening_port = server.server_addr().to_ip().map_or(port, |address| address.port());
+        Ok(Self { server: Some(server), snapshot, port, listening_port, on_network })
+    }
+
+    /// Replaces the published snapshot and returns its URL, on the local network when the server listens there.
+    pub fn publish(&self, html: String, ttl: Option<Duration>) -> Result<String, String> {
+        let token = random_token()?;
+        *self.snapshot.lock().unwrap() = Some(Snapshot { token: token.clone(), html, expires: ttl.map(|t| Instant::now() + t) });
+        let host = if self.on_network { local_ip().map(|ip| ip.to_string()) } else { None }.unwrap_or_else(|| "localhost".to_string());
+        Ok(format!("http://{}:{}/snapshot/{}", host, self.listening_port, token))
+    }
+
+    pub fn revoke(&self) { *self.snapshot.lock().unwrap() = None; }
+
+    pub fn is_suspended(&self) -> bool { self.server.is_none() }
+
+    /// Closes the port without forgetting the published snapshot.
+    pub fn suspend(&mut self) {
+        if let Some(server) = self.server.take() { server.unblock(); info!("Snapshot server suspended."); }
+    }
+
+    pub fn resume(&mut self) -> Result<(), String> {
+        if self.server.is_none() { self.server = Some(listen(self.listening_port, self.on_network, self.snapshot.clone())?); }
+        Ok(())
+    }
+}
+
+impl Drop for ShareServer {
+    fn drop(&mut self) { self.suspend(); }
+}
+
+fn listen(port: u16, on_network: bool, snapshot: Arc<Mutex<Option<Snapshot>>>) -> Result<Arc<tiny_http::Server>, String> {
+    let address = if on_network { Ipv4Addr::UNSPECIFIED } else { Ipv4Addr::LOCALHOST };
+    let server = Arc::new(tiny_http::Server::http((address, port)).map_err(|e| e.to_string())?);
+    info!("Snapshot server listening on {}:{}.", address, port);
+    let (thread_server, thread_snapshot) = (server.clone(), snapshot);
+    thread::spawn(move || {
+        for request in thread_server.incoming_requests() {
+            debug!("Snapshot request: {}", request.url());
+            let token = request.url().trim_start_matches("/snapshot/").to_string();
+            let response = match thread_snapshot.lock().unwrap().as_ref() {
+                Some(s) if s.token == token && s.expires.is_some_and(|e| Instant::now() > e) => tiny_http::Response::from_string("This snapshot link has expired.").with_status_code(410),
+                Some(s) if s.token == token => tiny_http::Response::from_string(s.html.clone())
+                    .with_header("Content-Type: text/html; charset=utf-8".parse::<tiny_http::Header>().unwrap()),
+                _ => tiny_http::Response::from_string("Not found").with_status_code(404),
+            };
+            if let Err(e) = request.respond(response) { warn!("Failed to answer snapshot request: {}", e); }
+        }
+        info!("Snapshot server stopped.");
+    });
+    Ok(server)
+}
+
+// The token is all that keeps the page private, so its 128 bits come from the OS generator
+fn random_token() -> Result<String, String> {
+    let mut bytes = [0u8; 16];
+    SystemRandom::new().fill(&mut bytes).map_err(|_| "no secure random numbers available".to_string())?;
+    Ok(bytes.iter().fold(String::new(), |mut out, b| { let _ = write!(out, "{:02x}", b); out }))
+}
+
+// Address of the interface used for outgoing traffic; connecting a UDP socket sends no packets
+fn local_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    socket.local_addr().ok().map(|a| a.ip())
+}
+
+fn svg_chart(points: &[HistoryPoint], channel: Channel, color: &str, date_format: DateFormat) -> String {
+    const W: f64 = 760.0;
+    const H: f64 = 200.0;
+    const M: f64 = 40.0;
+    let (Some(first), Some(last)) = (points.first(), points.last()) else { return String::new(); };
+    let value = |p: &HistoryPoint| channel.value(p).unwrap_or_default();
+    let (t0, t1) = (first.timestamp.timestamp() as f64, (last.timestamp.timestamp() as f64).max(first.timestamp.timestamp() as f64 + 1.0));
+    let min = points.iter().map(&value).fold(f64::INFINITY, f64::min) - 0.5;
+    let max = points.iter().map(&value).fold(f64::NEG_INFINITY, f64::max) + 0.5;
+    let x = |t: f64| M + (t - t0) / (t1 - t0) * (W - 2.0 * M);
+    let y = |v: f64| H - M / 2.0 - (v - min) / (max - min) * (H - M);
+    let polyline: Vec<String> = points.iter().map(|p| format!("{:.1},{:.1}", x(p.timestamp.timestamp() as f64), y(value(p)))).collect();
+    format!(
+        r##"<svg viewBox="0 0 {W} {H}" width="100%"><rect width="{W}" height="{H}" fill="#1e1e1e"/><polyline fill="none" stroke="{color}" stroke-width="2" points="{points}"/><text x="4" y="{ty}" fill="#aaa" font-size="12">{max_label}</text><text x="4" y="{by}" fill="#aaa" font-size="12">{min_label}</text><text x="{M}" y="{H}" fill="#aaa" font-size="12">{start}</text><text x="{ex}" y="{H}" fill="#aaa" font-size="12" text-anchor="end">{end}</text></svg>"##,
+        points = polyline.join(" "), max_label = channel.format(max), min_label = channel.format(min), ty = M / 2.0, by = H - M / 2.0, ex = W - M,
+        start = date_format.short_date_time(first.timestamp), end = date_format.short_date_time(last.timestamp),
+    )
+}
+
+/// Self-contained HTML page (inline SVG, no scripts) for the given readings.
+pub fn render_snapshot_html(points: &[HistoryPoint], date_format: DateFormat) -> String {
+    let mut html = String::from(r#"<!DOCTYPE html><html><head><meta charset="utf-8"><meta name="viewport" content="width=device-width, initial-scale=1"><title>Temperature Monitor snapshot</title><style>body{background:#141414;color:#eee;font-family:sans-serif;max-width:800px;margin:auto;padding:12px}td{padding:2px 12px}</style></head><body>"#);
+    let _ = write!(html, "<h2>Temperature Monitor – last 24 hours</h2><p>Snapshot taken {}</p>", date_format.date_time(Local::now()));
+    if points.is_empty() {
+        html.push_str("<p>No readings in the last 24 hours.</p></body></html>");
+        return html;
+    }
+    let _ = write!(html, "<table><tr><td></td><td>Current</td><td>Min</td><td>Max</td><td>Average</td></tr>");
+    for channel in [Channel::Temperature, Channel::Humidity] {
+        let values: Vec<f64> = points.iter().filter_map(|p| channel.value(p)).collect();
+        let (current, min, max, avg) = (values[values.len() - 1], values.iter().copied().fold(f64::INFINITY, f64::min), values.iter().copied().fold(f64::NEG_INFINITY, f64::max), values.iter().sum::<f64>() / values.len() as f64);
+        let _ = write!(html, "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>", channel.name(), channel.format(current), channel.format(min), channel.format(max), channel.format(avg));
+    }
+    html.push_str("</table>");
+    let _ = write!(html, "<h3>{}</h3>{}", Channel::Temperature.name(), svg_chart(points, Channel::Temperature, "#ff6464", date_format));
+    let _ = write!(html, "<h3>{}</h3>{}", Channel::Humidity.name(), svg_chart(points, Channel::Humidity, "#6464ff", date_format));
+    html.push_str("</body></html>");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_snapshot_is_served_on_this_pc_behind_a_random_token() {
+        use std::io::{Read, Write};
+        // Port 0: the system picks a free one, which the links then carry
+        let server = ShareServer::start(0, false).unwrap();
+        let get = |url: &str| {
+            let (authority, path) = url.trim_start_matches("http://").split_once('/').unwrap();
+            let port: u16 = authority.trim_start_matches("localhost:").parse().unwrap();
+            let mut stream = std::net::TcpStream::connect(("127.0.0.1", port)).unwrap();
+            write!(stream, "GET /{} HTTP/1.0\r\nHost: localhost\r\n\r\n", path).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let first = server.publish("<p>first</p>".to_string(), None).unwrap();
+        let token = first.rsplit('/').next().unwrap();
+        assert!(first.starts_with(&format!("http://localhost:{}/snapshot/", server.listening_port)) && server.listening_port != 0 && token.len() == 32 && token.chars().all(|c| c.is_ascii_hexdigit()), "{}", first);
+        let second = server.publish("<p>second</p>".to_string(), None).unwrap();
+        assert_ne!(first, second);
+        assert!(get(&second).contains("<p>second</p>"));
+        // A new link replaces the old one
+        assert!(get(&first).lines().next().unwrap().contains(" 404 "));
+    }
+}
@@ -0,0 +1,98 @@
+// --- Simulated sensor: a sine wave with noise in place of the Bluetooth scanner, for development without a sensor ---
+
+use chrono::{DateTime, Local};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc as async_mpsc, watch};
+
+use crate::ble::apply_calibration;
+use crate::model::{AppMessage, BleDataPoint, Config};
+
+/// Device id of simulated readings, so they are told apart from a real sensor in the logs.
+pub const SIMULATED_DEVICE: &str = "simulated";
+
+/// Shape of the simulated signal, read from `simulator` in the configuration file.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct SimulatorSettings {
+    pub temp_mean: f32,
+    pub temp_amplitude: f32,
+    /// Humidity swings against the temperature, as in a heated room
+    pub hum_mean: f32,
+    pub hum_amplitude: f32,
+    pub period_mins: f64,
+    /// Largest random deviation added to each reading
+    pub temp_noise: f32,
+    pub hum_noise: f32,
+    /// Seconds between readings; fractions are allowed so tests run fast
+    pub interval_secs: f64,
+    /// Same seed, same noise
+    pub seed: u64,
+}
+
+impl Default for SimulatorSettings {
+    fn default() -> Self {
+        Self { temp_mean: 22.0, temp_amplitude: 3.0, hum_mean: 50.0, hum_amplitude: 10.0, period_mins: 60.0, temp_noise: 0.2, hum_noise: 1.0, interval_secs: 5.0, seed: 1 }
+    }
+}
+
+/// Data folder of a simulated run without `--data-dir`, so simulated rows never end up in the real logs.
+pub fn scratch_dir() -> PathBuf { std::env::temp_dir().join("temp_monitor_simulated") }
+
+pub struct Simulator {
+    settings: SimulatorSettings,
+    start: DateTime<Local>,
+    // xorshift64 state; never zero
+    state: u64,
+}
+
+impl Simulator {
+    pub fn new(settings: SimulatorSettings, start: DateTime<Local>) -> Self {
+        let state = (settings.seed ^ 0x9E37_79B9_7F4A_7C15).max(1);
+        Self { settings, start, state }
+    }
+
+    // Uniform in [0, 1)
+    fn next_unit(&mut self) -> f32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        (self.state >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    // Triangular in (-1, 1): small deviations are more likely than large ones
+    fn noise(&mut self) -> f32 { self.next_unit() + self.next_unit() - 1.0 }
+
+    /// Temperature and humidity at `time`, rounded to the sensor's 0.1 °C and 1 % steps.
+    pub fn reading(&mut self, time: DateTime<Local>) -> (f32, u8) {
+        let s = self.settings.clone();
+        let wave = ((time - self.start).num_milliseconds() as f64 / 60_000.0 / s.period_mins.max(0.1) * std::f64::consts::TAU).sin() as f32;
+        let temp = s.temp_mean + s.temp_amplitude * wave + s.temp_noise * self.noise();
+        let hum = s.hum_mean - s.hum_amplitude * wave + s.hum_noise * self.noise();
+        ((temp * 10.0).round() / 10.0, hum.round().clamp(0.0, 100.0) as u8)
+    }
+}
+
+/// Sends a reading every `interval_secs` until shutdown; calibration applies as it does to a real sensor.
+pub async fn run(tx: async_mpsc::UnboundedSender<AppMessage>, shared_config: Arc<Mutex<Config>>, mut shutdown: watch::Receiver<bool>) {
+    let settings = shared_config.lock().unwrap().simulator.clone();
+    info!("Simulating a sensor: {:?}", settings);
+    let mut ticks = tokio::time::interval(Duration::from_secs_f64(settings.interval_secs.max(0.001)));
+    let mut simulator = Simulator::new(settings, Local::now());
+    let _ = tx.send(AppMessage::StatusUpdate("Simulating...".into()));
+    loop {
+        tokio::select! {
+            _ = ticks.tick() => {}
+            _ = shutdown.changed() => break,
+        }
+        let now = Local::now();
+        let (temp, hum) = simulator.reading(now);
+        let (temp, hum) = apply_calibration(temp, hum, &shared_config.lock().unwrap());
+        let data = BleDataPoint { timestamp: now, temp, hum, device_id: SIMULATED_DEVICE.into(), model: "Simulated", rssi: Some(-60), raw_data: Vec::new(), device_alarm: false, battery: None, persisted: false };
+        if tx.send(AppMessage::NewData(data)).is_err() { break; }
+    }
+    info!("Simulated sensor stopped.");
+}
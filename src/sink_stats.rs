@@ -0,0 +1,58 @@
+// --- Write counts, failures and latency of each storage/forwarding sink, for the diagnostics window ---
+
+use chrono::{DateTime, Local};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// Latency samples kept per sink for percentiles and the chart
+pub const MAX_SAMPLES: usize = 1000;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Sink { Csv, Influx, Webhook }
+
+impl Sink {
+    pub const ALL: [Sink; 3] = [Sink::Csv, Sink::Influx, Sink::Webhook];
+
+    pub fn label(&self) -> &'static str { match self { Sink::Csv => "CSV log", Sink::Influx => "InfluxDB", Sink::Webhook => "Webhook" } }
+}
+
+#[derive(Clone, Default)]
+pub struct SinkStats {
+    pub writes: u64,
+    pub failures: u64,
+    /// (finished at, latency in ms), oldest first
+    pub samples: VecDeque<(DateTime<Local>, f64)>,
+}
+
+impl SinkStats {
+    const fn new() -> Self { Self { writes: 0, failures: 0, samples: VecDeque::new() } }
+
+    pub fn percentile(&self, p: f64) -> Option<f64> {
+        if self.samples.is_empty() { return None; }
+        let mut values: Vec<f64> = self.samples.iter().map(|s| s.1).collect();
+        values.sort_by(f64::total_cmp);
+        Some(values[((values.len() - 1) as f64 * p).round() as usize])
+    }
+}
+
+static STATS: Mutex<[SinkStats; 3]> = Mutex::new([SinkStats::new(), SinkStats::new(), SinkStats::new()]);
+
+pub fn record(sink: Sink, ok: bool, latency: Duration) {
+    let mut stats = STATS.lock().unwrap_or_else(|e| e.into_inner());
+    let entry = &mut stats[sink as usize];
+    entry.writes += 1;
+    if !ok { entry.failures += 1; }
+    if entry.samples.len() >= MAX_SAMPLES { entry.samples.pop_front(); }
+    entry.samples.push_back((Local::now(), latency.as_secs_f64() * 1000.0));
+}
+
+/// Runs `write`, recording its outcome and duration for `sink`.
+pub fn timed<T, E>(sink: Sink, write: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+    let started = Instant::now();
+    let result = write();
+    record(sink, result.is_ok(), started.elapsed());
+    result
+}
+
+pub fn snapshot(sink: Sink) -> SinkStats { STATS.lock().unwrap_or_else(|e| e.into_inner())[sink as usize].clone() }
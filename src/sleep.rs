@@ -0,0 +1,162 @@
+// --- System sleep: spotting suspend/resume so the scanner restarts, and the sleep periods shown as gaps in the plots ---
+
+use chrono::{DateTime, Duration as ChronoDuration, Local, TimeZone};
+use log::{error, info};
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::{mpsc as async_mpsc, watch};
+
+use crate::model::AppMessage;
+use crate::storage::{format_log_timestamp, parse_log_timestamp};
+use crate::{history, paths};
+
+pub const SLEEP_LOG_FILE_NAME: &str = "sleep.csv";
+const HEADER: [&str; 2] = ["Asleep", "Awake"];
+/// How often the watcher looks at the clock.
+pub const TICK_SECS: u64 = 5;
+/// A tick this much later than due means the system was asleep (a busy machine is late by far less).
+pub const LATE_SECS: i64 = 30;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SleepPeriod { pub start: DateTime<Local>, pub end: DateTime<Local> }
+
+impl SleepPeriod {
+    pub fn minutes(&self) -> i64 { (self.end - self.start).num_minutes() }
+}
+
+/// Compares wall-clock ticks: the process is frozen during a suspend, so the first tick after
+/// resuming comes late by the time asleep. Windows also reports the suspend itself (power broadcast).
+pub struct SleepWatch { last_tick: DateTime<Local> }
+
+impl SleepWatch {
+    pub fn new(now: DateTime<Local>) -> Self { Self { last_tick: now } }
+
+    pub fn tick(&mut self, now: DateTime<Local>) -> Option<SleepPeriod> {
+        let last = std::mem::replace(&mut self.last_tick, now);
+        if let Some(suspended) = platform::take_resume() {
+            let start = Local.timestamp_opt(suspended, 0).single().filter(|t| *t >= last && *t < now).unwrap_or(last);
+            return Some(SleepPeriod { start, end: now });
+        }
+        (now - last > ChronoDuration::seconds(TICK_SECS as i64 + LATE_SECS)).then_some(SleepPeriod { start: last, end: now })
+    }
+}
+
+/// Runs until shutdown: logs each sleep, tells the GUI and signals `woke` so the scanner starts over.
+pub async fn watch_for_sleep(tx: async_mpsc::UnboundedSender<AppMessage>, woke: watch::Sender<()>, mut shutdown: watch::Receiver<bool>) {
+    let mut watch = SleepWatch::new(Local::now());
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_secs(TICK_SECS)) => {}
+            _ = shutdown.changed() => break,
+        }
+        let Some(period) = watch.tick(Local::now()) else { continue; };
+        info!("System was asleep for {} min, restarting the scanner.", period.minutes());
+        if let Err(e) = append(&sleep_log_path(), &period) { error!("Failed to append to sleep log: {}", e); }
+        let _ = woke.send(());
+        let _ = tx.send(AppMessage::Slept(period));
+    }
+}
+
+pub fn sleep_log_path() -> PathBuf { paths::data_dir().join(SLEEP_LOG_FILE_NAME) }
+
+pub fn append(path: &Path, period: &SleepPeriod) -> Result<(), csv::Error> {
+    paths::ensure_dirs();
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let is_new = file.metadata().map(|m| m.len() == 0).unwrap_or(true);
+    let mut wtr = csv::WriterBuilder::new().has_headers(false).from_writer(file);
+    if is_new { wtr.write_record(HEADER)?; }
+    wtr.write_record([format_log_timestamp(period.start), format_log_timestamp(period.end)])?;
+    wtr.flush()?;
+    Ok(())
+}
+
+pub fn read(path: &Path) -> Vec<SleepPeriod> {
+    let Ok(mut rdr) = csv::ReaderBuilder::new().flexible(true).from_path(path) else { return Vec::new(); };
+    rdr.records().flatten().filter_map(|row| Some(SleepPeriod { start: parse_log_timestamp(row.get(0)?)?, end: parse_log_timestamp(row.get(1)?)? })).collect()
+}
+
+/// Splits a plot line where the system slept between two readings, so no straight line is drawn across the gap.
+pub fn split_at_sleep(points: Vec<[f64; 2]>, periods: &[SleepPeriod]) -> Vec<Vec<[f64; 2]>> {
+    history::split_line(points, |x0, x1| periods.iter().any(|s| (x0..x1).contains(&(s.start.timestamp() as f64))))
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::ffi::c_void;
+    use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+    use std::sync::Once;
+
+    const DEVICE_NOTIFY_CALLBACK: u32 = 2;
+    const PBT_APMSUSPEND: u32 = 0x4;
+    const PBT_APMRESUMESUSPEND: u32 = 0x7;
+    const PBT_APMRESUMEAUTOMATIC: u32 = 0x12;
+
+    #[repr(C)]
+    struct SubscribeParameters { callback: unsafe extern "system" fn(*mut c_void, u32, *mut c_void) -> u32, context: *mut c_void }
+
+    #[link(name = "powrprof")]
+    extern "system" { fn PowerRegisterSuspendResumeNotification(flags: u32, recipient: *mut c_void, handle: *mut *mut c_void) -> u32; }
+
+    static SUSPENDED_AT: AtomicI64 = AtomicI64::new(0);
+    static RESUMED: AtomicBool = AtomicBool::new(false);
+    static REGISTER: Once = Once::new();
+
+    unsafe extern "system" fn on_power_event(_context: *mut c_void, kind: u32, _setting: *mut c_void) -> u32 {
+        match kind {
+            PBT_APMSUSPEND => SUSPENDED_AT.store(chrono::Local::now().timestamp(), Ordering::Relaxed),
+            PBT_APMRESUMESUSPEND | PBT_APMRESUMEAUTOMATIC => RESUMED.store(true, Ordering::Relaxed),
+            _ => {}
+        }
+        0
+    }
+
+    /// Unix time of the suspend when a resume was reported since the last call.
+    pub fn take_resume() -> Option<i64> {
+        REGISTER.call_once(|| {
+            // Registered for the app's lifetime, so the parameters are never freed
+            let parameters = Box::leak(Box::new(SubscribeParameters { callback: on_power_event, context: std::ptr::null_mut() }));
+            let mut handle = std::ptr::null_mut();
+            // SAFETY: `parameters` lives forever and `handle` is a valid out pointer
+            let result = unsafe { PowerRegisterSuspendResumeNotification(DEVICE_NOTIFY_CALLBACK, parameters as *mut SubscribeParameters as *mut c_void, &mut handle) };
+            if result != 0 { log::warn!("Power notifications unavailable (error {}), relying on the clock.", result); }
+        });
+        RESUMED.swap(false, Ordering::Relaxed).then(|| SUSPENDED_AT.load(Ordering::Relaxed))
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    pub fn take_resume() -> Option<i64> { None }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+
+    #[test]
+    fn a_late_clock_tick_is_a_sleep_that_breaks_the_plot_line() {
+        use chrono::Duration;
+        let data_dir = TempDataDir::new("sleep");
+        let start = Local.with_ymd_and_hms(2025, 1, 10, 22, 0, 0).unwrap();
+        let mut watch = SleepWatch::new(start);
+        // Ticks a little late on a busy machine are not a sleep; an hour late is
+        assert_eq!(watch.tick(start + Duration::seconds(12)), None);
+        let woke = start + Duration::seconds(12) + Duration::hours(1);
+        let period = watch.tick(woke).unwrap();
+        assert_eq!((period.start, period.end, period.minutes()), (start + Duration::seconds(12), woke, 60));
+        assert_eq!(watch.tick(woke + Duration::seconds(5)), None);
+
+        let path = data_dir.dir.join(SLEEP_LOG_FILE_NAME);
+        append(&path, &period).unwrap();
+        assert_eq!(read(&path), vec![period]);
+
+        let x = |t: DateTime<Local>| t.timestamp() as f64;
+        let points = vec![[x(start), 20.0], [x(start + Duration::seconds(10)), 20.1], [x(woke + Duration::seconds(3)), 18.0], [x(woke + Duration::seconds(8)), 18.1]];
+        let segments = split_at_sleep(points.clone(), &[period]);
+        assert_eq!(segments, vec![points[..2].to_vec(), points[2..].to_vec()]);
+        let elsewhere = SleepPeriod { start: start - Duration::days(1), end: start - Duration::hours(20) };
+        assert_eq!(split_at_sleep(points.clone(), &[elsewhere]), vec![points]);
+    }
+}
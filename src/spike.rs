@@ -0,0 +1,53 @@
+// --- Ingest sanity filter: drops readings far off the recent median (e.g. 0 °C / 0 % from malformed packets) ---
+
+use chrono::{DateTime, Duration as ChronoDuration, Local};
+use std::collections::VecDeque;
+
+// A filter needs a few readings to judge against, and gives in when a "spike" persists (a real step change)
+const MIN_SAMPLES: usize = 3;
+const PERSISTENT_REJECTS: usize = 3;
+
+#[derive(Default)]
+pub struct SpikeFilter {
+    recent: VecDeque<(DateTime<Local>, f32, u8)>,
+    rejected: Vec<(DateTime<Local>, f32, u8)>,
+}
+
+fn median(mut values: Vec<f32>) -> f32 {
+    values.sort_by(f32::total_cmp);
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) { (values[mid - 1] + values[mid]) / 2.0 } else { values[mid] }
+}
+
+impl SpikeFilter {
+    /// `Err` describes why the reading was rejected.
+    pub fn check(&mut self, timestamp: DateTime<Local>, temp: f32, hum: u8, max_temp_delta: f32, max_hum_delta: f32, window_secs: u64) -> Result<(), String> {
+        let cutoff = timestamp - ChronoDuration::seconds(window_secs as i64);
+        while self.recent.front().is_some_and(|(t, _, _)| *t < cutoff) { self.recent.pop_front(); }
+        self.rejected.retain(|(t, _, _)| *t >= cutoff);
+        if self.recent.len() >= MIN_SAMPLES {
+            let temp_median = median(self.recent.iter().map(|r| r.1).collect());
+            let hum_median = median(self.recent.iter().map(|r| r.2 as f32).collect());
+            let (temp_off, hum_off) = ((temp - temp_median).abs(), (hum as f32 - hum_median).abs());
+            if temp_off > max_temp_delta || hum_off > max_hum_delta {
+                self.rejected.push((timestamp, temp, hum));
+                if !self.consistent_rejects(max_temp_delta, max_hum_delta) {
+                    return Err(format!("{:.1} °C / {} % is {:.1} °C / {:.0} % off the recent median {:.1} °C / {:.0} %", temp, hum, temp_off, hum_off, temp_median, hum_median));
+                }
+                // The new level held for several readings: accept it and judge against it from now on
+                self.recent = self.rejected.drain(..).collect();
+                return Ok(());
+            }
+        }
+        self.rejected.clear();
+        self.recent.push_back((timestamp, temp, hum));
+        Ok(())
+    }
+
+    fn consistent_rejects(&self, max_temp_delta: f32, max_hum_delta: f32) -> bool {
+        if self.rejected.len() < PERSISTENT_REJECTS { return false; }
+        let temp_median = median(self.rejected.iter().map(|r| r.1).collect());
+        let hum_median = median(self.rejected.iter().map(|r| r.2 as f32).collect());
+        self.rejected.iter().all(|r| (r.1 - temp_median).abs() <= max_temp_delta && (r.2 as f32 - hum_median).abs() <= max_hum_delta)
+    }
+}
@@ -0,0 +1,140 @@
+// --- Storage: daily CSV logs, history loading and the configuration file ---
+
+use chrono::{DateTime, Local, NaiveDateTime, SecondsFormat};
+use std::fs;
+use std::path::{Path, PathBuf};
+use log::{info, debug};
+
+use crate::aggregate::Resolution;
+use crate::history::PointOrigin;
+use crate::model::{Config, HistoryPoint};
+use crate::{edit, integrity, paths, retention, rules};
+
+// The reading's own time picks the row and its day's file, so a replayed recording lands on the days it was made
+pub fn log_to_csv(timestamp: DateTime<Local>, temp: f32, hum: u8) -> std::io::Result<()> {
+    let _guard = edit::LOG_WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    // Use comma delimiter and dot decimal (ISO-style) — broadly Excel-friendly
+    let temp_str = format!("{:.1}", temp); // dot decimal
+    integrity::append_row(&paths::log_path(timestamp.date_naive()), &[format_log_timestamp(timestamp), temp_str, hum.to_string()])
+}
+
+/// A virtual sensor's log: it can be combined again from its members, so it goes without the journal.
+pub fn log_derived_to_csv(dir: &Path, timestamp: DateTime<Local>, temp: f32, hum: u8) -> std::io::Result<()> {
+    let _guard = edit::LOG_WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    fs::create_dir_all(dir)?;
+    integrity::append_line(&paths::log_path_in(dir, timestamp.date_naive()), &[format_log_timestamp(timestamp), format!("{:.1}", temp), hum.to_string()].join(","))
+}
+
+pub fn read_log_records(filename: &Path) -> Vec<csv::StringRecord> {
+    // Archived days are gzipped; either way try comma first, fall back to semicolon (backwards compatibility)
+    let read = |delimiter: u8| -> Vec<csv::StringRecord> {
+        match retention::open_log(filename) {
+            Ok(file) => csv::ReaderBuilder::new().delimiter(delimiter).from_reader(file).records().filter_map(Result::ok).collect(),
+            Err(_) => vec![],
+        }
+    };
+    let records = read(b',');
+    if !records.is_empty() { records } else { read(b';') }
+}
+
+/// Log timestamps are RFC 3339 with the UTC offset (`2025-11-26T14:23:45+01:00`), so they stay
+/// unambiguous across DST changes and when logs are imported on a machine in another time zone.
+pub fn format_log_timestamp(time: DateTime<Local>) -> String { time.to_rfc3339_opts(SecondsFormat::Secs, false) }
+
+/// Accepts RFC 3339 as well as the older naive local `%Y-%m-%dT%H:%M:%S`.
+pub fn parse_log_timestamp(text: &str) -> Option<DateTime<Local>> {
+    let text = text.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(text) { return Some(time.with_timezone(&Local)); }
+    NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S").ok()?.and_local_timezone(Local).earliest()
+}
+
+pub fn parse_history_record(record: &csv::StringRecord) -> Option<HistoryPoint> {
+    // New format: DateTime,Temperature,Humidity
+    if record.len() >= 3 {
+        if let Some(timestamp) = record.get(0).and_then(parse_log_timestamp) {
+            if let (Some(temp_str), Some(hum_str)) = (record.get(1), record.get(2)) {
+                if let (Ok(temp), Ok(hum)) = (temp_str.replace(',', ".").parse(), hum_str.parse()) {
+                    return Some(HistoryPoint { timestamp, temp, hum, origin: PointOrigin::Backfilled, rssi: None });
+                }
+            }
+        }
+    }
+
+    // Fallback to old format: Date, Time, Temp, Hum (semicolon-style legacy)
+    if let (Some(date_str), Some(time_str), Some(temp_str), Some(hum_str)) =
+        (record.get(0), record.get(1), record.get(2), record.get(3))
+    {
+        let datetime_str = format!("{} {}", date_str, time_str);
+        if let Ok(naive_dt) = NaiveDateTime::parse_from_str(&datetime_str, "%Y.%m.%d %H:%M:%S") {
+            // These rows carry no offset: a repeated hour in autumn takes its first occurrence, a skipped hour in spring drops the row
+            let Some(timestamp) = naive_dt.and_local_timezone(Local).earliest() else { debug!("Skipping legacy row at {}, a time skipped by a DST change.", datetime_str); return None; };
+            if let (Ok(temp), Ok(hum)) = (temp_str.replace(',', ".").parse(), hum_str.parse()) {
+                return Some(HistoryPoint { timestamp, temp, hum, origin: PointOrigin::Backfilled, rssi: None });
+            }
+        }
+    }
+    None
+}
+
+// Reads every daily log overlapping [start, end] (used by reports)
+pub fn load_history_range(start: DateTime<Local>, end: DateTime<Local>) -> Vec<HistoryPoint> { load_history_range_in(&paths::data_dir(), start, end) }
+
+// Days whose log was removed by retention fall back to the hourly means
+pub fn load_history_range_in(dir: &Path, start: DateTime<Local>, end: DateTime<Local>) -> Vec<HistoryPoint> {
+    let mut points = Vec::new();
+    let mut hourly: Option<Vec<HistoryPoint>> = None;
+    let mut day = start.date_naive();
+    while day <= end.date_naive() {
+        let filename = paths::log_path_in(dir, day);
+        let files: Vec<PathBuf> = [retention::compressed_path(&filename), filename].into_iter().filter(|f| f.exists()).collect();
+        for file in &files {
+            points.extend(read_log_records(file).iter().filter_map(parse_history_record).filter(|p| p.timestamp >= start && p.timestamp <= end));
+        }
+        if files.is_empty() {
+            let hourly = hourly.get_or_insert_with(|| read_log_records(&Resolution::Hour.path_in(dir)).iter().filter_map(parse_history_record).collect());
+            points.extend(hourly.iter().filter(|p| p.timestamp.date_naive() == day && p.timestamp >= start && p.timestamp <= end).cloned());
+        }
+        day = match day.succ_opt() { Some(next) => next, None => break };
+    }
+    points.sort_by_key(|p| p.timestamp);
+    debug!("Loaded {} points for range {} - {}.", points.len(), start, end);
+    points
+}
+
+pub fn load_config() -> Config {
+    let path = paths::config_path();
+    info!("Loading configuration from '{}'.", path.display());
+    let content = fs::read_to_string(path).ok();
+    let mut config: Config = content.as_deref().and_then(|c| serde_json::from_str(c).ok()).unwrap_or_default();
+    // Saved before alert rules existed: the fixed thresholds become the first rules
+    if content.is_some_and(|c| !c.contains("\"alert_rules\"")) { config.alert_rules = rules::from_thresholds(&config); }
+    config
+}
+
+pub fn save_config(config: &Config) {
+    paths::ensure_dirs();
+    if let Ok(content) = serde_json::to_string_pretty(config) { let _ = fs::write(paths::config_path(), content); }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+
+    #[test]
+    fn csv_log_rows_round_trip_through_the_parser() {
+        let data_dir = TempDataDir::new("csv_round_trip");
+        let now = Local::now();
+        log_to_csv(now, -3.25, 67).unwrap();
+        log_to_csv(now, 21.0, 40).unwrap();
+        let loaded = load_history_range(now - chrono::Duration::hours(1), now + chrono::Duration::hours(1));
+        assert_eq!(loaded.iter().map(|p| (p.temp, p.hum)).collect::<Vec<_>>(), vec![(-3.2, 67), (21.0, 40)]);
+        assert!(loaded.iter().all(|p| (now - p.timestamp).num_seconds().abs() < 60));
+        assert_eq!(data_dir.csv_rows().len(), 2);
+
+        // Legacy semicolon rows: date, time, decimal comma
+        let legacy = parse_history_record(&csv::StringRecord::from(vec!["2024.01.05", "07:30:00", "19,5", "55"])).unwrap();
+        assert_eq!((legacy.timestamp.naive_local().to_string(), legacy.temp, legacy.hum), ("2024-01-05 07:30:00".to_string(), 19.5, 55));
+        assert!(parse_history_record(&csv::StringRecord::from(vec!["yesterday", "warm", "45"])).is_none());
+    }
+}
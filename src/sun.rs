@@ -0,0 +1,112 @@
+// --- Night shading: sunrise/sunset from a location (or the time zone), or fixed manual times ---
+
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveDate, NaiveTime, Offset, Utc};
+use serde::{Deserialize, Serialize};
+
+// Rough latitude for the time-zone estimate; mid-latitudes are where day length matters to most users
+const AUTO_LATITUDE: f64 = 45.0;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum NightShading {
+    #[default]
+    Off,
+    /// Longitude estimated from the local UTC offset
+    TimeZone,
+    Location,
+    Manual,
+}
+
+impl NightShading {
+    pub const ALL: [NightShading; 4] = [NightShading::Off, NightShading::TimeZone, NightShading::Location, NightShading::Manual];
+
+    pub fn label(&self) -> &'static str {
+        match self { NightShading::Off => "Off", NightShading::TimeZone => "Estimate from time zone", NightShading::Location => "Latitude/longitude", NightShading::Manual => "Manual times" }
+    }
+}
+
+/// Daylight of one date; `AlwaysUp`/`AlwaysDown` are polar day and polar night.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Daylight { Between(DateTime<Utc>, DateTime<Utc>), AlwaysUp, AlwaysDown }
+
+/// Sunrise equation with the usual -0.833° refraction correction; accurate to a minute or two.
+/// Longitude is east-positive.
+pub fn daylight(date: NaiveDate, latitude: f64, longitude: f64) -> Daylight {
+    let epoch = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap_or_default();
+    let n = (date - epoch).num_days() as f64 + 0.0008;
+    let mean_solar_noon = n - longitude / 360.0;
+    let anomaly = (357.5291 + 0.98560028 * mean_solar_noon).rem_euclid(360.0).to_radians();
+    let center = 1.9148 * anomaly.sin() + 0.02 * (2.0 * anomaly).sin() + 0.0003 * (3.0 * anomaly).sin();
+    let ecliptic_longitude = (anomaly.to_degrees() + center + 180.0 + 102.9372).rem_euclid(360.0).to_radians();
+    let transit = 2451545.0 + mean_solar_noon + 0.0053 * anomaly.sin() - 0.0069 * (2.0 * ecliptic_longitude).sin();
+    let declination = (ecliptic_longitude.sin() * 23.44f64.to_radians().sin()).asin();
+    let lat = latitude.to_radians();
+    let cos_hour_angle = ((-0.833f64).to_radians().sin() - lat.sin() * declination.sin()) / (lat.cos() * declination.cos());
+    if cos_hour_angle > 1.0 { return Daylight::AlwaysDown; }
+    if cos_hour_angle < -1.0 { return Daylight::AlwaysUp; }
+    let half_day = cos_hour_angle.acos().to_degrees() / 360.0;
+    let to_utc = |julian: f64| DateTime::from_timestamp(((julian - 2440587.5) * 86400.0).round() as i64, 0).unwrap_or_default();
+    Daylight::Between(to_utc(transit - half_day), to_utc(transit + half_day))
+}
+
+pub struct NightSettings<'a> { pub mode: NightShading, pub latitude: f64, pub longitude: f64, pub sunrise: &'a str, pub sunset: &'a str }
+
+impl NightSettings<'_> {
+    fn daylight_on(&self, date: NaiveDate) -> Option<Daylight> {
+        match self.mode {
+            NightShading::Off => None,
+            NightShading::TimeZone => {
+                let offset_hours = Local::now().offset().fix().local_minus_utc() as f64 / 3600.0;
+                Some(daylight(date, AUTO_LATITUDE, offset_hours * 15.0))
+            }
+            NightShading::Location => Some(daylight(date, self.latitude, self.longitude)),
+            NightShading::Manual => {
+                let rise = NaiveTime::parse_from_str(self.sunrise.trim(), "%H:%M").ok()?;
+                let set = NaiveTime::parse_from_str(self.sunset.trim(), "%H:%M").ok()?;
+                let at = |t: NaiveTime| date.and_time(t).and_local_timezone(Local).earliest().map(|t| t.with_timezone(&Utc));
+                Some(Daylight::Between(at(rise)?, at(set)?))
+            }
+        }
+    }
+
+    /// Night intervals overlapping `[from, to]`, clipped to it.
+    pub fn night_intervals(&self, from: DateTime<Local>, to: DateTime<Local>) -> Vec<(DateTime<Local>, DateTime<Local>)> {
+        let mut nights = Vec::new();
+        let mut cursor = from.with_timezone(&Utc);
+        let end = to.with_timezone(&Utc);
+        let mut day = from.date_naive() - ChronoDuration::days(1);
+        while day <= to.date_naive() + ChronoDuration::days(1) && cursor < end {
+            let Some(daylight) = self.daylight_on(day) else { return Vec::new(); };
+            let next_midnight = (day + ChronoDuration::days(1)).and_hms_opt(0, 0, 0).and_then(|t| t.and_local_timezone(Local).earliest()).map(|t| t.with_timezone(&Utc));
+            let Some(next_midnight) = next_midnight else { break; };
+            match daylight {
+                Daylight::AlwaysUp => cursor = cursor.max(next_midnight),
+                // Polar night: leave the cursor, the night runs on to the next sunrise
+                Daylight::AlwaysDown => {}
+                Daylight::Between(rise, set) => {
+                    if rise > cursor { nights.push((cursor, rise.min(end))); }
+                    cursor = cursor.max(set);
+                }
+            }
+            day = match day.succ_opt() { Some(next) => next, None => break };
+        }
+        if cursor < end { nights.push((cursor, end)); }
+        nights.into_iter().filter(|(a, b)| b > a).map(|(a, b)| (a.with_timezone(&Local), b.with_timezone(&Local))).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sunrise_and_sunset_match_published_times() {
+        use chrono::Timelike;
+        // Prague, summer solstice 2024: sunrise 02:52 UTC, sunset 19:15 UTC
+        let Daylight::Between(rise, set) = daylight(NaiveDate::from_ymd_opt(2024, 6, 21).unwrap(), 50.08, 14.42) else { panic!("expected a sunrise in Prague") };
+        let minutes = |t: DateTime<Utc>| (t.hour() * 60 + t.minute()) as i32;
+        assert!((minutes(rise) - (2 * 60 + 52)).abs() <= 3, "sunrise {}", rise);
+        assert!((minutes(set) - (19 * 60 + 15)).abs() <= 3, "sunset {}", set);
+        assert_eq!(daylight(NaiveDate::from_ymd_opt(2024, 12, 21).unwrap(), 78.2, 15.6), Daylight::AlwaysDown);
+        assert_eq!(daylight(NaiveDate::from_ymd_opt(2024, 6, 21).unwrap(), 78.2, 15.6), Daylight::AlwaysUp);
+    }
+}
@@ -0,0 +1,189 @@
+// --- Telegram bot: alerts to a chat, and the current reading on /status ---
+
+use chrono::{Duration as ChronoDuration, Local};
+use log::{error, info, warn};
+use serde_json::{json, Value};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tokio::sync::watch;
+
+use crate::alerts::offline_after;
+use crate::model::{BleDataPoint, Config, HistoryPoint};
+use crate::processor::sleep_unless_stopped;
+use crate::sensors;
+use crate::storage::load_history_range;
+use crate::webhook::WebhookEvent;
+
+const API_URL: &str = "https://api.telegram.org";
+// Long polling: Telegram holds getUpdates open until a message arrives or this many seconds pass
+const POLL_TIMEOUT_SECS: u64 = 25;
+const MAX_ATTEMPTS: u32 = 3;
+const RETRY_DELAY: Duration = Duration::from_secs(10);
+const HELP_TEXT: &str = "Send /status for the current reading and the last 24 hours.";
+
+/// Latest accepted reading, kept by the processor for /status.
+pub type LatestReading = Arc<Mutex<Option<BleDataPoint>>>;
+
+/// A text message from a chat, as returned by getUpdates.
+#[derive(Clone, PartialEq, Debug)]
+pub struct IncomingMessage { pub update_id: i64, pub chat_id: i64, pub username: Option<String>, pub text: String }
+
+fn method_url(token: &str, method: &str) -> String { format!("{}/bot{}/{}", API_URL, token.trim(), method) }
+
+fn send_message(token: &str, chat_id: &str, text: &str) -> Result<(), String> {
+    ureq::post(&method_url(token, "sendMessage"))
+        .set("Content-Type", "application/json")
+        .timeout(Duration::from_secs(10))
+        .send_string(&json!({ "chat_id": chat_id.trim(), "text": text }).to_string())
+        .map(|_| ())
+        // The error text would contain the URL and with it the token
+        .map_err(|e| match e { ureq::Error::Status(code, _) => format!("HTTP {}", code), ureq::Error::Transport(t) => t.kind().to_string() })
+}
+
+fn deliver(token: &str, chat_id: &str, text: &str, stop: &watch::Receiver<bool>) {
+    for attempt in 1..=MAX_ATTEMPTS {
+        match send_message(token, chat_id, text) {
+            Ok(()) => { info!("Telegram message delivered."); return; }
+            Err(e) if attempt < MAX_ATTEMPTS => {
+                warn!("Telegram message failed (attempt {}/{}): {}", attempt, MAX_ATTEMPTS, e);
+                if !sleep_unless_stopped(stop, RETRY_DELAY) { warn!("Telegram message not retried, shutting down."); return; }
+            }
+            Err(e) => error!("Telegram message failed after {} attempts, giving up: {}", MAX_ATTEMPTS, e),
+        }
+    }
+}
+
+/// Pushes alert messages to the configured chat, in order, on its own thread; no retries after `stop`.
+pub fn run_sender(rx: mpsc::Receiver<WebhookEvent>, shared_config: Arc<Mutex<Config>>, stop: watch::Receiver<bool>) {
+    info!("Starting Telegram sender.");
+    for event in rx {
+        let config = shared_config.lock().unwrap().clone();
+        if !is_configured(&config) { continue; }
+        deliver(&config.telegram_bot_token, &config.telegram_chat_id, &event.message, &stop);
+    }
+    info!("Telegram sender terminated.");
+}
+
+pub fn is_configured(config: &Config) -> bool { config.telegram_enabled && !config.telegram_bot_token.trim().is_empty() && !config.telegram_chat_id.trim().is_empty() }
+
+/// Sends a test message on a background thread (settings "Test" button).
+pub fn send_test(token: &str, chat_id: &str) {
+    let (token, chat_id) = (token.to_string(), chat_id.to_string());
+    thread::spawn(move || deliver(&token, &chat_id, "Test message from Temperature Monitor", &watch::channel(false).1));
+}
+
+/// Text messages in a getUpdates response; other update types are skipped.
+pub fn parse_updates(response: &Value) -> Vec<IncomingMessage> {
+    response["result"].as_array().into_iter().flatten().filter_map(|update| {
+        let message = &update["message"];
+        Some(IncomingMessage {
+            update_id: update["update_id"].as_i64()?,
+            chat_id: message["chat"]["id"].as_i64()?,
+            username: message["chat"]["username"].as_str().map(str::to_string),
+            text: message["text"].as_str()?.to_string(),
+        })
+    }).collect()
+}
+
+/// Only the configured chat is answered, by numeric id or `@username`, so strangers who find the bot learn nothing.
+pub fn is_from_configured_chat(message: &IncomingMessage, config: &Config) -> bool {
+    let configured = config.telegram_chat_id.trim();
+    configured == message.chat_id.to_string() || message.username.as_ref().is_some_and(|name| configured.trim_start_matches('@').eq_ignore_ascii_case(name))
+}
+
+fn is_status_command(text: &str) -> bool {
+    // In groups commands arrive as "/status@MyBot"
+    text.split_whitespace().next().and_then(|command| command.split('@').next()) == Some("/status")
+}
+
+/// Reply to /status: the latest reading, or how long the sensor has been silent, and the last 24 hours.
+pub fn status_text(latest: Option<&BleDataPoint>, recent: &[HistoryPoint], config: &Config, now: chrono::DateTime<Local>) -> String {
+    let mut text = if config.room_name.trim().is_empty() { String::new() } else { format!("{}\n", config.room_name.trim()) };
+    if let Some(name) = sensors::friendly_name(config, &config.target_mac) { text.push_str(&format!("{}\n", name)); }
+    match latest {
+        Some(data) => {
+            text.push_str(&format!("{:.1} °C, {} % at {}", data.temp, data.hum, data.timestamp.format("%H:%M")));
+            let silent = (now - data.timestamp).to_std().unwrap_or_default();
+            if silent >= offline_after(config) { text.push_str(&format!("\nNo reading for {} min, the sensor may be offline.", silent.as_secs() / 60)); }
+        }
+        None => text.push_str("No reading received since the app started."),
+    }
+    if let (Some(min), Some(max)) = (recent.iter().map(|p| p.temp).reduce(f32::min), recent.iter().map(|p| p.temp).reduce(f32::max)) {
+        let mean = recent.iter().map(|p| p.temp).sum::<f32>() / recent.len() as f32;
+        let (hum_min, hum_max) = (recent.iter().map(|p| p.hum).min().unwrap_or(0), recent.iter().map(|p| p.hum).max().unwrap_or(0));
+        text.push_str(&format!("\nLast 24 h: {:.1} to {:.1} °C (mean {:.1}), {} to {} %", min, max, mean, hum_min, hum_max));
+    }
+    text.push_str(&format!("\nThresholds: {:.1} to {:.1} °C", config.temp_warn_low, config.temp_warn_high));
+    text
+}
+
+fn get_updates(token: &str, offset: i64) -> Result<Vec<IncomingMessage>, String> {
+    let response: Value = ureq::get(&method_url(token, "getUpdates"))
+        .query("offset", &offset.to_string())
+        .query("timeout", &POLL_TIMEOUT_SECS.to_string())
+        .timeout(Duration::from_secs(POLL_TIMEOUT_SECS + 10))
+        .call()
+        .map_err(|e| match e { ureq::Error::Status(code, _) => format!("HTTP {}", code), ureq::Error::Transport(t) => t.kind().to_string() })?
+        .into_string()
+        .map_err(|e| e.to_string())
+        .and_then(|body| serde_json::from_str(&body).map_err(|e| e.to_string()))?;
+    Ok(parse_updates(&response))
+}
+
+/// Answers /status from the configured chat until `stop`; idles while the bot is switched off.
+pub fn run_bot(shared_config: Arc<Mutex<Config>>, latest: LatestReading, stop: watch::Receiver<bool>) {
+    info!("Starting Telegram bot.");
+    let mut offset = 0;
+    while !*stop.borrow() {
+        let config = shared_config.lock().unwrap().clone();
+        if !is_configured(&config) { sleep_unless_stopped(&stop, Duration::from_secs(5)); continue; }
+        let messages = match get_updates(&config.telegram_bot_token, offset) {
+            Ok(messages) => messages,
+            Err(e) => { warn!("Telegram getUpdates failed: {}", e); sleep_unless_stopped(&stop, RETRY_DELAY); continue; }
+        };
+        for message in messages {
+            offset = offset.max(message.update_id + 1);
+            if !is_from_configured_chat(&message, &config) { info!("Ignoring a Telegram message from chat {}.", message.chat_id); continue; }
+            let reply = if is_status_command(&message.text) {
+                let now = Local::now();
+                let recent = load_history_range(now - ChronoDuration::hours(24), now);
+                status_text(latest.lock().unwrap().as_ref(), &recent, &config, now)
+            } else { HELP_TEXT.to_string() };
+            deliver(&config.telegram_bot_token, &config.telegram_chat_id, &reply, &stop);
+        }
+    }
+    info!("Telegram bot terminated.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+
+    #[test]
+    fn telegram_answers_only_the_configured_chat_with_a_status_summary() {
+        let response = serde_json::json!({ "ok": true, "result": [
+            { "update_id": 7, "message": { "chat": { "id": 42, "username": "owner" }, "text": "/status" } },
+            { "update_id": 8, "edited_message": { "chat": { "id": 42 }, "text": "/status" } },
+            { "update_id": 9, "message": { "chat": { "id": 1000 }, "text": "/status@MonitorBot" } },
+        ] });
+        let messages = parse_updates(&response);
+        assert_eq!(messages.iter().map(|m| (m.update_id, m.chat_id)).collect::<Vec<_>>(), vec![(7, 42), (9, 1000)]);
+        let config = Config { telegram_chat_id: "42".into(), room_name: "Cellar".into(), ..test_config() };
+        assert!(is_from_configured_chat(&messages[0], &config));
+        assert!(!is_from_configured_chat(&messages[1], &config));
+        assert!(is_from_configured_chat(&messages[0], &Config { telegram_chat_id: "@Owner".into(), ..test_config() }));
+
+        let now = Local::now();
+        let latest = BleDataPoint { persisted: true, ..reading(now - ChronoDuration::minutes(1), 21.5, 48) };
+        let recent: Vec<_> = [18.0, 24.0, 21.0].iter().map(|&temp| HistoryPoint { temp, hum: 45, ..history_point(&latest) }).collect();
+        let text = status_text(Some(&latest), &recent, &config, now);
+        assert!(text.starts_with("Cellar\n21.5 °C, 48 %"), "{}", text);
+        assert!(text.contains("Last 24 h: 18.0 to 24.0 °C (mean 21.0), 45 to 45 %"), "{}", text);
+        assert!(!text.contains("offline"), "{}", text);
+        // A stale reading is reported as such
+        assert!(status_text(Some(&latest), &[], &config, now + ChronoDuration::hours(2)).contains("sensor may be offline"));
+        assert!(status_text(None, &[], &config, now).contains("No reading"));
+    }
+}
@@ -0,0 +1,58 @@
+// --- Alert message templates shared by every alert channel (desktop toast, webhook, ...) ---
+
+use std::time::Duration;
+
+use crate::model::Config;
+use crate::sensors;
+use crate::webhook::AlertKind;
+
+pub const PLACEHOLDERS: [&str; 6] = ["{device}", "{value}", "{threshold}", "{duration}", "{room}", "{rule}"];
+
+/// Values substituted into a template; missing ones render as "–".
+#[derive(Clone, Debug, Default)]
+pub struct AlertVars {
+    pub value: Option<String>,
+    pub threshold: Option<String>,
+    pub duration: Option<Duration>,
+    /// Rule name, or its description when unnamed
+    pub rule: Option<String>,
+}
+
+pub fn default_template(kind: AlertKind) -> &'static str {
+    match kind {
+        AlertKind::TemperatureHigh => "Temperature {value} is above {threshold}",
+        AlertKind::TemperatureLow => "Temperature {value} is below {threshold}",
+        AlertKind::TemperatureNormal => "Temperature back to normal ({value})",
+        AlertKind::HumidityHigh => "Humidity {value} is above {threshold}",
+        AlertKind::HumidityLow => "Humidity {value} is below {threshold}",
+        AlertKind::HumidityNormal => "Humidity back to normal ({value})",
+        AlertKind::SensorOffline => "No reading from the sensor for {duration}",
+        AlertKind::SensorOnline => "Sensor is back online",
+        AlertKind::DeviceAlarm => "Sensor reports its on-device alarm ({value})",
+        AlertKind::DeviceAlarmCleared => "Sensor alarm cleared",
+        AlertKind::RuleTriggered => "{rule}: {value}",
+        AlertKind::RuleCleared => "{rule} no longer applies ({value})",
+    }
+}
+
+fn format_duration(d: Duration) -> String {
+    let mins = d.as_secs() / 60;
+    if mins >= 60 { format!("{} h {} min", mins / 60, mins % 60) } else { format!("{} min", mins) }
+}
+
+pub fn render(template: &str, device: &str, room: &str, vars: &AlertVars) -> String {
+    let or_dash = |v: Option<String>| v.filter(|v| !v.is_empty()).unwrap_or_else(|| "–".to_string());
+    template
+        .replace("{device}", &or_dash(Some(device.to_string())))
+        .replace("{room}", &or_dash(Some(room.to_string())))
+        .replace("{value}", &or_dash(vars.value.clone()))
+        .replace("{threshold}", &or_dash(vars.threshold.clone()))
+        .replace("{duration}", &or_dash(vars.duration.map(format_duration)))
+        .replace("{rule}", &or_dash(vars.rule.clone()))
+}
+
+/// The configured template for `kind` (or the built-in one when left empty), filled in.
+pub fn message(kind: AlertKind, config: &Config, vars: &AlertVars) -> String {
+    let template = config.alert_templates.get(kind.id()).map(|t| t.trim()).filter(|t| !t.is_empty()).unwrap_or(default_template(kind));
+    render(template, &sensors::display_name(config, &config.target_mac), &config.room_name, vars)
+}
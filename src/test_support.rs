@@ -0,0 +1,68 @@
+// --- Shared test fixtures: a scratch data folder, the test sensor's configuration and reading builders ---
+
+use chrono::{DateTime, Local, NaiveDate};
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use crate::history::PointOrigin;
+use crate::model::{BleDataPoint, Config, HistoryPoint};
+use crate::paths;
+
+pub const TARGET: &str = "B8:59:CE:33:0F:93";
+pub const OTHER: &str = "11:22:33:44:55:66";
+
+// The data folder override is process-wide, so storage tests run one at a time
+static STORAGE_LOCK: Mutex<()> = Mutex::new(());
+
+/// A fresh data folder under the temp dir, used in place of the real one until dropped.
+pub struct TempDataDir { pub dir: PathBuf, _guard: MutexGuard<'static, ()> }
+
+impl TempDataDir {
+    pub fn new(name: &str) -> Self {
+        let guard = STORAGE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = std::env::temp_dir().join(format!("temp_monitor_test_{}_{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        paths::set_data_dir_override(Some(dir.clone()));
+        Self { dir, _guard: guard }
+    }
+
+    pub fn csv_rows(&self) -> Vec<String> {
+        let path = paths::log_path(Local::now().date_naive());
+        std::fs::read_to_string(path).unwrap_or_default().lines().skip(1).map(str::to_string).collect()
+    }
+}
+
+impl Drop for TempDataDir {
+    fn drop(&mut self) {
+        paths::set_data_dir_override(None);
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+pub fn test_config() -> Config {
+    Config { target_mac: TARGET.to_string(), continuous_mode: true, duplicate_threshold_secs: 30, temp_warn_high: 30.0, temp_warn_low: 10.0, ..Config::default() }
+}
+
+/// `hour:min` on `day`, local time.
+pub fn local(day: NaiveDate, hour: u32, min: u32) -> DateTime<Local> {
+    day.and_hms_opt(hour, min, 0).unwrap().and_local_timezone(Local).earliest().unwrap()
+}
+
+pub fn today_at(hour: u32, min: u32) -> DateTime<Local> { local(Local::now().date_naive(), hour, min) }
+
+pub fn rfc3339(text: &str) -> DateTime<Local> { DateTime::parse_from_rfc3339(text).unwrap().with_timezone(&Local) }
+
+/// A logged reading without signal strength; struct update covers the other origins.
+pub fn point(timestamp: DateTime<Local>, temp: f32, hum: u8) -> HistoryPoint {
+    HistoryPoint { timestamp, temp, hum, origin: PointOrigin::Persisted, rssi: None }
+}
+
+/// A reading of the target sensor as the scanner hands it over, not yet logged.
+pub fn reading(timestamp: DateTime<Local>, temp: f32, hum: u8) -> BleDataPoint {
+    BleDataPoint { timestamp, temp, hum, device_id: TARGET.to_string(), model: "TP357", rssi: None, raw_data: Vec::new(), device_alarm: false, battery: None, persisted: false }
+}
+
+pub fn history_point(point: &BleDataPoint) -> HistoryPoint {
+    HistoryPoint { timestamp: point.timestamp, temp: point.temp, hum: point.hum, origin: PointOrigin::Persisted, rssi: point.rssi }
+}
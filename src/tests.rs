@@ -0,0 +1,533 @@
+// --- End-to-end pipeline tests: mock central -> scanner -> background processor -> GUI channel ---
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, watch};
+
+use crate::alerts::{temperature_alert, TempAlert};
+use crate::ble::scan_adapter;
+use crate::mock_ble::{MockCentral, ScriptedAdvertisement};
+use crate::model::{AppMessage, BleDataPoint, Config};
+use crate::paths;
+use crate::processor::background_data_processor;
+use crate::schedule::{self, PauseWindow};
+use crate::storage::load_history_range;
+use crate::test_support::*;
+
+/// Runs one scan over the scripted advertisements and returns the readings that reached the GUI
+/// together with the reported CSV write results.
+fn run_pipeline(config: Config, script: Vec<ScriptedAdvertisement>) -> (Vec<BleDataPoint>, Vec<bool>) {
+    let (scanner_tx, processor_rx) = mpsc::unbounded_channel();
+    let (gui_tx, mut gui_rx) = mpsc::unbounded_channel();
+    let (_shutdown_tx, shutdown_rx) = watch::channel(false);
+    let shared_config = Arc::new(Mutex::new(config.clone()));
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let processor = tokio::spawn(background_data_processor(processor_rx, gui_tx, shared_config, shutdown_rx));
+        scan_adapter(&MockCentral::new(script), &config, &scanner_tx).await;
+        drop(scanner_tx);
+        processor.await.unwrap();
+    });
+
+    let (mut points, mut writes) = (Vec::new(), Vec::new());
+    while let Ok(message) = gui_rx.try_recv() {
+        match message {
+            AppMessage::NewData(point) => points.push(point),
+            AppMessage::CsvWriteStatus(ok) => writes.push(ok),
+            AppMessage::StatusUpdate(_) | AppMessage::Alert(_) | AppMessage::AlertLogged(_) | AppMessage::AlarmSound(_) | AppMessage::Heartbeat | AppMessage::Control(_) | AppMessage::SensorSeen(_) | AppMessage::Captured(_) | AppMessage::Slept(_) | AppMessage::Paused(_) => {}
+        }
+    }
+    (points, writes)
+}
+
+#[test]
+fn decodes_target_advertisement_and_stores_it() {
+    let data_dir = TempDataDir::new("decode");
+    let (points, writes) = run_pipeline(test_config(), vec![ScriptedAdvertisement::tp357(TARGET, 23.4, 45)]);
+
+    assert_eq!(points.len(), 1);
+    assert!((points[0].temp - 23.4).abs() < 0.05);
+    assert_eq!(points[0].hum, 45);
+    assert_eq!(points[0].rssi, Some(-60));
+    assert_eq!(writes, vec![true]);
+
+    let rows = data_dir.csv_rows();
+    assert_eq!(rows.len(), 1);
+    assert!(rows[0].ends_with(",23.4,45"), "unexpected row {}", rows[0]);
+}
+
+#[test]
+fn decodes_negative_temperatures() {
+    let _data_dir = TempDataDir::new("negative");
+    let (points, _) = run_pipeline(test_config(), vec![ScriptedAdvertisement::tp357(TARGET, -12.7, 80)]);
+    assert_eq!(points.len(), 1);
+    assert!((points[0].temp + 12.7).abs() < 0.05);
+}
+
+#[test]
+fn ignores_other_devices() {
+    let data_dir = TempDataDir::new("other");
+    let (points, writes) = run_pipeline(test_config(), vec![ScriptedAdvertisement::tp357(OTHER, 21.0, 50), ScriptedAdvertisement::tp357(OTHER, 21.1, 50)]);
+    assert!(points.is_empty());
+    assert!(writes.is_empty());
+    assert!(data_dir.csv_rows().is_empty());
+}
+
+#[test]
+fn scan_diagnostics_count_advertisements_matches_and_parse_failures() {
+    use crate::scan_stats::{self, AdapterState};
+    let _data_dir = TempDataDir::new("scan_stats");
+    let unreadable = ScriptedAdvertisement { address: TARGET.to_string(), local_name: None, rssi: Some(-70), manufacturer_data: HashMap::new(), service_data: HashMap::new() };
+    let before = scan_stats::snapshot();
+    let (points, _) = run_pipeline(test_config(), vec![ScriptedAdvertisement::tp357(OTHER, 21.0, 50), unreadable, ScriptedAdvertisement::tp357(TARGET, 22.0, 50)]);
+    let after = scan_stats::snapshot();
+    assert_eq!(points.len(), 1);
+    // Other tests scan in parallel, so the shared counters only ever grow by at least this run's share
+    assert!(after.advertisements >= before.advertisements + 3 && after.matching >= before.matching + 2 && after.parse_failures > before.parse_failures);
+    assert!(after.scans > before.scans && after.scan_stopped.is_some());
+    assert_eq!(after.adapter_info.as_deref(), Some("mock adapter"));
+
+    let mut stats = scan_stats::ScanStats::default();
+    for decoded in [None, Some(false), Some(true), Some(true)] { stats.record_advertisement(decoded); }
+    assert_eq!((stats.advertisements, stats.matching, stats.parse_failures), (4, 3, 1));
+    assert_eq!(stats.decode_rate().map(|r| (r * 3.0).round()), Some(2.0));
+    stats.scan_started(chrono::Local::now());
+    assert_eq!(stats.adapter, AdapterState::Scanning);
+    stats.scan_stopped(chrono::Local::now());
+    assert_eq!(stats.adapter, AdapterState::Idle);
+}
+
+#[test]
+fn capture_logs_every_target_packet_with_its_company_id_and_rssi() {
+    use crate::capture::{self, CaptureView};
+    let _data_dir = TempDataDir::new("capture");
+    let config = Config { continuous_mode: true, ..test_config() };
+    capture::set_active(true);
+    run_pipeline(config, vec![ScriptedAdvertisement::tp357(TARGET, 23.4, 45), ScriptedAdvertisement::tp357(OTHER, 19.0, 60), ScriptedAdvertisement::tp357(TARGET, 23.5, 45)]);
+    capture::set_active(false);
+
+    let records = capture::read(&capture::capture_path());
+    assert_eq!(records.len(), 2, "only the target is captured");
+    assert_eq!((records[0].company_id, records[0].payload.clone(), records[0].rssi), (0xEAC2, vec![0x00, 45, 0x2C], Some(-60)));
+    // On air the company id comes first, little endian; 23.4 -> 23.5 °C only changes the temperature's low byte
+    assert_eq!(records[1].bytes(), vec![0xC2, 0xEB, 0x00, 45, 0x2C]);
+    assert_eq!(capture::changed(&records[0].bytes(), &records[1].bytes()), vec![false, true, false, false, false]);
+    assert_eq!(capture::changed(&[1, 2], &[1, 2, 3]), vec![false, false, true]);
+
+    let mut view = CaptureView::default();
+    view.resume((0..capture::VIEW_ROWS + 5).map(|i| capture::CaptureRecord { payload: vec![i as u8], ..records[0].clone() }).collect());
+    assert_eq!((view.records.len(), view.records[0].payload.clone()), (capture::VIEW_ROWS, vec![5]));
+}
+
+#[test]
+fn duplicate_threshold_drops_rapid_repeats() {
+    let data_dir = TempDataDir::new("dedup");
+    let script = vec![
+        ScriptedAdvertisement::tp357(TARGET, 22.0, 40),
+        ScriptedAdvertisement::tp357(OTHER, 30.0, 40),
+        ScriptedAdvertisement::tp357(TARGET, 22.1, 41),
+        ScriptedAdvertisement::tp357(TARGET, 22.2, 42),
+    ];
+    let (points, writes) = run_pipeline(test_config(), script);
+    assert_eq!(points.len(), 1);
+    assert_eq!(points[0].hum, 40);
+    assert_eq!(writes.len(), 1);
+    assert_eq!(data_dir.csv_rows().len(), 1);
+
+    let (points, _) = run_pipeline(Config { duplicate_threshold_secs: 0, ..test_config() }, vec![ScriptedAdvertisement::tp357(TARGET, 22.0, 40), ScriptedAdvertisement::tp357(TARGET, 22.1, 41)]);
+    assert_eq!(points.len(), 2);
+}
+
+#[test]
+fn value_changes_are_saved_within_the_duplicate_interval_when_enabled() {
+    let data_dir = TempDataDir::new("dedup_change");
+    let script = || vec![
+        ScriptedAdvertisement::tp357(TARGET, 22.0, 40),
+        ScriptedAdvertisement::tp357(TARGET, 22.3, 41),
+        // 2 °C jump: saved at once, and the next change is measured from it
+        ScriptedAdvertisement::tp357(TARGET, 24.0, 41),
+        ScriptedAdvertisement::tp357(TARGET, 23.6, 42),
+        ScriptedAdvertisement::tp357(TARGET, 23.5, 44),
+    ];
+    let (points, _) = run_pipeline(test_config(), script());
+    assert_eq!(points.len(), 1, "time gate alone keeps only the first reading");
+
+    let config = Config { duplicate_change_enabled: true, duplicate_change_temp: 0.5, duplicate_change_hum: 3, ..test_config() };
+    let (points, writes) = run_pipeline(config, script());
+    assert_eq!(points.iter().map(|p| (p.temp, p.hum)).collect::<Vec<_>>(), vec![(22.0, 40), (24.0, 41), (23.5, 44)]);
+    assert_eq!(writes, vec![true; 3]);
+    assert_eq!(data_dir.csv_rows().len(), 1 + 3, "plus the row of the time-gate run");
+
+    // Steps of 0.1 °C add up to exactly the threshold despite float rounding
+    let last = crate::processor::SavedReading { timestamp: chrono::Local::now(), temp: 22.1, hum: 40 };
+    let point = |temp: f32| BleDataPoint { temp, timestamp: last.timestamp, ..points[0].clone() };
+    let config = Config { duplicate_change_enabled: true, duplicate_change_temp: 0.5, ..test_config() };
+    assert!(crate::processor::is_new_reading(Some(&last), &point(22.6), &config));
+    assert!(!crate::processor::is_new_reading(Some(&last), &point(22.5), &config));
+}
+
+#[test]
+fn calibration_is_applied_before_storage() {
+    let _data_dir = TempDataDir::new("calibration");
+    let config = Config { temp_offset: -0.8, hum_offset: 3.0, ..test_config() };
+    let (points, _) = run_pipeline(config, vec![ScriptedAdvertisement::tp357(TARGET, 24.0, 98)]);
+    assert!((points[0].temp - 23.2).abs() < 0.05);
+    assert_eq!(points[0].hum, 100);
+
+    let now = chrono::Local::now();
+    let stored = load_history_range(now - chrono::Duration::hours(1), now + chrono::Duration::hours(1));
+    assert_eq!(stored.len(), 1);
+    assert!((stored[0].temp - 23.2).abs() < 0.05);
+    assert_eq!(stored[0].hum, 100);
+}
+
+#[test]
+fn alerts_follow_thresholds_and_pause_windows() {
+    let _data_dir = TempDataDir::new("alerts");
+    let config = test_config();
+    let (points, _) = run_pipeline(Config { duplicate_threshold_secs: 0, ..config.clone() }, vec![
+        ScriptedAdvertisement::tp357(TARGET, 31.5, 40),
+        ScriptedAdvertisement::tp357(TARGET, 20.0, 40),
+        ScriptedAdvertisement::tp357(TARGET, 4.0, 40),
+    ]);
+    let alerts: Vec<TempAlert> = points.iter().map(|p| temperature_alert(p.temp, &config, false)).collect();
+    assert_eq!(alerts, vec![TempAlert::High, TempAlert::Normal, TempAlert::Low]);
+    assert_eq!(temperature_alert(points[0].temp, &config, true), TempAlert::Normal);
+}
+
+#[test]
+fn pause_window_suppresses_logging_but_not_display() {
+    let data_dir = TempDataDir::new("pause");
+    let config = Config { pause_windows: vec![PauseWindow { label: "Always".into(), weekday: None, start: "00:00".into(), end: "00:00".into() }], ..test_config() };
+    let (points, writes) = run_pipeline(config.clone(), vec![ScriptedAdvertisement::tp357(TARGET, 25.0, 50)]);
+    assert_eq!(points.len(), 1);
+    assert!(writes.is_empty());
+    assert!(data_dir.csv_rows().is_empty());
+    // Recorded once, as it began (an end equal to the start is the whole day), and not again after a restart
+    run_pipeline(config.clone(), vec![ScriptedAdvertisement::tp357(TARGET, 25.1, 50)]);
+    let recorded = schedule::read(&schedule::pause_log_path());
+    assert_eq!(recorded, schedule::active_pause(&config.pause_windows, chrono::Local::now()).into_iter().collect::<Vec<_>>());
+    assert_eq!((recorded[0].label.as_str(), recorded[0].start.time()), ("Always", chrono::NaiveTime::MIN));
+}
+
+#[test]
+fn auto_detect_follows_first_thermopro_regardless_of_mac() {
+    let _data_dir = TempDataDir::new("autodetect");
+    let not_thermopro = ScriptedAdvertisement { address: TARGET.to_string(), local_name: None, rssi: Some(-50), manufacturer_data: HashMap::from([(0x004C, vec![0x02, 0x15, 0x00])]), service_data: HashMap::new() };
+    let config = Config { target_mac: String::new(), auto_detect_thermopro: true, duplicate_threshold_secs: 0, ..test_config() };
+    let (points, _) = run_pipeline(config, vec![
+        not_thermopro,
+        ScriptedAdvertisement::tp357(OTHER, 21.0, 50),
+        ScriptedAdvertisement::tp357(TARGET, 30.0, 40),
+        ScriptedAdvertisement::tp357(OTHER, 21.2, 51),
+    ]);
+    assert_eq!(points.len(), 2);
+    assert!(points.iter().all(|p| p.hum >= 50));
+}
+
+#[test]
+fn model_is_taken_from_the_advertised_name() {
+    let _data_dir = TempDataDir::new("models");
+    let config = Config { duplicate_threshold_secs: 0, ..test_config() };
+    let mut truncated = ScriptedAdvertisement::tp357(TARGET, 19.0, 55).named("TP359 (0F93)");
+    truncated.manufacturer_data.values_mut().for_each(|data| data.truncate(2));
+    let (points, _) = run_pipeline(config, vec![
+        ScriptedAdvertisement::tp357(TARGET, 20.5, 60).named("TP357S (0F93)"),
+        truncated,
+        ScriptedAdvertisement::tp357(TARGET, 21.0, 61).named("TP393 (0F93)"),
+        ScriptedAdvertisement::tp357(TARGET, 21.5, 62),
+    ]);
+    assert_eq!(points.iter().map(|p| p.model).collect::<Vec<_>>(), vec!["TP357S", "TP393", "TP357"]);
+    assert!((points[1].temp - 21.0).abs() < 0.05);
+}
+
+#[test]
+fn third_party_sensor_families_are_decoded() {
+    let _data_dir = TempDataDir::new("families");
+    let config = Config { duplicate_threshold_secs: 0, ..test_config() };
+    let advertisement = |manufacturer_data: HashMap<u16, Vec<u8>>, service_data: HashMap<u16, Vec<u8>>| ScriptedAdvertisement { address: TARGET.to_string(), local_name: None, rssi: Some(-70), manufacturer_data, service_data };
+    let (points, _) = run_pipeline(config, vec![
+        advertisement(HashMap::from([(0xEC88, vec![0x00, 0x03, 0x93, 0xD8, 0x64, 0x00])]), HashMap::new()),
+        advertisement(HashMap::new(), HashMap::from([(0x181A, vec![0xA4, 0xC1, 0x38, 0x00, 0x00, 0x01, 0x00, 0xEA, 45, 0x64, 0x0B, 0xB8, 0x01])])),
+        advertisement(HashMap::new(), HashMap::from([(0xFD3D, vec![0x54, 0x00, 0x64, 0x04, 0x80 | 23, 45])])),
+    ]);
+    assert_eq!(points.iter().map(|p| p.model).collect::<Vec<_>>(), vec!["H5075", "LYWSD03MMC", "SwitchBot Meter"]);
+    for point in &points {
+        assert!((point.temp - 23.4).abs() < 0.05, "{} decoded {}", point.model, point.temp);
+        assert!(point.hum == 45 || point.hum == 46, "{} decoded {} %", point.model, point.hum);
+        assert_eq!(point.battery, Some(100), "{} battery", point.model);
+    }
+}
+
+#[test]
+fn alert_history_round_trips_through_export_and_import() {
+    let data_dir = TempDataDir::new("alert_log");
+    let (points, _) = run_pipeline(Config { duplicate_threshold_secs: 0, ..test_config() }, vec![ScriptedAdvertisement::tp357(TARGET, 31.5, 40), ScriptedAdvertisement::tp357(TARGET, 20.0, 40)]);
+    assert_eq!(points.len(), 2);
+    let now = chrono::Local::now();
+    let alerts = crate::alert_log::load_range(now - chrono::Duration::hours(1), now + chrono::Duration::hours(1));
+    assert_eq!(alerts.iter().map(|a| a.event.as_str()).collect::<Vec<_>>(), vec!["temperature_high", "temperature_normal"]);
+
+    let exported = data_dir.dir.join("export.alerts.csv");
+    crate::alert_log::write_csv(&exported, &alerts).unwrap();
+    std::fs::remove_file(crate::alert_log::alert_log_path()).unwrap();
+    assert_eq!(crate::alert_log::import_csv(&exported).unwrap(), (2, 0));
+    assert_eq!(crate::alert_log::import_csv(&exported).unwrap(), (0, 2));
+}
+
+#[test]
+fn edits_rewrite_the_log_and_leave_an_audit_trail() {
+    use crate::edit::{self, PointEdit};
+    let data_dir = TempDataDir::new("edit");
+    let (points, _) = run_pipeline(Config { duplicate_threshold_secs: 0, ..test_config() }, vec![ScriptedAdvertisement::tp357(TARGET, 22.0, 40), ScriptedAdvertisement::tp357(TARGET, -40.0, 40), ScriptedAdvertisement::tp357(TARGET, 22.2, 41)]);
+    assert_eq!(points.len(), 3);
+
+    assert_eq!(edit::apply(&[history_point(&points[1])], PointEdit::Delete, "corrupt advertisement").unwrap(), 1);
+    let rows = data_dir.csv_rows();
+    assert_eq!(rows.len(), 2);
+    assert!(rows.iter().all(|r| !r.contains("-40.0")), "spike still logged: {:?}", rows);
+
+    assert_eq!(edit::apply(&[history_point(&points[2])], PointEdit::Correct { temp: 22.1, hum: 40 }, "").unwrap(), 1);
+    assert!(data_dir.csv_rows().iter().any(|r| r.ends_with(",22.1,40")));
+
+    let audit = std::fs::read_to_string(edit::edit_log_path()).unwrap();
+    let lines: Vec<&str> = audit.lines().skip(1).collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].contains(",delete,-40.0,40,,,corrupt advertisement"), "unexpected audit row {}", lines[0]);
+    assert!(lines[1].contains(",correct,22.2,41,22.1,40,"), "unexpected audit row {}", lines[1]);
+}
+
+#[test]
+fn spike_filter_drops_outliers_but_follows_real_steps() {
+    let data_dir = TempDataDir::new("spike");
+    let config = Config { duplicate_threshold_secs: 0, spike_filter_enabled: true, ..test_config() };
+    let script = [22.0, 22.1, 22.0, 0.0, 22.2].iter().map(|t| ScriptedAdvertisement::tp357(TARGET, *t, if *t == 0.0 { 0 } else { 45 })).collect();
+    let (points, _) = run_pipeline(config.clone(), script);
+    assert_eq!(points.iter().map(|p| p.temp).collect::<Vec<_>>(), vec![22.0, 22.1, 22.0, 22.2]);
+    assert_eq!(data_dir.csv_rows().len(), 1 + 3, "plus the row of the time-gate run");
+
+    // A level that holds is accepted after a few readings
+    let script = [22.0, 22.1, 22.0, 30.0, 30.1, 30.0, 30.2].iter().map(|t| ScriptedAdvertisement::tp357(TARGET, *t, 45)).collect();
+    let (points, _) = run_pipeline(config, script);
+    assert_eq!(points.iter().map(|p| p.temp).collect::<Vec<_>>(), vec![22.0, 22.1, 22.0, 30.0, 30.2]);
+}
+
+#[test]
+fn log_timestamps_carry_their_offset_and_old_rows_still_load() {
+    use chrono::{TimeZone, Utc};
+    let data_dir = TempDataDir::new("rfc3339");
+    run_pipeline(test_config(), vec![ScriptedAdvertisement::tp357(TARGET, 21.0, 50)]);
+    let row = data_dir.csv_rows().remove(0);
+    let stamp = row.split(',').next().unwrap();
+    assert!(chrono::DateTime::parse_from_rfc3339(stamp).is_ok(), "not RFC 3339: {}", row);
+
+    let utc = crate::storage::parse_log_timestamp("2025-03-30T00:30:00+00:00").unwrap();
+    assert_eq!(utc.with_timezone(&Utc), Utc.with_ymd_and_hms(2025, 3, 30, 0, 30, 0).unwrap());
+    let record = csv::StringRecord::from(vec!["2025-11-26T14:23:45", "23.4", "45"]);
+    let legacy = crate::storage::parse_history_record(&record).unwrap();
+    assert_eq!(legacy.timestamp.naive_local().to_string(), "2025-11-26 14:23:45");
+}
+
+#[test]
+fn torn_writes_are_isolated_and_repaired() {
+    use crate::integrity;
+    let data_dir = TempDataDir::new("integrity");
+    let path = paths::log_path(chrono::Local::now().date_naive());
+    std::fs::write(&path, "DateTime,Temperature,Humidity\n2025-11-26T14:23:45+01:00,23.4,45\n2025-11-26T14:24:4").unwrap();
+    run_pipeline(test_config(), vec![ScriptedAdvertisement::tp357(TARGET, 21.0, 50)]);
+    let rows = data_dir.csv_rows();
+    assert_eq!(rows.len(), 3, "new row glued onto the torn one: {:?}", rows);
+    assert!(rows[2].ends_with(",21.0,50"));
+
+    let damaged = integrity::check_logs(None);
+    assert_eq!(damaged.len(), 1);
+    assert_eq!(damaged[0].bad_lines, vec![(3, "2025-11-26T14:24:4".to_string())]);
+    assert_eq!(integrity::repair(&damaged[0]).unwrap(), 1);
+    assert_eq!(data_dir.csv_rows().len(), 2);
+    assert!(integrity::check_logs(None).is_empty());
+    assert!(path.with_extension("corrupt.txt").exists());
+}
+
+#[test]
+fn unconfigured_thermopro_is_offered_after_repeated_scans() {
+    use crate::sensors::{self, NewSensorTracker, SensorEntry};
+    let config = test_config();
+    let script = vec![ScriptedAdvertisement::tp357(OTHER, 21.0, 50).named("TP357 (0F55)"), ScriptedAdvertisement::tp357(OTHER, 21.1, 50), ScriptedAdvertisement::tp357(TARGET, 22.0, 40)];
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    tokio::runtime::Runtime::new().unwrap().block_on(scan_adapter(&MockCentral::new(script), &config, &tx));
+    let sightings: Vec<_> = std::iter::from_fn(|| rx.try_recv().ok()).filter_map(|m| if let AppMessage::SensorSeen(s) = m { Some(s) } else { None }).collect();
+    // Reported once per scan, and never for the target
+    assert_eq!(sightings.len(), 1);
+    assert_eq!((sightings[0].address.as_str(), sightings[0].model), (OTHER, "TP357"));
+
+    let mut tracker = NewSensorTracker::default();
+    let prompts: Vec<bool> = (0..sensors::SIGHTINGS_BEFORE_PROMPT).map(|_| tracker.record(sightings[0].clone(), &config).is_some()).collect();
+    assert_eq!(prompts, vec![false, false, true]);
+    let accepted = Config { sensors: vec![SensorEntry::new(&sightings[0])], ..config.clone() };
+    assert_eq!(accepted.sensors[0].name, "TP357 55:66");
+    assert!(tracker.record(sightings[0].clone(), &accepted).is_none());
+    tracker.snooze(OTHER);
+    assert!(tracker.record(sightings[0].clone(), &config).is_none());
+}
+
+#[test]
+fn opened_data_folder_receives_new_readings() {
+    let home = TempDataDir::new("profile_home");
+    run_pipeline(test_config(), vec![ScriptedAdvertisement::tp357(TARGET, 21.0, 40)]);
+    let cabin = home.dir.with_file_name(format!("{}_cabin", home.dir.file_name().unwrap().to_string_lossy()));
+    std::fs::create_dir_all(&cabin).unwrap();
+    paths::set_data_dir_override(Some(cabin.clone()));
+    run_pipeline(test_config(), vec![ScriptedAdvertisement::tp357(TARGET, 5.0, 70)]);
+    let today = chrono::Local::now().date_naive();
+    assert_eq!(load_history_range(local(today, 0, 0), chrono::Local::now()).iter().map(|p| p.temp).collect::<Vec<_>>(), vec![5.0]);
+    assert!(std::fs::read_to_string(paths::log_path_in(&home.dir, today)).unwrap().contains(",21.0,40"));
+    assert_ne!(paths::default_data_dir(), cabin);
+    let _ = std::fs::remove_dir_all(&cabin);
+}
+
+#[test]
+fn replay_feeds_a_recording_through_the_processor_in_recorded_time() {
+    use crate::rules::AlertRule;
+    use crate::{cli, replay};
+    use crate::storage::format_log_timestamp;
+    use chrono::{Duration as ChronoDuration, Timelike};
+    let data_dir = TempDataDir::new("replay");
+    // Ten minutes every 10 s, crossing 30 °C after five
+    let start = chrono::Local::now().with_nanosecond(0).unwrap() - ChronoDuration::hours(3);
+    let rows: String = (0..60).map(|i| format!("{},{:.1},50\n", format_log_timestamp(start + ChronoDuration::seconds(i * 10)), if i < 30 { 25.0 } else { 31.0 })).collect();
+    let source = data_dir.dir.join("recording.csv");
+    std::fs::write(&source, format!("DateTime,Temperature,Humidity\n{}", rows)).unwrap();
+    let args = cli::parse(["--replay".to_string(), source.display().to_string(), "--speed=0".to_string()]).unwrap();
+    assert_eq!((args.replay_speed(), args.data_dir_override()), (0.0, Some(replay::scratch_dir())));
+    assert!(cli::parse(["--speed".to_string(), "10".to_string()]).is_err());
+    assert_eq!(replay::delays(&replay::load(&source)[..3], 5.0), vec![std::time::Duration::ZERO, std::time::Duration::from_secs(2), std::time::Duration::from_secs(2)]);
+
+    let config = Config { alert_rules: vec![AlertRule { threshold: 30.0, duration_mins: 2, ..AlertRule::default() }], ..test_config() };
+    let (scanner_tx, processor_rx) = mpsc::unbounded_channel();
+    let (gui_tx, mut gui_rx) = mpsc::unbounded_channel();
+    let (_shutdown_tx, shutdown_rx) = watch::channel(false);
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let processor = tokio::spawn(background_data_processor(processor_rx, gui_tx, Arc::new(Mutex::new(config)), shutdown_rx.clone()));
+        replay::run(source.clone(), args.replay_speed(), scanner_tx, shutdown_rx).await;
+        processor.await.unwrap();
+    });
+    let (mut points, mut alerts) = (Vec::new(), Vec::new());
+    while let Ok(message) = gui_rx.try_recv() {
+        match message { AppMessage::NewData(point) => points.push(point), AppMessage::AlertLogged(record) => alerts.push(record), _ => {} }
+    }
+    // The duplicate interval and the hold time count in recorded time, not in how fast the rows arrived
+    assert_eq!(points.len(), 20);
+    assert_eq!(points[1].timestamp, start + ChronoDuration::seconds(30));
+    assert_eq!(alerts.iter().map(|a| (a.event.as_str(), a.timestamp)).collect::<Vec<_>>(), vec![("temperature_high", start + ChronoDuration::seconds(420))]);
+    // Logged at the recorded times as well, not when the rows were replayed
+    let logged = load_history_range(start, start + ChronoDuration::minutes(10));
+    assert_eq!(logged.iter().map(|p| p.timestamp).collect::<Vec<_>>(), points.iter().map(|p| p.timestamp).collect::<Vec<_>>());
+}
+
+#[test]
+fn simulated_sensor_follows_its_wave_and_feeds_the_processor() {
+    use crate::simulator::{self, Simulator, SimulatorSettings, SIMULATED_DEVICE};
+    use crate::cli;
+    use chrono::Duration as ChronoDuration;
+    let start = chrono::Local::now();
+    let quiet = SimulatorSettings { temp_noise: 0.0, hum_noise: 0.0, ..SimulatorSettings::default() };
+    // A quarter period in: warmest and driest
+    assert_eq!(Simulator::new(quiet.clone(), start).reading(start + ChronoDuration::minutes(15)), (25.0, 40));
+    assert_eq!(Simulator::new(quiet, start).reading(start + ChronoDuration::minutes(45)), (19.0, 60));
+    let noisy = |seed| { let mut s = Simulator::new(SimulatorSettings { seed, ..SimulatorSettings::default() }, start); (0..50).map(|i| s.reading(start + ChronoDuration::minutes(i))).collect::<Vec<_>>() };
+    assert_eq!(noisy(7), noisy(7));
+    assert_ne!(noisy(7), noisy(8));
+    assert!(noisy(7).iter().all(|(temp, hum)| (18.8..=25.2).contains(temp) && (39..=61).contains(hum)));
+    let args = cli::parse(["--simulate".to_string()]).unwrap();
+    assert_eq!(args.data_dir_override(), Some(simulator::scratch_dir()));
+    assert!(cli::parse(["--simulate", "--replay", "day.csv"].map(String::from)).is_err());
+
+    let _data_dir = TempDataDir::new("simulate");
+    let config = Config { duplicate_threshold_secs: 0, temp_offset: -1.0, simulator: SimulatorSettings { interval_secs: 0.01, ..SimulatorSettings::default() }, ..test_config() };
+    let (source_tx, processor_rx) = mpsc::unbounded_channel();
+    let (gui_tx, mut gui_rx) = mpsc::unbounded_channel();
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let shared_config = Arc::new(Mutex::new(config));
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let processor = tokio::spawn(background_data_processor(processor_rx, gui_tx, shared_config.clone(), shutdown_rx.clone()));
+        let source = tokio::spawn(simulator::run(source_tx, shared_config, shutdown_rx));
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        shutdown_tx.send(true).unwrap();
+        source.await.unwrap();
+        processor.await.unwrap();
+    });
+    let points: Vec<BleDataPoint> = std::iter::from_fn(|| gui_rx.try_recv().ok()).filter_map(|m| if let AppMessage::NewData(p) = m { Some(p) } else { None }).collect();
+    assert!(points.len() >= 5, "{}", points.len());
+    // Calibration applies to simulated readings too
+    assert!(points.iter().all(|p| p.device_id == SIMULATED_DEVICE && p.persisted && (17.8..=24.2).contains(&p.temp)));
+}
+
+#[test]
+fn processor_joins_its_workers_without_waiting_out_their_retries() {
+    use crate::rules::AlertRule;
+    use crate::sink_stats::{self, Sink};
+    let _data_dir = TempDataDir::new("workers");
+    // Nothing listens on port 1, so both sinks fail at once and would otherwise retry for minutes
+    let config = Config { influx_enabled: true, influx_url: "http://127.0.0.1:1".to_string(), webhook_enabled: true, webhook_url: "http://127.0.0.1:1/hook".to_string(), alert_rules: vec![AlertRule::default()], ..test_config() };
+    let failures = || (sink_stats::snapshot(Sink::Influx).failures, sink_stats::snapshot(Sink::Webhook).failures);
+    let (influx_before, webhook_before) = failures();
+    let started = std::time::Instant::now();
+    let (points, _) = run_pipeline(config, vec![ScriptedAdvertisement::tp357(TARGET, 31.0, 50)]);
+    assert_eq!(points.len(), 1);
+    assert!(started.elapsed() < std::time::Duration::from_secs(4), "{:?}", started.elapsed());
+    // Both made their attempt before the processor returned
+    let (influx_after, webhook_after) = failures();
+    assert!(influx_after > influx_before && webhook_after > webhook_before, "{:?}", failures());
+}
+
+#[test]
+fn losing_the_radio_mid_scan_ends_the_cycle_and_backs_off() {
+    use crate::ble::{retry_delay, RETRY_MAX_SECS};
+    let script = vec![ScriptedAdvertisement::tp357(TARGET, 21.5, 40), ScriptedAdvertisement::tp357(TARGET, 21.6, 41), ScriptedAdvertisement::tp357(TARGET, 21.7, 42)];
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let outcome = tokio::runtime::Runtime::new().unwrap().block_on(scan_adapter(&MockCentral::new(script).radio_off_after(1), &test_config(), &tx));
+    assert!(outcome.adapter_lost);
+    // Only the advertisement before the radio went off was read
+    assert_eq!(outcome.last_reading.map(|(_, temp, hum)| (temp, hum)), Some((21.5, 40)));
+    assert_eq!(std::iter::from_fn(|| rx.try_recv().ok()).filter(|m| matches!(m, AppMessage::NewData(_))).count(), 1);
+
+    let steady = tokio::runtime::Runtime::new().unwrap().block_on(scan_adapter(&MockCentral::new(vec![ScriptedAdvertisement::tp357(TARGET, 21.5, 40)]), &test_config(), &tx));
+    assert!(!steady.adapter_lost);
+    // 5, 10, 20 ... seconds, capped at five minutes
+    assert_eq!([1, 2, 3, 6, 7, 40].map(|failures| retry_delay(failures).as_secs()), [5, 10, 20, 160, RETRY_MAX_SECS, RETRY_MAX_SECS]);
+}
+
+#[test]
+fn virtual_sensor_combines_paired_readings_logs_them_and_raises_alerts_under_its_name() {
+    use crate::rules::AlertRule;
+    use crate::storage::format_log_timestamp;
+    use crate::virtual_sensor::{combine, Aggregate, VirtualSensor};
+    use chrono::Duration as ChronoDuration;
+    let start = chrono::Local::now() - ChronoDuration::hours(1);
+    let at = |secs, temp, hum| point(start + ChronoDuration::seconds(secs), temp, hum);
+    let series = vec![vec![at(0, 20.0, 40), at(60, 21.0, 41), at(120, 22.0, 42)], vec![at(5, 24.0, 50), at(125, 26.0, 51)], vec![at(-3, 19.0, 45), at(58, 19.5, 45), at(119, 20.0, 45)]];
+    let combined = |aggregate| combine(&series, aggregate, ChronoDuration::seconds(10)).iter().map(|p| (p.timestamp, p.temp, p.hum)).collect::<Vec<_>>();
+    // The first series sets the times; its reading at 60 s has no partner in the second and is left out
+    assert_eq!(combined(Aggregate::Mean), vec![(start, 21.0, 45), (start + ChronoDuration::seconds(120), 22.666666, 46)]);
+    assert_eq!(combined(Aggregate::Min), vec![(start, 19.0, 40), (start + ChronoDuration::seconds(120), 20.0, 42)]);
+    assert_eq!(combined(Aggregate::Max).iter().map(|p| p.1).collect::<Vec<_>>(), vec![24.0, 26.0]);
+    assert!(combine(&[], Aggregate::Mean, ChronoDuration::seconds(10)).is_empty());
+
+    // A second sensor logged by another copy of the app, 5 s before this one's reading
+    let data_dir = TempDataDir::new("virtual");
+    let upstairs = data_dir.dir.join("upstairs");
+    std::fs::create_dir_all(&upstairs).unwrap();
+    let now = chrono::Local::now();
+    std::fs::write(paths::log_path_in(&upstairs, now.date_naive()), format!("DateTime,Temperature,Humidity\n{},33.0,60\n", format_log_timestamp(now - ChronoDuration::seconds(5)))).unwrap();
+    let sensor = VirtualSensor { name: "House max".to_string(), folders: vec![String::new(), upstairs.display().to_string()], aggregate: Aggregate::Max, alerts: true };
+    let config = Config { virtual_sensors: vec![sensor.clone()], alert_rules: vec![AlertRule::default()], ..test_config() };
+    run_pipeline(config, vec![ScriptedAdvertisement::tp357(TARGET, 23.0, 50)]);
+    let logged = crate::storage::load_history_range_in(&sensor.folder(), now - ChronoDuration::minutes(1), chrono::Local::now());
+    assert_eq!(logged.iter().map(|p| (p.temp, p.hum)).collect::<Vec<_>>(), vec![(33.0, 60)]);
+    // This sensor's 23 °C raises nothing; the virtual maximum does, under the virtual sensor's name
+    let alerts = crate::alert_log::load_range(now - ChronoDuration::minutes(1), chrono::Local::now() + ChronoDuration::minutes(1));
+    assert_eq!(alerts.iter().map(|a| (a.event.as_str(), a.temperature)).collect::<Vec<_>>(), vec![("temperature_high", Some(33.0))]);
+    assert!(alerts[0].message.contains("House max"), "{}", alerts[0].message);
+}
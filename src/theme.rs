@@ -0,0 +1,62 @@
+// --- Window theme: dark (the original look), light or following the system, with an optional accent color ---
+
+use eframe::egui::{self, Color32, Visuals};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ThemeMode {
+    #[default]
+    Dark,
+    Light,
+    /// Dark or light as the operating system is set; dark where the system does not say
+    System,
+}
+
+impl ThemeMode {
+    pub const ALL: [ThemeMode; 3] = [ThemeMode::Dark, ThemeMode::Light, ThemeMode::System];
+
+    pub fn label(&self) -> &'static str {
+        match self { ThemeMode::Dark => "Dark", ThemeMode::Light => "Light", ThemeMode::System => "Follow the system" }
+    }
+}
+
+/// What the visuals were built from; they are rebuilt only when this changes.
+pub type ThemeKey = (ThemeMode, Option<[u8; 3]>, Option<eframe::Theme>);
+
+pub fn visuals(mode: ThemeMode, accent: Option<[u8; 3]>, system: Option<eframe::Theme>) -> Visuals {
+    let dark = match mode { ThemeMode::Dark => true, ThemeMode::Light => false, ThemeMode::System => system != Some(eframe::Theme::Light) };
+    let mut visuals = if dark { Visuals::dark() } else { Visuals::light() };
+    // Slightly translucent windows over the plots
+    if dark { visuals.window_fill = Color32::from_rgba_unmultiplied(20, 20, 20, 240); }
+    if let Some([r, g, b]) = accent {
+        let accent = Color32::from_rgb(r, g, b);
+        visuals.selection.bg_fill = accent;
+        visuals.selection.stroke = egui::Stroke::new(1.0, contrast_text(accent));
+        visuals.hyperlink_color = accent;
+        visuals.widgets.hovered.bg_stroke.color = accent;
+    }
+    visuals
+}
+
+// Black or white, whichever reads better on `background`
+fn contrast_text(background: Color32) -> Color32 {
+    let luminance = 0.299 * background.r() as f32 + 0.587 * background.g() as f32 + 0.114 * background.b() as f32;
+    if luminance > 140.0 { Color32::BLACK } else { Color32::WHITE }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn theme_follows_the_setting_or_the_system_and_applies_the_accent() {
+        assert!(visuals(ThemeMode::Dark, None, Some(eframe::Theme::Light)).dark_mode);
+        assert!(!visuals(ThemeMode::Light, None, None).dark_mode);
+        assert!(!visuals(ThemeMode::System, None, Some(eframe::Theme::Light)).dark_mode);
+        // Without a system theme (e.g. some Linux desktops) the original dark look stays
+        assert!(visuals(ThemeMode::System, None, None).dark_mode);
+        let accented = visuals(ThemeMode::Light, Some([230, 120, 0]), None);
+        assert_eq!(accented.selection.bg_fill, Color32::from_rgb(230, 120, 0));
+        assert_eq!(accented.hyperlink_color, Color32::from_rgb(230, 120, 0));
+    }
+}
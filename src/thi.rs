@@ -0,0 +1,55 @@
+// --- Temperature-humidity index (THI) with per-species stress bands for animal enclosures ---
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Species {
+    #[default]
+    Livestock,
+    Dog,
+    Rabbit,
+    Poultry,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Stress { None, Mild, Moderate, Severe }
+
+impl Stress {
+    pub fn label(&self) -> &'static str {
+        match self { Stress::None => "No heat stress", Stress::Mild => "Mild heat stress", Stress::Moderate => "Moderate heat stress", Stress::Severe => "Severe heat stress" }
+    }
+}
+
+impl Species {
+    pub const ALL: [Species; 4] = [Species::Livestock, Species::Dog, Species::Rabbit, Species::Poultry];
+
+    pub fn label(&self) -> &'static str {
+        match self { Species::Livestock => "Livestock (cattle, pigs)", Species::Dog => "Dogs", Species::Rabbit => "Rabbits", Species::Poultry => "Poultry" }
+    }
+
+    /// Lower edges of the Mild, Moderate and Severe bands, on this species' THI scale.
+    pub fn bands(&self) -> [f32; 3] {
+        match self {
+            Species::Livestock => [72.0, 79.0, 89.0],
+            // No dog-specific index exists; brachycephalic and long-coated breeds need the lower end
+            Species::Dog => [70.0, 75.0, 80.0],
+            // Marai et al. scale, °C-based
+            Species::Rabbit => [27.8, 28.9, 30.0],
+            Species::Poultry => [70.0, 75.0, 80.0],
+        }
+    }
+
+    /// NRC (1971) index for most species; rabbits use the °C-based index of Marai et al. (2001).
+    pub fn thi(&self, temp: f32, hum: u8) -> f32 {
+        let rh = hum as f32 / 100.0;
+        match self {
+            Species::Rabbit => temp - (0.31 - 0.31 * rh) * (temp - 14.4),
+            _ => (1.8 * temp + 32.0) - (0.55 - 0.55 * rh) * (1.8 * temp - 26.0),
+        }
+    }
+
+    pub fn stress(&self, thi: f32) -> Stress {
+        let [mild, moderate, severe] = self.bands();
+        if thi >= severe { Stress::Severe } else if thi >= moderate { Stress::Moderate } else if thi >= mild { Stress::Mild } else { Stress::None }
+    }
+}
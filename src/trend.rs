@@ -0,0 +1,71 @@
+// --- Trend: rate of change over the last minutes, as a value per hour and an arrow ---
+
+use chrono::{DateTime, Duration as ChronoDuration, Local};
+
+/// Rates within these bands (per hour) count as steady; sensor steps are 0.1 °C and 1 %.
+pub const STEADY_TEMP_PER_HOUR: f64 = 0.3;
+pub const STEADY_HUM_PER_HOUR: f64 = 2.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Trend { Rising, Steady, Falling }
+
+impl Trend {
+    pub fn classify(per_hour: f64, steady_band: f64) -> Trend {
+        if per_hour > steady_band { Trend::Rising } else if per_hour < -steady_band { Trend::Falling } else { Trend::Steady }
+    }
+
+    pub fn arrow(&self) -> &'static str { match self { Trend::Rising => "↑", Trend::Steady => "→", Trend::Falling => "↓" } }
+}
+
+/// Least-squares line through the samples within `window` of the newest one, as (value at the newest
+/// sample, change per hour). `None` until the samples span at least half the window, so a few readings
+/// after startup don't show a wild rate.
+pub fn fit(samples: impl DoubleEndedIterator<Item = (DateTime<Local>, f64)>, window: ChronoDuration) -> Option<(f64, f64)> {
+    let mut samples = samples.rev().peekable();
+    let newest = samples.peek()?.0;
+    let recent: Vec<(f64, f64)> = samples.take_while(|(t, _)| newest - *t <= window).map(|(t, v)| ((t - newest).num_milliseconds() as f64 / 3_600_000.0, v)).collect();
+    let oldest = recent.last()?.0;
+    if recent.len() < 3 || -oldest * 2.0 < window.num_milliseconds() as f64 / 3_600_000.0 { return None; }
+    let n = recent.len() as f64;
+    let (mean_t, mean_v) = (recent.iter().map(|(t, _)| t).sum::<f64>() / n, recent.iter().map(|(_, v)| v).sum::<f64>() / n);
+    let (covariance, variance) = recent.iter().fold((0.0, 0.0), |(c, v), (t, value)| (c + (t - mean_t) * (value - mean_v), v + (t - mean_t).powi(2)));
+    let slope = (variance > 0.0).then(|| covariance / variance)?;
+    // Times are hours relative to the newest sample, so the intercept is the fitted value there
+    Some((mean_v - slope * mean_t, slope))
+}
+
+/// Change per hour of the `fit` line.
+pub fn rate_per_hour(samples: impl DoubleEndedIterator<Item = (DateTime<Local>, f64)>, window: ChronoDuration) -> Option<f64> { fit(samples, window).map(|(_, slope)| slope) }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+
+    #[test]
+    fn trend_rate_needs_half_the_window_and_drives_rate_of_change_rules() {
+        use crate::model::Config;
+        use crate::rules::{AlertRule, Metric};
+        use crate::webhook::{AlertKind, AlertTracker};
+        let start = Local::now();
+        let window = ChronoDuration::minutes(30);
+        // Rising 0.1 °C a minute is 6 °C/h, whatever happened before the window
+        let samples: Vec<_> = (0..=60).map(|m| (start + ChronoDuration::minutes(m), if m < 30 { 20.0 } else { 20.0 + (m - 30) as f64 * 0.1 })).collect();
+        let rate = rate_per_hour(samples.iter().copied(), window).unwrap();
+        assert!((rate - 6.0).abs() < 1e-6, "{}", rate);
+        assert_eq!(Trend::classify(rate, 0.3).arrow(), "↑");
+        assert_eq!(Trend::classify(-0.2, 0.3), Trend::Steady);
+        assert_eq!(rate_per_hour(samples[..10].iter().copied(), window), None);
+
+        let at = |mins, temp| reading(start + ChronoDuration::minutes(mins), temp, 40);
+        let config = Config { alert_rules: vec![AlertRule { metric: Metric::TemperatureRate, threshold: 2.0, ..AlertRule::default() }], trend_window_mins: 30, ..test_config() };
+        let mut tracker = AlertTracker::default();
+        let mut fired = Vec::new();
+        for m in 0..=20 { fired.extend(tracker.on_reading(&at(m, 20.0 + m as f32 * 0.1), &config, false)); }
+        // Nothing until 15 minutes (half the window) of readings exist
+        assert_eq!(fired.iter().map(|e| (e.kind, e.timestamp)).collect::<Vec<_>>(), vec![(AlertKind::RuleTriggered, start + ChronoDuration::minutes(15))]);
+        assert_eq!(fired[0].message, "Temperature change above 2.0 °C/h: 6.0 °C/h");
+        let steady = (21..=60).flat_map(|m| tracker.on_reading(&at(m, 22.0), &config, false)).collect::<Vec<_>>();
+        assert_eq!(steady.first().map(|e| e.kind), Some(AlertKind::RuleCleared));
+    }
+}
@@ -0,0 +1,2235 @@
+// --- GUI: the app state, the main window and its tool windows ---
+
+use chrono::{DateTime, Datelike, Local};
+use eframe::egui;
+use egui_extras::{StripBuilder, Size};
+use egui_plot::PlotMemory;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use tokio::sync::{mpsc as async_mpsc, watch};
+use std::thread;
+use std::time::{Duration, Instant};
+use log::{info, warn, error, debug};
+
+mod panels;
+pub(crate) mod plots;
+
+use crate::aggregate::{self, PlotCache, Resolution, Summary};
+use crate::alarm::{self, SoundAlarm};
+use crate::alert_log::{self, AlertRecord};
+use crate::alerts::offline_after;
+use crate::ble::{expected_reading_interval_secs, scan_settings_changed};
+use crate::bookmarks::{self, PlotBookmark};
+use crate::calendar::{DateFormat, WeekStart};
+use crate::capture::{self, CaptureView};
+use crate::commands::{self, Command, CommandPalette};
+use crate::data_table::{DataTable, TableAction};
+use crate::dehumidifier::{self, DailyHumidityExcess};
+use crate::distribution::DistributionView;
+use crate::edit::{self, EditDialog, PointEdit};
+use crate::export::{self, ExportFormat};
+use crate::forecast::ForecastMethod;
+use crate::gradient::{self, GradientPoint};
+use crate::heatmap::CalendarHeatmap;
+use crate::history::{self, HistoryEviction, PlotSeries, PointOrigin};
+use crate::history_loader::HistoryLoader;
+use crate::i18n::{self, tr, trf, Language};
+use crate::import::{ImportJob, ImportState};
+use crate::keep_awake::{KeepAwake, KeepAwakeMode};
+use crate::layout::{self, WindowGeometry};
+use crate::link::PacketLog;
+use crate::migration::{self, MigrationItem};
+use crate::model::{AppMessage, BleDataPoint, Config, HistoryPoint, ScanControl};
+use crate::notes::{self, Note};
+use crate::overlay::DayOverlay;
+use crate::palette::{ColorScale, Palette};
+use crate::paths::{self, StorageMode};
+use crate::plot_image::{self, ImageSize};
+use crate::processor::{background_data_processor, SHUTDOWN_GRACE};
+use crate::repaint::{self, RepaintMode};
+use crate::report::{ColdChainReport, ReportPeriod};
+use crate::retention::{self, StorageUsage};
+use crate::scan_stats;
+use crate::schedule::{self, PauseInterval};
+use crate::selftest::{self, CheckResult, CheckStatus, Fix};
+use crate::sensors::{self, NewSensorTracker, SensorEntry, SensorSighting};
+use crate::share::{self, ShareServer};
+use crate::sink_stats::{self, Sink};
+use crate::sleep::{self, SleepPeriod};
+use crate::storage::{load_config, load_history_range, load_history_range_in, save_config};
+use crate::sun::NightShading;
+use crate::theme::{self, ThemeKey, ThemeMode};
+use crate::thi::Species;
+use crate::units::Channel;
+use crate::virtual_sensor::{Aggregate, VirtualSensor};
+use crate::webhook::{self, AlertKind};
+use crate::{autostart, cli, decoder, email, evidence, integrity, logging, power, profiles, quick_export, spawn_reading_source, telegram, templates};
+use panels::{draw_comfort_badges, draw_data_details, draw_heartbeat, draw_humidity_info, draw_link_stats, draw_palette_preview, draw_scan_metadata, draw_temperature_info, edit_alert_rules, edit_schedule_windows};
+use plots::{DAILY_PLOT_AFTER_DAYS, draw_combined_graph, draw_crosshair_readout, draw_humidity_graph, draw_rssi_graph, draw_series_legend, draw_temperature_graph, draw_vpd_graph, event_marker_color, plot_resolution};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+enum SettingsTab { #[default] General, AlertRules }
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+enum MainView { #[default] Plots, Data, Calendar, Distribution }
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct TempMonitorApp {
+    /// Read from this instance's own file (`--config`); eframe's storage is shared by every instance
+    #[serde(skip)] config: Config,
+    settings_open: bool,
+    #[serde(skip)] rx: async_mpsc::UnboundedReceiver<AppMessage>,
+    #[serde(skip)] shared_config: Arc<Mutex<Config>>,
+    #[serde(skip)] history: VecDeque<HistoryPoint>,
+    #[serde(skip)] plot_cache: PlotCache,
+    #[serde(skip)] plot_series: PlotSeries,
+    /// Bumped by `history_changed` on every change to `history`, so the cached plot series is rebuilt
+    #[serde(skip)] history_version: u64,
+    #[serde(skip)] last_data_point: Option<BleDataPoint>,
+    #[serde(skip)] last_csv_write_ok: bool,
+    #[serde(skip)] scan_status: String,
+    #[serde(skip)] last_heartbeat: Option<Instant>,
+    #[serde(skip)] packets: PacketLog,
+    #[serde(skip)] on_battery: bool,
+    #[serde(skip)] battery_override: bool,
+    #[serde(skip)] power_checked_at: Option<Instant>,
+    #[serde(skip)] maintenance_ran_at: Option<Instant>,
+    #[serde(skip)] storage_usage: Option<StorageUsage>,
+    #[serde(skip)] zoom_factor: f32,
+    #[serde(skip)] reset_plot: bool,
+    #[serde(skip)] runtime: Option<tokio::runtime::Runtime>,
+    #[serde(skip)] shutdown_tx: Option<watch::Sender<bool>>,
+    #[serde(skip)] scanner_control: Option<async_mpsc::UnboundedSender<AppMessage>>,
+    #[serde(skip)] scan_settings_tx: Option<watch::Sender<()>>,
+    #[serde(skip)] scanning_paused: bool,
+    #[serde(skip)] background_tasks: Vec<tokio::task::JoinHandle<()>>,
+    #[serde(skip)] config_changed: bool,
+    #[serde(skip)] toast_message: Option<(String, Instant)>,
+    #[serde(skip)] visible_range: Option<(f64, f64)>,
+    #[serde(skip)] temp_y_range: Option<(f64, f64)>,
+    #[serde(skip)] hum_y_range: Option<(f64, f64)>,
+    /// Time under the pointer on the temperature and humidity plots
+    #[serde(skip)] crosshair: [Option<f64>; 2],
+    #[serde(skip)] bookmarks: Vec<PlotBookmark>,
+    #[serde(skip)] bookmark_dialog_open: bool,
+    #[serde(skip)] bookmark_name: String,
+    #[serde(skip)] bookmark_lock_y: bool,
+    #[serde(skip)] pending_view: Option<PlotBookmark>,
+    #[serde(skip)] zoom_history: Vec<PlotBookmark>,
+    #[serde(skip)] report_open: bool,
+    #[serde(skip)] report_period: ReportPeriod,
+    #[serde(skip)] report_from: String,
+    #[serde(skip)] report_to: String,
+    #[serde(skip)] report: Option<ColdChainReport>,
+    #[serde(skip)] evidence_open: bool,
+    #[serde(skip)] evidence_start: String,
+    #[serde(skip)] evidence_end: String,
+    #[serde(skip)] evidence_verified: Option<Result<String, String>>,
+    #[serde(skip)] dehumidifier_open: bool,
+    #[serde(skip)] diagnostics_open: bool,
+    #[serde(skip)] capture_open: bool,
+    #[serde(skip)] capture_view: CaptureView,
+    #[serde(skip)] sleep_periods: Vec<SleepPeriod>,
+    #[serde(skip)] pause_periods: Vec<PauseInterval>,
+    #[serde(skip)] integrity_open: bool,
+    #[serde(skip)] damaged_logs: Vec<integrity::DamagedLog>,
+    #[serde(skip)] dehumidifier_days: i64,
+    #[serde(skip)] dehumidifier_stats: Vec<DailyHumidityExcess>,
+    #[serde(skip)] gradient_open: bool,
+    #[serde(skip)] gradient_days: i64,
+    #[serde(skip)] gradient_points: Vec<GradientPoint>,
+    #[serde(skip)] virtual_open: bool,
+    #[serde(skip)] virtual_selected: usize,
+    #[serde(skip)] virtual_days: i64,
+    #[serde(skip)] virtual_points: Vec<HistoryPoint>,
+    #[serde(skip)] alarm: SoundAlarm,
+    #[serde(skip)] alarm_acknowledged: bool,
+    /// An active alert rule asks for the sound alarm (reported by the processor)
+    #[serde(skip)] rule_alarm: bool,
+    #[serde(skip)] settings_tab: SettingsTab,
+    /// Alerts within the loaded history, for the Events window and the plot markers
+    #[serde(skip)] events: Vec<AlertRecord>,
+    #[serde(skip)] events_open: bool,
+    /// Notes within the loaded history, drawn as labeled markers
+    #[serde(skip)] notes: Vec<Note>,
+    #[serde(skip)] notes_open: bool,
+    #[serde(skip)] note_text: String,
+    #[serde(skip)] note_time: String,
+    #[serde(skip)] overlay: DayOverlay,
+    #[serde(skip)] last_reading_at: Instant,
+    #[serde(skip)] sensor_offline: bool,
+    #[serde(skip)] share_server: Option<ShareServer>,
+    #[serde(skip)] share_url: Option<String>,
+    #[serde(skip)] import_job: Option<ImportJob>,
+    #[serde(skip)] history_loader: Option<HistoryLoader>,
+    #[serde(skip)] migration_plan: Option<Vec<MigrationItem>>,
+    #[serde(skip)] setup_open: bool,
+    #[serde(skip)] setup_mode: StorageMode,
+    #[serde(skip)] main_view: MainView,
+    #[serde(skip)] data_table: DataTable,
+    #[serde(skip)] heatmap: CalendarHeatmap,
+    #[serde(skip)] distribution: DistributionView,
+    #[serde(skip)] point_edit: Option<EditDialog>,
+    #[serde(skip)] keep_awake: KeepAwake,
+    #[serde(skip)] unlock_pin: Option<String>,
+    #[serde(skip)] command_palette: Option<CommandPalette>,
+    #[serde(skip)] selftest_open: bool,
+    #[serde(skip)] selftest_results: Vec<CheckResult>,
+    #[serde(skip)] selftest_rx: Option<mpsc::Receiver<Vec<CheckResult>>>,
+    #[serde(skip)] new_sensors: NewSensorTracker,
+    #[serde(skip)] sensor_prompt: Option<SensorSighting>,
+    #[serde(skip)] profile_name: String,
+    #[serde(skip)] applied_theme: Option<ThemeKey>,
+}
+
+impl Default for TempMonitorApp {
+    fn default() -> Self {
+        let (_tx, rx) = async_mpsc::unbounded_channel();
+        Self {
+            config: Config::default(), settings_open: false, rx, shared_config: Arc::new(Mutex::new(Config::default())),
+            history: VecDeque::new(), plot_cache: PlotCache::default(), plot_series: PlotSeries::default(), history_version: 0, last_data_point: None, last_csv_write_ok: true, scan_status: "Initializing...".to_string(), last_heartbeat: None, packets: PacketLog::default(), on_battery: false, battery_override: false, power_checked_at: None, maintenance_ran_at: None, storage_usage: None,
+            zoom_factor: 1.0, reset_plot: false, runtime: None, shutdown_tx: None, scanner_control: None, scan_settings_tx: None, scanning_paused: false, background_tasks: Vec::new(), config_changed: false,
+            toast_message: None, visible_range: None, temp_y_range: None, hum_y_range: None, crosshair: [None; 2],
+            bookmarks: bookmarks::load_bookmarks(), bookmark_dialog_open: false, bookmark_name: String::new(), bookmark_lock_y: false, pending_view: None, zoom_history: Vec::new(),
+            report_open: false, report_period: ReportPeriod::Last24Hours, report_from: String::new(), report_to: String::new(), report: None,
+            evidence_open: false, evidence_start: String::new(), evidence_end: String::new(), evidence_verified: None,
+            dehumidifier_open: false, diagnostics_open: false, capture_open: false, capture_view: CaptureView::default(), sleep_periods: Vec::new(), pause_periods: Vec::new(), integrity_open: false, damaged_logs: Vec::new(), dehumidifier_days: 30, dehumidifier_stats: Vec::new(),
+            gradient_open: false, gradient_days: 1, gradient_points: Vec::new(), virtual_open: false, virtual_selected: 0, virtual_days: 1, virtual_points: Vec::new(),
+            alarm: SoundAlarm::default(), alarm_acknowledged: false, rule_alarm: false, settings_tab: SettingsTab::General, events: Vec::new(), events_open: false,
+            notes: Vec::new(), notes_open: false, note_text: String::new(), note_time: String::new(), overlay: DayOverlay::default(),
+            last_reading_at: Instant::now(), sensor_offline: false,
+            share_server: None, share_url: None, import_job: None, history_loader: None, migration_plan: None,
+            setup_open: false, setup_mode: StorageMode::Installed, main_view: MainView::Plots, data_table: DataTable::default(), heatmap: CalendarHeatmap::default(), distribution: DistributionView::default(), point_edit: None, keep_awake: KeepAwake::default(), unlock_pin: None, command_palette: None,
+            selftest_open: false, selftest_results: Vec::new(), selftest_rx: None, new_sensors: NewSensorTracker::default(), sensor_prompt: None, profile_name: String::new(), applied_theme: None,
+        }
+    }
+}
+
+impl TempMonitorApp {
+    pub(crate) fn new(cc: &eframe::CreationContext<'_>, args: &cli::Args) -> Self {
+        info!("Creating new TempMonitorApp instance.");
+        let mut app: Self = if let Some(storage) = cc.storage { eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default() } else { Default::default() };
+        app.config = load_config();
+        i18n::set_language(app.config.language);
+        if paths::is_first_run() {
+            info!("No configuration found in '{}', opening setup.", paths::config_dir().display());
+            app.setup_open = true;
+            app.setup_mode = if paths::has_legacy_portable_layout() { StorageMode::Portable } else { StorageMode::Installed };
+        } else {
+            app.check_legacy_migration();
+        }
+        if app.config.autostart {
+            if let Err(e) = autostart::set(true) { warn!("Could not refresh the start-with-system entry: {}", e); }
+        }
+        if args.start_minimized(&app.config) { cc.egui_ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true)); }
+        args.apply_to(&mut app.config);
+        if let Some(dir) = args.data_dir_override() {
+            info!("Using data folder '{}' from the command line.", dir.display());
+        } else if !app.config.data_folder.is_empty() {
+            let dir = PathBuf::from(&app.config.data_folder);
+            if dir.is_dir() { info!("Using data folder '{}'.", dir.display()); paths::set_data_dir_override(Some(dir)); } else { warn!("Data folder '{}' not found, using the default.", dir.display()); app.config.data_folder.clear(); }
+        }
+        // Finish a write cut off by a crash before the processor appends again, then look for damage from it
+        if let Some(note) = integrity::recover() { app.toast_message = Some((note, Instant::now())); }
+        app.damaged_logs = integrity::check_logs(Some(STARTUP_CHECK_DAYS));
+        app.sleep_periods = sleep::read(&sleep::sleep_log_path());
+        app.pause_periods = schedule::read(&schedule::pause_log_path());
+        if !app.damaged_logs.is_empty() {
+            warn!("Damaged rows found in {} recent log files.", app.damaged_logs.len());
+            app.integrity_open = !app.config.simple_mode;
+        }
+        let (gui_tx, gui_rx) = async_mpsc::unbounded_channel(); let (scanner_tx, processor_rx) = async_mpsc::unbounded_channel();
+        let (processor_tx, processor_out) = async_mpsc::unbounded_channel();
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let (control_tx, control_rx) = async_mpsc::unbounded_channel();
+        let (scan_settings_tx, scan_settings_rx) = watch::channel(());
+        app.rx = gui_rx;
+        let shared_config = Arc::new(Mutex::new(app.config.clone()));
+        app.shared_config = shared_config.clone();
+        info!("Starting background processor and Bluetooth scanner tasks.");
+        let rt = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+        app.background_tasks = vec![
+            rt.spawn(background_data_processor(processor_rx, processor_tx, shared_config.clone(), shutdown_rx.clone())),
+            rt.spawn(repaint::forward_and_wake(processor_out, gui_tx, cc.egui_ctx.clone())),
+            spawn_reading_source(&rt, args, scanner_tx, shared_config, control_rx, scan_settings_rx, shutdown_rx),
+        ];
+        app.runtime = Some(rt);
+        app.shutdown_tx = Some(shutdown_tx);
+        app.scanner_control = Some(control_tx);
+        app.scan_settings_tx = Some(scan_settings_tx);
+        app.start_history_load();
+        app.start_selftest(&cc.egui_ctx, false);
+        app
+    }
+
+    fn add_data_point(&mut self, data: BleDataPoint) {
+        debug!("Updating UI with new data point: {:?}", data);
+        let history_point = HistoryPoint { timestamp: data.timestamp, temp: data.temp, hum: data.hum, origin: if data.persisted { PointOrigin::Persisted } else { PointOrigin::LiveOnly }, rssi: data.rssi };
+        if self.config.history_hours > 0 && !self.config.load_all_history {
+            self.history.push_back(history_point);
+            history::keep_hours(&mut self.history, Local::now(), self.config.history_hours);
+        } else {
+            history::push_capped(&mut self.history, history_point, history_limit(&self.config), self.config.history_eviction);
+        }
+        self.history_changed();
+        self.last_data_point = Some(data);
+        self.last_reading_at = Instant::now();
+    }
+
+    // Replaces the history with today's log, read on a background thread so the window shows up at once
+    fn start_history_load(&mut self) {
+        info!("Loading history from CSV. Load all: {}", self.config.load_all_history);
+        self.history.clear();
+        self.history_changed();
+        // Kept by hours, the window may reach back into earlier days' logs
+        let today = Local::now().date_naive();
+        let first_day = if self.config.history_hours > 0 && !self.config.load_all_history { (Local::now() - chrono::Duration::hours(self.config.history_hours as i64)).date_naive() } else { today };
+        self.history_loader = Some(HistoryLoader::start(first_day.iter_days().take_while(|day| *day <= today).map(paths::log_path).collect()));
+    }
+
+    fn history_changed(&mut self) { self.history_version += 1; }
+
+    // Merges loaded chunks in front of the readings that arrived while loading
+    fn poll_history_loader(&mut self, ctx: &egui::Context) {
+        let Some(loader) = &self.history_loader else { return; };
+        let finished = loader.is_finished();
+        let chunks = loader.poll();
+        if !chunks.is_empty() {
+            let (limit, eviction) = (history_limit(&self.config), self.config.history_eviction);
+            let split = self.history.partition_point(|p| p.timestamp < loader.started_at);
+            let live: Vec<HistoryPoint> = self.history.drain(split..).collect();
+            if self.config.history_hours > 0 && !self.config.load_all_history {
+                self.history.extend(chunks.into_iter().flatten().chain(live));
+                history::keep_hours(&mut self.history, Local::now(), self.config.history_hours);
+            } else {
+                for point in chunks.into_iter().flatten().chain(live) { history::push_capped(&mut self.history, point, limit, eviction); }
+            }
+            self.history_changed();
+        }
+        if finished { info!("History loaded: {} points in memory.", self.history.len()); self.history_loader = None; self.reload_timeline(); } else { ctx.request_repaint_after(Duration::from_millis(100)); }
+    }
+
+    // `show` opens the results window when done; at startup it only opens when a check failed
+    fn start_selftest(&mut self, ctx: &egui::Context, show: bool) {
+        let Some(rt) = &self.runtime else { return; };
+        let (tx, rx) = mpsc::channel();
+        let (config, ctx) = (self.config.clone(), ctx.clone());
+        rt.spawn(async move { let _ = tx.send(selftest::run(config).await); ctx.request_repaint(); });
+        self.selftest_rx = Some(rx);
+        self.selftest_results.clear();
+        self.selftest_open = show;
+    }
+
+    fn poll_selftest(&mut self) {
+        let Some(results) = self.selftest_rx.as_ref().and_then(|rx| rx.try_recv().ok()) else { return; };
+        self.selftest_open |= results.iter().any(|r| r.status == CheckStatus::Fail);
+        self.selftest_results = results;
+        self.selftest_rx = None;
+    }
+
+    fn toggle_courtesy_mode(&mut self) {
+        let old_config = self.config.clone();
+        self.config.courtesy_mode = !self.config.courtesy_mode;
+        info!("Courtesy mode {}.", if self.config.courtesy_mode { "enabled" } else { "disabled" });
+        self.toast_message = Some((tr(if self.config.courtesy_mode { "Courtesy mode on" } else { "Courtesy mode off" }).to_owned(), Instant::now()));
+        self.sync_config(&old_config);
+    }
+
+    fn set_simple_mode(&mut self, simple: bool) {
+        let old_config = self.config.clone();
+        self.config.simple_mode = simple;
+        info!("Simple mode {}.", if simple { "enabled" } else { "disabled" });
+        if simple {
+            self.main_view = MainView::Plots;
+            (self.settings_open, self.report_open, self.evidence_open, self.dehumidifier_open, self.gradient_open, self.virtual_open, self.diagnostics_open, self.capture_open, self.integrity_open) = (false, false, false, false, false, false, false, false, false);
+        }
+        self.toast_message = Some((tr(if simple { "Simple mode" } else { "Full mode" }).to_owned(), Instant::now()));
+        self.sync_config(&old_config);
+    }
+
+    fn battery_saver_active(&self) -> bool { self.config.battery_saver && self.on_battery && !self.battery_override }
+
+    // Polls the power source and applies or lifts the battery saver (scan duty cycle, repaint rate, sharing)
+    fn update_power_state(&mut self) {
+        if self.power_checked_at.is_none_or(|t| t.elapsed() >= POWER_POLL_INTERVAL) {
+            self.power_checked_at = Some(Instant::now());
+            let on_battery = power::on_battery().unwrap_or(false);
+            if on_battery != self.on_battery { info!("Power source: {}.", if on_battery { "battery" } else { "AC" }); }
+            self.on_battery = on_battery;
+            if !on_battery { self.battery_override = false; }
+        }
+        let saver = self.battery_saver_active();
+        if saver != power::saver_active() {
+            info!("Battery saver {}.", if saver { "on" } else { "off" });
+            power::set_saver_active(saver);
+            if let Some(tx) = &self.scan_settings_tx { let _ = tx.send(()); }
+        }
+        let Some(server) = self.share_server.as_mut() else { return; };
+        if saver && self.config.battery_saver_stops_sharing {
+            server.suspend();
+        } else if server.is_suspended() {
+            if let Err(e) = server.resume() { warn!("Could not resume snapshot sharing: {}", e); }
+        }
+    }
+
+    // Summaries and retention run on a background thread shortly after start and then every few hours
+    fn run_maintenance(&mut self) {
+        if self.maintenance_ran_at.is_some_and(|t| t.elapsed() < MAINTENANCE_INTERVAL) { return; }
+        self.maintenance_ran_at = Some(Instant::now());
+        let retention = self.config.retention_enabled.then_some((self.config.retention_raw_days, self.config.retention_archive_days));
+        thread::spawn(move || {
+            let today = Local::now().date_naive();
+            if let Err(e) = aggregate::refresh(today) { error!("Updating the hourly and daily summaries failed: {}", e); }
+            let Some((raw_days, archive_days)) = retention else { return; };
+            if let Err(e) = retention::run(today, raw_days, archive_days) { error!("Data retention failed: {}", e); }
+        });
+    }
+
+    // Logging means scanning and outside pause windows; scheduled mode also needs a keep-awake window. Kiosk mode can keep the display on regardless
+    fn update_keep_awake(&mut self) {
+        let now = Local::now();
+        let logging = !self.scanning_paused && schedule::active_pause(&self.config.pause_windows, now).is_none();
+        let wanted = logging && match self.config.keep_awake {
+            KeepAwakeMode::Off => false,
+            KeepAwakeMode::WhileLogging => true,
+            KeepAwakeMode::Scheduled => schedule::active_pause(&self.config.keep_awake_windows, now).is_some(),
+        };
+        let display = self.config.kiosk_mode && self.config.kiosk_keep_display_on;
+        self.keep_awake.set(wanted || display, display);
+    }
+
+    // Deadlines the GUI must notice without a message: the toast going away and the sensor counting as offline
+    fn idle_repaint_interval(&self) -> Duration {
+        let toast = self.toast_message.as_ref().map(|(_, shown)| TOAST_DURATION.saturating_sub(shown.elapsed()));
+        let offline = (!self.scanning_paused && !self.sensor_offline).then(|| offline_after(&self.config).saturating_sub(self.last_reading_at.elapsed()));
+        repaint::idle_interval(self.config.repaint_mode, power::saver_active(), toast.into_iter().chain(offline))
+    }
+
+    fn toggle_scanning(&mut self) {
+        let control = if self.scanning_paused { ScanControl::Resume } else { ScanControl::Pause };
+        info!("Requesting scanner {:?}.", control);
+        if let Some(tx) = &self.scanner_control { let _ = tx.send(AppMessage::Control(control)); }
+    }
+
+    fn update_offline_state(&mut self) {
+        if self.scanning_paused { return; }
+        let offline = self.last_reading_at.elapsed() >= offline_after(&self.config);
+        if offline == self.sensor_offline { return; }
+        self.sensor_offline = offline;
+        if offline {
+            warn!("Sensor offline: no reading for {}s.", self.last_reading_at.elapsed().as_secs());
+        } else {
+            info!("Sensor back online.");
+        }
+    }
+
+    // Sounds while an alert rule with the sound channel is active (or the device alarm is on / the sensor is offline), until acknowledged
+    fn update_sound_alarm(&mut self) {
+        let device_alarm = self.last_data_point.as_ref().is_some_and(|d| d.device_alarm) && !self.sensor_offline;
+        let alarming = self.rule_alarm || device_alarm || (self.config.offline_sound_alarm && self.sensor_offline);
+        if !alarming { self.alarm_acknowledged = false; }
+        if self.config.sound_alarm_enabled && alarming && !self.alarm_acknowledged { self.alarm.start(&self.config.sound_alarm_file); } else { self.alarm.stop(); }
+    }
+
+    fn share_snapshot(&mut self, ctx: &egui::Context) {
+        if power::saver_active() && self.config.battery_saver_stops_sharing {
+            self.toast_message = Some((tr("Sharing is paused while on battery").to_owned(), Instant::now()));
+            return;
+        }
+        if self.share_server.as_ref().map(|s| (s.port, s.on_network)) != Some((self.config.share_port, self.config.share_on_network)) {
+            self.share_server = None;
+            match ShareServer::start(self.config.share_port, self.config.share_on_network) {
+                Ok(server) => self.share_server = Some(server),
+                Err(e) => {
+                    error!("Failed to start snapshot server on port {}: {}", self.config.share_port, e);
+                    self.toast_message = Some((trf("Cannot share on port {0}: {1}", &[&self.config.share_port, &e]), Instant::now()));
+                    return;
+                }
+            }
+        }
+        let Some(server) = &self.share_server else { return; };
+        let now = Local::now();
+        let html = share::render_snapshot_html(&load_history_range(now - chrono::Duration::hours(24), now), self.config.date_format);
+        let ttl = (self.config.share_expiry_hours > 0).then(|| Duration::from_secs(self.config.share_expiry_hours as u64 * 3600));
+        let url = match server.publish(html, ttl) {
+            Ok(url) => url,
+            Err(e) => { error!("Failed to publish snapshot: {}", e); self.toast_message = Some((trf("Cannot share: {0}", &[&e]), Instant::now())); return; }
+        };
+        info!("Published snapshot at {}", url);
+        ctx.output_mut(|o| o.copied_text = url.clone());
+        self.toast_message = Some((trf("Snapshot link copied: {0}", &[&url]), Instant::now()));
+        self.share_url = Some(url);
+    }
+
+    fn apply_storage_mode(&mut self, mode: StorageMode) {
+        if let Err(e) = paths::set_storage_mode(mode) {
+            error!("Failed to switch storage mode: {}", e);
+            self.toast_message = Some((trf("Cannot switch storage mode: {0}", &[&e]), Instant::now()));
+            return;
+        }
+        // Pick up an existing configuration in the new location, otherwise carry the current one over
+        if paths::config_path().exists() { self.config = load_config(); } else { save_config(&self.config); }
+        if let Ok(mut shared) = self.shared_config.lock() { *shared = self.config.clone(); }
+        self.start_history_load();
+        self.toast_message = Some((trf("{0} mode: data in {1}", &[&tr(mode.label()), &paths::data_dir().display()]), Instant::now()));
+        self.check_legacy_migration();
+    }
+
+    fn check_legacy_migration(&mut self) {
+        if self.config.legacy_migration_done { return; }
+        let plan = migration::plan();
+        if !plan.is_empty() { info!("Found {} legacy files next to the executable.", plan.len()); self.migration_plan = Some(plan); }
+    }
+
+    fn finish_legacy_migration(&mut self, migrate: bool) {
+        let Some(plan) = self.migration_plan.take() else { return; };
+        if migrate {
+            match migration::run(&plan) {
+                Ok(backup_dir) => {
+                    self.config = load_config();
+                    self.start_history_load();
+                    self.toast_message = Some((trf("Migrated {0} files, originals in {1}", &[&plan.len(), &backup_dir.display()]), Instant::now()));
+                }
+                Err(e) => {
+                    error!("Legacy migration failed: {}", e);
+                    self.toast_message = Some((trf("Migration failed: {0}", &[&e]), Instant::now()));
+                    return;
+                }
+            }
+        }
+        let old_config = self.config.clone();
+        self.config.legacy_migration_done = true;
+        self.sync_config(&old_config);
+        save_config(&self.config);
+    }
+
+    fn draw_migration_window(&mut self, ctx: &egui::Context) {
+        if self.setup_open { return; }
+        let Some(plan) = &self.migration_plan else { return; };
+        let (mut decision, mut later) = (None, false);
+        egui::Window::new(tr("Move old data")).collapsible(false).anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0)).show(ctx, |ui| {
+            ui.label(trf("Found configuration/logs from an older version in {0}.", &[&paths::exe_dir().display()]));
+            ui.label(tr("They can be moved to the current storage location:"));
+            egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                egui::Grid::new("migration_grid").striped(true).show(ui, |ui| {
+                    for item in plan {
+                        ui.label(item.source.file_name().unwrap_or_default().to_string_lossy());
+                        ui.label(format!("→ {}", item.target.display()));
+                        ui.label(tr(item.action.label()));
+                        ui.end_row();
+                    }
+                });
+            });
+            ui.label(egui::RichText::new(tr("The originals are kept in a legacy-backup folder next to the executable.")).color(egui::Color32::GRAY));
+            ui.horizontal(|ui| {
+                if ui.button(tr("Migrate")).clicked() { decision = Some(true); }
+                if ui.button(tr("Don't migrate")).on_hover_text(tr("Leave the files where they are and don't ask again")).clicked() { decision = Some(false); }
+                if ui.button(tr("Later")).clicked() { later = true; }
+            });
+        });
+        if later { self.migration_plan = None; }
+        if let Some(migrate) = decision { self.finish_legacy_migration(migrate); }
+    }
+
+    // Current plot view with both y ranges locked
+    fn current_view(&self, name: String) -> Option<PlotBookmark> {
+        Some(PlotBookmark { name, x_range: self.visible_range?, temp_y: self.temp_y_range, hum_y: self.hum_y_range, smoothing: self.config.smoothing_enabled, thresholds: self.config.show_threshold_lines })
+    }
+
+    fn push_zoom_history(&mut self) {
+        const MAX_ZOOM_HISTORY: usize = 50;
+        if let Some(view) = self.current_view(String::new()) {
+            if self.zoom_history.len() >= MAX_ZOOM_HISTORY { self.zoom_history.remove(0); }
+            self.zoom_history.push(view);
+        }
+    }
+
+    fn save_bookmark(&mut self) {
+        let name = self.bookmark_name.trim().to_string();
+        let Some(mut bookmark) = self.current_view(name.clone()) else { return; };
+        if !self.bookmark_lock_y { bookmark.temp_y = None; bookmark.hum_y = None; }
+        match self.bookmarks.iter_mut().find(|b| b.name == name) { Some(existing) => *existing = bookmark, None => self.bookmarks.push(bookmark) }
+        bookmarks::save_bookmarks(&self.bookmarks);
+        self.toast_message = Some((trf("View '{0}' saved", &[&name]), Instant::now()));
+    }
+
+    // Alerts and notes for the loaded history; live ones are appended as they come
+    fn reload_timeline(&mut self) {
+        let Some(start) = self.history.front().map(|p| p.timestamp) else { return; };
+        (self.events, self.notes) = (alert_log::load_range(start, Local::now()), notes::load_range(start, Local::now()));
+    }
+
+    fn recall_bookmark(&mut self, bookmark: PlotBookmark) {
+        info!("Recalling plot view '{}'.", bookmark.name);
+        let old_config = self.config.clone();
+        self.config.smoothing_enabled = bookmark.smoothing;
+        self.config.show_threshold_lines = bookmark.thresholds;
+        self.sync_config(&old_config);
+        // Views older than the loaded history need their days read from the logs; long ones only need the hourly summaries
+        if self.history.front().is_none_or(|p| p.timestamp.timestamp() as f64 > bookmark.x_range.0) {
+            if let Some(start) = DateTime::from_timestamp(bookmark.x_range.0 as i64, 0) {
+                let (start, now) = (start.with_timezone(&Local), Local::now());
+                let summaries = if plot_resolution(&self.config, bookmark.x_range).is_some() { aggregate::load(Resolution::Hour, start, now) } else { Vec::new() };
+                // Days not summarised yet (e.g. yesterday before the next maintenance run) come from the raw logs
+                let raw_from = summaries.last().and_then(|s| s.start.date_naive().succ_opt()?.and_hms_opt(0, 0, 0)?.and_local_timezone(Local).earliest()).unwrap_or(start);
+                self.history = summaries.iter().map(Summary::to_point).chain(load_history_range(raw_from, now)).collect();
+                self.history_changed();
+                self.reload_timeline();
+            }
+        }
+        self.pending_view = Some(bookmark);
+    }
+
+    // Corner popup instead of a window, so it never covers the current values; not offered in simple mode
+    fn draw_sensor_prompt(&mut self, ctx: &egui::Context) {
+        if self.config.simple_mode { return; }
+        let Some(sighting) = self.sensor_prompt.clone() else { return; };
+        let mut answer = None;
+        egui::Area::new("sensor_prompt".into()).anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-20.0, -20.0)).show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.label(trf("New sensor {0} ({1}) detected — add it?", &[&sighting.model, &sighting.address]));
+                ui.horizontal(|ui| {
+                    if ui.button(tr("Add")).clicked() { answer = Some("add"); }
+                    if ui.button(tr("Not now")).clicked() { answer = Some("later"); }
+                    if ui.button(tr("Never")).on_hover_text(tr("Don't ask about this device again")).clicked() { answer = Some("never"); }
+                });
+            });
+        });
+        let Some(answer) = answer else { return; };
+        self.sensor_prompt = None;
+        let old_config = self.config.clone();
+        match answer {
+            "add" => {
+                let entry = SensorEntry::new(&sighting);
+                info!("Added sensor '{}' ({}).", entry.name, entry.mac);
+                self.toast_message = Some((trf("Added '{0}' – choose it as the target in Settings", &[&entry.name]), Instant::now()));
+                self.config.sensors.push(entry);
+            }
+            "never" => { info!("Ignoring sensor {} from now on.", sighting.address); self.config.ignored_sensors.push(sighting.address); }
+            _ => self.new_sensors.snooze(&sighting.address),
+        }
+        self.sync_config(&old_config);
+    }
+
+    fn draw_unlock_window(&mut self, ctx: &egui::Context) {
+        let Some(pin) = &mut self.unlock_pin else { return; };
+        let mut is_open = true;
+        let mut unlock = false;
+        egui::Window::new(tr("Full mode")).open(&mut is_open).collapsible(false).resizable(false).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr("PIN:"));
+                let response = ui.add(egui::TextEdit::singleline(pin).password(true).desired_width(100.0));
+                response.request_focus();
+                unlock = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                unlock |= ui.button(tr("Unlock")).clicked();
+            });
+        });
+        if unlock {
+            if *pin == self.config.admin_pin {
+                self.unlock_pin = None;
+                self.set_simple_mode(false);
+            } else {
+                warn!("Wrong PIN entered for leaving simple mode.");
+                pin.clear();
+                self.toast_message = Some((tr("Wrong PIN").to_owned(), Instant::now()));
+            }
+            return;
+        }
+        if !is_open { self.unlock_pin = None; }
+    }
+
+    fn draw_bookmark_window(&mut self, ctx: &egui::Context) {
+        if !self.bookmark_dialog_open { return; }
+        let mut is_open = true;
+        let mut save = false;
+        egui::Window::new(tr("Save view")).open(&mut is_open).collapsible(false).resizable(false).show(ctx, |ui| {
+            ui.horizontal(|ui| { ui.label(tr("Name:")); ui.text_edit_singleline(&mut self.bookmark_name); });
+            ui.checkbox(&mut self.bookmark_lock_y, tr("Lock Y axes to the current range"));
+            if self.bookmarks.iter().any(|b| b.name == self.bookmark_name.trim()) { ui.label(egui::RichText::new(tr("A view with this name will be replaced.")).color(egui::Color32::YELLOW)); }
+            if ui.add_enabled(!self.bookmark_name.trim().is_empty() && self.visible_range.is_some(), egui::Button::new(tr("Save"))).clicked() { save = true; }
+        });
+        if save { self.save_bookmark(); is_open = false; }
+        self.bookmark_dialog_open = is_open;
+    }
+
+    fn visible_points(&self) -> Vec<HistoryPoint> {
+        match self.visible_range {
+            Some((start, end)) => self.history.iter().filter(|p| { let t = p.timestamp.timestamp() as f64; t >= start && t <= end }).cloned().collect(),
+            None => self.history.iter().cloned().collect(),
+        }
+    }
+
+    fn write_export(&mut self, path: &Path, points: &[HistoryPoint], format: ExportFormat) -> bool {
+        let (alerts, notes) = match (points.first(), points.last()) { (Some(first), Some(last)) => (alert_log::load_range(first.timestamp, last.timestamp), notes::load_range(first.timestamp, last.timestamp)), _ => (Vec::new(), Vec::new()) };
+        info!("Exporting {} points to '{}'.", points.len(), path.display());
+        match export::export_history(path, points, &alerts, &notes, self.config.export_derived_columns, sensors::friendly_name(&self.config, &self.config.target_mac), format) {
+            Ok(()) => true,
+            Err(e) => { error!("Export to '{}' failed: {}", path.display(), e); self.toast_message = Some((tr("Export failed!").to_owned(), Instant::now())); false }
+        }
+    }
+
+    fn export_visible_range(&mut self, format: ExportFormat) {
+        let points = self.visible_points();
+        if points.is_empty() { self.toast_message = Some((tr("Nothing to export in the visible range.").to_owned(), Instant::now())); return; }
+        let default_name = format!("export_{}.{}", Local::now().format("%Y-%m-%d_%H%M%S"), format.extension());
+        let Some(path) = rfd::FileDialog::new().add_filter(format.filter_name(), &[format.extension()]).set_file_name(default_name).save_file() else { return; };
+        if self.write_export(&path, &points, format) { self.toast_message = Some((trf("Exported {0} records.", &[&points.len()]), Instant::now())); }
+    }
+
+    // The plots as on screen (same time range and y axes, same theme) at `plot_image_size`, drawn off screen
+    fn save_plot_image(&mut self, ctx: &egui::Context) {
+        let Some(view) = self.current_view(String::new()).filter(|_| !self.history.is_empty()) else { self.toast_message = Some((tr("Nothing to save in the visible range.").to_owned(), Instant::now())); return; };
+        let default_name = format!("plot_{}.png", Local::now().format("%Y-%m-%d_%H%M%S"));
+        let Some(path) = rfd::FileDialog::new().add_filter("PNG", &["png"]).set_file_name(default_name).save_file() else { return; };
+        let time = |t: f64| self.config.date_format.date_time(DateTime::from_timestamp(t as i64, 0).unwrap_or_default().with_timezone(&Local));
+        let caption = format!("{}, {} – {}", sensors::display_name(&self.config, &self.config.target_mac), time(view.x_range.0), time(view.x_range.1));
+        let saved = (self.pending_view.replace(view), self.crosshair);
+        info!("Saving a {}x{} plot image to '{}'.", self.config.plot_image_size.width, self.config.plot_image_size.height, path.display());
+        let image = plot_image::render(self.config.plot_image_size, &ctx.style().visuals, |ui| {
+            let ctx = ui.ctx().clone();
+            ui.label(egui::RichText::new(&caption).size(16.0).strong());
+            StripBuilder::new(ui).sizes(Size::remainder(), if self.config.combined_plot { 1 } else { 2 }).vertical(|mut strip| {
+                if self.config.combined_plot { strip.cell(|ui| draw_combined_graph(self, ui, &ctx)); return; }
+                strip.cell(|ui| { ui.label(egui::RichText::new(tr("Temperature")).size(14.0).strong()); draw_temperature_graph(self, ui, &ctx); });
+                strip.cell(|ui| { ui.label(egui::RichText::new(tr("Humidity")).size(14.0).strong()); draw_humidity_graph(self, ui, &ctx); });
+            });
+        });
+        (self.pending_view, self.crosshair) = saved;
+        match plot_image::save_png(&path, &image) {
+            Ok(()) => self.toast_message = Some((trf("Plot saved to {0}", &[&path.display()]), Instant::now())),
+            Err(e) => { error!("Saving the plot image to '{}' failed: {}", path.display(), e); self.toast_message = Some((tr("Saving the plot image failed!").to_owned(), Instant::now())); }
+        }
+    }
+
+    // Bound to `quick_export_shortcut`: no dialog, the file name comes from `quick_export_name`
+    fn quick_export(&mut self) {
+        let points = self.visible_points();
+        if points.is_empty() { self.toast_message = Some((tr("Nothing to export in the visible range.").to_owned(), Instant::now())); return; }
+        let dir = if self.config.quick_export_dir.trim().is_empty() { paths::data_dir().join("exports") } else { PathBuf::from(self.config.quick_export_dir.trim()) };
+        if let Err(e) = fs::create_dir_all(&dir) {
+            error!("Cannot create quick export folder '{}': {}", dir.display(), e);
+            self.toast_message = Some((trf("Quick export failed: {0}", &[&e]), Instant::now()));
+            return;
+        }
+        let format = self.config.quick_export_format;
+        let path = quick_export::unique_path(&dir, &quick_export::file_name(&self.config.quick_export_name, &self.config.room_name, Local::now(), format));
+        if self.write_export(&path, &points, format) { self.toast_message = Some((trf("Exported {0} records to {1}", &[&points.len(), &path.display()]), Instant::now())); }
+    }
+}
+
+impl eframe::App for TempMonitorApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, self);
+        if self.config_changed {
+            info!("Configuration change detected, saving to file.");
+            save_config(&self.config);
+            self.config_changed = false;
+        }
+    }
+
+    // Stops the scanner (ending an active scan) and lets the processor finish its last write
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        info!("Shutting down background tasks.");
+        if let Some(shutdown_tx) = self.shutdown_tx.take() { let _ = shutdown_tx.send(true); }
+        if let Some(rt) = self.runtime.take() {
+            let tasks = std::mem::take(&mut self.background_tasks);
+            rt.block_on(async { let _ = tokio::time::timeout(SHUTDOWN_GRACE, futures::future::join_all(tasks)).await; });
+            rt.shutdown_timeout(Duration::from_secs(1));
+        }
+        info!("Background tasks stopped.");
+    }
+
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        self.update_power_state();
+        self.update_keep_awake();
+        self.run_maintenance();
+        self.poll_history_loader(ctx);
+        self.poll_selftest();
+        ctx.request_repaint_after(self.idle_repaint_interval());
+        while let Ok(message) = self.rx.try_recv() {
+            match message {
+                AppMessage::NewData(data_point) => { self.add_data_point(data_point); if self.main_view == MainView::Data && self.data_table.shows_today() { self.data_table.reload(); } }
+                AppMessage::StatusUpdate(status) => { debug!("Scanner status update: {}", status); self.scan_status = status; },
+                AppMessage::CsvWriteStatus(ok) => self.last_csv_write_ok = ok,
+                AppMessage::Alert(message) => self.toast_message = Some((message, Instant::now())),
+                AppMessage::AlertLogged(record) => self.events.push(record),
+                AppMessage::AlarmSound(on) => self.rule_alarm = on,
+                AppMessage::Control(control) => {
+                    self.scanning_paused = control == ScanControl::Pause;
+                    if !self.scanning_paused { self.last_reading_at = Instant::now(); }
+                    self.toast_message = Some((tr(if self.scanning_paused { "Scanning paused – Bluetooth adapter released" } else { "Scanning resumed" }).to_owned(), Instant::now()));
+                }
+                AppMessage::SensorSeen(sighting) => {
+                    if let Some(sighting) = self.new_sensors.record(sighting, &self.config) {
+                        if self.sensor_prompt.is_none() { info!("New sensor {} ({}) seen repeatedly, asking to add it.", sighting.model, sighting.address); self.sensor_prompt = Some(sighting); }
+                    }
+                }
+                AppMessage::Captured(record) => self.capture_view.push(record),
+                AppMessage::Slept(period) => {
+                    self.toast_message = Some((trf("The PC was asleep for {0} min; scanning restarted.", &[&period.minutes()]), Instant::now()));
+                    self.sleep_periods.push(period);
+                }
+                AppMessage::Paused(interval) => self.pause_periods.push(interval),
+                AppMessage::Heartbeat => {
+                    self.last_heartbeat = Some(Instant::now());
+                    self.packets.record(Local::now());
+                    if self.config.heartbeat_tick { alarm::play_tick(); }
+                }
+            }
+        }
+        let (simple, kiosk) = (self.config.simple_mode, self.config.kiosk_mode);
+        // Before the other shortcuts: egui ignores an extra Shift, so Ctrl+Shift+E would also run Ctrl+E
+        if let Some(shortcut) = self.config.quick_export_enabled.then(|| quick_export::parse_shortcut(&self.config.quick_export_shortcut)).flatten() {
+            if ctx.input_mut(|i| i.consume_shortcut(&shortcut)) { self.quick_export(); }
+        }
+        self.handle_shortcuts(ctx);
+        self.update_offline_state();
+        self.update_sound_alarm();
+        self.track_window(ctx);
+        // Set only on a change (settings or the system theme), so widgets keep their own style tweaks between frames
+        let theme_key = (self.config.theme, self.config.accent_color, frame.info().system_theme);
+        if self.applied_theme != Some(theme_key) { ctx.set_visuals(theme::visuals(theme_key.0, theme_key.1, theme_key.2)); self.applied_theme = Some(theme_key); }
+        egui::TopBottomPanel::top("menu_bar").show_animated(ctx, !kiosk, |ui| {
+            egui::menu::bar(ui, |ui| {
+                ui.menu_button(tr("File"), |ui| {
+                    if simple {
+                        if ui.button(tr("Full mode...")).on_hover_text(tr("Show all panels and settings")).clicked() {
+                            ui.close_menu();
+                            if self.config.admin_pin.is_empty() { self.set_simple_mode(false); } else { self.unlock_pin = Some(String::new()); }
+                        }
+                        if ui.button(tr("Export visible range (CSV)...")).clicked() { ui.close_menu(); self.export_visible_range(ExportFormat::Csv); }
+                        if ui.button(tr("Export visible range (Excel)...")).clicked() { ui.close_menu(); self.export_visible_range(ExportFormat::Xlsx); }
+                        if ui.button(tr("Save plot as image...")).clicked() { ui.close_menu(); self.save_plot_image(ctx); }
+                        if ui.button(tr("Quit")).clicked() { ctx.send_viewport_cmd(egui::ViewportCommand::Close); }
+                        return;
+                    }
+                    if ui.button(tr("Settings")).clicked() { self.settings_open = true; ui.close_menu(); }
+                    if ui.button(tr("Open data folder...")).on_hover_text(tr("Switch to another dataset, e.g. a backup or another location")).clicked() {
+                        ui.close_menu();
+                        if let Some(dir) = rfd::FileDialog::new().set_directory(paths::data_dir()).pick_folder() { self.open_data_folder(dir.display().to_string()); }
+                    }
+                    if !self.config.data_folder.is_empty() || !self.config.recent_data_folders.is_empty() {
+                        ui.menu_button(tr("Recent data folders"), |ui| {
+                            let mut open = None;
+                            for folder in &self.config.recent_data_folders {
+                                if ui.add_enabled(*folder != self.config.data_folder, egui::Button::new(folder)).clicked() { open = Some(folder.clone()); }
+                            }
+                            ui.separator();
+                            if ui.add_enabled(!self.config.data_folder.is_empty(), egui::Button::new(trf("Default ({0})", &[&paths::default_data_dir().display()]))).clicked() { open = Some(String::new()); }
+                            if let Some(folder) = open { ui.close_menu(); self.open_data_folder(folder); }
+                        });
+                    }
+                    ui.menu_button(tr("Profiles"), |ui| {
+                        let mut switch_to = None;
+                        for profile in &self.config.profiles {
+                            if ui.radio(profile.name == self.config.active_profile, &profile.name).clicked() { switch_to = Some(profile.name.clone()); }
+                        }
+                        if !self.config.profiles.is_empty() { ui.separator(); }
+                        ui.horizontal(|ui| {
+                            ui.add(egui::TextEdit::singleline(&mut self.profile_name).hint_text(tr("Profile name")).desired_width(120.0));
+                            let name = self.profile_name.trim().to_string();
+                            if ui.add_enabled(!name.is_empty(), egui::Button::new(tr("Save current"))).on_hover_text(tr("Store the sensor, thresholds, calibration and scan timing under this name")).clicked() {
+                                let old_config = self.config.clone();
+                                profiles::save_as(&mut self.config, &name);
+                                self.sync_config(&old_config);
+                                save_config(&self.config);
+                                self.toast_message = Some((trf("Saved profile '{0}'", &[&name]), Instant::now()));
+                                self.profile_name.clear();
+                            }
+                        });
+                        let active = self.config.active_profile.clone();
+                        if !active.is_empty() && ui.button(trf("Delete '{0}'", &[&active])).on_hover_text(tr("The current settings stay as they are")).clicked() {
+                            let old_config = self.config.clone();
+                            self.config.profiles.retain(|p| p.name != active);
+                            self.config.active_profile.clear();
+                            self.sync_config(&old_config);
+                            save_config(&self.config);
+                        }
+                        if let Some(name) = switch_to { ui.close_menu(); self.switch_profile(&name); }
+                    });
+                    if ui.button(tr("Import settings...")).on_hover_text(tr("Replace the settings with an exported file; data folders and start-with-system stay as they are")).clicked() {
+                        ui.close_menu();
+                        if let Some(path) = rfd::FileDialog::new().add_filter("JSON", &["json"]).pick_file() { self.import_settings(&path); }
+                    }
+                    if ui.button(tr("Export settings...")).on_hover_text(tr("All settings and profiles as JSON, including the webhook URL; passwords, tokens and the PIN are left out")).clicked() {
+                        ui.close_menu();
+                        if let Some(path) = rfd::FileDialog::new().add_filter("JSON", &["json"]).set_file_name("temp_monitor_settings.json").save_file() {
+                            self.toast_message = Some((match profiles::export(&self.config, &path) {
+                                Ok(()) => trf("Settings exported to {0}", &[&path.display()]),
+                                Err(e) => { error!("Settings export to '{}' failed: {}", path.display(), e); trf("Settings export failed: {0}", &[&e]) }
+                            }, Instant::now()));
+                        }
+                    }
+                    if ui.add_enabled(self.import_job.is_none(), egui::Button::new(tr("Import CSV history..."))).clicked() {
+                        ui.close_menu();
+                        if let Some(path) = rfd::FileDialog::new().add_filter("CSV", &["csv"]).pick_file() { self.import_job = Some(ImportJob::start(path)); }
+                    }
+                    if ui.button(tr("Import alert history...")).on_hover_text(tr("Merge alerts exported from another machine (*.alerts.csv)")).clicked() {
+                        ui.close_menu();
+                        if let Some(path) = rfd::FileDialog::new().add_filter("CSV", &["csv"]).pick_file() {
+                            self.toast_message = Some((match alert_log::import_csv(&path) {
+                                Ok((added, skipped)) => trf("Imported {0} alerts ({1} already present)", &[&added, &skipped]),
+                                Err(e) => { error!("Alert import from '{}' failed: {}", path.display(), e); trf("Alert import failed: {0}", &[&e]) }
+                            }, Instant::now()));
+                        }
+                    }
+                    if ui.button(tr("Export visible range (CSV)...")).clicked() { ui.close_menu(); self.export_visible_range(ExportFormat::Csv); }
+                    if ui.button(tr("Export visible range (Excel)...")).clicked() { ui.close_menu(); self.export_visible_range(ExportFormat::Xlsx); }
+                    if ui.button(tr("Save plot as image...")).clicked() { ui.close_menu(); self.save_plot_image(ctx); }
+                    if ui.button(tr("Cold-chain report...")).clicked() { self.report_open = true; ui.close_menu(); }
+                    if ui.button(tr("Evidence export...")).on_hover_text(tr("Signed, tamper-evident record of readings, gaps, alerts and edits, e.g. for a landlord or insurer")).clicked() {
+                        let today = Local::now().date_naive();
+                        if self.evidence_end.is_empty() { (self.evidence_start, self.evidence_end) = ((today - chrono::Duration::days(30)).to_string(), today.to_string()); }
+                        self.evidence_open = true;
+                        ui.close_menu();
+                    }
+                    if ui.button(tr("Share snapshot link")).on_hover_text(tr("Publish the last 24 hours as a read-only page behind a private link")).clicked() { ui.close_menu(); self.share_snapshot(ctx); }
+                    if self.share_url.is_some() && ui.button(tr("Stop sharing")).clicked() {
+                        if let Some(server) = &self.share_server { server.revoke(); }
+                        self.share_url = None;
+                        ui.close_menu();
+                    }
+                    if ui.button(tr("Dehumidifier estimate...")).clicked() { self.dehumidifier_open = true; self.refresh_dehumidifier_stats(); ui.close_menu(); }
+                    if ui.button(tr("Sensor gradient...")).on_hover_text(tr("Temperature difference between two sensors, e.g. floor vs ceiling")).clicked() { self.gradient_open = true; self.refresh_gradient(); ui.close_menu(); }
+                    if ui.button(tr("Virtual sensors...")).on_hover_text(tr("Mean, minimum or maximum of several sensors, e.g. a house average")).clicked() { self.virtual_open = true; self.refresh_virtual_sensor(); ui.close_menu(); }
+                    if ui.button(tr("Events...")).on_hover_text(tr("Threshold breaches, offline periods and recoveries")).clicked() { self.reload_timeline(); self.events_open = true; ui.close_menu(); }
+                    if ui.button(tr("Diagnostics...")).clicked() { self.diagnostics_open = true; ui.close_menu(); }
+                    if ui.button(tr("Advertisement capture...")).on_hover_text(tr("Raw packets of the sensor with changed bytes highlighted, for decoding the payload")).clicked() { self.capture_view.resume(capture::read(&capture::capture_path())); self.capture_open = true; ui.close_menu(); }
+                    if ui.button(tr("Self-test...")).on_hover_text(tr("Check Bluetooth, permissions, storage, settings and the clock")).clicked() { self.start_selftest(ctx, true); ui.close_menu(); }
+                    if ui.button(tr("Check logs for damage...")).clicked() { self.damaged_logs = integrity::check_logs(None); self.integrity_open = true; ui.close_menu(); }
+                    if ui.button(tr("Simple mode")).on_hover_text(tr("Only the current values and plots, for other household members")).clicked() { ui.close_menu(); self.set_simple_mode(true); }
+                    if ui.button(tr("Quit")).clicked() { ctx.send_viewport_cmd(egui::ViewportCommand::Close); }
+                });
+                if !simple {
+                    ui.separator();
+                    if ui.selectable_label(self.main_view == MainView::Plots, tr("📈 Plots")).clicked() { self.main_view = MainView::Plots; }
+                    if ui.selectable_label(self.main_view == MainView::Data, tr("📋 Data")).on_hover_text(tr("Readings of one day as a table")).clicked() { self.main_view = MainView::Data; self.data_table.reload(); }
+                    if ui.selectable_label(self.main_view == MainView::Calendar, tr("📅 Calendar")).on_hover_text(tr("Daily temperatures of a month as a colored calendar")).clicked() { self.main_view = MainView::Calendar; self.heatmap.reload(); }
+                    if ui.selectable_label(self.main_view == MainView::Distribution, tr("📊 Distribution")).on_hover_text(tr("Histograms and percentiles of the range shown in the plots")).clicked() { self.main_view = MainView::Distribution; }
+                    ui.separator();
+                    ui.menu_button(tr("Views"), |ui| {
+                        if ui.checkbox(&mut self.config.combined_plot, tr("Combined plot (dual axes)")).on_hover_text(tr("Temperature and humidity in one plot")).changed() { save_config(&self.config); ui.close_menu(); }
+                        if ui.checkbox(&mut self.config.day_overlay, tr("Compare with another day")).on_hover_text(tr("Draw the chosen day's curves behind today's, aligned by time of day")).changed() { save_config(&self.config); }
+                        if self.config.day_overlay {
+                            ui.horizontal(|ui| {
+                                let yesterday = Local::now().date_naive() - chrono::Duration::days(1);
+                                if ui.button("◀").on_hover_text(tr("Previous day")).clicked() { self.overlay.day -= chrono::Duration::days(1); }
+                                ui.label(self.config.date_format.date(self.overlay.day));
+                                if ui.add_enabled(self.overlay.day < yesterday, egui::Button::new("▶")).on_hover_text(tr("Next day")).clicked() { self.overlay.day += chrono::Duration::days(1); }
+                                if ui.button(tr("Yesterday")).clicked() { self.overlay.day = yesterday; }
+                            });
+                        }
+                        ui.separator();
+                        if ui.button(tr("Save current view...")).clicked() { self.bookmark_name.clear(); self.bookmark_dialog_open = true; ui.close_menu(); }
+                        if !self.bookmarks.is_empty() { ui.separator(); }
+                        let (mut recall, mut remove) = (None, None);
+                        for (i, bookmark) in self.bookmarks.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                if ui.button(&bookmark.name).clicked() { recall = Some(bookmark.clone()); ui.close_menu(); }
+                                if ui.small_button("🗑").on_hover_text(tr("Delete view")).clicked() { remove = Some(i); }
+                            });
+                        }
+                        if let Some(bookmark) = recall { self.recall_bookmark(bookmark); }
+                        if let Some(i) = remove { self.bookmarks.remove(i); bookmarks::save_bookmarks(&self.bookmarks); }
+                    });
+                    if ui.button(tr("📝 Note")).on_hover_text(tr("Add a timestamped note to the timeline, e.g. when you open a window or turn on the humidifier")).clicked() { self.open_notes(); }
+                }
+                ui.separator();
+                if ui.button("➖").on_hover_text(tr("Zoom out")).clicked() { self.push_zoom_history(); self.zoom_factor = 0.7; }
+                if ui.button("➕").on_hover_text(tr("Zoom in")).clicked() { self.push_zoom_history(); self.zoom_factor = 1.25; }
+                if ui.add_enabled(!self.zoom_history.is_empty(), egui::Button::new("⮪")).on_hover_text(tr("Back to the previous zoom (right-drag on a plot to zoom to a selection)")).clicked() { self.pending_view = self.zoom_history.pop(); }
+                if ui.button("⛶").on_hover_text(tr("Center plot")).clicked() { self.reset_plot = true; }
+                if !simple {
+                    ui.separator();
+                    let courtesy_scheduled = schedule::active_pause(&self.config.courtesy_windows, Local::now()).is_some();
+                    let (pause_label, pause_hint) = if self.scanning_paused { ("▶ Resume scan", "Start scanning again") } else { ("⏸ Pause scan", "Stop scanning and release the Bluetooth adapter, e.g. for pairing another device") };
+                    if ui.button(tr(pause_label)).on_hover_text(tr(pause_hint)).clicked() { self.toggle_scanning(); }
+                    if ui.selectable_label(self.config.courtesy_mode || courtesy_scheduled, tr("🎧 Courtesy")).on_hover_text(tr("Short scans to spare Bluetooth audio (Ctrl+B)")).clicked() { self.toggle_courtesy_mode(); }
+                }
+                if let Some(loader) = &self.history_loader {
+                    ui.separator();
+                    let fraction = loader.fraction();
+                    ui.add(egui::ProgressBar::new(fraction).desired_width(160.0).text(trf("Loading history {0} %", &[&format!("{:.0}", fraction * 100.0)])));
+                }
+                if self.alarm.is_active() {
+                    ui.separator();
+                    if ui.button(egui::RichText::new(tr("🔕 Acknowledge alarm")).color(egui::Color32::RED)).clicked() { info!("Alarm acknowledged."); self.alarm_acknowledged = true; self.alarm.stop(); }
+                }
+                if self.on_battery && self.config.battery_saver {
+                    ui.separator();
+                    if ui.selectable_label(!self.battery_override, tr("🔋 Battery saver")).on_hover_text(tr("On battery: longer scan pauses, slower refresh. Click to run at full speed anyway.")).clicked() {
+                        self.battery_override = !self.battery_override;
+                    }
+                }
+                if self.config.heartbeat_dot || self.config.comfort_badges {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if self.config.heartbeat_dot { draw_heartbeat(ui, self.last_heartbeat); }
+                        if let (true, Some(point)) = (self.config.comfort_badges, self.history.back()) { draw_comfort_badges(ui, point); }
+                    });
+                }
+            });
+        });
+        let scan = scan_stats::snapshot();
+        if scan.failures > 0 && !self.scanning_paused {
+            egui::TopBottomPanel::top("adapter_banner").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let problem = if scan.adapter == scan_stats::AdapterState::PoweredOff { tr("Bluetooth is switched off.") } else { tr("No Bluetooth adapter found.") };
+                    let wait = scan.retry_at.map_or(0, |at| (at - Local::now()).num_seconds().max(0));
+                    ui.label(egui::RichText::new(format!("⚠ {} {}", problem, trf("Retrying in {0} s (attempt {1}).", &[&wait, &(scan.failures + 1)]))).color(egui::Color32::from_rgb(230, 140, 0)));
+                    if ui.button(tr("Retry now")).clicked() { if let Some(tx) = &self.scan_settings_tx { let _ = tx.send(()); } }
+                });
+            });
+            ctx.request_repaint_after(Duration::from_secs(1));
+        }
+        if self.reset_plot { info!("Resetting plot view."); ctx.memory_mut(|memory| { memory.data.remove::<PlotMemory>(egui::Id::new("linked_plots")); }); }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if self.main_view == MainView::Calendar && !simple && !kiosk {
+                let old_config = self.config.clone();
+                let (scale, week_start) = (ColorScale::temperature(&self.config), self.config.week_start);
+                let clicked = self.heatmap.show(ui, &mut self.config.heatmap_value, scale, week_start);
+                self.sync_config(&old_config);
+                if let Some(day) = clicked { self.show_day(day); }
+                return;
+            }
+            if self.main_view == MainView::Distribution && !simple && !kiosk {
+                let points = self.visible_points();
+                self.distribution.show(ui, &points, self.config.date_format);
+                return;
+            }
+            if self.main_view == MainView::Data && !simple && !kiosk {
+                match self.data_table.show(ui, self.config.date_format) {
+                    Some(TableAction::Copy(text)) => {
+                        ctx.output_mut(|o| o.copied_text = text);
+                        self.toast_message = Some((tr("Selected rows copied to the clipboard").to_string(), Instant::now()));
+                    }
+                    Some(TableAction::Edit(points)) => self.point_edit = EditDialog::new(&points),
+                    None => {}
+                }
+                return;
+            }
+            let extra_plots = [self.config.vpd_enabled, self.config.rssi_plot].iter().filter(|on| **on).count();
+            let main_layout = layout::main_layout(ui.available_size(), self.config.combined_plot, extra_plots, kiosk);
+            let strip = main_layout.plots.iter().fold(StripBuilder::new(ui).size(Size::relative(main_layout.header).at_least(main_layout.header_min)), |strip, size| strip.size(Size::relative(*size)));
+            let strip = if main_layout.footer > 0.0 { strip.size(Size::relative(main_layout.footer)) } else { strip };
+            strip.vertical(|mut strip| {
+                let active_pause = schedule::active_pause(&self.config.pause_windows, Local::now());
+                strip.cell(|ui| {
+                    // Kiosk mode: only the two readings, large enough to read across the room
+                    let (cells, scale) = (if kiosk { 2 } else if simple { 3 } else { 4 }, main_layout.text_scale);
+                    let draw_cell = |ui: &mut egui::Ui, cell: usize| match cell {
+                        0 => { ui.vertical_centered(|ui| draw_temperature_info(ui, &self.history, &self.config, active_pause.is_some(), scale)); }
+                        1 => { ui.vertical_centered(|ui| draw_humidity_info(ui, &self.history, &self.config, scale)); }
+                        2 => { ui.vertical(|ui| draw_scan_metadata(ui, &self.last_data_point, &self.scan_status, active_pause.as_ref(), self.sensor_offline.then(|| self.last_reading_at.elapsed()), simple)); }
+                        _ => { ui.vertical(|ui| draw_data_details(ui, &self.last_data_point, sensors::friendly_name(&self.config, &self.config.target_mac), self.last_csv_write_ok)); }
+                    };
+                    // Narrow window: the readings side by side, the scan status and details in a second row
+                    let split = if main_layout.stacked_header { 2 } else { cells };
+                    for row in [0..split, split..cells].into_iter().filter(|row| !row.is_empty()) { ui.columns(row.len(), |columns| for (column, cell) in columns.iter_mut().zip(row.clone()) { draw_cell(column, cell); }); }
+                });
+                if self.config.combined_plot {
+                    strip.cell(|ui| { ui.label(egui::RichText::new(tr("Temperature and humidity")).size(14.0).strong()); draw_combined_graph(self, ui, ctx); });
+                } else {
+                    strip.cell(|ui| { ui.horizontal_wrapped(|ui| { ui.label(egui::RichText::new(tr("Temperature")).size(14.0).strong()); draw_series_legend(self, ui, Channel::Temperature); draw_crosshair_readout(self, ui); }); draw_temperature_graph(self, ui, ctx); });
+                    strip.cell(|ui| { ui.horizontal_wrapped(|ui| { ui.label(egui::RichText::new(tr("Humidity")).size(14.0).strong()); draw_series_legend(self, ui, Channel::Humidity); }); draw_humidity_graph(self, ui, ctx); });
+                }
+                if self.config.vpd_enabled {
+                    strip.cell(|ui| { ui.label(egui::RichText::new(tr("Vapor pressure deficit (VPD)")).size(14.0).strong()); draw_vpd_graph(self, ui); });
+                }
+                if self.config.rssi_plot {
+                    strip.cell(|ui| { ui.horizontal(|ui| { ui.label(egui::RichText::new(tr("Signal strength (RSSI)")).size(14.0).strong()); draw_link_stats(ui, &self.history, &self.packets); }); draw_rssi_graph(self, ui); });
+                }
+                if main_layout.footer > 0.0 {
+                    strip.cell(|ui| { ui.separator(); ui.vertical_centered(|ui| { ui.horizontal_centered(|ui| { ui.label(egui::RichText::new(tr("Author: Soběslav Holec")).size(20.0).color(ui.visuals().strong_text_color())); });});});
+                }
+            });
+        });
+
+        self.pending_view = None;
+
+        if let Some((message, created_at)) = &self.toast_message {
+            egui::Area::new("toast_area".into())
+                .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -20.0))
+                .show(ctx, |ui| {
+                    let frame = egui::Frame::popup(ui.style());
+                    frame.show(ui, |ui| { ui.label(message); });
+                });
+            if created_at.elapsed() > TOAST_DURATION {
+                self.toast_message = None;
+            }
+        }
+
+        self.draw_sensor_prompt(ctx);
+
+        if self.zoom_factor != 1.0 { self.zoom_factor = 1.0; }
+        if self.reset_plot { self.reset_plot = false; }
+        self.draw_settings_window(ctx);
+        self.draw_report_window(ctx);
+        self.draw_evidence_window(ctx);
+        self.draw_dehumidifier_window(ctx);
+        self.draw_gradient_window(ctx);
+        self.draw_virtual_sensors_window(ctx);
+        self.draw_setup_window(ctx);
+        self.draw_import_window(ctx);
+        self.draw_bookmark_window(ctx);
+        self.draw_migration_window(ctx);
+        self.draw_point_edit_window(ctx);
+        self.draw_diagnostics_window(ctx);
+        self.draw_capture_window(ctx);
+        self.draw_integrity_window(ctx);
+        self.draw_events_window(ctx);
+        self.draw_notes_window(ctx);
+        self.draw_selftest_window(ctx);
+        self.draw_unlock_window(ctx);
+        self.draw_command_palette(ctx);
+    }
+}
+
+impl TempMonitorApp {
+    fn draw_point_edit_window(&mut self, ctx: &egui::Context) {
+        let Some(dialog) = &mut self.point_edit else { return; };
+        let mut open = true;
+        let mut result = None;
+        egui::Window::new(tr("Edit readings")).open(&mut open).collapsible(false).resizable(false).show(ctx, |ui| {
+            let count = dialog.points.len();
+            if count == 1 {
+                ui.label(trf("Reading at {0}", &[&self.config.date_format.date_time_secs(dialog.points[0].timestamp)]));
+            } else {
+                ui.label(trf("{0} readings, {1} – {2}", &[&count, &self.config.date_format.date_time_secs(dialog.points[0].timestamp), &self.config.date_format.date_time_secs(dialog.points[count - 1].timestamp)]));
+            }
+            ui.add(egui::DragValue::new(&mut dialog.temp).prefix(tr("Temperature (°C): ")).speed(0.1).clamp_range(-40.0..=70.0));
+            ui.add(egui::DragValue::new(&mut dialog.hum).prefix(tr("Humidity (%): ")).clamp_range(0..=100));
+            ui.add(egui::TextEdit::singleline(&mut dialog.note).hint_text(tr("Reason (kept in edits.csv)")));
+            ui.horizontal(|ui| {
+                if ui.button(tr(if count == 1 { "Save correction" } else { "Set all to these values" })).clicked() { result = Some(PointEdit::Correct { temp: dialog.temp, hum: dialog.hum }); }
+                if ui.button(egui::RichText::new(if count == 1 { tr("🗑 Delete reading").to_owned() } else { trf("🗑 Delete {0} readings", &[&count]) }).color(egui::Color32::LIGHT_RED)).clicked() { result = Some(PointEdit::Delete); }
+            });
+            ui.label(egui::RichText::new(tr("Changes are written to the daily CSV log; every change is recorded in edits.csv in the data folder.")).weak());
+        });
+        if let (Some(edit), Some(dialog)) = (result, result.and_then(|_| self.point_edit.take())) {
+            match edit::apply(&dialog.points, edit, dialog.note.trim()) {
+                Ok(changed) => {
+                    let targets: Vec<DateTime<Local>> = dialog.points.iter().map(|p| p.timestamp).collect();
+                    match edit {
+                        PointEdit::Delete => self.history.retain(|p| !targets.contains(&p.timestamp)),
+                        PointEdit::Correct { temp, hum } => for p in self.history.iter_mut().filter(|p| targets.contains(&p.timestamp)) { p.temp = temp; p.hum = hum; },
+                    }
+                    self.history_changed();
+                    self.data_table.reload();
+                    let skipped = dialog.points.len().saturating_sub(changed);
+                    self.toast_message = Some((if skipped == 0 { trf("{0} readings updated in the CSV log", &[&changed]) } else { trf("{0} readings updated in the CSV log, {1} not found there", &[&changed, &skipped]) }, Instant::now()));
+                }
+                Err(e) => { error!("Editing readings failed: {}", e); self.toast_message = Some((trf("Editing readings failed: {0}", &[&e]), Instant::now())); }
+            }
+        } else if !open { self.point_edit = None; }
+    }
+
+    fn draw_settings_window(&mut self, ctx: &egui::Context) {
+        if self.settings_open {
+            let mut is_open = self.settings_open;
+            let old_config = self.config.clone();
+            egui::Window::new(tr("Settings")).id(egui::Id::new("settings_window")).open(&mut is_open).show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.settings_tab, SettingsTab::General, tr("General"));
+                    ui.selectable_value(&mut self.settings_tab, SettingsTab::AlertRules, tr("Alert rules"));
+                });
+                ui.separator();
+                if self.settings_tab == SettingsTab::AlertRules { edit_alert_rules(ui, &mut self.config.alert_rules, self.config.week_start); return; }
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_label(tr("Theme")).selected_text(tr(self.config.theme.label())).show_ui(ui, |ui| {
+                        for mode in ThemeMode::ALL { ui.selectable_value(&mut self.config.theme, mode, tr(mode.label())); }
+                    });
+                    let mut custom_accent = self.config.accent_color.is_some();
+                    if ui.checkbox(&mut custom_accent, tr("Accent color")).changed() { self.config.accent_color = custom_accent.then_some([0, 150, 200]); }
+                    if let Some(accent) = &mut self.config.accent_color { ui.color_edit_button_srgb(accent); }
+                });
+                egui::ComboBox::from_label("Language / Jazyk").selected_text(self.config.language.label()).show_ui(ui, |ui| {
+                    for language in Language::ALL { ui.selectable_value(&mut self.config.language, language, language.label()); }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(trf("Storage: {0} ({1})", &[&tr(paths::storage_mode().label()), &paths::data_dir().display()]));
+                    if ui.button(tr("Change...")).clicked() { self.setup_mode = paths::storage_mode(); self.setup_open = true; }
+                });
+                ui.separator();
+                ui.label(tr("Target MAC address:")); ui.add_enabled(!self.config.auto_detect_thermopro, egui::TextEdit::singleline(&mut self.config.target_mac));
+                ui.label(egui::RichText::new(trf("Supported sensors: {0}", &[&decoder::families().collect::<Vec<_>>().join(", ")])).weak());
+                ui.checkbox(&mut self.config.auto_detect_thermopro, tr("Use the first ThermoPro found (ignore MAC)"))
+                    .on_hover_text(tr("Matches the TP357 advertisement layout instead of the address; useful when the address is random or changed after a battery swap"));
+                if !self.config.target_mac.trim().is_empty() && sensors::entry(&self.config, self.config.target_mac.trim()).is_none() && ui.button(tr("Name this sensor")).on_hover_text(tr("Add the target to the known sensors to give it a name and plot color")).clicked() {
+                    let model = self.last_data_point.as_ref().map_or("TP357", |data| data.model);
+                    self.config.sensors.push(SensorEntry { mac: self.config.target_mac.trim().to_ascii_uppercase(), model: model.to_string(), ..SensorEntry::default() });
+                }
+                if !self.config.sensors.is_empty() {
+                    ui.label(tr("Known sensors:"));
+                    let mut remove = None;
+                    for (i, sensor) in self.config.sensors.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.add(egui::TextEdit::singleline(&mut sensor.name).hint_text(tr("e.g. Bedroom")).desired_width(140.0));
+                            let mut custom_color = sensor.color.is_some();
+                            if ui.checkbox(&mut custom_color, "").on_hover_text(tr("Own plot color")).changed() { sensor.color = custom_color.then_some([255, 100, 100]); }
+                            if let Some(color) = &mut sensor.color { ui.color_edit_button_srgb(color); }
+                            ui.label(egui::RichText::new(format!("{} {}", sensor.model, sensor.mac)).weak());
+                            let is_target = !self.config.auto_detect_thermopro && self.config.target_mac.eq_ignore_ascii_case(&sensor.mac);
+                            if ui.add_enabled(!is_target, egui::Button::new(tr(if is_target { "Target" } else { "Use" }))).on_hover_text(tr("Read this sensor")).clicked() { self.config.target_mac = sensor.mac.clone(); self.config.auto_detect_thermopro = false; }
+                            if ui.small_button("🗑").on_hover_text(tr("Forget sensor")).clicked() { remove = Some(i); }
+                        });
+                    }
+                    if let Some(i) = remove { self.config.sensors.remove(i); }
+                }
+                if !self.config.ignored_sensors.is_empty() && ui.button(trf("Ask again about {0} ignored sensors", &[&self.config.ignored_sensors.len()])).clicked() { self.config.ignored_sensors.clear(); }
+                ui.separator();
+                ui.add(egui::DragValue::new(&mut self.config.scan_timeout_secs).prefix(tr("Scan timeout (s): ")));
+                ui.add(egui::DragValue::new(&mut self.config.scan_pause_secs).prefix(tr("Pause between scans (s): ")));
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.config.adaptive_scan, tr("Adaptive pause")).on_hover_text(tr("Longest pause while readings hold steady, shortest while they change quickly or are near a warning threshold"));
+                    let max = self.config.adaptive_max_pause_secs;
+                    ui.add_enabled(self.config.adaptive_scan, egui::DragValue::new(&mut self.config.adaptive_min_pause_secs).prefix(tr("from (s): ")).clamp_range(1..=max));
+                    let min = self.config.adaptive_min_pause_secs;
+                    ui.add_enabled(self.config.adaptive_scan, egui::DragValue::new(&mut self.config.adaptive_max_pause_secs).prefix(tr("to (s): ")).clamp_range(min..=3600));
+                });
+                ui.separator();
+                ui.add(egui::DragValue::new(&mut self.config.duplicate_threshold_secs).prefix(tr("Duplicate interval (s): ")));
+                ui.label(tr("Records from the same device will be ignored for this duration."));
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.config.duplicate_change_enabled, tr("Save changes at once")).on_hover_text(tr("A reading that moved this much since the last saved one is saved within the duplicate interval too"));
+                    ui.add_enabled(self.config.duplicate_change_enabled, egui::DragValue::new(&mut self.config.duplicate_change_temp).prefix("Δ °C ≥ ").speed(0.1).clamp_range(0.1..=10.0).fixed_decimals(1));
+                    ui.add_enabled(self.config.duplicate_change_enabled, egui::DragValue::new(&mut self.config.duplicate_change_hum).prefix("Δ % ≥ ").clamp_range(1..=50));
+                });
+                ui.checkbox(&mut self.config.spike_filter_enabled, tr("Reject spikes")).on_hover_text(tr("Drop readings far off the recent median, e.g. 0 °C / 0 % from malformed packets. They are written to the app log instead."));
+                if self.config.spike_filter_enabled {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::DragValue::new(&mut self.config.spike_max_temp_delta).prefix(tr("Max Δ °C: ")).speed(0.1).clamp_range(0.5..=50.0));
+                        ui.add(egui::DragValue::new(&mut self.config.spike_max_hum_delta).prefix(tr("Max Δ %: ")).speed(0.5).clamp_range(1.0..=100.0));
+                        ui.add(egui::DragValue::new(&mut self.config.spike_window_secs).prefix(tr("Window (s): ")).clamp_range(60..=86400));
+                    });
+                }
+                ui.checkbox(&mut self.config.persistence_overlay, tr("Debug: mark saved / live-only / loaded points"))
+                    .on_hover_text(tr("Rings every plotted point by origin, to check the chart against the CSV log. Readings inside the duplicate interval are neither saved nor plotted."));
+                ui.separator();
+                ui.checkbox(&mut self.config.continuous_mode, tr("Continuous mode"));
+                ui.label(tr("⚠️ Continuous mode only speeds up scanning; duplicate interval still applies."));
+                ui.separator();
+                ui.checkbox(&mut self.config.courtesy_mode, tr("Courtesy mode (Ctrl+B)"));
+                ui.label(tr("Short duty-cycled scans that leave Bluetooth headphones undisturbed."));
+                ui.add(egui::DragValue::new(&mut self.config.courtesy_scan_secs).prefix(tr("Courtesy scan (s): ")).clamp_range(1..=60));
+                ui.add(egui::DragValue::new(&mut self.config.courtesy_pause_secs).prefix(tr("Courtesy pause (s): ")).clamp_range(1..=600));
+                ui.label(tr("Courtesy mode schedule:"));
+                edit_schedule_windows(ui, &mut self.config.courtesy_windows, self.config.week_start, "courtesy_weekday", tr("Add courtesy window"));
+                ui.separator();
+                ui.checkbox(&mut self.config.autostart, tr("Start with the system")).on_hover_text(tr("Keeps logging after a reboot without anyone opening the app"));
+                ui.add_enabled(self.config.autostart, egui::Checkbox::new(&mut self.config.autostart_minimized, tr("Start minimized")));
+                egui::ComboBox::from_label(tr("Sleep")).selected_text(tr(self.config.keep_awake.label())).show_ui(ui, |ui| {
+                    for mode in KeepAwakeMode::ALL { ui.selectable_value(&mut self.config.keep_awake, mode, tr(mode.label())); }
+                }).response.on_hover_text(tr("Sleep stops Bluetooth scanning and leaves gaps in the log. The display may still turn off."));
+                ui.checkbox(&mut self.config.kiosk_keep_display_on, tr("Keep the display on in kiosk mode")).on_hover_text(tr("Kiosk mode (F11) shows the readings fullscreen, e.g. on a wall-mounted display"));
+                if self.config.keep_awake == KeepAwakeMode::Scheduled {
+                    edit_schedule_windows(ui, &mut self.config.keep_awake_windows, self.config.week_start, "keep_awake_weekday", tr("Add keep-awake window"));
+                }
+                ui.separator();
+                ui.checkbox(&mut self.config.load_all_history, tr("Load full history from CSV on startup"));
+                ui.label(tr("⚠️ Restart the application for changes to take effect."));
+                if self.config.load_all_history { ui.label(egui::RichText::new(tr("WARNING: May slow down startup.")).color(egui::Color32::YELLOW)); } else {
+                    ui.add(egui::DragValue::new(&mut self.config.history_hours).prefix(tr("Keep the last ")).suffix(tr(" h in memory (0 = by points)")).clamp_range(0..=24 * 31))
+                        .on_hover_text(trf("Readings older than {0} min are merged into {1}-minute means", &[&history::FULL_RESOLUTION_MINS, &history::COARSE_MINS]));
+                    ui.add_enabled_ui(self.config.history_hours == 0, |ui| { ui.horizontal(|ui| {
+                        ui.add(egui::DragValue::new(&mut self.config.history_cap).prefix(tr("Keep in memory: ")).suffix(tr(" points")).clamp_range(10..=1_000_000).speed(10.0));
+                        egui::ComboBox::from_id_source("history_eviction").selected_text(tr(self.config.history_eviction.label())).show_ui(ui, |ui| {
+                            for strategy in HistoryEviction::ALL { ui.selectable_value(&mut self.config.history_eviction, strategy, tr(strategy.label())); }
+                        });
+                    }); });
+                }
+                ui.separator();
+                ui.checkbox(&mut self.config.retention_enabled, tr("Compress old logs")).on_hover_text(tr("Older daily logs are gzipped and summarised hourly in hourly.csv"));
+                if self.config.retention_enabled {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::DragValue::new(&mut self.config.retention_raw_days).prefix(tr("Keep raw for ")).suffix(tr(" days")).clamp_range(1..=3650));
+                        ui.add(egui::DragValue::new(&mut self.config.retention_archive_days).prefix(tr("Delete archives after ")).suffix(tr(" days (0 = never)")).clamp_range(0..=36500));
+                    });
+                }
+                let usage = self.storage_usage.get_or_insert_with(retention::storage_usage);
+                ui.label(egui::RichText::new(trf("Data folder: {0} in total; {1} daily logs ({2}), {3} archived ({4})", &[&retention::format_bytes(usage.total_bytes),
+                    &usage.raw_files, &retention::format_bytes(usage.raw_bytes), &usage.archived_files, &retention::format_bytes(usage.archived_bytes)])).weak());
+                ui.separator();
+                ui.add(egui::DragValue::new(&mut self.config.temp_warn_high).prefix(tr("Warning threshold (°C): ")).speed(0.1));
+                ui.add(egui::DragValue::new(&mut self.config.temp_warn_low).prefix(tr("Lower threshold (°C): ")).speed(0.1));
+                ui.add(egui::DragValue::new(&mut self.config.hum_warn_high).prefix(tr("Humidity upper threshold (%): ")).speed(0.5).clamp_range(0.0..=100.0));
+                ui.add(egui::DragValue::new(&mut self.config.hum_warn_low).prefix(tr("Humidity lower threshold (%): ")).speed(0.5).clamp_range(0.0..=100.0));
+                ui.checkbox(&mut self.config.show_threshold_lines, tr("Show thresholds on the plots"));
+                ui.checkbox(&mut self.config.show_event_markers, tr("Mark alerts on the plots"));
+                ui.checkbox(&mut self.config.show_notes, tr("Show notes on the plots"));
+                ui.label(egui::RichText::new(tr("The thresholds color the current values and the plot guides; alerts come from the Alert rules tab.")).weak());
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_label(tr("Colors")).selected_text(tr(self.config.color_palette.label())).show_ui(ui, |ui| {
+                        for palette in Palette::ALL { ui.selectable_value(&mut self.config.color_palette, palette, tr(palette.label())); }
+                    }).response.on_hover_text(tr("Colors of the plotted points and the current values"));
+                    draw_palette_preview(ui, self.config.color_palette);
+                });
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut self.config.temp_color_min).prefix(tr("Colors from (°C): ")).speed(0.5).clamp_range(-50.0..=self.config.temp_color_max - 1.0));
+                    ui.add(egui::DragValue::new(&mut self.config.temp_color_max).prefix(tr("to ")).speed(0.5).clamp_range(self.config.temp_color_min + 1.0..=100.0));
+                    ui.add(egui::DragValue::new(&mut self.config.hum_color_min).prefix(tr("Colors from (%): ")).speed(0.5).clamp_range(0.0..=self.config.hum_color_max - 1.0));
+                    ui.add(egui::DragValue::new(&mut self.config.hum_color_max).prefix(tr("to ")).speed(0.5).clamp_range(self.config.hum_color_min + 1.0..=100.0));
+                });
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_label(tr("Night shading")).selected_text(tr(self.config.night_shading.label())).show_ui(ui, |ui| {
+                        for mode in NightShading::ALL { ui.selectable_value(&mut self.config.night_shading, mode, tr(mode.label())); }
+                    });
+                });
+                match self.config.night_shading {
+                    NightShading::Location => { ui.horizontal(|ui| {
+                        ui.add(egui::DragValue::new(&mut self.config.latitude).prefix(tr("Latitude: ")).speed(0.01).clamp_range(-90.0..=90.0));
+                        ui.add(egui::DragValue::new(&mut self.config.longitude).prefix(tr("Longitude (east +): ")).speed(0.01).clamp_range(-180.0..=180.0));
+                    }); }
+                    NightShading::Manual => { ui.horizontal(|ui| {
+                        ui.label(tr("Sunrise:")); ui.add(egui::TextEdit::singleline(&mut self.config.sunrise).desired_width(50.0));
+                        ui.label(tr("Sunset:")); ui.add(egui::TextEdit::singleline(&mut self.config.sunset).desired_width(50.0));
+                    }); }
+                    NightShading::TimeZone => { ui.label(egui::RichText::new(tr("Rough: assumes 45° latitude and the middle of your time zone.")).weak()); }
+                    NightShading::Off => {}
+                }
+                ui.add(egui::DragValue::new(&mut self.config.aggregate_plot_hours).prefix(tr("Plot hourly min/max/mean beyond ")).suffix(tr(" h (0 = never)")).clamp_range(0..=8760))
+                    .on_hover_text(trf("Daily beyond {0} days; long saved views then load the summaries instead of every reading", &[&DAILY_PLOT_AFTER_DAYS]));
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.config.smoothing_enabled, tr("Show moving average"));
+                    ui.add_enabled(self.config.smoothing_enabled, egui::DragValue::new(&mut self.config.smoothing_window_mins).suffix(tr(" min")).clamp_range(1..=1440));
+                });
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.config.break_lines_at_gaps, tr("Break lines at gaps")).on_hover_text(tr("No line is drawn across a stretch without readings"));
+                    let interval = expected_reading_interval_secs(&self.config);
+                    ui.add_enabled(self.config.break_lines_at_gaps, egui::DragValue::new(&mut self.config.gap_intervals).prefix(tr("longer than ")).suffix(format!(" × {} s", interval)).clamp_range(2..=100));
+                });
+                ui.add(egui::DragValue::new(&mut self.config.trend_window_mins).prefix(tr("Trend over the last ")).suffix(tr(" min")).clamp_range(5..=360))
+                    .on_hover_text(tr("Rate of change shown next to the current values and used by the change alert rules"));
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.config.forecast_enabled, tr("Show forecast")).on_hover_text(tr("Extrapolates the last hour as a dashed line on the temperature plot"));
+                    ui.add_enabled_ui(self.config.forecast_enabled, |ui| {
+                        egui::ComboBox::from_id_source("forecast_method").selected_text(tr(self.config.forecast_method.label())).show_ui(ui, |ui| {
+                            for method in ForecastMethod::ALL { ui.selectable_value(&mut self.config.forecast_method, method, tr(method.label())); }
+                        });
+                        ui.add(egui::DragValue::new(&mut self.config.forecast_horizon_mins).prefix(tr("for ")).suffix(tr(" min")).clamp_range(10..=360));
+                    });
+                });
+                ui.checkbox(&mut self.config.sound_alarm_enabled, tr("Sound alarm when a threshold is exceeded"));
+                if self.config.sound_alarm_enabled {
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Sound file:"));
+                        ui.add(egui::TextEdit::singleline(&mut self.config.sound_alarm_file).hint_text(tr("built-in beep")).desired_width(180.0));
+                        if ui.button(tr("Browse...")).clicked() {
+                            if let Some(path) = rfd::FileDialog::new().add_filter("Audio", &["wav", "mp3", "ogg", "flac"]).pick_file() { self.config.sound_alarm_file = path.display().to_string(); }
+                        }
+                        if ui.button(tr("Test")).clicked() { alarm::play_test(&self.config.sound_alarm_file); }
+                    });
+                    ui.checkbox(&mut self.config.offline_sound_alarm, tr("Also sound the alarm when the sensor goes offline"));
+                }
+                ui.checkbox(&mut self.config.device_alarm_passthrough, tr("Pass through the sensor's own alarm (experimental)"))
+                    .on_hover_text(trf("Treats byte {0} & 0x{1} of the advertisement as the on-device alarm flag", &[&self.config.device_alarm_byte, &format!("{:02X}", self.config.device_alarm_mask)]));
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.config.heartbeat_dot, tr("Heartbeat dot")).on_hover_text(tr("Blinks in the toolbar on every decoded advertisement"));
+                    ui.checkbox(&mut self.config.heartbeat_tick, tr("Tick sound"));
+                });
+                ui.add(egui::DragValue::new(&mut self.config.offline_missed_intervals).prefix(tr("Sensor offline after missed readings: ")).clamp_range(1..=100));
+                ui.separator();
+                ui.add(egui::DragValue::new(&mut self.config.temp_offset).prefix(tr("Temperature offset (°C): ")).speed(0.1).clamp_range(-10.0..=10.0));
+                ui.add(egui::DragValue::new(&mut self.config.hum_offset).prefix(tr("Humidity offset (%): ")).speed(0.5).clamp_range(-30.0..=30.0));
+                ui.label(tr("Calibration offsets are applied before readings are logged or displayed."));
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.config.thi_enabled, tr("Show temperature-humidity index (THI) for"));
+                    egui::ComboBox::from_id_source("thi_species").selected_text(tr(self.config.thi_species.label())).show_ui(ui, |ui| {
+                        for species in Species::ALL { ui.selectable_value(&mut self.config.thi_species, species, tr(species.label())); }
+                    });
+                });
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.config.vpd_enabled, tr("VPD for growers")).on_hover_text(tr("Vapor pressure deficit under the humidity reading and as a plot with the target band"));
+                    ui.add_enabled(self.config.vpd_enabled, egui::DragValue::new(&mut self.config.vpd_leaf_offset).prefix(tr("leaf offset: ")).suffix(" °C").speed(0.1).clamp_range(-10.0..=5.0))
+                        .on_hover_text(tr("Leaf temperature minus air temperature; leaves under lights usually run 1 to 3 °C cooler"));
+                    ui.add_enabled(self.config.vpd_enabled, egui::DragValue::new(&mut self.config.vpd_target_low).prefix(tr("target: ")).speed(0.05).clamp_range(0.0..=self.config.vpd_target_high).fixed_decimals(2));
+                    ui.add_enabled(self.config.vpd_enabled, egui::DragValue::new(&mut self.config.vpd_target_high).prefix("– ").suffix(" kPa").speed(0.05).clamp_range(self.config.vpd_target_low..=5.0).fixed_decimals(2));
+                });
+                ui.checkbox(&mut self.config.rssi_plot, tr("Signal strength plot")).on_hover_text(tr("RSSI of every live reading and packets received per hour, for finding a spot with reliable reception"));
+                ui.checkbox(&mut self.config.comfort_badges, tr("Show mold-risk and comfort badges in the toolbar")).on_hover_text(tr("From the latest temperature and humidity; alert rules can use the mold risk too"));
+                ui.checkbox(&mut self.config.export_derived_columns, tr("Include derived columns (dew point) in exports"));
+                ui.horizontal(|ui| {
+                    ui.label(tr("Plot image size:"));
+                    ui.add(egui::DragValue::new(&mut self.config.plot_image_size.width).suffix(" px").clamp_range(ImageSize::MIN..=ImageSize::MAX).speed(10.0));
+                    ui.add(egui::DragValue::new(&mut self.config.plot_image_size.height).prefix("× ").suffix(" px").clamp_range(ImageSize::MIN..=ImageSize::MAX).speed(10.0));
+                }).response.on_hover_text(tr("Resolution of File → Save plot as image; text grows with the width"));
+                ui.checkbox(&mut self.config.quick_export_enabled, tr("Quick export hotkey"));
+                if self.config.quick_export_enabled {
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Shortcut:"));
+                        let valid = quick_export::parse_shortcut(&self.config.quick_export_shortcut).is_some();
+                        ui.add(egui::TextEdit::singleline(&mut self.config.quick_export_shortcut).desired_width(110.0).text_color_opt((!valid).then_some(egui::Color32::RED)));
+                        egui::ComboBox::from_id_source("quick_export_format").selected_text(self.config.quick_export_format.filter_name()).show_ui(ui, |ui| {
+                            for format in ExportFormat::ALL { ui.selectable_value(&mut self.config.quick_export_format, format, format.filter_name()); }
+                        });
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Folder:"));
+                        ui.add(egui::TextEdit::singleline(&mut self.config.quick_export_dir).hint_text(tr("exports in the data folder")).desired_width(220.0));
+                        if ui.button(tr("Browse...")).clicked() { if let Some(dir) = rfd::FileDialog::new().pick_folder() { self.config.quick_export_dir = dir.display().to_string(); } }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(tr("File name:"));
+                        ui.add(egui::TextEdit::singleline(&mut self.config.quick_export_name).desired_width(180.0)).on_hover_text(trf("Placeholders: {0}", &[&quick_export::NAME_PLACEHOLDERS]));
+                    });
+                }
+                ui.separator();
+                ui.label(tr("Scheduled pauses (no logging or warnings):"));
+                edit_schedule_windows(ui, &mut self.config.pause_windows, self.config.week_start, "pause_weekday", tr("Add pause window"));
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label(tr("Simple mode PIN:"));
+                    ui.add(egui::TextEdit::singleline(&mut self.config.admin_pin).password(true).hint_text(tr("none")).desired_width(80.0))
+                        .on_hover_text(tr("Needed to leave simple mode (File → Simple mode); leave empty to allow anyone"));
+                });
+                ui.horizontal(|ui| { ui.label(tr("Room:")); ui.add(egui::TextEdit::singleline(&mut self.config.room_name).hint_text(tr("e.g. Cellar")).desired_width(160.0)); });
+                egui::CollapsingHeader::new(tr("Alert messages")).show(ui, |ui| {
+                    ui.label(trf("Used by every alert channel. Placeholders: {0}. Leave empty for the default.", &[&templates::PLACEHOLDERS.join(" ")]));
+                    egui::Grid::new("alert_templates").num_columns(2).show(ui, |ui| {
+                        for kind in AlertKind::ALL {
+                            ui.label(kind.id());
+                            let template = self.config.alert_templates.entry(kind.id().to_string()).or_default();
+                            ui.add(egui::TextEdit::singleline(template).hint_text(templates::default_template(kind)).desired_width(280.0));
+                            ui.end_row();
+                        }
+                    });
+                    self.config.alert_templates.retain(|_, t| !t.trim().is_empty());
+                });
+                ui.checkbox(&mut self.config.webhook_enabled, tr("Send alerts to a webhook (JSON POST)"));
+                if self.config.webhook_enabled {
+                    ui.horizontal(|ui| {
+                        ui.label(tr("URL:"));
+                        ui.add(egui::TextEdit::singleline(&mut self.config.webhook_url).hint_text(tr("https://ntfy.sh/my-topic")).desired_width(220.0));
+                        if ui.button(tr("Test")).clicked() { webhook::send_test(&self.config.webhook_url); }
+                    });
+                }
+                ui.checkbox(&mut self.config.telegram_enabled, tr("Send alerts to Telegram and answer /status"))
+                    .on_hover_text(tr("Create a bot with @BotFather; only the chat below gets alerts and replies"));
+                if self.config.telegram_enabled {
+                    egui::Grid::new("telegram_grid").num_columns(2).show(ui, |ui| {
+                        ui.label(tr("Bot token:"));
+                        ui.add(egui::TextEdit::singleline(&mut self.config.telegram_bot_token).password(true).desired_width(220.0));
+                        ui.end_row();
+                        ui.label(tr("Chat id:"));
+                        ui.horizontal(|ui| {
+                            ui.add(egui::TextEdit::singleline(&mut self.config.telegram_chat_id).hint_text(tr("123456789 or @channel")).desired_width(150.0));
+                            if ui.button(tr("Test")).clicked() { telegram::send_test(&self.config.telegram_bot_token, &self.config.telegram_chat_id); }
+                        });
+                        ui.end_row();
+                    });
+                }
+                ui.checkbox(&mut self.config.email_enabled, tr("Send alerts by email (SMTP)"));
+                if self.config.email_enabled {
+                    egui::Grid::new("email_grid").num_columns(2).show(ui, |ui| {
+                        ui.label(tr("SMTP server:"));
+                        ui.horizontal(|ui| {
+                            ui.add(egui::TextEdit::singleline(&mut self.config.smtp_host).hint_text(tr("smtp.example.com")).desired_width(150.0));
+                            ui.add(egui::DragValue::new(&mut self.config.smtp_port).prefix(tr("port ")).clamp_range(1..=65535));
+                            let before = self.config.smtp_security;
+                            egui::ComboBox::from_id_source("smtp_security").selected_text(tr(self.config.smtp_security.label())).show_ui(ui, |ui| {
+                                for security in email::SmtpSecurity::ALL { ui.selectable_value(&mut self.config.smtp_security, security, tr(security.label())); }
+                            });
+                            // Follow the usual port unless a custom one was entered
+                            if self.config.smtp_security != before && self.config.smtp_port == before.default_port() { self.config.smtp_port = self.config.smtp_security.default_port(); }
+                        });
+                        ui.end_row();
+                        ui.label(tr("User name:"));
+                        ui.add(egui::TextEdit::singleline(&mut self.config.smtp_username).desired_width(220.0));
+                        ui.end_row();
+                        ui.label(tr("Password:"));
+                        ui.add(egui::TextEdit::singleline(&mut self.config.smtp_password).password(true).desired_width(220.0));
+                        ui.end_row();
+                        ui.label(tr("From:"));
+                        ui.add(egui::TextEdit::singleline(&mut self.config.email_from).hint_text(tr("monitor@example.com")).desired_width(220.0));
+                        ui.end_row();
+                        ui.label(tr("To:"));
+                        ui.horizontal(|ui| {
+                            ui.add(egui::TextEdit::singleline(&mut self.config.email_to).hint_text(tr("lab@example.com, qa@example.com")).desired_width(220.0));
+                            if ui.button(tr("Test")).clicked() { email::send_test(&self.config); }
+                        });
+                        ui.end_row();
+                    });
+                    ui.add(egui::DragValue::new(&mut self.config.email_min_interval_mins).prefix(tr("At most one email every (min): ")).clamp_range(0..=1440))
+                        .on_hover_text(tr("Alerts in between are collected and sent together, none are dropped"));
+                    egui::CollapsingHeader::new(tr("Email subjects")).show(ui, |ui| {
+                        ui.label(trf("Placeholders: {0}. Leave empty for the default.", &[&email::SUBJECT_PLACEHOLDERS.join(" ")]));
+                        egui::Grid::new("email_subject_templates").num_columns(2).show(ui, |ui| {
+                            for kind in AlertKind::ALL {
+                                ui.label(kind.id());
+                                let template = self.config.email_subject_templates.entry(kind.id().to_string()).or_default();
+                                ui.add(egui::TextEdit::singleline(template).hint_text(email::DEFAULT_SUBJECT).desired_width(280.0));
+                                ui.end_row();
+                            }
+                        });
+                        self.config.email_subject_templates.retain(|_, t| !t.trim().is_empty());
+                    });
+                }
+                ui.separator();
+                ui.checkbox(&mut self.config.battery_saver, tr("Battery saver (longer scan pauses and slower refresh on battery)"));
+                ui.add_enabled(self.config.battery_saver, egui::Checkbox::new(&mut self.config.battery_saver_stops_sharing, tr("Also pause snapshot sharing on battery")));
+                egui::ComboBox::from_label(tr("Refresh")).selected_text(tr(self.config.repaint_mode.label())).show_ui(ui, |ui| {
+                    for mode in RepaintMode::ALL { ui.selectable_value(&mut self.config.repaint_mode, mode, tr(mode.label())); }
+                }).response.on_hover_text(tr("Redrawing only when a reading or status arrives saves CPU and battery; the clock keeps relative times ticking"));
+                ui.add(egui::DragValue::new(&mut self.config.share_port).prefix(tr("Snapshot sharing port: ")).clamp_range(1024..=65535));
+                ui.add(egui::DragValue::new(&mut self.config.share_expiry_hours).prefix(tr("Snapshot link expires after (h, 0 = never): ")).clamp_range(0..=720));
+                ui.checkbox(&mut self.config.share_on_network, tr("Let other devices on the network open snapshot links")).on_hover_text(tr("Off: links only open on this PC"));
+                ui.separator();
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_label(tr("First day of week")).selected_text(tr(self.config.week_start.label())).show_ui(ui, |ui| {
+                        for week_start in WeekStart::ALL { ui.selectable_value(&mut self.config.week_start, week_start, tr(week_start.label())); }
+                    });
+                    egui::ComboBox::from_label(tr("Date format")).selected_text(tr(self.config.date_format.label())).show_ui(ui, |ui| {
+                        for format in DateFormat::ALL { ui.selectable_value(&mut self.config.date_format, format, tr(format.label())); }
+                    });
+                });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_label(tr("Log level")).selected_text(&self.config.log_level).show_ui(ui, |ui| {
+                        for level in logging::LEVELS { ui.selectable_value(&mut self.config.log_level, level.to_string(), level); }
+                    });
+                    ui.label(egui::RichText::new(logging::log_path().display().to_string()).weak());
+                });
+                ui.separator();
+                ui.checkbox(&mut self.config.influx_enabled, tr("Forward readings to InfluxDB v2"));
+                if self.config.influx_enabled {
+                    egui::Grid::new("influx_grid").num_columns(2).show(ui, |ui| {
+                        ui.label(tr("URL:")); ui.text_edit_singleline(&mut self.config.influx_url); ui.end_row();
+                        ui.label(tr("Organization:")); ui.text_edit_singleline(&mut self.config.influx_org); ui.end_row();
+                        ui.label(tr("Bucket:")); ui.text_edit_singleline(&mut self.config.influx_bucket); ui.end_row();
+                        ui.label(tr("Token:")); ui.add(egui::TextEdit::singleline(&mut self.config.influx_token).password(true)); ui.end_row();
+                        ui.label(tr("Measurement:")); ui.text_edit_singleline(&mut self.config.influx_measurement); ui.end_row();
+                    });
+                }
+            });
+            if self.config.autostart != old_config.autostart {
+                if let Err(e) = autostart::set(self.config.autostart) {
+                    error!("Changing the start-with-system entry failed: {}", e);
+                    self.toast_message = Some((trf("Could not change start with the system: {0}", &[&e]), Instant::now()));
+                    self.config.autostart = old_config.autostart;
+                }
+            }
+            if !is_open || self.config != old_config { self.sync_config(&old_config); }
+            if !is_open { self.storage_usage = None; }
+            if self.config.retention_raw_days != old_config.retention_raw_days || self.config.retention_archive_days != old_config.retention_archive_days { self.maintenance_ran_at = None; }
+            self.settings_open = is_open;
+        }
+    }
+
+    fn draw_setup_window(&mut self, ctx: &egui::Context) {
+        if !self.setup_open { return; }
+        egui::Window::new(tr("Setup")).collapsible(false).resizable(false).anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0)).show(ctx, |ui| {
+            ui.label(tr("Where should Temperature Monitor keep its configuration and CSV logs?"));
+            ui.separator();
+            ui.radio_value(&mut self.setup_mode, StorageMode::Installed, tr("Installed – per-user folders (recommended)"));
+            ui.radio_value(&mut self.setup_mode, StorageMode::Portable, tr("Portable – next to the executable (USB stick)"));
+            ui.label(egui::RichText::new(trf("Executable folder: {0}", &[&paths::exe_dir().display()])).color(egui::Color32::GRAY));
+            if self.setup_mode == StorageMode::Portable { ui.label(tr("A 'portable.flag' file will be created next to the executable.")); }
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button(tr("Continue")).clicked() { self.apply_storage_mode(self.setup_mode); self.setup_open = false; }
+                if !paths::is_first_run() && ui.button(tr("Cancel")).clicked() { self.setup_open = false; }
+            });
+        });
+    }
+
+    fn draw_import_window(&mut self, ctx: &egui::Context) {
+        let Some(job) = &self.import_job else { return; };
+        let progress = job.progress();
+        if progress.is_running() { ctx.request_repaint_after(Duration::from_millis(200)); }
+        let mut close = false;
+        egui::Window::new(tr("Import history")).collapsible(false).resizable(false).show(ctx, |ui| {
+            ui.label(trf("Source: {0}", &[&job.source.display()]));
+            ui.add(egui::ProgressBar::new(progress.fraction()).show_percentage());
+            ui.label(trf("{0} / {1} rows, {2} rows/s", &[&progress.rows_done, &progress.rows_total, &format!("{:.0}", progress.rows_per_sec())]));
+            match &progress.state {
+                ImportState::Reading => { ui.label(tr("Reading file...")); }
+                ImportState::Importing => { ui.label(trf("Remaining: {0}", &[&progress.eta().map_or(tr("estimating...").to_string(), |eta| format!("{} s", eta.as_secs()))])); }
+                ImportState::Done => { ui.label(trf("Done: {0} rows imported, {1} skipped (duplicates or unreadable).", &[&progress.imported, &progress.skipped])); }
+                ImportState::Cancelled => { ui.label(trf("Cancelled: {0} rows imported before stopping.", &[&progress.imported])); }
+                ImportState::Failed(e) => { ui.label(egui::RichText::new(trf("Import failed: {0}", &[&e])).color(egui::Color32::RED)); }
+            }
+            if progress.is_running() {
+                if ui.button(tr("Cancel")).clicked() { job.cancel(); }
+            } else if ui.button(tr("Close")).clicked() { close = true; }
+        });
+        if close {
+            if progress.imported > 0 { self.start_history_load(); }
+            self.import_job = None;
+        }
+    }
+
+    // Marks the configuration for saving (if it differs from `old_config`) and pushes it to the background tasks
+    fn sync_config(&mut self, old_config: &Config) {
+        if self.config != *old_config { info!("Configuration change detected."); self.config_changed = true; }
+        if self.config.log_level != old_config.log_level { logging::set_level(&self.config.log_level); }
+        if self.config.language != old_config.language { i18n::set_language(self.config.language); }
+        if let Ok(mut shared) = self.shared_config.lock() { *shared = self.config.clone(); debug!("Shared configuration updated."); }
+        if scan_settings_changed(old_config, &self.config) {
+            if let Some(tx) = &self.scan_settings_tx { let _ = tx.send(()); }
+        }
+    }
+
+    fn switch_profile(&mut self, name: &str) {
+        let old_config = self.config.clone();
+        if !profiles::switch(&mut self.config, name) { return; }
+        info!("Switched to profile '{}' (sensor {}).", name, self.config.target_mac);
+        self.sync_config(&old_config);
+        save_config(&self.config);
+        self.toast_message = Some((trf("Profile: {0}", &[&name]), Instant::now()));
+    }
+
+    fn import_settings(&mut self, path: &Path) {
+        match profiles::import(path, &self.config) {
+            Ok(imported) => {
+                let old_config = std::mem::replace(&mut self.config, imported);
+                info!("Imported settings from '{}'.", path.display());
+                self.sync_config(&old_config);
+                save_config(&self.config);
+                self.toast_message = Some((trf("Settings imported from {0}", &[&path.display()]), Instant::now()));
+            }
+            Err(e) => { error!("Settings import from '{}' failed: {}", path.display(), e); self.toast_message = Some((trf("Settings import failed: {0}", &[&e]), Instant::now())); }
+        }
+    }
+
+    // Points every log, sink and reader at `folder` (empty: the storage mode's folder) and reloads what is shown
+    fn open_data_folder(&mut self, folder: String) {
+        if !folder.is_empty() && !Path::new(&folder).is_dir() {
+            warn!("Data folder '{}' not found.", folder);
+            self.toast_message = Some((trf("Folder '{0}' not found", &[&folder]), Instant::now()));
+            self.config.recent_data_folders.retain(|f| *f != folder);
+            return;
+        }
+        let old_config = self.config.clone();
+        paths::set_data_dir_override((!folder.is_empty()).then(|| PathBuf::from(&folder)));
+        paths::ensure_dirs();
+        info!("Switched data folder to '{}'.", paths::data_dir().display());
+        self.config.recent_data_folders.retain(|f| *f != folder);
+        if !folder.is_empty() { self.config.recent_data_folders.insert(0, folder.clone()); }
+        self.config.recent_data_folders.truncate(MAX_RECENT_DATA_FOLDERS);
+        self.config.data_folder = folder;
+        self.toast_message = Some((integrity::recover().unwrap_or_else(|| trf("Data folder: {0}", &[&paths::data_dir().display()])), Instant::now()));
+        self.damaged_logs = integrity::check_logs(Some(STARTUP_CHECK_DAYS));
+        if !self.damaged_logs.is_empty() { self.integrity_open = true; }
+        self.sleep_periods = sleep::read(&sleep::sleep_log_path());
+        self.pause_periods = schedule::read(&schedule::pause_log_path());
+        (self.storage_usage, self.maintenance_ran_at, self.pending_view) = (None, None, None);
+        self.zoom_history.clear();
+        self.reset_plot = true;
+        self.start_history_load();
+        if self.main_view == MainView::Data { self.data_table.reload(); }
+        self.sync_config(&old_config);
+        save_config(&self.config);
+    }
+
+    fn refresh_dehumidifier_stats(&mut self) {
+        let end = Local::now();
+        let points = load_history_range(end - chrono::Duration::days(self.dehumidifier_days), end);
+        let max_gap = chrono::Duration::seconds((expected_reading_interval_secs(&self.config) * 3) as i64);
+        self.dehumidifier_stats = dehumidifier::daily_time_above(&points, self.config.rh_setpoint, max_gap);
+    }
+
+    fn refresh_gradient(&mut self) {
+        let end = Local::now();
+        let start = end - chrono::Duration::days(self.gradient_days);
+        let folder = |dir: &str| if dir.trim().is_empty() { paths::data_dir() } else { PathBuf::from(dir.trim()) };
+        let a = load_history_range_in(&folder(&self.config.gradient_sensor_a_dir), start, end);
+        let b = load_history_range_in(&folder(&self.config.gradient_sensor_b_dir), start, end);
+        // Two copies of the app scan independently, so readings are paired within one reading interval
+        let max_pair = chrono::Duration::seconds(expected_reading_interval_secs(&self.config) as i64);
+        self.gradient_points = gradient::difference(&a, &b, max_pair);
+        info!("Sensor gradient: {} paired points from {} / {} readings.", self.gradient_points.len(), a.len(), b.len());
+    }
+
+    fn refresh_virtual_sensor(&mut self) {
+        let end = Local::now();
+        let max_pair = chrono::Duration::seconds(expected_reading_interval_secs(&self.config) as i64);
+        self.virtual_points = self.config.virtual_sensors.get(self.virtual_selected).map(|s| s.load(end - chrono::Duration::days(self.virtual_days), end, max_pair)).unwrap_or_default();
+    }
+
+    fn draw_diagnostics_window(&mut self, ctx: &egui::Context) {
+        if !self.diagnostics_open { return; }
+        use egui_plot::{GridMark, Legend, Line, Plot, PlotPoints};
+        let sinks: Vec<(Sink, sink_stats::SinkStats)> = Sink::ALL.into_iter()
+            .filter(|sink| match sink { Sink::Csv => true, Sink::Influx => self.config.influx_enabled, Sink::Webhook => self.config.webhook_enabled })
+            .map(|sink| (sink, sink_stats::snapshot(sink))).collect();
+        let scan = scan_stats::snapshot();
+        let time = |t: Option<DateTime<Local>>| t.map_or("–".to_string(), |t| t.format("%H:%M:%S").to_string());
+        let mut is_open = self.diagnostics_open;
+        egui::Window::new(tr("Diagnostics")).open(&mut is_open).default_width(600.0).show(ctx, |ui| {
+            ui.label(egui::RichText::new(tr("Bluetooth scanner since start")).strong());
+            egui::Grid::new("scan_stats").num_columns(2).striped(true).show(ui, |ui| {
+                ui.label(tr("Status")); ui.label(tr(&self.scan_status)); ui.end_row();
+                ui.label(tr("Adapter")); ui.label(match &scan.adapter_info { Some(info) => format!("{} ({})", tr(scan.adapter.label()), info), None => tr(scan.adapter.label()).to_string() }); ui.end_row();
+                if scan.failures > 0 { ui.label(tr("Reconnect attempts")); ui.label(trf("{0} failed, next at {1}", &[&scan.failures, &time(scan.retry_at)])); ui.end_row(); }
+                ui.label(tr("Last scan started")); ui.label(trf("{0} ({1} scans)", &[&time(scan.scan_started), &scan.scans])); ui.end_row();
+                ui.label(tr("Last scan stopped")); ui.label(time(scan.scan_stopped)); ui.end_row();
+                ui.label(tr("Advertisements seen")); ui.label(scan.advertisements.to_string()); ui.end_row();
+                ui.label(tr("Matching the target")); ui.label(scan.matching.to_string()); ui.end_row();
+                ui.label(tr("Parse failures"));
+                ui.label(egui::RichText::new(format!("{}{}", scan.parse_failures, scan.decode_rate().map_or(String::new(), |r| trf(" ({0} % decoded)", &[&format!("{:.0}", r * 100.0)]))))
+                    .color(if scan.parse_failures > 0 { egui::Color32::LIGHT_RED } else { ui.visuals().text_color() }));
+                ui.end_row();
+                ui.label(tr("Last Bluetooth error"));
+                match &scan.last_error { Some((at, message)) => ui.label(egui::RichText::new(format!("{} {}", at.format("%H:%M:%S"), message)).color(egui::Color32::LIGHT_RED)), None => ui.label(tr("None")) };
+                ui.end_row();
+            });
+            ui.separator();
+            ui.label(egui::RichText::new(tr("Sink writes since start")).strong());
+            egui::Grid::new("sink_stats").striped(true).show(ui, |ui| {
+                for title in ["Sink", "Writes", "Failures", "p50 (ms)", "p95 (ms)", "p99 (ms)"] { ui.label(egui::RichText::new(tr(title)).strong()); }
+                ui.end_row();
+                for (sink, stats) in &sinks {
+                    ui.label(tr(sink.label()));
+                    ui.label(stats.writes.to_string());
+                    ui.label(egui::RichText::new(stats.failures.to_string()).color(if stats.failures > 0 { egui::Color32::LIGHT_RED } else { ui.visuals().text_color() }));
+                    for p in [0.5, 0.95, 0.99] { ui.label(stats.percentile(p).map(|ms| format!("{:.1}", ms)).unwrap_or_else(|| "–".to_string())); }
+                    ui.end_row();
+                }
+            });
+            ui.separator();
+            ui.label(egui::RichText::new(trf("Write latency (last {0} writes per sink)", &[&sink_stats::MAX_SAMPLES])).strong());
+            Plot::new("sink_latency").height(220.0).legend(Legend::default()).allow_scroll(false)
+                .x_axis_formatter(|mark: GridMark, _, _| DateTime::from_timestamp(mark.value as i64, 0).unwrap_or_default().with_timezone(&Local).format("%H:%M").to_string())
+                .y_axis_formatter(|mark: GridMark, _, _| format!("{} ms", mark.value))
+                .show(ui, |plot_ui| {
+                    for (sink, stats) in &sinks {
+                        let points: Vec<[f64; 2]> = stats.samples.iter().map(|(t, ms)| [t.timestamp_millis() as f64 / 1000.0, *ms]).collect();
+                        plot_ui.line(Line::new(PlotPoints::new(points)).name(tr(sink.label())));
+                    }
+                });
+        });
+        self.diagnostics_open = is_open;
+    }
+
+    // Live hex view: one row per packet, bytes that differ from the previous packet in a stronger color
+    fn draw_capture_window(&mut self, ctx: &egui::Context) {
+        if !self.capture_open { return; }
+        let mut is_open = self.capture_open;
+        egui::Window::new(tr("Advertisement capture")).open(&mut is_open).default_width(640.0).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let mut on = capture::active();
+                if ui.checkbox(&mut on, tr("Capture")).changed() { capture::set_active(on); info!("Advertisement capture {}.", if on { "started" } else { "stopped" }); }
+                ui.label(egui::RichText::new(trf("Every advertisement of the sensor is appended to {0}", &[&capture::capture_path().display()])).weak());
+            });
+            ui.horizontal(|ui| {
+                ui.label(trf("{0} packets shown (last {1})", &[&self.capture_view.records.len(), &capture::VIEW_ROWS]));
+                if ui.button(tr("Clear view")).clicked() { self.capture_view.records.clear(); }
+            });
+            ui.separator();
+            let width = self.capture_view.records.iter().map(|r| r.payload.len() + 2).max().unwrap_or(0);
+            ui.label(egui::RichText::new(format!("{:<14}{:>5}  {}", tr("Time"), "RSSI", (0..width).map(|i| format!("{:02}", i)).collect::<Vec<_>>().join(" "))).monospace().strong());
+            egui::ScrollArea::vertical().max_height(360.0).stick_to_bottom(true).show(ui, |ui| {
+                let mut previous: Vec<u8> = Vec::new();
+                for record in &self.capture_view.records {
+                    let bytes = record.bytes();
+                    let mut job = egui::text::LayoutJob::default();
+                    let font = egui::FontId::monospace(13.0);
+                    let plain = egui::TextFormat::simple(font.clone(), ui.visuals().weak_text_color());
+                    let rssi = record.rssi.map_or("–".to_string(), |r| r.to_string());
+                    job.append(&format!("{:<14}{:>5} ", record.timestamp.format("%H:%M:%S%.3f"), rssi), 0.0, plain.clone());
+                    for (byte, changed) in bytes.iter().zip(capture::changed(&previous, &bytes)) {
+                        let format = if changed { egui::TextFormat::simple(font.clone(), egui::Color32::from_rgb(255, 170, 60)) } else { egui::TextFormat::simple(font.clone(), ui.visuals().text_color()) };
+                        job.append(&format!("{:02X}", byte), 6.0, format);
+                    }
+                    ui.label(job).on_hover_text(trf("Company id {0}, payload {1}", &[&format!("0x{:04X}", record.company_id), &capture::hex(&record.payload)]));
+                    previous = bytes;
+                }
+            });
+        });
+        self.capture_open = is_open;
+    }
+
+    fn draw_integrity_window(&mut self, ctx: &egui::Context) {
+        if !self.integrity_open { return; }
+        let mut is_open = self.integrity_open;
+        let mut repair = None;
+        egui::Window::new(tr("Log check")).open(&mut is_open).default_width(560.0).show(ctx, |ui| {
+            if self.damaged_logs.is_empty() { ui.label(tr("✔ No damaged rows found.")); return; }
+            ui.label(tr("These rows could not be read, usually because a crash or power cut interrupted a write. Repairing moves them to a .corrupt.txt file next to the log."));
+            egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                for (i, damaged) in self.damaged_logs.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new(damaged.path.file_name().and_then(|n| n.to_str()).unwrap_or_default()).strong());
+                        ui.label(trf("{0} damaged rows", &[&damaged.bad_lines.len()]));
+                        if ui.button(tr("Repair")).clicked() { repair = Some(i); }
+                    });
+                    for (line, text) in damaged.bad_lines.iter().take(5) { ui.label(egui::RichText::new(format!("  {}: {}", line, text)).monospace().weak()); }
+                    if damaged.bad_lines.len() > 5 { ui.label(egui::RichText::new(trf("  … and {0} more", &[&(damaged.bad_lines.len() - 5)])).weak()); }
+                }
+            });
+        });
+        if let Some(i) = repair {
+            let damaged = self.damaged_logs.remove(i);
+            self.toast_message = Some((match integrity::repair(&damaged) {
+                Ok(moved) => trf("Moved {0} damaged rows aside", &[&moved]),
+                Err(e) => { error!("Repairing '{}' failed: {}", damaged.path.display(), e); trf("Repair failed: {0}", &[&e]) }
+            }, Instant::now()));
+        }
+        self.integrity_open = is_open;
+    }
+
+    fn draw_events_window(&mut self, ctx: &egui::Context) {
+        if !self.events_open { return; }
+        let mut is_open = self.events_open;
+        let mut show = None;
+        egui::Window::new(tr("Events")).open(&mut is_open).default_width(640.0).show(ctx, |ui| {
+            if self.events.is_empty() { ui.label(tr("No alerts in the loaded history.")); return; }
+            egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                egui::Grid::new("events_grid").num_columns(5).striped(true).show(ui, |ui| {
+                    for event in self.events.iter().rev() {
+                        ui.label(egui::RichText::new("⏺").color(event_marker_color(event)));
+                        ui.label(self.config.date_format.date_time_secs(event.timestamp));
+                        ui.label(tr(event.severity.label()));
+                        ui.label(&event.message);
+                        if ui.small_button(tr("Show")).on_hover_text(tr("Zoom the plots to an hour either side")).clicked() { show = Some(event.timestamp); }
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+        if let Some(t) = show { self.show_around(t); }
+        self.events_open = is_open;
+    }
+
+    // Saved with the config on the next autosave or at exit, like the other settings
+    fn track_window(&mut self, ctx: &egui::Context) {
+        let Some(window) = ctx.input(|i| WindowGeometry::from_viewport(i.viewport(), self.config.window)) else { return; };
+        if self.config.window != Some(window) { self.config.window = Some(window); self.config_changed = true; }
+    }
+
+    // Fullscreen without the menu bar and footer; F11 or the command palette leaves it again
+    fn set_kiosk_mode(&mut self, on: bool, ctx: &egui::Context) {
+        info!("Kiosk mode {}.", if on { "on" } else { "off" });
+        self.config.kiosk_mode = on;
+        self.config_changed = true;
+        ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(on));
+    }
+
+    // Keys without Ctrl/Cmd (Space, +, -, Home, F11) are left alone while a text field has focus
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.input_mut(|i| i.consume_shortcut(&commands::PALETTE_SHORTCUT)) { self.command_palette = Some(CommandPalette::default()); }
+        if self.command_palette.is_some() { return; }
+        let (typing, simple) = (ctx.wants_keyboard_input(), self.config.simple_mode);
+        for command in Command::ALL.into_iter().filter(|c| !simple || c.in_simple_mode()) {
+            for shortcut in command.shortcuts().iter().filter(|s| !typing || s.modifiers.command) {
+                if ctx.input_mut(|i| i.consume_shortcut(shortcut)) { self.run_command(command, ctx); }
+            }
+        }
+    }
+
+    fn run_command(&mut self, command: Command, ctx: &egui::Context) {
+        info!("Running command '{}'.", command.label());
+        match command {
+            Command::Settings => self.settings_open = true,
+            Command::ZoomIn => { self.push_zoom_history(); self.zoom_factor = 1.25; }
+            Command::ZoomOut => { self.push_zoom_history(); self.zoom_factor = 0.7; }
+            Command::ResetView => self.reset_plot = true,
+            Command::ToggleScanning => self.toggle_scanning(),
+            Command::ExportCsv => self.export_visible_range(ExportFormat::Csv),
+            Command::ExportExcel => self.export_visible_range(ExportFormat::Xlsx),
+            Command::SavePlotImage => self.save_plot_image(ctx),
+            Command::CourtesyMode => self.toggle_courtesy_mode(),
+            Command::Note => self.open_notes(),
+            Command::PlotsView => self.main_view = MainView::Plots,
+            Command::DataView => { self.main_view = MainView::Data; self.data_table.reload(); }
+            Command::CalendarView => { self.main_view = MainView::Calendar; self.heatmap.reload(); }
+            Command::DistributionView => self.main_view = MainView::Distribution,
+            Command::CombinedPlot => { self.config.combined_plot = !self.config.combined_plot; save_config(&self.config); }
+            Command::Events => { self.reload_timeline(); self.events_open = true; }
+            Command::Diagnostics => self.diagnostics_open = true,
+            Command::SelfTest => self.start_selftest(ctx, true),
+            Command::ColdChainReport => self.report_open = true,
+            Command::SimpleMode => self.set_simple_mode(true),
+            Command::Kiosk => self.set_kiosk_mode(!self.config.kiosk_mode, ctx),
+            Command::Quit => ctx.send_viewport_cmd(egui::ViewportCommand::Close),
+        }
+    }
+
+    // Ctrl+P: type to filter, arrows to pick, Enter to run, Escape to close
+    fn draw_command_palette(&mut self, ctx: &egui::Context) {
+        let Some(palette) = &mut self.command_palette else { return; };
+        let (mut run, mut close) = (None, false);
+        let (up, down, enter, escape) = ctx.input_mut(|i| (i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp), i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown), i.key_pressed(egui::Key::Enter), i.key_pressed(egui::Key::Escape)));
+        egui::Window::new("command_palette").title_bar(false).resizable(false).anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 60.0)).show(ctx, |ui| {
+            let response = ui.add(egui::TextEdit::singleline(&mut palette.query).hint_text(tr("Type a command...")).desired_width(340.0));
+            response.request_focus();
+            let matches = commands::matching(&palette.query, self.config.simple_mode, |c| tr(c.label()).to_string());
+            if response.changed() { palette.selected = 0; }
+            if down { palette.selected += 1; }
+            if up { palette.selected = palette.selected.saturating_sub(1); }
+            palette.selected = palette.selected.min(matches.len().saturating_sub(1));
+            for (i, command) in matches.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    if ui.selectable_label(i == palette.selected, tr(command.label())).clicked() { run = Some(*command); }
+                    if let Some(shortcut) = command.shortcuts().first() { ui.label(egui::RichText::new(ctx.format_shortcut(shortcut)).weak()); }
+                });
+            }
+            if matches.is_empty() { ui.label(egui::RichText::new(tr("No matching command")).weak()); }
+            if enter { run = run.or(matches.get(palette.selected).copied()); close = true; }
+        });
+        if run.is_some() || close || escape { self.command_palette = None; }
+        if let Some(command) = run { self.run_command(command, ctx); }
+    }
+
+    fn open_notes(&mut self) {
+        self.reload_timeline();
+        self.note_text.clear();
+        self.note_time = Local::now().format(notes::INPUT_FORMAT).to_string();
+        self.notes_open = true;
+    }
+
+    fn draw_notes_window(&mut self, ctx: &egui::Context) {
+        if !self.notes_open { return; }
+        let mut is_open = self.notes_open;
+        let (mut add, mut show, mut remove) = (false, None, None);
+        egui::Window::new(tr("Notes")).open(&mut is_open).default_width(520.0).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.note_time).hint_text(tr("YYYY-MM-DD HH:MM")).desired_width(130.0));
+                let response = ui.add(egui::TextEdit::singleline(&mut self.note_text).hint_text(tr("e.g. opened window")).desired_width(260.0));
+                let valid = notes::parse_time(&self.note_time).is_some() && !self.note_text.trim().is_empty();
+                add = valid && (ui.add_enabled(valid, egui::Button::new(tr("Add"))).clicked() || (response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter))));
+            });
+            if notes::parse_time(&self.note_time).is_none() { ui.label(egui::RichText::new(tr("Time as YYYY-MM-DD HH:MM")).color(egui::Color32::RED)); }
+            ui.separator();
+            if self.notes.is_empty() { ui.label(tr("No notes in the loaded history.")); return; }
+            egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                egui::Grid::new("notes_grid").num_columns(4).striped(true).show(ui, |ui| {
+                    for (i, note) in self.notes.iter().enumerate().rev() {
+                        ui.label(self.config.date_format.date_time(note.timestamp));
+                        ui.label(&note.text);
+                        if ui.small_button(tr("Show")).on_hover_text(tr("Zoom the plots to an hour either side")).clicked() { show = Some(note.timestamp); }
+                        if ui.small_button("🗑").on_hover_text(tr("Delete note")).clicked() { remove = Some(i); }
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+        if add {
+            if let Some(timestamp) = notes::parse_time(&self.note_time) {
+                let note = Note { timestamp, text: self.note_text.trim().to_string() };
+                match notes::append(&note) {
+                    Ok(()) => { self.notes.push(note); self.notes.sort_by_key(|n| n.timestamp); self.note_text.clear(); }
+                    Err(e) => { error!("Saving the note failed: {}", e); self.toast_message = Some((trf("Saving the note failed: {0}", &[&e]), Instant::now())); }
+                }
+            }
+        }
+        if let Some(i) = remove {
+            match notes::remove(&self.notes[i]) {
+                Ok(()) => { self.notes.remove(i); }
+                Err(e) => self.toast_message = Some((trf("Deleting the note failed: {0}", &[&e]), Instant::now())),
+            }
+        }
+        if let Some(t) = show { self.show_around(t); }
+        self.notes_open = is_open;
+    }
+
+    // Zooms the plots to an hour either side of `t`
+    fn show_around(&mut self, t: DateTime<Local>) {
+        self.show_range("Event", t - chrono::Duration::hours(1), t + chrono::Duration::hours(1));
+    }
+
+    fn show_day(&mut self, day: chrono::NaiveDate) {
+        let bounds = |date: chrono::NaiveDate| date.and_hms_opt(0, 0, 0).and_then(|t| t.and_local_timezone(Local).earliest());
+        if let (Some(start), Some(end)) = (bounds(day), bounds(day + chrono::Duration::days(1))) { self.show_range("Day", start, end); }
+    }
+
+    fn show_range(&mut self, name: &str, start: DateTime<Local>, end: DateTime<Local>) {
+        self.main_view = MainView::Plots;
+        self.recall_bookmark(PlotBookmark { name: name.to_string(), x_range: (start.timestamp() as f64, end.timestamp() as f64), temp_y: None, hum_y: None, smoothing: self.config.smoothing_enabled, thresholds: self.config.show_threshold_lines });
+    }
+
+    fn draw_selftest_window(&mut self, ctx: &egui::Context) {
+        if !self.selftest_open { return; }
+        let mut is_open = self.selftest_open;
+        let (mut rerun, mut open_settings) = (false, false);
+        egui::Window::new(tr("Self-test")).open(&mut is_open).default_width(560.0).show(ctx, |ui| {
+            if self.selftest_rx.is_some() { ui.horizontal(|ui| { ui.spinner(); ui.label(tr("Checking...")); }); return; }
+            egui::Grid::new("selftest_results").striped(true).num_columns(3).show(ui, |ui| {
+                for result in &self.selftest_results {
+                    let color = match result.status { CheckStatus::Pass => egui::Color32::LIGHT_GREEN, CheckStatus::Warn => egui::Color32::YELLOW, CheckStatus::Fail => egui::Color32::LIGHT_RED };
+                    ui.label(egui::RichText::new(format!("{} {}", result.status.icon(), result.name)).color(color).strong());
+                    ui.vertical(|ui| {
+                        ui.label(&result.detail);
+                        if !result.hint.is_empty() { ui.label(egui::RichText::new(&result.hint).weak()); }
+                    });
+                    match &result.fix {
+                        // Settings stay behind the PIN in simple mode
+                        Some(Fix::AppSettings) if !self.config.simple_mode => { if ui.button(tr("Open settings")).clicked() { open_settings = true; } }
+                        Some(Fix::Open { label, target }) => { if ui.button(tr(label)).clicked() { selftest::open(target); } }
+                        _ => { ui.label(""); }
+                    }
+                    ui.end_row();
+                }
+            });
+            ui.separator();
+            if ui.button(tr("Run again")).clicked() { rerun = true; }
+        });
+        self.selftest_open = is_open;
+        if open_settings { self.settings_open = true; }
+        if rerun { self.start_selftest(ctx, true); }
+    }
+
+    fn draw_dehumidifier_window(&mut self, ctx: &egui::Context) {
+        if !self.dehumidifier_open { return; }
+        use egui_plot::{Bar, BarChart, GridMark, Plot};
+        let mut is_open = self.dehumidifier_open;
+        let old_config = self.config.clone();
+        let mut refresh = false;
+        egui::Window::new(tr("Dehumidifier estimate")).open(&mut is_open).default_width(600.0).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                refresh |= ui.add(egui::DragValue::new(&mut self.config.rh_setpoint).prefix(tr("RH setpoint (%): ")).speed(0.5).clamp_range(1.0..=99.0)).changed();
+                ui.add(egui::DragValue::new(&mut self.config.dehumidifier_power_w).prefix(tr("Dehumidifier power (W): ")).speed(5.0).clamp_range(1.0..=5000.0));
+                egui::ComboBox::from_id_source("dehumidifier_days").selected_text(trf("Last {0} days", &[&self.dehumidifier_days])).show_ui(ui, |ui| {
+                    for days in [7, 14, 30, 90] { refresh |= ui.selectable_value(&mut self.dehumidifier_days, days, trf("Last {0} days", &[&days])).changed(); }
+                });
+            });
+            let (power, date_format) = (self.config.dehumidifier_power_w, self.config.date_format);
+            let bars: Vec<Bar> = self.dehumidifier_stats.iter()
+                .map(|d| Bar::new(d.date.num_days_from_ce() as f64, d.hours()).name(trf("{0}: {1} h, {2} kWh (peak {3}%)", &[&date_format.short_date(d.date), &format!("{:.1}", d.hours()), &format!("{:.2}", d.energy_kwh(power)), &d.peak])))
+                .collect();
+            let chart = BarChart::new(bars).color(egui::Color32::from_rgb(100, 100, 255)).name(tr("Runtime (h)"));
+            Plot::new("dehumidifier_plot").height(220.0).allow_drag(false).allow_zoom(false).allow_scroll(false).include_y(0.0)
+                .x_axis_formatter(move |mark: GridMark, _, _| chrono::NaiveDate::from_num_days_from_ce_opt(mark.value as i32).map(|d| date_format.short_date(d)).unwrap_or_default())
+                .y_axis_formatter(|mark: GridMark, _, _| format!("{:.0} h", mark.value))
+                .show(ui, |plot_ui| plot_ui.bar_chart(chart));
+            let total_hours: f64 = self.dehumidifier_stats.iter().map(|d| d.hours()).sum();
+            let days = self.dehumidifier_stats.len().max(1) as f64;
+            ui.label(trf("Above {0}% RH: {1} h in total, {2} h/day on average.", &[&format!("{:.0}", self.config.rh_setpoint), &format!("{:.1}", total_hours), &format!("{:.1}", total_hours / days)]));
+            ui.label(trf("Estimated energy at {0} W: {1} kWh in total, {2} kWh/day on average.", &[&format!("{:.0}", power), &format!("{:.2}", total_hours * power as f64 / 1000.0), &format!("{:.2}", total_hours * power as f64 / 1000.0 / days)]));
+            let mut weekly: BTreeMap<chrono::NaiveDate, f64> = BTreeMap::new();
+            for d in &self.dehumidifier_stats { *weekly.entry(self.config.week_start.start_of_week(d.date)).or_default() += d.hours(); }
+            egui::CollapsingHeader::new(tr("Per week")).show(ui, |ui| {
+                for (week, hours) in &weekly { ui.label(trf("Week of {0}: {1} h, {2} kWh", &[&date_format.date(*week), &format!("{:.1}", hours), &format!("{:.2}", hours * power as f64 / 1000.0)])); }
+            });
+            ui.label(egui::RichText::new(tr("Runtime assumes the dehumidifier runs whenever humidity is above the setpoint.")).color(egui::Color32::GRAY));
+        });
+        if refresh { self.refresh_dehumidifier_stats(); }
+        if self.config != old_config { self.sync_config(&old_config); }
+        self.dehumidifier_open = is_open;
+    }
+
+    fn draw_gradient_window(&mut self, ctx: &egui::Context) {
+        if !self.gradient_open { return; }
+        use egui_plot::{GridMark, HLine, Line, LineStyle, Plot, PlotPoints};
+        let mut is_open = self.gradient_open;
+        let old_config = self.config.clone();
+        let mut refresh = false;
+        egui::Window::new(tr("Sensor gradient")).open(&mut is_open).default_width(600.0).show(ctx, |ui| {
+            egui::Grid::new("gradient_sensors").num_columns(3).show(ui, |ui| {
+                for (label, dir) in [("Sensor A data folder:", &mut self.config.gradient_sensor_a_dir), ("Sensor B data folder:", &mut self.config.gradient_sensor_b_dir)] {
+                    ui.label(tr(label));
+                    refresh |= ui.add(egui::TextEdit::singleline(dir).hint_text(tr("This app's logs")).desired_width(320.0)).lost_focus();
+                    if ui.button(tr("Browse...")).clicked() {
+                        if let Some(folder) = rfd::FileDialog::new().pick_folder() { *dir = folder.display().to_string(); refresh = true; }
+                    }
+                    ui.end_row();
+                }
+            });
+            ui.horizontal(|ui| {
+                refresh |= ui.add(egui::DragValue::new(&mut self.config.gradient_warn_low).prefix(tr("Lower threshold (°C): ")).speed(0.1).clamp_range(-50.0..=50.0)).changed();
+                refresh |= ui.add(egui::DragValue::new(&mut self.config.gradient_warn_high).prefix(tr("Upper threshold (°C): ")).speed(0.1).clamp_range(-50.0..=50.0)).changed();
+                egui::ComboBox::from_id_source("gradient_days").selected_text(trf("Last {0} days", &[&self.gradient_days])).show_ui(ui, |ui| {
+                    for days in [1, 7, 30] { refresh |= ui.selectable_value(&mut self.gradient_days, days, trf("Last {0} days", &[&days])).changed(); }
+                });
+            });
+            let (low, high) = (self.config.gradient_warn_low, self.config.gradient_warn_high);
+            let points: Vec<[f64; 2]> = self.gradient_points.iter().map(|p| [p.timestamp.timestamp() as f64, p.delta as f64]).collect();
+            Plot::new("gradient_plot").height(240.0).allow_scroll(false)
+                .x_axis_formatter(|mark: GridMark, _, _| DateTime::from_timestamp(mark.value as i64, 0).unwrap_or_default().with_timezone(&Local).format("%d.%m. %H:%M").to_string())
+                .y_axis_formatter(|mark: GridMark, _, _| format!("{:+.1} °C", mark.value))
+                .show(ui, |plot_ui| {
+                    plot_ui.hline(HLine::new(high).color(egui::Color32::from_rgb(255, 80, 80)).style(LineStyle::dashed_loose()));
+                    plot_ui.hline(HLine::new(low).color(egui::Color32::from_rgb(80, 140, 255)).style(LineStyle::dashed_loose()));
+                    plot_ui.line(Line::new(PlotPoints::new(points)).color(egui::Color32::from_rgb(255, 160, 60)).name("A − B"));
+                });
+            let max_gap = chrono::Duration::seconds((expected_reading_interval_secs(&self.config) * 3) as i64);
+            match gradient::stats(&self.gradient_points, low, high, max_gap) {
+                Some(stats) => {
+                    ui.label(trf("A − B: min {0} °C, max {1} °C, mean {2} °C.", &[&format!("{:+.1}", stats.min), &format!("{:+.1}", stats.max), &format!("{:+.1}", stats.mean)]));
+                    ui.label(trf("Above {0} °C for {1} h, below {2} °C for {3} h ({4} % of the time outside).",
+                        &[&format!("{:+.1}", high), &format!("{:.1}", stats.secs_above as f64 / 3600.0), &format!("{:+.1}", low), &format!("{:.1}", stats.secs_below as f64 / 3600.0), &format!("{:.0}", stats.share_outside() * 100.0)]));
+                }
+                None => { ui.label(tr("No overlapping readings of both sensors in this period.")); }
+            }
+            ui.label(egui::RichText::new(tr("Log each sensor with its own copy of the app (e.g. a portable copy) and point the folders at their data.")).color(egui::Color32::GRAY));
+        });
+        if refresh { self.refresh_gradient(); }
+        if self.config != old_config { self.sync_config(&old_config); }
+        self.gradient_open = is_open;
+    }
+
+    fn draw_virtual_sensors_window(&mut self, ctx: &egui::Context) {
+        if !self.virtual_open { return; }
+        use egui_plot::{GridMark, Line, Plot, PlotPoints};
+        let mut is_open = self.virtual_open;
+        let old_config = self.config.clone();
+        let mut refresh = false;
+        egui::Window::new(tr("Virtual sensors")).open(&mut is_open).default_width(600.0).show(ctx, |ui| {
+            ui.label(egui::RichText::new(tr("Combines the logs of sensors that each have their own copy of the app, paired like in the gradient window. The combined readings are logged while this app runs and can raise alerts.")).color(egui::Color32::GRAY));
+            let mut remove = None;
+            for (i, sensor) in self.config.virtual_sensors.iter_mut().enumerate() {
+                ui.separator();
+                ui.horizontal(|ui| {
+                    refresh |= ui.radio_value(&mut self.virtual_selected, i, "").on_hover_text(tr("Show in the plot below")).changed();
+                    ui.add(egui::TextEdit::singleline(&mut sensor.name).desired_width(160.0));
+                    egui::ComboBox::from_id_source(("virtual_aggregate", i)).selected_text(tr(sensor.aggregate.label())).show_ui(ui, |ui| {
+                        for aggregate in Aggregate::ALL { refresh |= ui.selectable_value(&mut sensor.aggregate, aggregate, tr(aggregate.label())).changed(); }
+                    });
+                    ui.checkbox(&mut sensor.alerts, tr("Alerts")).on_hover_text(tr("Check the combined readings against the alert rules too"));
+                    if ui.button("🗑").clicked() { remove = Some(i); }
+                });
+                let mut remove_folder = None;
+                for (j, dir) in sensor.folders.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        refresh |= ui.add(egui::TextEdit::singleline(dir).hint_text(tr("This app's logs")).desired_width(380.0)).lost_focus();
+                        if ui.button(tr("Browse...")).clicked() {
+                            if let Some(folder) = rfd::FileDialog::new().pick_folder() { *dir = folder.display().to_string(); refresh = true; }
+                        }
+                        if ui.button("🗑").clicked() { remove_folder = Some(j); }
+                    });
+                }
+                if let Some(j) = remove_folder { sensor.folders.remove(j); refresh = true; }
+                if ui.button(tr("Add sensor folder")).clicked() { sensor.folders.push(String::new()); }
+            }
+            if let Some(i) = remove { self.config.virtual_sensors.remove(i); self.virtual_selected = 0; refresh = true; }
+            if ui.button(tr("Add virtual sensor")).clicked() { self.config.virtual_sensors.push(VirtualSensor::default()); refresh = true; }
+            let Some(sensor) = self.config.virtual_sensors.get(self.virtual_selected) else { return; };
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(&sensor.name).strong());
+                egui::ComboBox::from_id_source("virtual_days").selected_text(trf("Last {0} days", &[&self.virtual_days])).show_ui(ui, |ui| {
+                    for days in [1, 7, 30] { refresh |= ui.selectable_value(&mut self.virtual_days, days, trf("Last {0} days", &[&days])).changed(); }
+                });
+            });
+            let points: Vec<[f64; 2]> = self.virtual_points.iter().map(|p| [p.timestamp.timestamp() as f64, p.temp as f64]).collect();
+            Plot::new("virtual_plot").height(240.0).allow_scroll(false)
+                .x_axis_formatter(|mark: GridMark, _, _| DateTime::from_timestamp(mark.value as i64, 0).unwrap_or_default().with_timezone(&Local).format("%d.%m. %H:%M").to_string())
+                .y_axis_formatter(|mark: GridMark, _, _| format!("{:.1} °C", mark.value))
+                .show(ui, |plot_ui| plot_ui.line(Line::new(PlotPoints::new(points)).color(egui::Color32::from_rgb(255, 160, 60)).name(&sensor.name)));
+            match self.virtual_points.last() {
+                Some(last) => { ui.label(trf("{0} combined readings, the last {1} °C and {2} %.", &[&self.virtual_points.len(), &format!("{:.1}", last.temp), &last.hum])); }
+                None => { ui.label(tr("No readings of all its sensors close enough together in this period.")); }
+            }
+            ui.label(egui::RichText::new(trf("Logged in {0}", &[&sensor.folder().display()])).color(egui::Color32::GRAY));
+        });
+        if refresh { self.refresh_virtual_sensor(); }
+        if self.config != old_config { self.sync_config(&old_config); }
+        self.virtual_open = is_open;
+    }
+
+    fn draw_evidence_window(&mut self, ctx: &egui::Context) {
+        if !self.evidence_open { return; }
+        let mut is_open = self.evidence_open;
+        let (mut export, mut verify) = (false, false);
+        let parse = |text: &str| chrono::NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d").ok();
+        egui::Window::new(tr("Evidence export")).open(&mut is_open).default_width(460.0).show(ctx, |ui| {
+            ui.label(tr("Readings, gaps, alerts and any edits of the period in one file. Every row is chained to the previous one by a SHA-256 hash and the result is signed, so a changed, added or removed row is detected by \"Verify\"."));
+            ui.horizontal(|ui| {
+                for (label, text) in [("Start:", &mut self.evidence_start), ("End:", &mut self.evidence_end)] {
+                    ui.label(tr(label));
+                    let valid = parse(text).is_some();
+                    ui.add(egui::TextEdit::singleline(text).hint_text(tr("YYYY-MM-DD")).desired_width(90.0).text_color_opt((!valid).then_some(egui::Color32::RED)));
+                }
+            });
+            ui.horizontal(|ui| {
+                export = ui.add_enabled(parse(&self.evidence_start).is_some() && parse(&self.evidence_end).is_some(), egui::Button::new(tr("Export..."))).clicked();
+                verify = ui.button(tr("Verify file...")).clicked();
+            });
+            match &self.evidence_verified {
+                Some(Ok(note)) => { ui.label(egui::RichText::new(format!("✔ {}", note)).color(egui::Color32::LIGHT_GREEN)); }
+                Some(Err(e)) => { ui.label(egui::RichText::new(format!("✖ {}", e)).color(egui::Color32::LIGHT_RED)); }
+                None => {}
+            }
+            if let Some(fingerprint) = evidence::key_fingerprint() { ui.label(egui::RichText::new(trf("Signing key of this installation: {0}", &[&fingerprint])).weak()); }
+        });
+        if let (true, Some(start), Some(end)) = (export, parse(&self.evidence_start), parse(&self.evidence_end)) {
+            let start = start.and_hms_opt(0, 0, 0).and_then(|t| t.and_local_timezone(Local).earliest()).unwrap_or_else(Local::now);
+            let end = end.and_hms_opt(23, 59, 59).and_then(|t| t.and_local_timezone(Local).latest()).unwrap_or_else(Local::now).min(Local::now());
+            let default_name = format!("evidence_{}_{}.csv", start.format("%Y-%m-%d"), end.format("%Y-%m-%d"));
+            if let Some(path) = rfd::FileDialog::new().add_filter("CSV", &["csv"]).set_file_name(default_name).save_file() {
+                let max_gap = chrono::Duration::seconds((expected_reading_interval_secs(&self.config) * 3) as i64);
+                let result = evidence::export(&path, &load_history_range(start, end), &alert_log::load_range(start, end), start, end, max_gap, &self.config.room_name);
+                self.toast_message = Some((match result {
+                    Ok(summary) => trf("Exported {0} readings, {1} gaps, {2} alerts, {3} edits", &[&summary.readings, &summary.gaps, &summary.alerts, &summary.edits]),
+                    Err(e) => { error!("Evidence export to '{}' failed: {}", path.display(), e); trf("Evidence export failed: {0}", &[&e]) }
+                }, Instant::now()));
+            }
+        }
+        if verify {
+            if let Some(path) = rfd::FileDialog::new().add_filter("CSV", &["csv"]).pick_file() {
+                let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string();
+                self.evidence_verified = Some(match evidence::verify(&path) {
+                    Ok((rows, fingerprint)) => Ok(trf("{0} is intact: {1} rows, signed by key {2}", &[&name, &rows, &fingerprint])),
+                    Err(e) => { warn!("Evidence file '{}' failed verification: {}", path.display(), e); Err(format!("{}: {}", name, e)) }
+                });
+            }
+        }
+        self.evidence_open = is_open;
+    }
+
+    fn draw_report_window(&mut self, ctx: &egui::Context) {
+        if !self.report_open { return; }
+        let mut is_open = self.report_open;
+        let now = Local::now();
+        if self.report_to.is_empty() { (self.report_from, self.report_to) = ((now - chrono::Duration::hours(24)).format(notes::INPUT_FORMAT).to_string(), now.format(notes::INPUT_FORMAT).to_string()); }
+        egui::Window::new(tr("Cold-chain report")).open(&mut is_open).default_width(560.0).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_label(tr("Period")).selected_text(tr(self.report_period.label())).show_ui(ui, |ui| {
+                    for period in ReportPeriod::ALL { ui.selectable_value(&mut self.report_period, period, tr(period.label())); }
+                });
+                if self.report_period == ReportPeriod::Custom {
+                    for (label, text) in [("Start:", &mut self.report_from), ("End:", &mut self.report_to)] {
+                        ui.label(tr(label));
+                        let valid = notes::parse_time(text).is_some();
+                        ui.add(egui::TextEdit::singleline(text).hint_text(tr("YYYY-MM-DD HH:MM")).desired_width(120.0).text_color_opt((!valid).then_some(egui::Color32::RED)));
+                    }
+                }
+                let range = self.report_period.range(now, self.config.week_start)
+                    .or_else(|| Some((notes::parse_time(&self.report_from)?, notes::parse_time(&self.report_to)?)).filter(|(start, end)| start < end));
+                if let (true, Some((start, end))) = (ui.add_enabled(range.is_some(), egui::Button::new(tr("Generate"))).clicked(), range) {
+                    let points = load_history_range(start, end);
+                    let max_gap = chrono::Duration::seconds((expected_reading_interval_secs(&self.config) * 3) as i64);
+                    info!("Generating cold-chain report from {} points.", points.len());
+                    let paused: Vec<PauseInterval> = self.pause_periods.iter().filter(|p| p.end > start && p.start < end).cloned().collect();
+                    self.report = Some(ColdChainReport::build(&points, start, end, self.config.temp_warn_low, self.config.temp_warn_high, max_gap, &paused));
+                }
+            });
+            ui.label(tr("Acceptance range uses the warning thresholds from Settings."));
+            ui.separator();
+            if let Some(report) = &self.report {
+                let text = report.to_text(self.config.date_format);
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| { ui.label(egui::RichText::new(&text).monospace()); });
+                ui.separator();
+                if ui.button(tr("Save report...")).clicked() {
+                    let default_name = format!("cold_chain_report_{}.txt", Local::now().format("%Y-%m-%d"));
+                    if let Some(path) = rfd::FileDialog::new().add_filter("Text", &["txt"]).set_file_name(default_name).save_file() {
+                        match fs::write(&path, text) {
+                            Ok(()) => { info!("Report saved to '{}'.", path.display()); self.toast_message = Some((tr("Report saved.").to_owned(), Instant::now())); }
+                            Err(e) => { error!("Failed to save report: {}", e); self.toast_message = Some((tr("Saving report failed!").to_owned(), Instant::now())); }
+                        }
+                    }
+                }
+            }
+        });
+        self.report_open = is_open;
+    }
+}
+
+// Recent logs checked for damaged rows at startup; older ones via File → Check logs
+const STARTUP_CHECK_DAYS: i64 = 2;
+
+const POWER_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+
+const MAX_RECENT_DATA_FOLDERS: usize = 8;
+
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(6 * 3600);
+
+// Points kept in memory for the plots; unlimited when the full history is loaded
+fn history_limit(config: &Config) -> usize { if config.load_all_history { usize::MAX } else { config.history_cap } }
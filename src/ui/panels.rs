@@ -0,0 +1,232 @@
+// --- Panels: current values, scan status, badges and the alert-rule and schedule editors ---
+
+use chrono::Local;
+use eframe::egui;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::alerts::{temperature_alert, TempAlert};
+use crate::calendar::WeekStart;
+use crate::comfort::{self, Comfort, MoldRisk};
+use crate::i18n::{tr, trf};
+use crate::link::{PacketLog, RssiStats};
+use crate::model::{BleDataPoint, Config, HistoryPoint};
+use crate::palette::{ColorScale, Palette};
+use crate::power;
+use crate::rules::{AlertRule, Comparison, Metric, QuietHours, Severity};
+use crate::schedule::{PauseInterval, PauseWindow, WEEKDAY_NAMES};
+use crate::thi::Stress;
+use crate::trend::{self, Trend};
+use crate::units::Channel;
+use crate::vpd::{self, VpdStatus};
+
+
+// Shaded bands for scheduled pause windows within the plotted time range
+pub(crate) fn edit_alert_rules(ui: &mut egui::Ui, rules: &mut Vec<AlertRule>, week_start: WeekStart) {
+    ui.label(egui::RichText::new(tr("A rule fires once its condition has held for the set time and clears when it no longer holds. Quiet hours mute some of its channels, e.g. sound at night or email on Saturday and Sunday (00:00–00:00). Webhook, Telegram and email also need to be set up on the General tab.")).weak());
+    let mut remove = None;
+    egui::ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+        for (i, rule) in rules.iter_mut().enumerate() {
+            ui.group(|ui| {
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut rule.enabled, "");
+                    let description = rule.describe();
+                    ui.add(egui::TextEdit::singleline(&mut rule.name).hint_text(description).desired_width(220.0));
+                    egui::ComboBox::from_id_source(("rule_severity", i)).selected_text(tr(rule.severity.label())).show_ui(ui, |ui| {
+                        for severity in Severity::ALL { ui.selectable_value(&mut rule.severity, severity, tr(severity.label())); }
+                    });
+                    if ui.small_button("🗑").on_hover_text(tr("Delete rule")).clicked() { remove = Some(i); }
+                });
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_id_source(("rule_metric", i)).selected_text(tr(rule.metric.label())).show_ui(ui, |ui| {
+                        for metric in Metric::ALL { ui.selectable_value(&mut rule.metric, metric, tr(metric.label())); }
+                    });
+                    egui::ComboBox::from_id_source(("rule_comparison", i)).width(70.0).selected_text(tr(rule.comparison.label())).show_ui(ui, |ui| {
+                        for comparison in Comparison::ALL { ui.selectable_value(&mut rule.comparison, comparison, tr(comparison.label())); }
+                    });
+                    ui.add(egui::DragValue::new(&mut rule.threshold).suffix(format!(" {}", rule.metric.unit())).speed(0.1));
+                    ui.add(egui::DragValue::new(&mut rule.duration_mins).prefix(tr("for ")).suffix(tr(" min")).clamp_range(0..=1440));
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr("Channels:"));
+                    ui.checkbox(&mut rule.channels.toast, tr("Notification"));
+                    ui.checkbox(&mut rule.channels.sound, tr("Sound"));
+                    ui.checkbox(&mut rule.channels.webhook, tr("Webhook"));
+                    ui.checkbox(&mut rule.channels.telegram, tr("Telegram"));
+                    ui.checkbox(&mut rule.channels.email, tr("Email"));
+                });
+                egui::CollapsingHeader::new(trf("Quiet hours ({0})", &[&rule.quiet_hours.len()])).id_source(("rule_quiet", i)).show(ui, |ui| {
+                    let mut remove_quiet = None;
+                    for (j, quiet) in rule.quiet_hours.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            edit_schedule_window(ui, &mut quiet.window, week_start, ("rule_quiet_weekday", i, j));
+                            ui.label(tr("mute:"));
+                            ui.checkbox(&mut quiet.muted.toast, tr("Notification"));
+                            ui.checkbox(&mut quiet.muted.sound, tr("Sound"));
+                            ui.checkbox(&mut quiet.muted.webhook, tr("Webhook"));
+                            ui.checkbox(&mut quiet.muted.telegram, tr("Telegram"));
+                            ui.checkbox(&mut quiet.muted.email, tr("Email"));
+                            if ui.button("🗑").clicked() { remove_quiet = Some(j); }
+                        });
+                    }
+                    if let Some(j) = remove_quiet { rule.quiet_hours.remove(j); }
+                    if ui.button(tr("Add quiet hours")).clicked() { rule.quiet_hours.push(QuietHours::default()); }
+                });
+            });
+        }
+    });
+    if let Some(i) = remove { rules.remove(i); }
+    if ui.button(tr("Add rule")).clicked() { rules.push(AlertRule::default()); }
+}
+
+pub(crate) fn edit_schedule_window(ui: &mut egui::Ui, window: &mut PauseWindow, week_start: WeekStart, id_source: impl std::hash::Hash) {
+    ui.add(egui::TextEdit::singleline(&mut window.label).desired_width(90.0));
+    egui::ComboBox::from_id_source(id_source).selected_text(tr(window.weekday_label())).show_ui(ui, |ui| {
+        ui.selectable_value(&mut window.weekday, None, tr("Every day"));
+        for day in week_start.day_order() { ui.selectable_value(&mut window.weekday, Some(day as u8), tr(WEEKDAY_NAMES[day])); }
+    });
+    ui.add(egui::TextEdit::singleline(&mut window.start).desired_width(45.0));
+    ui.label("–");
+    ui.add(egui::TextEdit::singleline(&mut window.end).desired_width(45.0));
+    if !window.is_valid() { ui.label(egui::RichText::new(tr("HH:MM")).color(egui::Color32::RED)); } else if window.is_all_day() { ui.label(tr("(all day)")); }
+}
+
+pub(crate) fn edit_schedule_windows(ui: &mut egui::Ui, windows: &mut Vec<PauseWindow>, week_start: WeekStart, id_source: &str, add_label: &str) {
+    let mut remove_index = None;
+    for (i, window) in windows.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            edit_schedule_window(ui, window, week_start, (id_source, i));
+            if ui.button("🗑").clicked() { remove_index = Some(i); }
+        });
+    }
+    if let Some(i) = remove_index { windows.remove(i); }
+    if ui.button(add_label).clicked() { windows.push(PauseWindow::default()); }
+}
+
+// `scale` enlarges the text in kiosk mode, 1.0 otherwise
+pub(crate) fn draw_temperature_info(ui: &mut egui::Ui, history: &VecDeque<HistoryPoint>, config: &Config, paused: bool, scale: f32) {
+    let temp_min = history.iter().map(|p| p.temp).min_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal)).unwrap_or(0.0);
+    let temp_max = history.iter().map(|p| p.temp).max_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal)).unwrap_or(0.0);
+    ui.label(egui::RichText::new(tr(Channel::Temperature.name())).size(22.0 * scale).color(egui::Color32::GRAY));
+    if let Some(point) = history.back() {
+        let current_temp = point.temp;
+        let color = match temperature_alert(current_temp, config, paused) {
+            TempAlert::High => egui::Color32::GOLD,
+            TempAlert::Low => egui::Color32::from_rgb(120, 180, 255),
+            TempAlert::Normal => ColorScale::temperature(config).color(current_temp as f64),
+        };
+        ui.label(egui::RichText::new(Channel::Temperature.format(current_temp as f64)).size(42.0 * scale).color(color));
+        draw_trend(ui, history, config, |p| p.temp as f64, trend::STEADY_TEMP_PER_HOUR, Metric::TemperatureRate, scale);
+    } else { ui.label(egui::RichText::new(tr("N/A")).size(32.0 * scale)); }
+    ui.label(egui::RichText::new(trf("Min: {0} / Max: {1}", &[&Channel::Temperature.format(temp_min as f64), &Channel::Temperature.format(temp_max as f64)])).size(20.0 * scale).color(ui.visuals().strong_text_color()));
+}
+
+// Arrow and rate per hour over `trend_window_mins`; nothing until enough readings have come in
+pub(crate) fn draw_trend(ui: &mut egui::Ui, history: &VecDeque<HistoryPoint>, config: &Config, value: impl Fn(&HistoryPoint) -> f64, steady_band: f64, rate: Metric, scale: f32) {
+    let Some(per_hour) = trend::rate_per_hour(history.iter().map(|p| (p.timestamp, value(p))), chrono::Duration::minutes(config.trend_window_mins as i64)) else { return; };
+    let trend = Trend::classify(per_hour, steady_band);
+    let color = if trend == Trend::Steady { egui::Color32::GRAY } else { ui.visuals().strong_text_color() };
+    ui.label(egui::RichText::new(format!("{} {:+.1} {}", trend.arrow(), per_hour, rate.unit())).size(20.0 * scale).color(color))
+        .on_hover_text(trf("Change over the last {0} min", &[&config.trend_window_mins]));
+}
+
+pub(crate) fn draw_humidity_info(ui: &mut egui::Ui, history: &VecDeque<HistoryPoint>, config: &Config, scale: f32) {
+    let hum_min = history.iter().map(|p| p.hum).min().unwrap_or(0);
+    let hum_max = history.iter().map(|p| p.hum).max().unwrap_or(0);
+    ui.label(egui::RichText::new(tr(Channel::Humidity.name())).size(22.0 * scale).color(egui::Color32::GRAY));
+    if let Some(point) = history.back() {
+        ui.label(egui::RichText::new(Channel::Humidity.format(point.hum as f64)).size(42.0 * scale).color(ColorScale::humidity(config).color(point.hum as f64)));
+        draw_trend(ui, history, config, |p| p.hum as f64, trend::STEADY_HUM_PER_HOUR, Metric::HumidityRate, scale);
+    } else { ui.label(egui::RichText::new(tr("N/A")).size(32.0 * scale)); }
+    ui.label(egui::RichText::new(trf("Min: {0} / Max: {1}", &[&Channel::Humidity.format(hum_min as f64), &Channel::Humidity.format(hum_max as f64)])).size(20.0 * scale).color(ui.visuals().strong_text_color()));
+    if let (true, Some(point)) = (config.thi_enabled, history.back()) {
+        let species = config.thi_species;
+        let thi = species.thi(point.temp, point.hum);
+        let stress = species.stress(thi);
+        let color = match stress { Stress::None => egui::Color32::LIGHT_GREEN, Stress::Mild => egui::Color32::YELLOW, Stress::Moderate => egui::Color32::from_rgb(255, 150, 50), Stress::Severe => egui::Color32::RED };
+        ui.label(egui::RichText::new(format!("THI {:.1} – {}", thi, tr(stress.label()))).size(17.0 * scale).color(color))
+            .on_hover_text(trf("{0}: mild from {1}, moderate from {2}, severe from {3}", &[&tr(species.label()), &format!("{:.1}", species.bands()[0]), &format!("{:.1}", species.bands()[1]), &format!("{:.1}", species.bands()[2])]));
+    }
+    if let (true, Some(point)) = (config.vpd_enabled, history.back()) {
+        let value = vpd::vpd(point.temp, point.hum, config.vpd_leaf_offset);
+        let status = VpdStatus::classify(value, (config.vpd_target_low, config.vpd_target_high));
+        let color = if status == VpdStatus::InRange { egui::Color32::LIGHT_GREEN } else { egui::Color32::from_rgb(255, 150, 50) };
+        ui.label(egui::RichText::new(format!("VPD {} – {}", vpd::format(value as f64), tr(status.label()))).size(17.0 * scale).color(color))
+            .on_hover_text(trf("Leaf {0} °C from the air, target {1} to {2} kPa", &[&format!("{:+.1}", config.vpd_leaf_offset), &format!("{:.2}", config.vpd_target_low), &format!("{:.2}", config.vpd_target_high)]));
+    }
+}
+
+// Simple mode leaves out the scanner status and RSSI
+pub(crate) fn draw_scan_metadata(ui: &mut egui::Ui, last_data: &Option<BleDataPoint>, status: &str, pause: Option<&PauseInterval>, offline_for: Option<Duration>, simple: bool) {
+    if !simple { ui.horizontal(|ui| { ui.label(egui::RichText::new(tr("Status:")).color(egui::Color32::GRAY)); ui.label(tr(status)); }); }
+    if let Some(offline_for) = offline_for {
+        ui.label(egui::RichText::new(trf("⚠ Sensor offline – no reading for {0} min", &[&(offline_for.as_secs() / 60)])).color(egui::Color32::RED).strong());
+    }
+    if let Some(pause) = pause {
+        ui.label(egui::RichText::new(trf("⏸ Logging paused ({0}) until {1}", &[&pause.label, &pause.end.format("%H:%M")])).color(egui::Color32::YELLOW));
+    }
+    if let Some(data) = last_data {
+        ui.horizontal(|ui| { ui.label(egui::RichText::new(tr("Updated:")).size(17.0).color(egui::Color32::GRAY)); ui.label(data.timestamp.format("%H:%M:%S").to_string()); });
+        if simple { return; }
+        ui.horizontal(|ui| { ui.label(egui::RichText::new(tr("RSSI:")).size(17.0).color(egui::Color32::GRAY)); if let Some(rssi) = data.rssi { ui.label(Channel::Rssi.format(rssi as f64)); } else { ui.label(tr("N/A")); }});
+    }
+}
+
+// Right-to-left: the mold badge sits next to the heartbeat, the comfort badge left of it
+pub(crate) fn draw_comfort_badges(ui: &mut egui::Ui, point: &HistoryPoint) {
+    let badge = |ui: &mut egui::Ui, text: String, color: egui::Color32| ui.label(egui::RichText::new(format!(" {} ", text)).color(egui::Color32::BLACK).background_color(color));
+    let risk = MoldRisk::assess(point.temp, point.hum);
+    let color = match risk { MoldRisk::Low => egui::Color32::LIGHT_GREEN, MoldRisk::Elevated => egui::Color32::YELLOW, MoldRisk::High => egui::Color32::from_rgb(255, 120, 80) };
+    let critical = if point.temp > 0.0 { trf("Humidity {0} % – mold can grow above {1} % at {2} °C", &[&point.hum, &format!("{:.0}", comfort::critical_rh(point.temp)), &format!("{:.1}", point.temp)]) } else { tr("No mold growth below freezing").to_string() };
+    badge(ui, tr(risk.label()).to_string(), color).on_hover_text(critical);
+    let comfort = Comfort::assess(point.temp, point.hum);
+    let color = if comfort == Comfort::Comfortable { egui::Color32::LIGHT_GREEN } else { egui::Color32::from_rgb(255, 200, 90) };
+    badge(ui, tr(comfort.label()).to_string(), color)
+        .on_hover_text(trf("Comfort zone: {0} to {1} °C, {2} to {3} % humidity, dew point up to {4} °C", &[&comfort::COMFORT_TEMP.0, &comfort::COMFORT_TEMP.1, &comfort::COMFORT_RH.0, &comfort::COMFORT_RH.1, &comfort::COMFORT_MAX_DEW_POINT]));
+}
+
+pub(crate) const HEARTBEAT_FADE: Duration = Duration::from_millis(800);
+
+// Strip showing the palette from the low to the high end
+pub(crate) fn draw_palette_preview(ui: &mut egui::Ui, palette: Palette) {
+    const STEPS: usize = 32;
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(120.0, 12.0), egui::Sense::hover());
+    for i in 0..STEPS {
+        let x = |i: usize| rect.left() + rect.width() * i as f32 / STEPS as f32;
+        let step = egui::Rect::from_x_y_ranges(x(i)..=x(i + 1), rect.y_range());
+        ui.painter().rect_filled(step, 0.0, palette.sample(i as f64 / (STEPS - 1) as f64, false));
+    }
+}
+
+// Dot that lights up on every decoded advertisement and fades out
+pub(crate) fn draw_heartbeat(ui: &mut egui::Ui, last_heartbeat: Option<Instant>) {
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(12.0, 12.0), egui::Sense::hover());
+    let since = last_heartbeat.map_or(HEARTBEAT_FADE, |t| t.elapsed().min(HEARTBEAT_FADE));
+    let glow = 1.0 - since.as_secs_f32() / HEARTBEAT_FADE.as_secs_f32();
+    ui.painter().circle_filled(rect.center(), 4.0, egui::Color32::from_gray(60));
+    ui.painter().circle_filled(rect.center(), 4.0, egui::Color32::from_rgba_unmultiplied(80, 220, 120, (glow * 255.0) as u8));
+    if glow > 0.0 && !power::saver_active() { ui.ctx().request_repaint(); }
+    response.on_hover_text(last_heartbeat.map_or(tr("No advertisement decoded yet").to_string(), |t| trf("Last advertisement {0} s ago", &[&t.elapsed().as_secs()])));
+}
+
+// Packets in the last hour and the RSSI spread of the plotted readings; the hover lists every hour
+pub(crate) fn draw_link_stats(ui: &mut egui::Ui, history: &VecDeque<HistoryPoint>, packets: &PacketLog) {
+    let now = Local::now();
+    let per_hour = packets.per_hour(now);
+    let rssi = RssiStats::of(history.iter()).map_or(String::new(), |s| trf(" · RSSI mean {0}, min {1}, max {2}", &[&Channel::Rssi.format(s.mean), &Channel::Rssi.format(s.min as f64), &Channel::Rssi.format(s.max as f64)]));
+    let hours: String = per_hour.iter().rev().map(|(start, n)| format!("{}  {}\n", start.format("%H:00"), n)).collect();
+    ui.label(egui::RichText::new(format!("{}{}", trf("{0} packets in the last hour", &[&packets.last_hour(now)]), rssi)).weak())
+        .on_hover_text(format!("{}\n{}", tr("Packets received per hour, newest first:"), hours));
+}
+
+pub(crate) fn draw_data_details(ui: &mut egui::Ui, last_data: &Option<BleDataPoint>, sensor_name: Option<&str>, csv_ok: bool) {
+    if let Some(data) = last_data {
+        if data.device_alarm { ui.label(egui::RichText::new(tr("🔔 Device alarm")).color(egui::Color32::RED).strong()); }
+        if let Some(name) = sensor_name { ui.horizontal(|ui| { ui.label(egui::RichText::new(tr("Sensor:")).size(17.0).color(egui::Color32::GRAY)); ui.label(egui::RichText::new(name).strong()); }); }
+        ui.horizontal(|ui| { ui.label(egui::RichText::new(tr("Device ID:")).size(17.0).color(egui::Color32::GRAY)); let id = egui::RichText::new(data.device_id.to_string()); ui.label(if sensor_name.is_some() { id.weak() } else { id }); });
+        ui.horizontal(|ui| { ui.label(egui::RichText::new(tr("Model:")).size(17.0).color(egui::Color32::GRAY)); ui.label(data.model); });
+        ui.horizontal(|ui| { ui.label(egui::RichText::new(tr("Raw data:")).size(17.0).color(egui::Color32::GRAY)); ui.label(data.raw_data.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" ")); });
+        ui.horizontal(|ui| { ui.label(egui::RichText::new(tr("CSV Write:")).size(17.0).color(egui::Color32::GRAY)); if csv_ok { ui.label(egui::RichText::new(tr("OK")).color(egui::Color32::GREEN)); } else { ui.label(egui::RichText::new(tr("Error")).color(egui::Color32::RED)); } });
+    }
+}
@@ -0,0 +1,557 @@
+// --- Plots: temperature, humidity, combined, VPD and signal-strength graphs with their bands, markers and overlays ---
+
+use chrono::{DateTime, Local};
+use eframe::egui;
+use std::collections::VecDeque;
+use std::time::Instant;
+use log::info;
+
+use crate::aggregate::{Resolution, Summary};
+use crate::alert_log::AlertRecord;
+use crate::ble::expected_reading_interval_secs;
+use crate::edit::EditDialog;
+use crate::history::{self, PointOrigin};
+use crate::i18n::tr;
+use crate::legend::{self, LegendColors};
+use crate::model::{Config, HistoryPoint};
+use crate::notes::Note;
+use crate::palette::{ColorScale, COLOR_BUCKETS};
+use crate::rules::Severity;
+use crate::schedule::PauseInterval;
+use crate::sensors;
+use crate::sleep::{self, SleepPeriod};
+use crate::sun::NightSettings;
+use crate::units::Channel;
+use crate::webhook::AlertKind;
+use crate::{forecast, vpd};
+use super::TempMonitorApp;
+
+// Persistence overlay: rings around each point in a colour/shape per origin, with counts in the legend
+pub(crate) fn draw_origin_markers(plot_ui: &mut egui_plot::PlotUi, history: &VecDeque<HistoryPoint>, value: impl Fn(&HistoryPoint) -> f64) {
+    use egui_plot::{MarkerShape, Points};
+    for origin in PointOrigin::ALL {
+        let points: Vec<[f64; 2]> = history.iter().filter(|p| p.origin == origin).map(|p| [p.timestamp.timestamp() as f64, value(p)]).collect();
+        if points.is_empty() { continue; }
+        let (shape, color) = match origin {
+            PointOrigin::Persisted => (MarkerShape::Circle, egui::Color32::from_rgb(80, 200, 120)),
+            PointOrigin::LiveOnly => (MarkerShape::Cross, egui::Color32::from_rgb(255, 160, 0)),
+            PointOrigin::Backfilled => (MarkerShape::Square, egui::Color32::from_rgb(150, 150, 150)),
+            PointOrigin::Summary => (MarkerShape::Diamond, egui::Color32::from_rgb(200, 120, 255)),
+        };
+        let count = points.len();
+        plot_ui.points(Points::new(points).shape(shape).filled(false).radius(6.0).color(color).name(format!("{}: {}", origin.label(), count)));
+    }
+}
+
+// The pauses as recorded when they began, see `schedule::PAUSE_LOG_FILE_NAME`
+pub(crate) fn draw_pause_bands(plot_ui: &mut egui_plot::PlotUi, history: &VecDeque<HistoryPoint>, pauses: &[PauseInterval], y_min: f64, y_max: f64) {
+    let (Some(first), Some(last)) = (history.front(), history.back()) else { return; };
+    for interval in pauses.iter().filter(|p| p.end >= first.timestamp && p.start <= last.timestamp) {
+        let (x0, x1) = (interval.start.timestamp() as f64, interval.end.timestamp() as f64);
+        let band = egui_plot::Polygon::new(egui_plot::PlotPoints::new(vec![[x0, y_min], [x1, y_min], [x1, y_max], [x0, y_max]]))
+            .fill_color(egui::Color32::from_rgba_unmultiplied(200, 200, 200, 25)).stroke(egui::Stroke::NONE).name(format!("Paused: {}", interval.label));
+        plot_ui.polygon(band);
+    }
+}
+
+// The lines break off here, see `sleep::split_at_sleep`
+pub(crate) fn draw_sleep_bands(plot_ui: &mut egui_plot::PlotUi, history: &VecDeque<HistoryPoint>, periods: &[SleepPeriod], y_min: f64, y_max: f64) {
+    let (Some(first), Some(last)) = (history.front(), history.back()) else { return; };
+    for period in periods.iter().filter(|p| p.end >= first.timestamp && p.start <= last.timestamp) {
+        let (x0, x1) = (period.start.timestamp() as f64, period.end.timestamp() as f64);
+        let band = egui_plot::Polygon::new(egui_plot::PlotPoints::new(vec![[x0, y_min], [x1, y_min], [x1, y_max], [x0, y_max]]))
+            .fill_color(egui::Color32::from_rgba_unmultiplied(255, 160, 0, 25)).stroke(egui::Stroke::new(1.0, egui::Color32::from_rgba_unmultiplied(255, 160, 0, 90))).name("PC asleep");
+        plot_ui.polygon(band);
+    }
+}
+
+// One `Points` item per color step rather than per reading, so a frame costs a few items however long the history is
+pub(crate) fn draw_colored_points(plot_ui: &mut egui_plot::PlotUi, history: &VecDeque<HistoryPoint>, scale: ColorScale, value: impl Fn(&HistoryPoint) -> f64) {
+    let mut buckets: Vec<Vec<[f64; 2]>> = vec![Vec::new(); COLOR_BUCKETS];
+    for p in history {
+        let y = value(p);
+        buckets[scale.bucket(y)].push([p.timestamp.timestamp() as f64, y]);
+    }
+    for (bucket, points) in buckets.into_iter().enumerate().filter(|(_, points)| !points.is_empty()) {
+        plot_ui.points(egui_plot::Points::new(points).radius(3.0).color(scale.bucket_color(bucket)).highlight(true));
+    }
+}
+
+pub(crate) fn draw_night_bands(plot_ui: &mut egui_plot::PlotUi, history: &VecDeque<HistoryPoint>, config: &Config, y_min: f64, y_max: f64) {
+    let (Some(first), Some(last)) = (history.front(), history.back()) else { return; };
+    let settings = NightSettings { mode: config.night_shading, latitude: config.latitude, longitude: config.longitude, sunrise: &config.sunrise, sunset: &config.sunset };
+    for (start, end) in settings.night_intervals(first.timestamp, last.timestamp) {
+        let (x0, x1) = (start.timestamp() as f64, end.timestamp() as f64);
+        let band = egui_plot::Polygon::new(egui_plot::PlotPoints::new(vec![[x0, y_min], [x1, y_min], [x1, y_max], [x0, y_max]]))
+            .fill_color(egui::Color32::from_rgba_unmultiplied(60, 70, 140, 30)).stroke(egui::Stroke::NONE).name("Night");
+        plot_ui.polygon(band);
+    }
+}
+
+// Threshold lines plus shaded bands beyond them, so excursions stand out without reading values
+pub(crate) fn draw_threshold_guides(plot_ui: &mut egui_plot::PlotUi, history: &VecDeque<HistoryPoint>, low: f64, high: f64, y_min: f64, y_max: f64, channel: Channel) {
+    let (Some(first), Some(last)) = (history.front(), history.back()) else { return; };
+    let (x0, x1) = (first.timestamp.timestamp() as f64, last.timestamp.timestamp() as f64);
+    let margin = ((high - low).abs() * 0.1).max(1.0);
+    let (top, bottom) = (y_max.max(high) + margin, y_min.min(low) - margin);
+    let band = |y0: f64, y1: f64, color: egui::Color32| egui_plot::Polygon::new(egui_plot::PlotPoints::new(vec![[x0, y0], [x1, y0], [x1, y1], [x0, y1]])).fill_color(color).stroke(egui::Stroke::NONE);
+    plot_ui.polygon(band(high, top, egui::Color32::from_rgba_unmultiplied(255, 80, 80, 20)).name(format!("Above {}", channel.format(high))));
+    plot_ui.polygon(band(bottom, low, egui::Color32::from_rgba_unmultiplied(80, 140, 255, 20)).name(format!("Below {}", channel.format(low))));
+    plot_ui.hline(egui_plot::HLine::new(high).color(egui::Color32::from_rgb(255, 80, 80)).style(egui_plot::LineStyle::dashed_loose()).name(format!("Upper threshold {}", channel.format(high))));
+    plot_ui.hline(egui_plot::HLine::new(low).color(egui::Color32::from_rgb(80, 140, 255)).style(egui_plot::LineStyle::dashed_loose()).name(format!("Lower threshold {}", channel.format(low))));
+}
+
+pub(crate) fn event_marker_color(event: &AlertRecord) -> egui::Color32 {
+    if event.event == AlertKind::SensorOffline.id() { return egui::Color32::from_rgb(150, 150, 150); }
+    if event.is_recovery() { return egui::Color32::from_rgb(80, 200, 120); }
+    match event.severity { Severity::Critical => egui::Color32::from_rgb(230, 40, 40), Severity::Warning => egui::Color32::from_rgb(255, 160, 0), Severity::Info => egui::Color32::from_rgb(100, 160, 255) }
+}
+
+// A vertical line per alert inside the loaded history, so excursions and outages show where they happened
+pub(crate) fn draw_event_markers(plot_ui: &mut egui_plot::PlotUi, history: &VecDeque<HistoryPoint>, events: &[AlertRecord]) {
+    let (Some(first), Some(last)) = (history.front(), history.back()) else { return; };
+    for event in events.iter().filter(|e| (first.timestamp..=last.timestamp).contains(&e.timestamp)) {
+        plot_ui.vline(egui_plot::VLine::new(event.timestamp.timestamp() as f64).color(event_marker_color(event)).style(egui_plot::LineStyle::dashed_dense()));
+    }
+}
+
+// Notes as labeled vertical lines; the label sits at `y_top` inside the data range so it never stretches the auto bounds
+pub(crate) fn draw_note_markers(plot_ui: &mut egui_plot::PlotUi, history: &VecDeque<HistoryPoint>, notes: &[Note], y_top: f64) {
+    let (Some(first), Some(last)) = (history.front(), history.back()) else { return; };
+    let color = egui::Color32::from_rgb(190, 140, 255);
+    for note in notes.iter().filter(|n| (first.timestamp..=last.timestamp).contains(&n.timestamp)) {
+        let x = note.timestamp.timestamp() as f64;
+        plot_ui.vline(egui_plot::VLine::new(x).color(color).width(1.5));
+        plot_ui.text(egui_plot::Text::new(egui_plot::PlotPoint::new(x, y_top), egui::RichText::new(format!(" 📝 {}", note.text)).color(color)).anchor(egui::Align2::LEFT_TOP));
+    }
+}
+
+// Dashed projection from the newest reading; the legend says when it reaches a threshold
+pub(crate) fn draw_forecast(plot_ui: &mut egui_plot::PlotUi, history: &VecDeque<HistoryPoint>, config: &Config) {
+    let Some(newest) = history.back().filter(|_| config.forecast_enabled).map(|p| p.timestamp) else { return; };
+    let mut samples: Vec<(DateTime<Local>, f64)> = history.iter().rev().take_while(|p| newest - p.timestamp <= chrono::Duration::minutes(forecast::FIT_WINDOW_MINS)).map(|p| (p.timestamp, p.temp as f64)).collect();
+    samples.reverse();
+    let Some(projection) = forecast::project(&samples, config.forecast_method, config.forecast_horizon_mins) else { return; };
+    let (high, low) = (config.temp_warn_high as f64, config.temp_warn_low as f64);
+    let mut name = format!("Forecast ({})", config.forecast_method.label().to_lowercase());
+    if let Some((threshold, t)) = forecast::crossing(&projection, high, true).map(|t| (high, t)).or_else(|| forecast::crossing(&projection, low, false).map(|t| (low, t))) {
+        name.push_str(&format!(": {} at {}", Channel::Temperature.format(threshold), t.format("%H:%M")));
+    }
+    plot_ui.line(egui_plot::Line::new(egui_plot::PlotPoints::new(projection)).color(egui::Color32::from_rgb(255, 140, 0)).width(2.0).style(egui_plot::LineStyle::dashed_dense()).name(name));
+}
+
+// The chosen day's curve on today's timeline, in the channel's color but faded so today's data stays in front
+pub(crate) fn overlay_line(app: &mut TempMonitorApp, channel: &str, color: egui::Color32, value: impl Fn(&HistoryPoint) -> f64) -> Option<egui_plot::Line> {
+    if !app.config.day_overlay { return None; }
+    let name = format!("{} on {}", channel, app.config.date_format.short_date(app.overlay.day));
+    let points: Vec<[f64; 2]> = app.overlay.points(Local::now().date_naive()).iter().map(|p| [p.timestamp.timestamp() as f64, value(p)]).collect();
+    Some(egui_plot::Line::new(egui_plot::PlotPoints::new(points)).color(color.gamma_multiply(0.35)).width(2.0).name(name))
+}
+
+// Legend name with the target's name from settings appended, e.g. "Temperature (raw) – Bedroom"
+fn sensor_label(config: &Config, name: &str) -> String {
+    sensors::friendly_name(config, &config.target_mac).map_or(name.to_string(), |sensor| format!("{} – {}", name, sensor))
+}
+
+// The target's plot color from settings, or the channel's own
+fn sensor_color(config: &Config, fallback: egui::Color32) -> egui::Color32 { sensors::target_color(config).map_or(fallback, |[r, g, b]| egui::Color32::from_rgb(r, g, b)) }
+
+// Bounds for a recalled view: the locked y range, or one fitted to the data inside the time range
+pub(crate) fn view_bounds(history: &VecDeque<HistoryPoint>, x_range: (f64, f64), y_lock: Option<(f64, f64)>, value: impl Fn(&HistoryPoint) -> f64, pad: f64) -> egui_plot::PlotBounds {
+    let (y_min, y_max) = y_lock.unwrap_or_else(|| {
+        let values = history.iter().filter(|p| (x_range.0..=x_range.1).contains(&(p.timestamp.timestamp() as f64))).map(&value);
+        let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+        if min.is_finite() { (min - pad, max + pad) } else { (0.0, 1.0) }
+    });
+    egui_plot::PlotBounds::from_min_max([x_range.0, y_min], [x_range.1, y_max])
+}
+
+// Checkboxes beside the temperature or humidity plot's title; the choice is saved with the config
+pub(crate) fn draw_series_legend(app: &mut TempMonitorApp, ui: &mut egui::Ui, channel: Channel) {
+    let (raw, smoothed) = if channel == Channel::Humidity { ((100, 100, 255), (120, 220, 255)) } else { ((255, 100, 100), (255, 200, 120)) };
+    let colors = LegendColors { raw: sensor_color(&app.config, egui::Color32::from_rgb(raw.0, raw.1, raw.2)), smoothed: egui::Color32::from_rgb(smoothed.0, smoothed.1, smoothed.2) };
+    let (smoothing, thresholds) = (app.config.smoothing_enabled, app.config.show_threshold_lines);
+    let toggles = if channel == Channel::Humidity { &mut app.config.hum_series } else { &mut app.config.temp_series };
+    let before = *toggles;
+    if legend::show(ui, toggles, colors, smoothing, thresholds) {
+        let mut old_config = app.config.clone();
+        *(if channel == Channel::Humidity { &mut old_config.hum_series } else { &mut old_config.temp_series }) = before;
+        app.sync_config(&old_config);
+    }
+}
+
+// Time under the pointer on the temperature (0) or humidity (1) plot; a change repaints so the readout keeps up
+fn track_crosshair(app: &mut TempMonitorApp, plot: usize, pointer: Option<egui_plot::PlotPoint>, ctx: &egui::Context) {
+    let x = pointer.map(|pos| pos.x);
+    if app.crosshair[plot] != x { app.crosshair[plot] = x; ctx.request_repaint(); }
+}
+
+// Both values of the reading under the crosshair of either plot, e.g. "14:05:30  21.4°C  48%"
+pub(crate) fn draw_crosshair_readout(app: &TempMonitorApp, ui: &mut egui::Ui) {
+    let Some(x) = app.crosshair[0].or(app.crosshair[1]) else { return; };
+    let Some(point) = history::nearest(&app.history, x, gap_secs(&app.config)) else { return; };
+    ui.label(egui::RichText::new(format!("{}  {}  {}", point.timestamp.format("%H:%M:%S"), Channel::Temperature.format(point.temp as f64), Channel::Humidity.format(point.hum as f64))).monospace());
+}
+
+pub(crate) fn draw_temperature_graph(app: &mut TempMonitorApp, ui: &mut egui::Ui, ctx: &egui::Context) {
+    use egui_plot::{GridMark, Line, Plot, PlotPoints};
+    let temp_data_points = app.plot_series.get(&app.history, app.history_version).temp.clone();
+    let (color, name) = (sensor_color(&app.config, egui::Color32::from_rgb(255, 100, 100)), sensor_label(&app.config, "Temperature (raw)"));
+    let temp_lines: Vec<_> = line_segments(app, temp_data_points.clone()).into_iter().map(|segment| Line::new(PlotPoints::new(segment)).color(color).width(2.0).name(&name)).collect();
+    let summaries = app.visible_range.and_then(|range| plot_resolution(&app.config, range)).map(|r| (r, app.plot_cache.get(&app.history, r).to_vec()));
+    let temp_smoothed = app.config.smoothing_enabled.then(|| Line::new(PlotPoints::new(moving_average(&temp_data_points, app.config.smoothing_window_mins as f64 * 60.0)))
+        .color(egui::Color32::from_rgb(255, 200, 120)).width(2.5).name(format!("Temperature ({} min average)", app.config.smoothing_window_mins)));
+    let temp_overlay = overlay_line(app, "Temperature", egui::Color32::from_rgb(255, 100, 100), |p| p.temp as f64);
+
+    let mut plot = Plot::new("temperature_plot").height(ui.available_height()).width(ui.available_width())
+        .link_axis(egui::Id::new("linked_plots"), true, false).link_cursor(egui::Id::new("linked_plots"), true, false).show_background(false).allow_drag(true).allow_zoom(true)
+        .allow_boxed_zoom(true).boxed_zoom_pointer_button(egui::PointerButton::Secondary)
+        .auto_bounds(egui::Vec2b::new(true, true)).show_x(false)
+        .label_formatter(|_name, value| { let time = DateTime::from_timestamp(value.x as i64, 0).unwrap_or_default().with_timezone(&Local); Channel::Temperature.hover_label(time, value.y) })
+        .x_axis_formatter(|mark: GridMark, _, _| { let time = DateTime::from_timestamp(mark.value as i64, 0).unwrap_or_default().with_timezone(&Local); time.format("%H:%M").to_string() })
+        .y_axis_formatter(|mark: GridMark, _, _| Channel::Temperature.format(mark.value));
+    if app.reset_plot { plot = plot.reset(); }
+    if app.config.smoothing_enabled || app.config.persistence_overlay || app.config.day_overlay || app.config.forecast_enabled || summaries.is_some() { plot = plot.legend(egui_plot::Legend::default()); }
+    if let (Some(min), Some(max)) = (app.history.iter().map(|p| p.temp).min_by(|a, b| a.partial_cmp(b).unwrap()), app.history.iter().map(|p| p.temp).max_by(|a, b| a.partial_cmp(b).unwrap())) {
+        if (max - min).abs() < f32::EPSILON { plot = plot.include_y(min - 0.5).include_y(max + 0.5); }
+    }
+
+    plot.show(ui, |plot_ui| {
+        if let (Some(min), Some(max)) = (app.history.iter().map(|p| p.temp).reduce(f32::min), app.history.iter().map(|p| p.temp).reduce(f32::max)) {
+            draw_night_bands(plot_ui, &app.history, &app.config, min as f64 - 0.5, max as f64 + 0.5);
+            draw_pause_bands(plot_ui, &app.history, &app.pause_periods, min as f64 - 0.5, max as f64 + 0.5);
+            draw_sleep_bands(plot_ui, &app.history, &app.sleep_periods, min as f64 - 0.5, max as f64 + 0.5);
+            if app.config.show_notes { draw_note_markers(plot_ui, &app.history, &app.notes, max as f64 + 0.5); }
+            if app.config.show_threshold_lines && app.config.temp_series.thresholds { draw_threshold_guides(plot_ui, &app.history, app.config.temp_warn_low as f64, app.config.temp_warn_high as f64, min as f64, max as f64, Channel::Temperature); }
+        }
+        if app.config.show_event_markers { draw_event_markers(plot_ui, &app.history, &app.events); }
+        if let Some(line) = temp_overlay { plot_ui.line(line); }
+        draw_forecast(plot_ui, &app.history, &app.config);
+        if let Some((resolution, summaries)) = &summaries {
+            draw_summary_lines(plot_ui, summaries, *resolution, egui::Color32::from_rgb(255, 100, 100), "Temperature", |s| (s.temp_mean as f64, s.temp_min as f64, s.temp_max as f64));
+        } else {
+            // line, broken where the PC slept
+            if app.config.temp_series.raw { for line in temp_lines { plot_ui.line(line); } }
+            if let Some(line) = temp_smoothed.filter(|_| app.config.temp_series.smoothed) { plot_ui.line(line); }
+
+            // colored points by value (0 to 40 °C unless set in Settings)
+            if app.config.temp_series.points {
+                let scale = ColorScale::temperature(&app.config);
+                draw_colored_points(plot_ui, &app.history, scale, |p| p.temp as f64);
+                if app.config.persistence_overlay { draw_origin_markers(plot_ui, &app.history, |p| p.temp as f64); }
+            }
+        }
+
+        if app.zoom_factor != 1.0 { plot_ui.zoom_bounds(egui::vec2(app.zoom_factor, app.zoom_factor), plot_ui.plot_bounds().center()); }
+        if plot_ui.response().drag_started_by(egui::PointerButton::Secondary) { app.push_zoom_history(); }
+        if let Some(view) = &app.pending_view { plot_ui.set_plot_bounds(view_bounds(&app.history, view.x_range, view.temp_y, |p| p.temp as f64, 0.5)); }
+        let bounds = plot_ui.plot_bounds();
+        app.visible_range = Some((bounds.min()[0], bounds.max()[0]));
+        app.temp_y_range = Some((bounds.min()[1], bounds.max()[1]));
+        track_crosshair(app, 0, plot_ui.pointer_coordinate(), ctx);
+
+        if plot_ui.response().clicked() {
+            if let Some(pos) = plot_ui.pointer_coordinate() {
+                let closest_point = app.history.iter().min_by_key(|p| (p.timestamp.timestamp() as f64 - pos.x).abs() as u64);
+                if let Some(point) = closest_point {
+                    if (point.temp as f64 - pos.y).abs() < 1.0 {
+                        let text_to_copy = format!("Time: {}, Temperature: {}", point.timestamp.format("%H:%M:%S"), Channel::Temperature.format(point.temp as f64));
+                        ctx.output_mut(|o| o.copied_text = text_to_copy.clone());
+                        app.toast_message = Some((tr("Copied to clipboard!").to_owned(), Instant::now()));
+                        info!("Copied to clipboard: {}", text_to_copy);
+                    }
+                }
+            }
+        }
+        // Right-click without dragging (a drag zooms) opens the editor for bogus readings
+        if plot_ui.response().secondary_clicked() {
+            if let Some(pos) = plot_ui.pointer_coordinate() {
+                let closest_point = app.history.iter().min_by_key(|p| (p.timestamp.timestamp() as f64 - pos.x).abs() as u64);
+                if let Some(point) = closest_point.filter(|p| (p.temp as f64 - pos.y).abs() < 1.0) { app.point_edit = EditDialog::new(std::slice::from_ref(point)); }
+            }
+        }
+    });
+}
+
+pub(crate) fn draw_humidity_graph(app: &mut TempMonitorApp, ui: &mut egui::Ui, ctx: &egui::Context) {
+    use egui_plot::{GridMark, Line, Plot, PlotPoints};
+    let hum_data_points = app.plot_series.get(&app.history, app.history_version).hum.clone();
+    let (color, name) = (sensor_color(&app.config, egui::Color32::from_rgb(100, 100, 255)), sensor_label(&app.config, "Humidity (raw)"));
+    let hum_lines: Vec<_> = line_segments(app, hum_data_points.clone()).into_iter().map(|segment| Line::new(PlotPoints::new(segment)).color(color).width(2.0).name(&name)).collect();
+    let summaries = app.visible_range.and_then(|range| plot_resolution(&app.config, range)).map(|r| (r, app.plot_cache.get(&app.history, r).to_vec()));
+    let hum_smoothed = app.config.smoothing_enabled.then(|| Line::new(PlotPoints::new(moving_average(&hum_data_points, app.config.smoothing_window_mins as f64 * 60.0)))
+        .color(egui::Color32::from_rgb(120, 220, 255)).width(2.5).name(format!("Humidity ({} min average)", app.config.smoothing_window_mins)));
+    let hum_overlay = overlay_line(app, "Humidity", egui::Color32::from_rgb(100, 100, 255), |p| p.hum as f64);
+
+    let mut plot = Plot::new("humidity_plot").height(ui.available_height()).width(ui.available_width())
+        .link_axis(egui::Id::new("linked_plots"), true, false).link_cursor(egui::Id::new("linked_plots"), true, false).show_background(false).allow_drag(true).allow_zoom(true)
+        .allow_boxed_zoom(true).boxed_zoom_pointer_button(egui::PointerButton::Secondary)
+        .auto_bounds(egui::Vec2b::new(true, true)).show_axes([true, true])
+        .label_formatter(|_name, value| { let time = DateTime::from_timestamp(value.x as i64, 0).unwrap_or_default().with_timezone(&Local); Channel::Humidity.hover_label(time, value.y) })
+        .x_axis_formatter(|mark: GridMark, _, _| { let time = DateTime::from_timestamp(mark.value as i64, 0).unwrap_or_default().with_timezone(&Local); time.format("%H:%M").to_string() })
+        .y_axis_formatter(|mark: GridMark, _, _| Channel::Humidity.format(mark.value));
+    if app.reset_plot { plot = plot.reset(); }
+    if app.config.smoothing_enabled || app.config.persistence_overlay || app.config.day_overlay || summaries.is_some() { plot = plot.legend(egui_plot::Legend::default()); }
+    if let (Some(min), Some(max)) = (app.history.iter().map(|p| p.hum).min(), app.history.iter().map(|p| p.hum).max()) {
+        if min == max { plot = plot.include_y(min as f64 - 1.0).include_y(max as f64 + 1.0); }
+    }
+    
+    plot.show(ui, |plot_ui| {
+        if let (Some(min), Some(max)) = (app.history.iter().map(|p| p.hum).min(), app.history.iter().map(|p| p.hum).max()) {
+            draw_night_bands(plot_ui, &app.history, &app.config, min as f64 - 1.0, max as f64 + 1.0);
+            draw_pause_bands(plot_ui, &app.history, &app.pause_periods, min as f64 - 1.0, max as f64 + 1.0);
+            draw_sleep_bands(plot_ui, &app.history, &app.sleep_periods, min as f64 - 1.0, max as f64 + 1.0);
+            if app.config.show_notes { draw_note_markers(plot_ui, &app.history, &app.notes, max as f64 + 1.0); }
+            if app.config.show_threshold_lines && app.config.hum_series.thresholds { draw_threshold_guides(plot_ui, &app.history, app.config.hum_warn_low as f64, app.config.hum_warn_high as f64, min as f64, max as f64, Channel::Humidity); }
+        }
+        if app.config.show_event_markers { draw_event_markers(plot_ui, &app.history, &app.events); }
+        if let Some(line) = hum_overlay { plot_ui.line(line); }
+        if let Some((resolution, summaries)) = &summaries {
+            draw_summary_lines(plot_ui, summaries, *resolution, egui::Color32::from_rgb(100, 100, 255), "Humidity", |s| (s.hum_mean as f64, s.hum_min as f64, s.hum_max as f64));
+        } else {
+            // line, broken where the PC slept
+            if app.config.hum_series.raw { for line in hum_lines { plot_ui.line(line); } }
+            if let Some(line) = hum_smoothed.filter(|_| app.config.hum_series.smoothed) { plot_ui.line(line); }
+
+            // colored points by value (0 to 100 % unless set in Settings)
+            if app.config.hum_series.points {
+                let scale = ColorScale::humidity(&app.config);
+                draw_colored_points(plot_ui, &app.history, scale, |p| p.hum as f64);
+                if app.config.persistence_overlay { draw_origin_markers(plot_ui, &app.history, |p| p.hum as f64); }
+            }
+        }
+
+        if app.zoom_factor != 1.0 { plot_ui.zoom_bounds(egui::vec2(app.zoom_factor, app.zoom_factor), plot_ui.plot_bounds().center()); }
+        if plot_ui.response().drag_started_by(egui::PointerButton::Secondary) { app.push_zoom_history(); }
+        if let Some(view) = &app.pending_view { plot_ui.set_plot_bounds(view_bounds(&app.history, view.x_range, view.hum_y, |p| p.hum as f64, 1.0)); }
+        let bounds = plot_ui.plot_bounds();
+        app.hum_y_range = Some((bounds.min()[1], bounds.max()[1]));
+        track_crosshair(app, 1, plot_ui.pointer_coordinate(), ctx);
+
+        if plot_ui.response().clicked() {
+            if let Some(pos) = plot_ui.pointer_coordinate() {
+                let closest_point = app.history.iter().min_by_key(|p| (p.timestamp.timestamp() as f64 - pos.x).abs() as u64);
+                if let Some(point) = closest_point {
+                    if (point.hum as f64 - pos.y).abs() < 2.0 {
+                        let text_to_copy = format!("Time: {}, Humidity: {}", point.timestamp.format("%H:%M:%S"), Channel::Humidity.format(point.hum as f64));
+                        ctx.output_mut(|o| o.copied_text = text_to_copy.clone());
+                        app.toast_message = Some((tr("Copied to clipboard!").to_owned(), Instant::now()));
+                        info!("Copied to clipboard: {}", text_to_copy);
+                    }
+                }
+            }
+        }
+        // Right-click without dragging (a drag zooms) opens the editor for bogus readings
+        if plot_ui.response().secondary_clicked() {
+            if let Some(pos) = plot_ui.pointer_coordinate() {
+                let closest_point = app.history.iter().min_by_key(|p| (p.timestamp.timestamp() as f64 - pos.x).abs() as u64);
+                if let Some(point) = closest_point.filter(|p| (p.hum as f64 - pos.y).abs() < 2.0) { app.point_edit = EditDialog::new(std::slice::from_ref(point)); }
+            }
+        }
+    });
+}
+
+// VPD of every reading with the target band; the x axis is linked with the other plots
+pub(crate) fn draw_vpd_graph(app: &mut TempMonitorApp, ui: &mut egui::Ui) {
+    use egui_plot::{GridMark, Line, Plot, PlotPoints, Polygon};
+    let offset = app.config.vpd_leaf_offset;
+    let points: Vec<[f64; 2]> = app.history.iter().map(|p| [p.timestamp.timestamp() as f64, vpd::vpd(p.temp, p.hum, offset) as f64]).collect();
+    let (low, high) = (app.config.vpd_target_low as f64, app.config.vpd_target_high as f64);
+    let range = points.iter().map(|p| p[1]).fold((low, high), |(lo, hi), v| (lo.min(v), hi.max(v)));
+    let mut plot = Plot::new("vpd_plot").height(ui.available_height()).width(ui.available_width())
+        .link_axis(egui::Id::new("linked_plots"), true, false).show_background(false).allow_drag(true).allow_zoom(true)
+        .allow_boxed_zoom(true).boxed_zoom_pointer_button(egui::PointerButton::Secondary)
+        .auto_bounds(egui::Vec2b::new(true, true)).show_axes([true, true])
+        .label_formatter(|_name, value| { let time = DateTime::from_timestamp(value.x as i64, 0).unwrap_or_default().with_timezone(&Local); format!("Time: {}\nVPD: {}", time.format("%H:%M:%S"), vpd::format(value.y)) })
+        .x_axis_formatter(|mark: GridMark, _, _| { let time = DateTime::from_timestamp(mark.value as i64, 0).unwrap_or_default().with_timezone(&Local); time.format("%H:%M").to_string() })
+        .y_axis_formatter(|mark: GridMark, _, _| format!("{:.1} kPa", mark.value));
+    if app.reset_plot { plot = plot.reset(); }
+
+    plot.show(ui, |plot_ui| {
+        draw_night_bands(plot_ui, &app.history, &app.config, range.0 - 0.1, range.1 + 0.1);
+        draw_pause_bands(plot_ui, &app.history, &app.pause_periods, range.0 - 0.1, range.1 + 0.1);
+        if let (Some(first), Some(last)) = (points.first(), points.last()) {
+            plot_ui.polygon(Polygon::new(PlotPoints::new(vec![[first[0], low], [last[0], low], [last[0], high], [first[0], high]]))
+                .fill_color(egui::Color32::from_rgba_unmultiplied(80, 200, 120, 30)).stroke(egui::Stroke::NONE).name(format!("Target {:.2} to {:.2} kPa", low, high)));
+        }
+        if app.config.show_notes { draw_note_markers(plot_ui, &app.history, &app.notes, range.1 + 0.1); }
+        if app.config.show_event_markers { draw_event_markers(plot_ui, &app.history, &app.events); }
+        plot_ui.line(Line::new(PlotPoints::new(points)).color(egui::Color32::from_rgb(80, 200, 120)).width(2.0).name("VPD"));
+
+        if app.zoom_factor != 1.0 { plot_ui.zoom_bounds(egui::vec2(app.zoom_factor, app.zoom_factor), plot_ui.plot_bounds().center()); }
+        if plot_ui.response().drag_started_by(egui::PointerButton::Secondary) { app.push_zoom_history(); }
+        if let Some(view) = &app.pending_view { plot_ui.set_plot_bounds(view_bounds(&app.history, view.x_range, None, |p| vpd::vpd(p.temp, p.hum, offset) as f64, 0.1)); }
+    });
+}
+
+// Signal strength of the live readings; loaded rows carry none, so the line starts with this session
+pub(crate) fn draw_rssi_graph(app: &mut TempMonitorApp, ui: &mut egui::Ui) {
+    use egui_plot::{GridMark, Plot, PlotPoints, Points};
+    let points: Vec<[f64; 2]> = app.history.iter().filter_map(|p| Some([p.timestamp.timestamp() as f64, p.rssi? as f64])).collect();
+    let range = points.iter().map(|p| p[1]).fold((-90.0_f64, -50.0_f64), |(lo, hi), v| (lo.min(v), hi.max(v)));
+    let mut plot = Plot::new("rssi_plot").height(ui.available_height()).width(ui.available_width())
+        .link_axis(egui::Id::new("linked_plots"), true, false).show_background(false).allow_drag(true).allow_zoom(true)
+        .allow_boxed_zoom(true).boxed_zoom_pointer_button(egui::PointerButton::Secondary)
+        .auto_bounds(egui::Vec2b::new(true, true)).show_axes([true, true])
+        .label_formatter(|_name, value| { let time = DateTime::from_timestamp(value.x as i64, 0).unwrap_or_default().with_timezone(&Local); format!("Time: {}\nRSSI: {}", time.format("%H:%M:%S"), Channel::Rssi.format(value.y)) })
+        .x_axis_formatter(|mark: GridMark, _, _| { let time = DateTime::from_timestamp(mark.value as i64, 0).unwrap_or_default().with_timezone(&Local); time.format("%H:%M").to_string() })
+        .y_axis_formatter(|mark: GridMark, _, _| Channel::Rssi.format(mark.value));
+    if app.reset_plot { plot = plot.reset(); }
+
+    plot.show(ui, |plot_ui| {
+        draw_night_bands(plot_ui, &app.history, &app.config, range.0 - 2.0, range.1 + 2.0);
+        draw_pause_bands(plot_ui, &app.history, &app.pause_periods, range.0 - 2.0, range.1 + 2.0);
+        if app.config.show_event_markers { draw_event_markers(plot_ui, &app.history, &app.events); }
+        // Dots rather than a line: RSSI jumps between packets and gaps are the interesting part
+        plot_ui.points(Points::new(PlotPoints::new(points)).color(egui::Color32::from_rgb(230, 160, 60)).radius(1.5).name("RSSI"));
+
+        if app.zoom_factor != 1.0 { plot_ui.zoom_bounds(egui::vec2(app.zoom_factor, app.zoom_factor), plot_ui.plot_bounds().center()); }
+        if plot_ui.response().drag_started_by(egui::PointerButton::Secondary) { app.push_zoom_history(); }
+        if let Some(view) = &app.pending_view { plot_ui.set_plot_bounds(view_bounds(&app.history, view.x_range, Some((range.0 - 2.0, range.1 + 2.0)), |_| 0.0, 0.0)); }
+    });
+}
+
+// Both series in one plot: temperature on the left axis, humidity scaled onto it and labelled on the right axis
+pub(crate) fn draw_combined_graph(app: &mut TempMonitorApp, ui: &mut egui::Ui, ctx: &egui::Context) {
+    use egui_plot::{AxisHints, GridMark, HPlacement, Line, Plot, PlotPoints};
+    let temp_range = app.history.iter().map(|p| p.temp as f64).fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+    let hum_range = app.history.iter().map(|p| p.hum as f64).fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+    let axis = DualAxis::fit((temp_range.0 - 0.5, temp_range.1 + 0.5), (hum_range.0 - 1.0, hum_range.1 + 1.0));
+    let series = app.plot_series.get(&app.history, app.history_version);
+    let temp_data_points = series.temp.clone();
+    let hum_data_points: Vec<[f64; 2]> = series.hum.iter().map(|p| [p[0], axis.to_left(p[1])]).collect();
+    let summaries = app.visible_range.and_then(|range| plot_resolution(&app.config, range)).map(|r| (r, app.plot_cache.get(&app.history, r).to_vec()));
+    let overlays = [overlay_line(app, "Temperature", egui::Color32::from_rgb(255, 100, 100), |p| p.temp as f64), overlay_line(app, "Humidity", egui::Color32::from_rgb(100, 100, 255), |p| axis.to_left(p.hum as f64))];
+
+    let time_label = |x: f64| DateTime::from_timestamp(x as i64, 0).unwrap_or_default().with_timezone(&Local);
+    let mut plot = Plot::new("combined_plot").height(ui.available_height()).width(ui.available_width())
+        .link_axis(egui::Id::new("linked_plots"), true, false).show_background(false).allow_drag(true).allow_zoom(true)
+        .allow_boxed_zoom(true).boxed_zoom_pointer_button(egui::PointerButton::Secondary)
+        .auto_bounds(egui::Vec2b::new(true, true)).legend(egui_plot::Legend::default())
+        .label_formatter(move |name, value| if name.starts_with("Humidity") { Channel::Humidity.hover_label(time_label(value.x), axis.to_right(value.y)) } else { Channel::Temperature.hover_label(time_label(value.x), value.y) })
+        .x_axis_formatter(move |mark: GridMark, _, _| time_label(mark.value).format("%H:%M").to_string())
+        .custom_y_axes(vec![
+            AxisHints::new_y().label(tr("Temperature")).formatter(|mark: GridMark, _, _| Channel::Temperature.format(mark.value)),
+            AxisHints::new_y().label(tr("Humidity")).placement(HPlacement::Right).formatter(move |mark: GridMark, _, _| Channel::Humidity.format(axis.to_right(mark.value))),
+        ]);
+    if app.reset_plot { plot = plot.reset(); }
+
+    plot.show(ui, |plot_ui| {
+        if temp_range.0.is_finite() {
+            draw_night_bands(plot_ui, &app.history, &app.config, temp_range.0 - 0.5, temp_range.1 + 0.5);
+            draw_pause_bands(plot_ui, &app.history, &app.pause_periods, temp_range.0 - 0.5, temp_range.1 + 0.5);
+            draw_sleep_bands(plot_ui, &app.history, &app.sleep_periods, temp_range.0 - 0.5, temp_range.1 + 0.5);
+            if app.config.show_notes { draw_note_markers(plot_ui, &app.history, &app.notes, temp_range.1 + 0.5); }
+            if app.config.show_threshold_lines { draw_threshold_guides(plot_ui, &app.history, app.config.temp_warn_low as f64, app.config.temp_warn_high as f64, temp_range.0, temp_range.1, Channel::Temperature); }
+        }
+        if app.config.show_event_markers { draw_event_markers(plot_ui, &app.history, &app.events); }
+        for line in overlays.into_iter().flatten() { plot_ui.line(line); }
+        draw_forecast(plot_ui, &app.history, &app.config);
+        if let Some((resolution, summaries)) = &summaries {
+            draw_summary_lines(plot_ui, summaries, *resolution, egui::Color32::from_rgb(255, 100, 100), "Temperature", |s| (s.temp_mean as f64, s.temp_min as f64, s.temp_max as f64));
+            draw_summary_lines(plot_ui, summaries, *resolution, egui::Color32::from_rgb(100, 100, 255), "Humidity", |s| (axis.to_left(s.hum_mean as f64), axis.to_left(s.hum_min as f64), axis.to_left(s.hum_max as f64)));
+        } else {
+            if app.config.smoothing_enabled {
+                let window = app.config.smoothing_window_mins as f64 * 60.0;
+                plot_ui.line(Line::new(PlotPoints::new(moving_average(&temp_data_points, window))).color(egui::Color32::from_rgb(255, 200, 120)).width(2.5).name(format!("Temperature ({} min average)", app.config.smoothing_window_mins)));
+                plot_ui.line(Line::new(PlotPoints::new(moving_average(&hum_data_points, window))).color(egui::Color32::from_rgb(120, 220, 255)).width(2.5).name(format!("Humidity ({} min average)", app.config.smoothing_window_mins)));
+            }
+            // Both channels share the plot, so they keep their own colors and only take the sensor's name
+            let (temp_name, hum_name) = (sensor_label(&app.config, "Temperature"), sensor_label(&app.config, "Humidity"));
+            for segment in line_segments(app, temp_data_points) { plot_ui.line(Line::new(PlotPoints::new(segment)).color(egui::Color32::from_rgb(255, 100, 100)).width(2.0).name(&temp_name)); }
+            for segment in line_segments(app, hum_data_points) { plot_ui.line(Line::new(PlotPoints::new(segment)).color(egui::Color32::from_rgb(100, 100, 255)).width(2.0).name(&hum_name)); }
+            if app.config.persistence_overlay { draw_origin_markers(plot_ui, &app.history, |p| p.temp as f64); }
+        }
+
+        if app.zoom_factor != 1.0 { plot_ui.zoom_bounds(egui::vec2(app.zoom_factor, app.zoom_factor), plot_ui.plot_bounds().center()); }
+        if plot_ui.response().drag_started_by(egui::PointerButton::Secondary) { app.push_zoom_history(); }
+        if let Some(view) = &app.pending_view { plot_ui.set_plot_bounds(view_bounds(&app.history, view.x_range, view.temp_y, |p| p.temp as f64, 0.5)); }
+        let bounds = plot_ui.plot_bounds();
+        app.visible_range = Some((bounds.min()[0], bounds.max()[0]));
+        app.temp_y_range = Some((bounds.min()[1], bounds.max()[1]));
+        app.hum_y_range = Some((axis.to_right(bounds.min()[1]), axis.to_right(bounds.max()[1])));
+
+        // A click near either line picks the reading at that time
+        let (clicked, secondary_clicked) = (plot_ui.response().clicked(), plot_ui.response().secondary_clicked());
+        let picked = plot_ui.pointer_coordinate().filter(|_| clicked || secondary_clicked).and_then(|pos| {
+            let point = app.history.iter().min_by_key(|p| (p.timestamp.timestamp() as f64 - pos.x).abs() as u64)?;
+            ((point.temp as f64 - pos.y).abs() < 1.0 || (axis.to_left(point.hum as f64) - pos.y).abs() < 2.0 * axis.scale).then(|| point.clone())
+        });
+        if let Some(point) = picked.clone().filter(|_| clicked) {
+            let text_to_copy = format!("Time: {}, Temperature: {}, Humidity: {}", point.timestamp.format("%H:%M:%S"), Channel::Temperature.format(point.temp as f64), Channel::Humidity.format(point.hum as f64));
+            ctx.output_mut(|o| o.copied_text = text_to_copy.clone());
+            app.toast_message = Some((tr("Copied to clipboard!").to_owned(), Instant::now()));
+            info!("Copied to clipboard: {}", text_to_copy);
+        }
+        // Right-click without dragging (a drag zooms) opens the editor for bogus readings
+        if let Some(point) = picked.filter(|_| secondary_clicked) { app.point_edit = EditDialog::new(std::slice::from_ref(&point)); }
+    });
+}
+
+/// Linear map from humidity onto the temperature axis, so both ranges fill the same height.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct DualAxis { scale: f64, offset: f64 }
+
+impl DualAxis {
+    pub(crate) fn fit(temp: (f64, f64), hum: (f64, f64)) -> Self {
+        if !(temp.0.is_finite() && temp.1.is_finite() && hum.0.is_finite() && hum.1.is_finite()) { return Self { scale: 1.0, offset: 0.0 }; }
+        let scale = (temp.1 - temp.0).max(1.0) / (hum.1 - hum.0).max(1.0);
+        Self { scale, offset: temp.0 - hum.0 * scale }
+    }
+
+    pub(crate) fn to_left(self, hum: f64) -> f64 { hum * self.scale + self.offset }
+
+    pub(crate) fn to_right(self, y: f64) -> f64 { (y - self.offset) / self.scale }
+}
+
+pub(crate) const DAILY_PLOT_AFTER_DAYS: u32 = 90;
+
+// Summaries replace the raw readings once the visible span exceeds `aggregate_plot_hours`
+pub(crate) fn plot_resolution(config: &Config, visible: (f64, f64)) -> Option<Resolution> {
+    let hours = (visible.1 - visible.0) / 3600.0;
+    if config.aggregate_plot_hours == 0 || hours <= config.aggregate_plot_hours as f64 { return None; }
+    Some(if hours > DAILY_PLOT_AFTER_DAYS as f64 * 24.0 { Resolution::Day } else { Resolution::Hour })
+}
+
+// Mean line with thin min and max lines
+pub(crate) fn draw_summary_lines(plot_ui: &mut egui_plot::PlotUi, summaries: &[Summary], resolution: Resolution, color: egui::Color32, name: &str, value: impl Fn(&Summary) -> (f64, f64, f64)) {
+    use egui_plot::{Line, LineStyle, PlotPoints};
+    let series = |pick: &dyn Fn((f64, f64, f64)) -> f64| PlotPoints::new(summaries.iter().map(|s| [s.start.timestamp() as f64, pick(value(s))]).collect());
+    plot_ui.line(Line::new(series(&|v| v.1)).color(color.gamma_multiply(0.6)).style(LineStyle::dotted_dense()).name(format!("{} ({} min)", name, resolution.label())));
+    plot_ui.line(Line::new(series(&|v| v.2)).color(color.gamma_multiply(0.6)).style(LineStyle::dotted_dense()).name(format!("{} ({} max)", name, resolution.label())));
+    plot_ui.line(Line::new(series(&|v| v.0)).color(color).width(2.0).name(format!("{} ({} mean)", name, resolution.label())));
+}
+
+// Trailing time-window mean over [x, y] points sorted by x
+// Raw lines break where the PC slept and, unless turned off, where readings are missing for several intervals
+pub(crate) fn line_segments(app: &TempMonitorApp, points: Vec<[f64; 2]>) -> Vec<Vec<[f64; 2]>> {
+    let segments = sleep::split_at_sleep(points, &app.sleep_periods);
+    if !app.config.break_lines_at_gaps { return segments; }
+    segments.into_iter().flat_map(|segment| history::split_at_gaps(segment, gap_secs(&app.config))).collect()
+}
+
+// Time without a reading that counts as a gap
+fn gap_secs(config: &Config) -> f64 { (expected_reading_interval_secs(config) * config.gap_intervals.max(1) as u64) as f64 }
+
+pub(crate) fn moving_average(points: &[[f64; 2]], window_secs: f64) -> Vec<[f64; 2]> {
+    let (mut start, mut sum) = (0, 0.0);
+    points.iter().enumerate().map(|(i, p)| {
+        sum += p[1];
+        while points[start][0] < p[0] - window_secs { sum -= points[start][1]; start += 1; }
+        [p[0], sum / (i + 1 - start) as f64]
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dual_axis_maps_humidity_onto_the_temperature_range() {
+        let axis = DualAxis::fit((18.0, 24.0), (40.0, 70.0));
+        assert!((axis.to_left(40.0) - 18.0).abs() < 1e-9 && (axis.to_left(70.0) - 24.0).abs() < 1e-9);
+        assert!((axis.to_right(axis.to_left(55.0)) - 55.0).abs() < 1e-9);
+        // No data yet: identity, so the axes still show something sensible
+        assert_eq!(DualAxis::fit((f64::INFINITY, f64::NEG_INFINITY), (f64::INFINITY, f64::NEG_INFINITY)).to_left(50.0), 50.0);
+    }
+}
@@ -0,0 +1,52 @@
+// --- Channel registry: names, units, number formats and value derivation in one place ---
+
+use chrono::{DateTime, Local};
+
+use crate::model::{dew_point, HistoryPoint};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Channel { Temperature, Humidity, DewPoint, Rssi }
+
+impl Channel {
+    pub fn name(&self) -> &'static str {
+        match self { Channel::Temperature => "Temperature", Channel::Humidity => "Humidity", Channel::DewPoint => "Dew point", Channel::Rssi => "RSSI" }
+    }
+
+    pub fn unit(&self) -> &'static str {
+        match self { Channel::Temperature | Channel::DewPoint => "°C", Channel::Humidity => "%", Channel::Rssi => "dBm" }
+    }
+
+    pub fn decimals(&self) -> usize {
+        match self { Channel::Temperature | Channel::DewPoint => 1, Channel::Humidity | Channel::Rssi => 0 }
+    }
+
+    /// Column name in CSV files (kept stable for existing spreadsheets and the importer).
+    pub fn csv_header(&self) -> &'static str {
+        match self { Channel::Temperature => "Temperature", Channel::Humidity => "Humidity", Channel::DewPoint => "DewPoint", Channel::Rssi => "RSSI" }
+    }
+
+    /// Column header with unit, e.g. "Temperature (°C)".
+    pub fn header(&self) -> String { format!("{} ({})", self.name(), self.unit()) }
+
+    /// Bare number at the channel's precision, as written to CSV.
+    pub fn format_number(&self, value: f64) -> String { format!("{:.*}", self.decimals(), value) }
+
+    /// Number with unit, e.g. "23.4°C"; used for axis ticks and value labels.
+    pub fn format(&self, value: f64) -> String {
+        let sep = if self.unit().starts_with('°') || self.unit() == "%" { "" } else { " " };
+        format!("{}{}{}", self.format_number(value), sep, self.unit())
+    }
+
+    /// Plot hover text for a point at `time`.
+    pub fn hover_label(&self, time: DateTime<Local>, value: f64) -> String { format!("Time: {}\n{}: {}", time.format("%H:%M:%S"), self.name(), self.format(value)) }
+
+    /// Value of the channel for a stored reading; `None` for channels that are not logged.
+    pub fn value(&self, p: &HistoryPoint) -> Option<f64> {
+        match self {
+            Channel::Temperature => Some(p.temp as f64),
+            Channel::Humidity => Some(p.hum as f64),
+            Channel::DewPoint => Some(dew_point(p.temp, p.hum) as f64),
+            Channel::Rssi => None,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+// --- Virtual sensors: the mean, minimum or maximum of several sensors' logs (e.g. a house average), logged into a folder of their own ---
+
+use chrono::{DateTime, Duration as ChronoDuration, Local};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::gradient;
+use crate::history::PointOrigin;
+use crate::model::{BleDataPoint, HistoryPoint};
+use crate::paths;
+use crate::storage::load_history_range_in;
+
+/// Folder under the data folder holding one subfolder of daily logs per virtual sensor.
+pub const VIRTUAL_DIR_NAME: &str = "virtual";
+pub const VIRTUAL_MODEL: &str = "Virtual";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Aggregate { Mean, Min, Max }
+
+impl Aggregate {
+    pub const ALL: [Aggregate; 3] = [Aggregate::Mean, Aggregate::Min, Aggregate::Max];
+
+    pub fn label(&self) -> &'static str { match self { Aggregate::Mean => "Mean", Aggregate::Min => "Minimum", Aggregate::Max => "Maximum" } }
+
+    fn apply(&self, values: impl Iterator<Item = f32>) -> f32 {
+        match self {
+            Aggregate::Mean => { let (sum, count) = values.fold((0.0, 0), |(sum, count), v| (sum + v, count + 1)); sum / count.max(1) as f32 }
+            Aggregate::Min => values.fold(f32::MAX, f32::min),
+            Aggregate::Max => values.fold(f32::MIN, f32::max),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct VirtualSensor {
+    pub name: String,
+    /// Data folders of the member sensors, each logged by its own copy of the app; empty means this app's own logs.
+    /// The first one sets the times of the combined readings.
+    pub folders: Vec<String>,
+    pub aggregate: Aggregate,
+    /// Checks the combined readings against the alert rules too
+    pub alerts: bool,
+}
+
+impl Default for VirtualSensor {
+    fn default() -> Self { Self { name: "House average".to_string(), folders: vec![String::new(), String::new()], aggregate: Aggregate::Mean, alerts: false } }
+}
+
+impl VirtualSensor {
+    /// Where its combined readings are logged, in the same daily files as a real sensor's.
+    pub fn folder(&self) -> PathBuf {
+        let name: String = self.name.trim().chars().map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect();
+        paths::data_dir().join(VIRTUAL_DIR_NAME).join(if name.is_empty() { "unnamed".to_string() } else { name })
+    }
+
+    pub fn member_dirs(&self) -> Vec<PathBuf> {
+        self.folders.iter().map(|dir| if dir.trim().is_empty() { paths::data_dir() } else { PathBuf::from(dir.trim()) }).collect()
+    }
+
+    /// Combined readings of `[start, end]`, computed from the members' logs.
+    pub fn load(&self, start: DateTime<Local>, end: DateTime<Local>, max_pair: ChronoDuration) -> Vec<HistoryPoint> {
+        let series: Vec<Vec<HistoryPoint>> = self.member_dirs().iter().map(|dir| load_history_range_in(dir, start, end)).collect();
+        combine(&series, self.aggregate, max_pair)
+    }
+
+    /// The combined reading at `now`, when every member has one within `max_pair` of it.
+    pub fn latest(&self, now: DateTime<Local>, max_pair: ChronoDuration) -> Option<BleDataPoint> {
+        let point = self.load(now - max_pair * 2, now, max_pair).pop().filter(|p| now - p.timestamp <= max_pair)?;
+        Some(BleDataPoint { timestamp: point.timestamp, temp: point.temp, hum: point.hum, device_id: self.name.clone(), model: VIRTUAL_MODEL, rssi: None, raw_data: Vec::new(), device_alarm: false, battery: None, persisted: true })
+    }
+}
+
+/// Aligns the series on the readings of the first with the gradient's pairing, and aggregates each group;
+/// a reading is left out when any other series has no partner for it.
+pub fn combine(series: &[Vec<HistoryPoint>], aggregate: Aggregate, max_pair: ChronoDuration) -> Vec<HistoryPoint> {
+    let Some((first, others)) = series.split_first() else { return Vec::new(); };
+    let partners: Vec<Vec<Option<&HistoryPoint>>> = others.iter().map(|other| gradient::nearest(first, other, max_pair)).collect();
+    first.iter().enumerate().filter_map(|(i, p)| {
+        let group: Vec<&HistoryPoint> = std::iter::once(Some(p)).chain(partners.iter().map(|other| other[i])).collect::<Option<_>>()?;
+        let hum = aggregate.apply(group.iter().map(|q| q.hum as f32)).round() as u8;
+        Some(HistoryPoint { timestamp: p.timestamp, temp: aggregate.apply(group.iter().map(|q| q.temp)), hum, origin: PointOrigin::Persisted, rssi: None })
+    }).collect()
+}
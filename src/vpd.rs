@@ -0,0 +1,37 @@
+// --- Vapor pressure deficit (kPa) for growers: air temperature and humidity with a leaf-temperature offset ---
+
+/// Saturation vapor pressure over water in kPa (Tetens equation).
+pub fn saturation_pressure(temp: f32) -> f32 { 0.6108 * (17.27 * temp / (temp + 237.3)).exp() }
+
+/// Leaf VPD: saturation pressure at the leaf minus the vapor pressure of the air. `leaf_offset` is the leaf
+/// temperature minus the air temperature (leaves under lights usually run 1–3 °C cooler); negative VPD means dew on the leaves.
+pub fn vpd(temp: f32, hum: u8, leaf_offset: f32) -> f32 { saturation_pressure(temp + leaf_offset) - saturation_pressure(temp) * hum as f32 / 100.0 }
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VpdStatus { Low, InRange, High }
+
+impl VpdStatus {
+    pub fn classify(vpd: f32, target: (f32, f32)) -> VpdStatus {
+        if vpd < target.0 { VpdStatus::Low } else if vpd > target.1 { VpdStatus::High } else { VpdStatus::InRange }
+    }
+
+    pub fn label(&self) -> &'static str { match self { VpdStatus::Low => "too low (humid)", VpdStatus::InRange => "in target", VpdStatus::High => "too high (dry)" } }
+}
+
+pub fn format(vpd: f64) -> String { format!("{:.2} kPa", vpd) }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vpd_uses_the_leaf_temperature_and_classifies_against_the_target_band() {
+        assert!((saturation_pressure(25.0) - 3.168).abs() < 0.005, "{}", saturation_pressure(25.0));
+        assert!((vpd(25.0, 60, 0.0) - 1.267).abs() < 0.005);
+        // Leaves 2 °C below the air hold less vapor, so the deficit the plant sees is smaller
+        let leaf = vpd(25.0, 60, -2.0);
+        assert!((leaf - 0.909).abs() < 0.005, "{}", leaf);
+        assert!(vpd(20.0, 100, -1.0) < 0.0);
+        assert_eq!([VpdStatus::classify(leaf, (0.8, 1.2)), VpdStatus::classify(0.5, (0.8, 1.2)), VpdStatus::classify(1.5, (0.8, 1.2))], [VpdStatus::InRange, VpdStatus::Low, VpdStatus::High]);
+    }
+}
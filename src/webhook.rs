@@ -0,0 +1,217 @@
+// --- Webhook alerts: JSON POST on threshold crossings and sensor offline (ntfy.sh, Slack, scripts) ---
+
+use chrono::{DateTime, Duration as ChronoDuration, Local};
+use log::{error, info, warn};
+use serde_json::json;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tokio::sync::watch;
+
+use crate::model::{BleDataPoint, Config};
+use crate::processor::sleep_unless_stopped;
+use crate::rules::{Channels, Comparison, Metric, RuleEngine, Severity, Transition};
+use crate::sensors;
+use crate::sink_stats::{self, Sink};
+use crate::templates::{self, AlertVars};
+
+const MAX_ATTEMPTS: u32 = 5;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AlertKind { TemperatureHigh, TemperatureLow, TemperatureNormal, HumidityHigh, HumidityLow, HumidityNormal, SensorOffline, SensorOnline, DeviceAlarm, DeviceAlarmCleared, RuleTriggered, RuleCleared }
+
+impl AlertKind {
+    pub const ALL: [AlertKind; 12] = [
+        AlertKind::TemperatureHigh, AlertKind::TemperatureLow, AlertKind::TemperatureNormal, AlertKind::HumidityHigh, AlertKind::HumidityLow,
+        AlertKind::HumidityNormal, AlertKind::SensorOffline, AlertKind::SensorOnline, AlertKind::DeviceAlarm, AlertKind::DeviceAlarmCleared,
+        AlertKind::RuleTriggered, AlertKind::RuleCleared,
+    ];
+
+    /// Value of the `event` field in the payload.
+    pub fn id(&self) -> &'static str {
+        match self {
+            AlertKind::TemperatureHigh => "temperature_high",
+            AlertKind::TemperatureLow => "temperature_low",
+            AlertKind::TemperatureNormal => "temperature_normal",
+            AlertKind::HumidityHigh => "humidity_high",
+            AlertKind::HumidityLow => "humidity_low",
+            AlertKind::HumidityNormal => "humidity_normal",
+            AlertKind::SensorOffline => "sensor_offline",
+            AlertKind::SensorOnline => "sensor_online",
+            AlertKind::DeviceAlarm => "device_alarm",
+            AlertKind::DeviceAlarmCleared => "device_alarm_cleared",
+            AlertKind::RuleTriggered => "rule_triggered",
+            AlertKind::RuleCleared => "rule_cleared",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<AlertKind> { AlertKind::ALL.into_iter().find(|k| k.id() == id) }
+
+    /// Events that end an excursion (back in range, sensor online again, alarm or rule cleared).
+    pub fn is_recovery(&self) -> bool {
+        matches!(self, AlertKind::TemperatureNormal | AlertKind::HumidityNormal | AlertKind::SensorOnline | AlertKind::DeviceAlarmCleared | AlertKind::RuleCleared)
+    }
+
+    // Temperature and humidity rules keep the event ids of the former fixed thresholds
+    fn for_transition(transition: &Transition) -> AlertKind {
+        match (transition.rule.metric, transition.rule.comparison, transition.active) {
+            (Metric::Temperature, Comparison::Above, true) => AlertKind::TemperatureHigh,
+            (Metric::Temperature, Comparison::Below, true) => AlertKind::TemperatureLow,
+            (Metric::Temperature, _, false) => AlertKind::TemperatureNormal,
+            (Metric::Humidity, Comparison::Above, true) => AlertKind::HumidityHigh,
+            (Metric::Humidity, Comparison::Below, true) => AlertKind::HumidityLow,
+            (Metric::Humidity, _, false) => AlertKind::HumidityNormal,
+            (_, _, true) => AlertKind::RuleTriggered,
+            (_, _, false) => AlertKind::RuleCleared,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WebhookEvent {
+    pub kind: AlertKind,
+    pub message: String,
+    pub device: String,
+    /// The sensor's name from settings, or the MAC when it has none
+    pub device_name: String,
+    pub temperature: Option<f32>,
+    pub humidity: Option<u8>,
+    pub timestamp: DateTime<Local>,
+    pub severity: Severity,
+    /// Channels the alert goes to (the rule's for threshold alerts, all for the others)
+    pub channels: Channels,
+}
+
+impl WebhookEvent {
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "event": self.kind.id(),
+            "message": self.message,
+            "device": self.device,
+            "device_name": self.device_name,
+            "temperature": self.temperature.map(|t| (t * 10.0).round() / 10.0),
+            "humidity": self.humidity,
+            "timestamp": self.timestamp.to_rfc3339(),
+            "severity": self.severity.id(),
+        })
+    }
+}
+
+/// Remembers rule and sensor states so only changes are reported, not every reading above a threshold.
+#[derive(Default)]
+pub struct AlertTracker {
+    rules: RuleEngine,
+    device_alarm: bool,
+    offline: bool,
+}
+
+impl AlertTracker {
+    pub fn on_reading(&mut self, data: &BleDataPoint, config: &Config, paused: bool) -> Vec<WebhookEvent> {
+        let event = |kind: AlertKind, vars: AlertVars, severity: Severity, channels: Channels| {
+            WebhookEvent { kind, message: templates::message(kind, config, &vars), device: config.target_mac.clone(), device_name: sensors::display_name(config, &config.target_mac), temperature: Some(data.temp), humidity: Some(data.hum), timestamp: data.timestamp, severity, channels }
+        };
+        let temp_value = AlertVars { value: Some(format!("{:.1} °C", data.temp)), ..AlertVars::default() };
+        let mut events = Vec::new();
+        if std::mem::take(&mut self.offline) { events.push(event(AlertKind::SensorOnline, temp_value.clone(), Severity::Info, Channels::default())); }
+        for transition in self.rules.evaluate(data, &config.alert_rules, paused, ChronoDuration::minutes(config.trend_window_mins as i64)) {
+            let (rule, kind) = (&transition.rule, AlertKind::for_transition(&transition));
+            let vars = AlertVars {
+                value: Some(rule.metric.format(transition.value)),
+                threshold: transition.active.then(|| rule.metric.format(rule.threshold)),
+                rule: Some(if rule.name.trim().is_empty() { rule.describe() } else { rule.name.trim().to_string() }),
+                duration: None,
+            };
+            let mut alert = event(kind, vars, if transition.active { rule.severity } else { Severity::Info }, rule.channels_at(data.timestamp));
+            // The rule kinds name the rule in their text already
+            if !rule.name.trim().is_empty() && !matches!(kind, AlertKind::RuleTriggered | AlertKind::RuleCleared) { alert.message = format!("{}: {}", rule.name.trim(), alert.message); }
+            events.push(alert);
+        }
+        if data.device_alarm != std::mem::replace(&mut self.device_alarm, data.device_alarm) {
+            events.push(event(if data.device_alarm { AlertKind::DeviceAlarm } else { AlertKind::DeviceAlarmCleared }, temp_value, if data.device_alarm { Severity::Warning } else { Severity::Info }, Channels::default()));
+        }
+        events
+    }
+
+    /// Whether an active rule asks for the sound alarm at `now`.
+    pub fn sounding(&self, now: DateTime<Local>) -> bool { self.rules.sounding(now) }
+
+    /// Reports the sensor offline once when no reading arrived for `offline_after`.
+    pub fn on_silence(&mut self, silent_for: Duration, offline_after: Duration, config: &Config) -> Option<WebhookEvent> {
+        if self.offline || silent_for < offline_after { return None; }
+        self.offline = true;
+        Some(WebhookEvent {
+            kind: AlertKind::SensorOffline, message: templates::message(AlertKind::SensorOffline, config, &AlertVars { duration: Some(silent_for), ..AlertVars::default() }),
+            device: config.target_mac.clone(), device_name: sensors::display_name(config, &config.target_mac), temperature: None, humidity: None, timestamp: Local::now(), severity: Severity::Warning, channels: Channels::default(),
+        })
+    }
+}
+
+// Err((retryable, message)); client errors other than timeouts and rate limits will not succeed on retry
+fn post(url: &str, payload: &serde_json::Value) -> Result<(), (bool, String)> {
+    ureq::post(url)
+        .set("Content-Type", "application/json")
+        .timeout(Duration::from_secs(10))
+        .send_string(&payload.to_string())
+        .map(|_| ())
+        .map_err(|e| match e {
+            ureq::Error::Status(code, _) if (400..500).contains(&code) && code != 408 && code != 429 => (false, format!("HTTP {}", code)),
+            other => (true, other.to_string()),
+        })
+}
+
+fn deliver(url: &str, event: &WebhookEvent, stop: &watch::Receiver<bool>) {
+    let payload = event.to_json();
+    let mut backoff = INITIAL_BACKOFF;
+    for attempt in 1..=MAX_ATTEMPTS {
+        match sink_stats::timed(Sink::Webhook, || post(url, &payload)) {
+            Ok(()) => { info!("Webhook '{}' delivered.", event.kind.id()); return; }
+            Err((false, e)) => { error!("Webhook '{}' rejected, not retrying: {}", event.kind.id(), e); return; }
+            Err((true, e)) if attempt < MAX_ATTEMPTS => {
+                warn!("Webhook '{}' failed (attempt {}/{}), retrying in {}s: {}", event.kind.id(), attempt, MAX_ATTEMPTS, backoff.as_secs(), e);
+                if !sleep_unless_stopped(stop, backoff) { warn!("Webhook '{}' not retried, shutting down.", event.kind.id()); return; }
+                backoff *= 2;
+            }
+            Err((true, e)) => error!("Webhook '{}' failed after {} attempts, giving up: {}", event.kind.id(), MAX_ATTEMPTS, e),
+        }
+    }
+}
+
+/// Runs on its own thread so retries never hold up logging; events are delivered in order.
+/// After `stop` the queued events still get one attempt each, until the channel closes.
+pub fn run_sender(rx: mpsc::Receiver<WebhookEvent>, shared_config: Arc<Mutex<Config>>, stop: watch::Receiver<bool>) {
+    info!("Starting webhook sender.");
+    for event in rx {
+        let config = shared_config.lock().unwrap().clone();
+        if !config.webhook_enabled || config.webhook_url.trim().is_empty() { continue; }
+        deliver(config.webhook_url.trim(), &event, &stop);
+    }
+    info!("Webhook sender terminated.");
+}
+
+/// Sends a sample payload on a background thread (settings "Test" button).
+pub fn send_test(url: &str) {
+    let url = url.trim().to_string();
+    let event = WebhookEvent { kind: AlertKind::TemperatureHigh, message: "Test alert from Temperature Monitor".to_string(), device: String::new(), device_name: String::new(), temperature: None, humidity: None, timestamp: Local::now(), severity: Severity::Info, channels: Channels::default() };
+    thread::spawn(move || deliver(&url, &event, &watch::channel(false).1));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+
+    #[test]
+    fn alert_messages_use_configured_templates() {
+        let _data_dir = TempDataDir::new("templates");
+        let mut config = Config { room_name: "Cellar".into(), duplicate_threshold_secs: 0, ..test_config() };
+        config.alert_templates.insert("temperature_high".into(), "{room}: {value} > {threshold} ({device})".into());
+        let mut tracker = AlertTracker::default();
+        let events = tracker.on_reading(&BleDataPoint { device_id: String::new(), ..reading(Local::now(), 31.5, 40) }, &config, false);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].message, format!("Cellar: 31.5 °C > 30.0 °C ({})", TARGET));
+
+        let offline = tracker.on_silence(Duration::from_secs(600), Duration::from_secs(300), &config).unwrap();
+        assert_eq!(offline.message, "No reading from the sensor for 10 min");
+    }
+}